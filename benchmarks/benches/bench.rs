@@ -1,5 +1,6 @@
 use benchmarks::{Account, Block, BlockHeader, Generate, SignedTransaction};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::de::Limits;
+use borsh::{BorshDeserialize, BorshSerialize, Reader};
 use rand::SeedableRng;
 use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 use speedy::Endianness;
@@ -7,6 +8,24 @@ use speedy::{Readable, Writable};
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 
+/// A writer that drops the bytes and counts the `write` calls, standing in for an unbuffered file
+/// or socket where every call is a syscall.
+#[derive(Default)]
+struct CountingWriter {
+    writes: usize,
+}
+
+impl std::io::Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 fn ser_obj<T>(group_name: &str, num_samples: usize, c: &mut Criterion)
 where
     for<'a> T: Generate
@@ -126,6 +145,20 @@ where
                 b.iter(|| T::try_from_slice(d).unwrap());
             },
         );
+        group.bench_with_input(
+            BenchmarkId::new("borsh_limited", benchmark_param_display.clone()),
+            borsh_data,
+            |b, d| {
+                b.iter(|| T::try_from_slice_limited(d, &Limits::default()).unwrap());
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("borsh_reader", benchmark_param_display.clone()),
+            borsh_data,
+            |b, d| {
+                b.iter(|| Reader::new(d.as_slice()).deserialize::<T>().unwrap());
+            },
+        );
         group.bench_with_input(
             BenchmarkId::new("speedy", benchmark_param_display),
             speedy_data,
@@ -137,6 +170,42 @@ where
     group.finish();
 }
 
+fn ser_writer_obj<T>(group_name: &str, num_samples: usize, c: &mut Criterion)
+where
+    T: Generate + BorshSerialize + 'static,
+{
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+    let mut group = c.benchmark_group(group_name);
+
+    let objects: Vec<_> = (0..num_samples).map(|_| T::generate(&mut rng)).collect();
+    for (i, obj) in objects.iter().enumerate() {
+        let mut unbuffered = CountingWriter::default();
+        borsh::to_writer(&mut unbuffered, obj).unwrap();
+        let mut buffered = CountingWriter::default();
+        borsh::to_writer_buffered(&mut buffered, obj).unwrap();
+        let benchmark_param_display = format!(
+            "idx={}; writes={} vs {}",
+            i, unbuffered.writes, buffered.writes
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("to_writer", benchmark_param_display.clone()),
+            obj,
+            |b, d| {
+                b.iter(|| borsh::to_writer(CountingWriter::default(), d).unwrap());
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("to_writer_buffered", benchmark_param_display),
+            obj,
+            |b, d| {
+                b.iter(|| borsh::to_writer_buffered(CountingWriter::default(), d).unwrap());
+            },
+        );
+    }
+    group.finish();
+}
+
 fn ser_account(c: &mut Criterion) {
     ser_obj::<Account>("ser_account", 10, c);
 }
@@ -176,5 +245,57 @@ criterion_group!(
     ser_header,
     ser_block
 );
+fn ser_writer_header(c: &mut Criterion) {
+    ser_writer_obj::<BlockHeader>("ser_writer_header", 3, c);
+}
+
+fn ser_writer_block(c: &mut Criterion) {
+    ser_writer_obj::<Block>("ser_writer_block", 3, c);
+}
+
+/// Serializes many small objects, each into a new vector or all into one reused buffer.
+fn ser_many_obj<T>(group_name: &str, num_objects: usize, c: &mut Criterion)
+where
+    T: Generate + BorshSerialize + 'static,
+{
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+
+    let objects: Vec<_> = (0..num_objects).map(|_| T::generate(&mut rng)).collect();
+    let size: usize = objects.iter().map(|t| t.try_to_vec().unwrap().len()).sum();
+
+    group.throughput(Throughput::Bytes(size as u64));
+    group.bench_function("try_to_vec", |b| {
+        b.iter(|| {
+            for obj in &objects {
+                criterion::black_box(obj.try_to_vec().unwrap());
+            }
+        });
+    });
+    group.bench_function("to_vec_in", |b| {
+        let mut buf = Vec::new();
+        b.iter(|| {
+            for obj in &objects {
+                buf.clear();
+                borsh::to_vec_in(obj, &mut buf).unwrap();
+                criterion::black_box(&buf);
+            }
+        });
+    });
+    group.finish();
+}
+
+fn ser_many_accounts(c: &mut Criterion) {
+    ser_many_obj::<Account>("ser_many_accounts", 1_000_000, c);
+}
+
 criterion_group!(de_benches, de_account, de_transaction, de_header, de_block);
-criterion_main!(ser_benches, de_benches);
+criterion_group!(ser_writer_benches, ser_writer_header, ser_writer_block);
+criterion_group!(ser_many_benches, ser_many_accounts);
+criterion_main!(
+    ser_benches,
+    de_benches,
+    ser_writer_benches,
+    ser_many_benches
+);
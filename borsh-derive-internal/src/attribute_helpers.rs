@@ -1,16 +1,628 @@
+use proc_macro2::Span;
 use quote::ToTokens;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, Error, Meta, NestedMeta, Path};
+use syn::{Attribute, Error, Field, Ident, Lit, LitInt, Meta, NestedMeta, Path, Token, Type};
 
-pub fn contains_skip(attrs: &[Attribute]) -> bool {
-    for attr in attrs.iter() {
-        if let Ok(Meta::Path(path)) = attr.parse_meta() {
-            if path.to_token_stream().to_string().as_str() == "borsh_skip" {
-                return true;
+const BORSH: &str = "borsh";
+const BORSH_SKIP: &str = "borsh_skip";
+const SKIP: &str = "skip";
+const NAMED_VARIANTS: &str = "named_variants";
+const SCHEMA: &str = "schema";
+const SKIP_TYPE_PARAMS: &str = "skip_type_params";
+const TYPE_PARAMS: &str = "type_params";
+const CONTEXT: &str = "context";
+const DE_CONTEXT: &str = "de_context";
+const SER_CONTEXT: &str = "ser_context";
+const WITH_CONTEXT: &str = "with_context";
+const CANONICAL_NAN: &str = "canonical_nan";
+const BIG_ENDIAN: &str = "big_endian";
+const OPTIONAL_TAIL: &str = "optional_tail";
+const WITH: &str = "with";
+const VALIDATE: &str = "validate";
+const DECLARATION: &str = "declaration";
+const RESERVE: &str = "reserve";
+const CRATE: &str = "crate";
+const TAG: &str = "tag";
+const REQUIRE_SCHEMA: &str = "require_schema";
+
+/// Field-level options, set either with the standalone `#[borsh_skip]` attribute or inside
+/// `#[borsh(...)]`.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// `#[borsh_skip]` or `#[borsh(skip)]`: the field is not written on serialization and is
+    /// filled with `Default::default()` on deserialization.
+    pub skip: bool,
+    /// `#[borsh(with_context)]`: the field is (de)serialized with the context of the container.
+    pub with_context: bool,
+    /// `#[borsh(canonical_nan)]`: the float field is (de)serialized with `CanonicalNan`, which
+    /// accepts NaN and encodes it with a single bit pattern.
+    pub canonical_nan: bool,
+    /// `#[borsh(big_endian)]`: the integer field is (de)serialized with `BigEndian`, most
+    /// significant byte first, and declared as `BigEndian<T>` in the schema.
+    pub big_endian: bool,
+    /// `#[borsh(optional_tail)]` or `#[borsh(optional_tail = "function")]`: the field is filled
+    /// with `Default::default()`, or `function()`, when the input ends before it.
+    pub optional_tail: bool,
+    /// The `function` of `#[borsh(optional_tail = "function")]`.
+    pub tail_default: Option<Path>,
+    /// `#[borsh(with = "module")]`: the field is (de)serialized with `module::serialize` and
+    /// `module::deserialize` instead of its `BorshSerialize` and `BorshDeserialize` impls.
+    pub with: Option<Path>,
+    /// `#[borsh(schema(with = "module"))]`: the schema of the field is `module::declaration()`
+    /// and the definitions added by `module::add_definitions_recursively`, instead of the ones of
+    /// its type.
+    pub schema_with: Option<Path>,
+    /// `#[borsh(schema(declaration = "Type"))]`: the field is declared as `Type` in the schema,
+    /// which must be a primitive or have its definition added by another type of the container.
+    pub schema_declaration: Option<String>,
+    /// `#[borsh(reserve = N)]`: room for up to `N` elements is allocated before deserializing the
+    /// field with `BorshDeserializeReserve`.
+    pub reserve: Option<usize>,
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter() {
+            if attr.path.is_ident(BORSH_SKIP) {
+                result.set_skip(attr.span())?;
+            } else if attr.path.is_ident(BORSH) {
+                for nested in borsh_meta_list(attr)? {
+                    match &nested {
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident(SKIP) => {
+                            result.set_skip(path.span())?;
+                        }
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident(WITH_CONTEXT) => {
+                            if result.with_context {
+                                return Err(Error::new(
+                                    path.span(),
+                                    "`with_context` is specified more than once",
+                                ));
+                            }
+                            result.with_context = true;
+                        }
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident(CANONICAL_NAN) => {
+                            if result.canonical_nan {
+                                return Err(Error::new(
+                                    path.span(),
+                                    "`canonical_nan` is specified more than once",
+                                ));
+                            }
+                            result.canonical_nan = true;
+                        }
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident(BIG_ENDIAN) => {
+                            if result.big_endian {
+                                return Err(Error::new(
+                                    path.span(),
+                                    "`big_endian` is specified more than once",
+                                ));
+                            }
+                            result.big_endian = true;
+                        }
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident(OPTIONAL_TAIL) => {
+                            result.set_optional_tail(path.span())?;
+                        }
+                        NestedMeta::Meta(Meta::NameValue(name_value))
+                            if name_value.path.is_ident(OPTIONAL_TAIL) =>
+                        {
+                            result.set_optional_tail(name_value.path.span())?;
+                            result.tail_default = Some(parse_path(&name_value.lit)?);
+                        }
+                        NestedMeta::Meta(Meta::NameValue(name_value))
+                            if name_value.path.is_ident(WITH) =>
+                        {
+                            if result.with.is_some() {
+                                return Err(Error::new(
+                                    name_value.path.span(),
+                                    "`with` is specified more than once",
+                                ));
+                            }
+                            result.with = Some(parse_path(&name_value.lit)?);
+                        }
+                        NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(SCHEMA) => {
+                            result.parse_schema(list.nested.iter())?;
+                        }
+                        NestedMeta::Meta(Meta::NameValue(name_value))
+                            if name_value.path.is_ident(RESERVE) =>
+                        {
+                            if result.reserve.is_some() {
+                                return Err(Error::new(
+                                    name_value.path.span(),
+                                    "`reserve` is specified more than once",
+                                ));
+                            }
+                            result.reserve = Some(match &name_value.lit {
+                                Lit::Int(lit_int) => lit_int.base10_parse()?,
+                                lit => {
+                                    return Err(Error::new(
+                                        lit.span(),
+                                        "expected a number of elements",
+                                    ))
+                                }
+                            });
+                        }
+                        _ => {
+                            return Err(Error::new(
+                                nested.span(),
+                                "unknown borsh field attribute, expected `skip`, `with`, `with_context`, `canonical_nan`, `big_endian`, `optional_tail`, `schema` or `reserve`",
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+        if result.skip && result.with_context {
+            return Err(borsh_attr_error(
+                attrs,
+                "a skipped field cannot be (de)serialized `with_context`",
+            ));
+        }
+        if result.canonical_nan && (result.skip || result.with_context) {
+            return Err(borsh_attr_error(
+                attrs,
+                "`canonical_nan` cannot be combined with `skip` or `with_context`",
+            ));
+        }
+        if result.big_endian && (result.skip || result.with_context || result.canonical_nan) {
+            return Err(borsh_attr_error(
+                attrs,
+                "`big_endian` cannot be combined with `skip`, `with_context` or `canonical_nan`",
+            ));
+        }
+        if result.with.is_some()
+            && (result.skip || result.with_context || result.canonical_nan || result.big_endian)
+        {
+            return Err(borsh_attr_error(
+                attrs,
+                "`with` cannot be combined with `skip`, `with_context`, `canonical_nan` or `big_endian`",
+            ));
+        }
+        if result.reserve.is_some()
+            && (result.skip
+                || result.with_context
+                || result.canonical_nan
+                || result.big_endian
+                || result.with.is_some())
+        {
+            return Err(borsh_attr_error(
+                attrs,
+                "`reserve` cannot be combined with `skip`, `with`, `with_context`, `canonical_nan` or `big_endian`",
+            ));
+        }
+        if result.skip && result.optional_tail {
+            return Err(borsh_attr_error(
+                attrs,
+                "a skipped field cannot be `optional_tail`, it is never read",
+            ));
+        }
+        if result.skip && (result.schema_with.is_some() || result.schema_declaration.is_some()) {
+            return Err(borsh_attr_error(
+                attrs,
+                "a skipped field has no schema to override",
+            ));
+        }
+        Ok(result)
+    }
+
+    /// Parses the items of `schema(...)`.
+    fn parse_schema<'a>(
+        &mut self,
+        nested: impl Iterator<Item = &'a NestedMeta>,
+    ) -> syn::Result<()> {
+        for nested in nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident(WITH) => {
+                    if self.schema_with.is_some() {
+                        return Err(Error::new(
+                            name_value.path.span(),
+                            "`schema(with)` is specified more than once",
+                        ));
+                    }
+                    self.schema_with = Some(parse_path(&name_value.lit)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident(DECLARATION) =>
+                {
+                    if self.schema_declaration.is_some() {
+                        return Err(Error::new(
+                            name_value.path.span(),
+                            "`schema(declaration)` is specified more than once",
+                        ));
+                    }
+                    self.schema_declaration = Some(match &name_value.lit {
+                        Lit::Str(lit_str) => lit_str.value(),
+                        lit => {
+                            return Err(Error::new(
+                                lit.span(),
+                                "expected a declaration in a string literal",
+                            ))
+                        }
+                    });
+                }
+                _ => {
+                    return Err(Error::new(
+                        nested.span(),
+                        "unknown borsh schema field attribute, expected `with` or `declaration`",
+                    ))
+                }
+            }
+            if self.schema_with.is_some() && self.schema_declaration.is_some() {
+                return Err(Error::new(
+                    nested.span(),
+                    "`schema(with)` and `schema(declaration)` cannot be combined",
+                ));
             }
         }
+        Ok(())
+    }
+
+    fn set_optional_tail(&mut self, span: Span) -> syn::Result<()> {
+        if self.optional_tail {
+            return Err(Error::new(
+                span,
+                "`optional_tail` is specified more than once",
+            ));
+        }
+        self.optional_tail = true;
+        Ok(())
+    }
+
+    fn set_skip(&mut self, span: Span) -> syn::Result<()> {
+        if self.skip {
+            return Err(Error::new(
+                span,
+                "`skip` is specified more than once, `#[borsh_skip]` and `#[borsh(skip)]` are equivalent",
+            ));
+        }
+        self.skip = true;
+        Ok(())
+    }
+}
+
+/// Container-level options, set inside `#[borsh(...)]` on a struct, enum or union.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    /// `#[borsh(named_variants)]`: the enum variant is encoded by its name, as a string, instead of
+    /// by its `u8` discriminant.
+    pub named_variants: bool,
+    /// `#[borsh(schema(skip_type_params))]`: the type parameters are left out of the schema
+    /// declaration and do not get a `BorshSchema` bound.
+    pub schema_skip_type_params: bool,
+    /// `#[borsh(schema(type_params = "T, U"))]`: the type parameters that are part of the schema
+    /// declaration and get a `BorshSchema` bound, instead of those that the serialized fields use.
+    /// The structs of enum variants are derived with it, to be declared like their enum.
+    pub schema_type_params: Option<Vec<Ident>>,
+    /// `#[borsh(de_context = "Ctx")]` or `#[borsh(context = "Ctx")]`: `BorshDeserializeWith<Ctx>`
+    /// is derived instead of `BorshDeserialize`.
+    pub de_context: Option<Type>,
+    /// `#[borsh(ser_context = "Ctx")]` or `#[borsh(context = "Ctx")]`: `BorshSerializeWith<Ctx>`
+    /// is derived instead of `BorshSerialize`.
+    pub ser_context: Option<Type>,
+    /// `#[borsh(validate = "function")]`: `function(&value)` is called after deserialization and
+    /// `borsh_init`, and its error fails the deserialization.
+    pub validate: Option<Path>,
+    /// `#[borsh(crate = "path")]`: the borsh crate is referred to as `path` in the generated code,
+    /// for crates that depend on it through a re-export.
+    pub crate_path: Option<Path>,
+    /// `#[borsh(require_schema)]`: the derived `BorshSerialize` and `BorshDeserialize` impls
+    /// require `BorshSchema` for the type of every field that is (de)serialized with its own
+    /// impls, so that a field without a schema fails to compile.
+    pub require_schema: bool,
+}
+
+impl ContainerAttrs {
+    /// Parses the attributes of an enum.
+    pub fn parse_enum(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::parse(attrs, true)
+    }
+
+    /// Parses the attributes of a struct or a union.
+    pub fn parse_struct(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::parse(attrs, false)
+    }
+
+    fn parse(attrs: &[Attribute], is_enum: bool) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident(BORSH)) {
+            for nested in borsh_meta_list(attr)? {
+                match &nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident(NAMED_VARIANTS) => {
+                        if !is_enum {
+                            return Err(Error::new(
+                                path.span(),
+                                "`named_variants` is only supported on enums",
+                            ));
+                        }
+                        if result.named_variants {
+                            return Err(Error::new(
+                                path.span(),
+                                "`named_variants` is specified more than once",
+                            ));
+                        }
+                        result.named_variants = true;
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(SCHEMA) => {
+                        for nested in list.nested.iter() {
+                            match nested {
+                                NestedMeta::Meta(Meta::Path(path))
+                                    if path.is_ident(SKIP_TYPE_PARAMS) =>
+                                {
+                                    if result.schema_skip_type_params {
+                                        return Err(Error::new(
+                                            path.span(),
+                                            "`skip_type_params` is specified more than once",
+                                        ));
+                                    }
+                                    result.schema_skip_type_params = true;
+                                }
+                                NestedMeta::Meta(Meta::NameValue(name_value))
+                                    if name_value.path.is_ident(TYPE_PARAMS) =>
+                                {
+                                    if result.schema_type_params.is_some() {
+                                        return Err(Error::new(
+                                            name_value.path.span(),
+                                            "`type_params` is specified more than once",
+                                        ));
+                                    }
+                                    result.schema_type_params =
+                                        Some(parse_idents(&name_value.lit)?);
+                                }
+                                _ => return Err(Error::new(
+                                    nested.span(),
+                                    "unknown borsh schema attribute, expected `skip_type_params` or `type_params`",
+                                )),
+                            }
+                        }
+                        if result.schema_skip_type_params && result.schema_type_params.is_some() {
+                            return Err(Error::new(
+                                list.span(),
+                                "`skip_type_params` and `type_params` cannot be combined",
+                            ));
+                        }
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident(CONTEXT) =>
+                    {
+                        let context = parse_type(&name_value.lit)?;
+                        set_context(&mut result.de_context, &name_value.path, context.clone())?;
+                        set_context(&mut result.ser_context, &name_value.path, context)?;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident(DE_CONTEXT) =>
+                    {
+                        let context = parse_type(&name_value.lit)?;
+                        set_context(&mut result.de_context, &name_value.path, context)?;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident(SER_CONTEXT) =>
+                    {
+                        let context = parse_type(&name_value.lit)?;
+                        set_context(&mut result.ser_context, &name_value.path, context)?;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident(VALIDATE) =>
+                    {
+                        if result.validate.is_some() {
+                            return Err(Error::new(
+                                name_value.path.span(),
+                                "`validate` is specified more than once",
+                            ));
+                        }
+                        result.validate = Some(parse_path(&name_value.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident(CRATE) =>
+                    {
+                        if result.crate_path.is_some() {
+                            return Err(Error::new(
+                                name_value.path.span(),
+                                "`crate` is specified more than once",
+                            ));
+                        }
+                        result.crate_path = Some(parse_path(&name_value.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident(REQUIRE_SCHEMA) => {
+                        if result.require_schema {
+                            return Err(Error::new(
+                                path.span(),
+                                "`require_schema` is specified more than once",
+                            ));
+                        }
+                        result.require_schema = true;
+                    }
+                    _ => return Err(Error::new(
+                        nested.span(),
+                        "unknown borsh container attribute, expected `named_variants`, `schema`, `context`, `de_context`, `ser_context`, `validate`, `crate` or `require_schema`",
+                    )),
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Variant-level options, set inside `#[borsh(...)]` on an enum variant.
+#[derive(Default)]
+pub struct VariantAttrs {
+    /// `#[borsh(tag = N)]`: the variant is written with the tag `N`, which fits in a `u8`, instead
+    /// of its position in the enum.
+    pub tag: Option<LitInt>,
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident(BORSH)) {
+            for nested in borsh_meta_list(attr)? {
+                match &nested {
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident(TAG) =>
+                    {
+                        if result.tag.is_some() {
+                            return Err(Error::new(
+                                name_value.path.span(),
+                                "`tag` is specified more than once",
+                            ));
+                        }
+                        result.tag =
+                            Some(match &name_value.lit {
+                                Lit::Int(lit_int) if lit_int.base10_parse::<u8>().is_ok() => {
+                                    lit_int.clone()
+                                }
+                                lit => return Err(Error::new(
+                                    lit.span(),
+                                    "expected a tag from 0 to 255, the tags of variants are `u8`",
+                                )),
+                            });
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            nested.span(),
+                            "unknown borsh variant attribute, expected `tag`",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn set_context(slot: &mut Option<Type>, path: &Path, context: Type) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(Error::new(
+            path.span(),
+            "the context is specified more than once",
+        ));
+    }
+    *slot = Some(context);
+    Ok(())
+}
+
+/// An error pointing at the first `#[borsh(...)]` attribute, for errors about the combination of
+/// options.
+fn borsh_attr_error(attrs: &[Attribute], message: &str) -> Error {
+    match attrs.iter().find(|attr| attr.path.is_ident(BORSH)) {
+        Some(attr) => Error::new_spanned(attr, message),
+        None => Error::new(Span::call_site(), message),
+    }
+}
+
+/// Parses the type in the string literal of `name = "Type"`.
+fn parse_type(lit: &Lit) -> syn::Result<Type> {
+    match lit {
+        Lit::Str(lit_str) => lit_str.parse(),
+        _ => Err(Error::new(
+            lit.span(),
+            "expected a type in a string literal",
+        )),
+    }
+}
+
+/// Parses the path in the string literal of `name = "path"`.
+fn parse_path(lit: &Lit) -> syn::Result<Path> {
+    match lit {
+        Lit::Str(lit_str) => lit_str.parse(),
+        _ => Err(Error::new(
+            lit.span(),
+            "expected a path in a string literal",
+        )),
+    }
+}
+
+/// Parses the comma-separated identifiers in the string literal of `name = "T, U"`.
+fn parse_idents(lit: &Lit) -> syn::Result<Vec<Ident>> {
+    match lit {
+        Lit::Str(lit_str) => Ok(lit_str
+            .parse_with(Punctuated::<Ident, Token![,]>::parse_terminated)?
+            .into_iter()
+            .collect()),
+        _ => Err(Error::new(
+            lit.span(),
+            "expected identifiers in a string literal",
+        )),
+    }
+}
+
+/// Returns the items of a `#[borsh(...)]` attribute.
+fn borsh_meta_list(attr: &Attribute) -> syn::Result<Vec<NestedMeta>> {
+    match attr.parse_meta()? {
+        Meta::List(meta_list) => Ok(meta_list.nested.into_iter().collect()),
+        meta => Err(Error::new(meta.span(), "expected `#[borsh(...)]`")),
+    }
+}
+
+/// Returns whether the field is (de)serialized with `context`, the context of the derived trait.
+/// Fails if the field is marked so but the container has no context for either trait.
+pub fn field_with_context(
+    attrs: &[Attribute],
+    context: Option<&Type>,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<bool> {
+    let with_context = FieldAttrs::parse(attrs)?.with_context;
+    if with_context && container_attrs.de_context.is_none() && container_attrs.ser_context.is_none()
+    {
+        return Err(borsh_attr_error(
+            attrs,
+            "`with_context` requires `#[borsh(context = \"...\")]` on the container",
+        ));
+    }
+    Ok(with_context && context.is_some())
+}
+
+pub fn contains_skip(attrs: &[Attribute]) -> syn::Result<bool> {
+    Ok(FieldAttrs::parse(attrs)?.skip)
+}
+
+/// Returns the position of the first field of a struct with `#[borsh(optional_tail)]`, if any,
+/// and checks that every field after it has the attribute too, or is skipped.
+pub fn optional_tail_start<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> syn::Result<Option<usize>> {
+    let mut start = None;
+    for (index, field) in fields.into_iter().enumerate() {
+        if FieldAttrs::parse(&field.attrs)?.optional_tail {
+            start = start.or(Some(index));
+        } else if start.is_some() && !is_skipped(field)? {
+            return Err(Error::new_spanned(
+                field,
+                "a field without `optional_tail` cannot follow one with `optional_tail`",
+            ));
+        }
+    }
+    Ok(start)
+}
+
+/// Fails if one of the fields of an enum variant has `#[borsh(optional_tail)]`, which only the
+/// fields of structs can have.
+pub fn reject_optional_tail<'a>(fields: impl IntoIterator<Item = &'a Field>) -> syn::Result<()> {
+    for field in fields {
+        if FieldAttrs::parse(&field.attrs)?.optional_tail {
+            return Err(borsh_attr_error(
+                &field.attrs,
+                "`optional_tail` is only supported on the fields of structs",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the field is left out of the data: it has `#[borsh(skip)]`, or its type is
+/// `PhantomData`, which has nothing to serialize.
+pub fn is_skipped(field: &Field) -> syn::Result<bool> {
+    Ok(contains_skip(&field.attrs)? || is_phantom_data(&field.ty))
+}
+
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "PhantomData"),
+        _ => false,
     }
-    false
 }
 
 pub fn contains_initialize_with(attrs: &[Attribute]) -> syn::Result<Option<Path>> {
@@ -0,0 +1,46 @@
+use syn::{Ident, Path};
+
+/// Options of the generated code that do not come from the attributes of the item, for the proc
+/// macros that generate Borsh impls, such as the ones of frameworks that re-export borsh:
+///
+/// ```
+/// use borsh_derive_internal::{struct_ser, DeriveConfig};
+/// use syn::{parse_quote, ItemStruct};
+///
+/// let input: ItemStruct = parse_quote! {
+///     struct Point {
+///         x: u32,
+///         y: u32,
+///     }
+/// };
+/// let config = DeriveConfig::new(parse_quote!(::my_framework::borsh));
+/// let tokens = struct_ser(&input, &config).unwrap().to_string();
+/// assert!(tokens.contains(":: my_framework :: borsh :: ser :: BorshSerialize for Point"));
+/// ```
+///
+/// The `#[borsh(...)]` attributes of the item are honored the same way as by the derives, and a
+/// `#[borsh(crate = "path")]` on the item takes precedence over [`DeriveConfig::crate_path`].
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct DeriveConfig {
+    /// Path of the borsh crate in the generated code, `borsh` by default.
+    pub crate_path: Path,
+}
+
+impl DeriveConfig {
+    pub fn new(crate_path: Path) -> Self {
+        Self { crate_path }
+    }
+
+    /// Returns the path of the borsh crate for an item with the `#[borsh(crate = "...")]` of
+    /// `override_path`, if any.
+    pub fn crate_path_for(&self, override_path: Option<&Path>) -> Path {
+        override_path.unwrap_or(&self.crate_path).clone()
+    }
+}
+
+impl Default for DeriveConfig {
+    fn default() -> Self {
+        Self::new(Ident::new("borsh", proc_macro2::Span::call_site()).into())
+    }
+}
@@ -0,0 +1,229 @@
+use core::convert::TryFrom;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Field, Path, WhereClause};
+
+use crate::attribute_helpers::{field_with_context, ContainerAttrs, FieldAttrs};
+
+/// Returns the expression that deserializes a field that is not skipped, and adds the bound it
+/// needs. The fields with `#[borsh(with_context)]` are read with `BorshDeserializeWith`, the
+/// fields with `#[borsh(canonical_nan)]` with `CanonicalNan`, the fields with
+/// `#[borsh(big_endian)]` with `BigEndian`, the fields with `#[borsh(reserve = N)]` with
+/// `BorshDeserializeReserve`, and the fields with `#[borsh(with = "module")]` with
+/// `module::deserialize`.
+pub fn deserialize_field(
+    field: &Field,
+    cratename: &Path,
+    container_attrs: &ContainerAttrs,
+    where_clause: &mut WhereClause,
+) -> syn::Result<TokenStream2> {
+    let field_type = &field.ty;
+    let context = container_attrs.de_context.as_ref();
+    let field_attrs = FieldAttrs::parse(&field.attrs)?;
+    require_schema(
+        field,
+        &field_attrs,
+        cratename,
+        container_attrs,
+        where_clause,
+    );
+    if let Some(with) = &field_attrs.with {
+        Ok(quote! {
+            #with::deserialize(reader)?
+        })
+    } else if let Some(reserve) = field_attrs.reserve {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::de::BorshDeserializeReserve
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::de::BorshDeserializeReserve::deserialize_reader_reserve(reader, #reserve)?
+        })
+    } else if field_attrs.canonical_nan {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::nan::CanonicalNan
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::nan::CanonicalNan::deserialize_canonical_nan(reader)?
+        })
+    } else if field_attrs.big_endian {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::big_endian::BigEndian
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::big_endian::BigEndian::deserialize_big_endian(reader)?
+        })
+    } else if field_with_context(&field.attrs, context, container_attrs)? {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::de::BorshDeserializeWith<#context>
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::de::BorshDeserializeWith::<#context>::deserialize_reader_with(reader, ctx)?
+        })
+    } else {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::BorshDeserialize
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::BorshDeserialize::deserialize_reader(reader)?
+        })
+    }
+}
+
+/// Returns the expression that fills a skipped field on deserialization, and adds the `Default`
+/// bound it needs, which a type parameter used only by skipped fields does not have otherwise.
+pub fn skipped_field(field: &Field, where_clause: &mut WhereClause) -> TokenStream2 {
+    let field_type = &field.ty;
+    where_clause.predicates.push(
+        syn::parse2(quote! {
+            #field_type: ::core::default::Default
+        })
+        .unwrap(),
+    );
+    quote! { ::core::default::Default::default() }
+}
+
+/// Returns the expression that deserializes a field with `#[borsh(optional_tail)]` with
+/// `read_field`, unless the input has ended before it. The field is then filled with the
+/// function of `#[borsh(optional_tail = "function")]`, or with `Default::default()`, which adds
+/// the `Default` bound. `reader` is an `OptionalTail` at this point.
+pub fn optional_tail_field(
+    field: &Field,
+    read_field: TokenStream2,
+    where_clause: &mut WhereClause,
+) -> syn::Result<TokenStream2> {
+    let default = match FieldAttrs::parse(&field.attrs)?.tail_default {
+        Some(function) => quote! { #function() },
+        None => skipped_field(field, where_clause),
+    };
+    Ok(quote! {
+        if reader.is_at_end()? { #default } else { #read_field }
+    })
+}
+
+/// Returns the statement that serializes `value`, a reference to a field that is not skipped, and
+/// adds the bound it needs. The fields with `#[borsh(with_context)]` are written with
+/// `BorshSerializeWith`, the fields with `#[borsh(canonical_nan)]` with `CanonicalNan`, the fields
+/// with `#[borsh(big_endian)]` with `BigEndian`, and the fields with `#[borsh(with = "module")]`
+/// with `module::serialize`.
+pub fn serialize_field(
+    field: &Field,
+    value: TokenStream2,
+    cratename: &Path,
+    container_attrs: &ContainerAttrs,
+    where_clause: &mut WhereClause,
+) -> syn::Result<TokenStream2> {
+    let field_type = &field.ty;
+    let context = container_attrs.ser_context.as_ref();
+    let field_attrs = FieldAttrs::parse(&field.attrs)?;
+    require_schema(
+        field,
+        &field_attrs,
+        cratename,
+        container_attrs,
+        where_clause,
+    );
+    if let Some(with) = &field_attrs.with {
+        Ok(quote! {
+            #with::serialize(#value, writer)?;
+        })
+    } else if field_attrs.canonical_nan {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::nan::CanonicalNan
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::nan::CanonicalNan::serialize_canonical_nan(#value, writer)?;
+        })
+    } else if field_attrs.big_endian {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::big_endian::BigEndian
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::big_endian::BigEndian::serialize_big_endian(#value, writer)?;
+        })
+    } else if field_with_context(&field.attrs, context, container_attrs)? {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::ser::BorshSerializeWith<#context>
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::ser::BorshSerializeWith::<#context>::serialize_with(#value, writer, ctx)?;
+        })
+    } else {
+        where_clause.predicates.push(
+            syn::parse2(quote! {
+                #field_type: #cratename::ser::BorshSerialize
+            })
+            .unwrap(),
+        );
+        Ok(quote! {
+            #cratename::BorshSerialize::serialize(#value, writer)?;
+        })
+    }
+}
+
+/// Adds the `BorshSchema` bound of a field under `#[borsh(require_schema)]`, unless the field is
+/// written with `with` or has its schema overridden. The bound is spanned to the type of the
+/// field, which the error of a missing impl then points at.
+fn require_schema(
+    field: &Field,
+    field_attrs: &FieldAttrs,
+    cratename: &Path,
+    container_attrs: &ContainerAttrs,
+    where_clause: &mut WhereClause,
+) {
+    if !container_attrs.require_schema
+        || field_attrs.with.is_some()
+        || field_attrs.schema_with.is_some()
+        || field_attrs.schema_declaration.is_some()
+    {
+        return;
+    }
+    let field_type = &field.ty;
+    where_clause.predicates.push(
+        syn::parse2(quote_spanned! {field_type.span()=>
+            #field_type: #cratename::BorshSchema
+        })
+        .unwrap(),
+    );
+}
+
+/// Returns the statement that runs the `#[borsh(validate = "function")]` of the container on
+/// `return_value`, if any.
+pub fn validate_call(container_attrs: &ContainerAttrs, cratename: &Path) -> Option<TokenStream2> {
+    container_attrs.validate.as_ref().map(|validate| {
+        quote! {
+            #validate(&return_value).map_err(::core::convert::Into::<#cratename::maybestd::io::Error>::into)?;
+        }
+    })
+}
+
+/// Returns the index of an unnamed field, which is a `u32` in the generated code.
+pub fn field_index(index: usize, field: &Field) -> syn::Result<u32> {
+    u32::try_from(index)
+        .map_err(|_| syn::Error::new_spanned(field, "up to 2^32 fields are supported"))
+}
@@ -1,13 +1,17 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{Fields, Ident, ItemEnum, WhereClause};
+use syn::{Fields, ItemEnum, WhereClause};
 
 use crate::{
-    attribute_helpers::{contains_initialize_with, contains_skip},
-    enum_discriminant_map::discriminant_map,
+    attribute_helpers::{
+        contains_initialize_with, is_skipped, reject_optional_tail, ContainerAttrs,
+    },
+    config::DeriveConfig,
+    context::{deserialize_field, skipped_field, validate_call},
+    enum_discriminant_map::tag_map,
 };
 
-pub fn enum_de(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2> {
+pub fn enum_de(input: &ItemEnum, config: &DeriveConfig) -> syn::Result<TokenStream2> {
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let mut where_clause = where_clause.map_or_else(
@@ -18,31 +22,40 @@ pub fn enum_de(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
         Clone::clone,
     );
     let init_method = contains_initialize_with(&input.attrs)?;
+    let container_attrs = ContainerAttrs::parse_enum(&input.attrs)?;
+    let cratename = config.crate_path_for(container_attrs.crate_path.as_ref());
+    let named_variants = container_attrs.named_variants;
+    let context = container_attrs.de_context.as_ref();
     let mut variant_arms = TokenStream2::new();
-    let discriminants = discriminant_map(&input.variants);
+    let mut variant_name_arms = TokenStream2::new();
+    let discriminants = tag_map(input, &container_attrs)?;
     for variant in input.variants.iter() {
         let variant_ident = &variant.ident;
         let discriminant = discriminants.get(variant_ident).unwrap();
+        let variant_name = variant_ident.to_string();
+        variant_name_arms.extend(quote! {
+            #variant_name => #discriminant,
+        });
         let mut variant_header = TokenStream2::new();
+        reject_optional_tail(&variant.fields)?;
         match &variant.fields {
             Fields::Named(fields) => {
                 for field in &fields.named {
                     let field_name = field.ident.as_ref().unwrap();
-                    if contains_skip(&field.attrs) {
+                    if is_skipped(field)? {
+                        let value = skipped_field(field, &mut where_clause);
                         variant_header.extend(quote! {
-                            #field_name: Default::default(),
+                            #field_name: #value,
                         });
                     } else {
-                        let field_type = &field.ty;
-                        where_clause.predicates.push(
-                            syn::parse2(quote! {
-                                #field_type: #cratename::BorshDeserialize
-                            })
-                            .unwrap(),
-                        );
-
+                        let read_field = deserialize_field(
+                            field,
+                            &cratename,
+                            &container_attrs,
+                            &mut where_clause,
+                        )?;
                         variant_header.extend(quote! {
-                            #field_name: #cratename::BorshDeserialize::deserialize_reader(reader)?,
+                            #field_name: #read_field,
                         });
                     }
                 }
@@ -50,20 +63,17 @@ pub fn enum_de(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
             }
             Fields::Unnamed(fields) => {
                 for field in fields.unnamed.iter() {
-                    if contains_skip(&field.attrs) {
-                        variant_header.extend(quote! { Default::default(), });
+                    if is_skipped(field)? {
+                        let value = skipped_field(field, &mut where_clause);
+                        variant_header.extend(quote! { #value, });
                     } else {
-                        let field_type = &field.ty;
-                        where_clause.predicates.push(
-                            syn::parse2(quote! {
-                                #field_type: #cratename::BorshDeserialize
-                            })
-                            .unwrap(),
-                        );
-
-                        variant_header.extend(
-                            quote! { #cratename::BorshDeserialize::deserialize_reader(reader)?, },
-                        );
+                        let read_field = deserialize_field(
+                            field,
+                            &cratename,
+                            &container_attrs,
+                            &mut where_clause,
+                        )?;
+                        variant_header.extend(quote! { #read_field, });
                     }
                 }
                 variant_header = quote! { ( #variant_header )};
@@ -82,17 +92,57 @@ pub fn enum_de(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
     } else {
         quote! {}
     };
+    let validate = validate_call(&container_attrs, &cratename);
+
+    let read_tag = if named_variants {
+        quote! {
+            let variant_name = <#cratename::maybestd::string::String as #cratename::de::BorshDeserialize>::deserialize_reader(reader)?;
+            let tag: u8 = match variant_name.as_str() {
+                #variant_name_arms
+                _ => return Err(#cratename::maybestd::io::Error::new(
+                    #cratename::maybestd::io::ErrorKind::InvalidInput,
+                    #cratename::maybestd::format!("Unexpected variant name: {:?}", variant_name),
+                )),
+            };
+        }
+    } else {
+        quote! {
+            let tag = <u8 as #cratename::de::BorshDeserialize>::deserialize_reader(reader)?;
+        }
+    };
+
+    if let Some(context) = context {
+        return Ok(quote! {
+            impl #impl_generics #cratename::de::BorshDeserializeWith<#context> for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn deserialize_reader_with<R: #cratename::maybestd::io::Read>(reader: &mut R, ctx: &mut #context) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
+                    #read_tag
+                    let variant_tag = tag;
+                    let mut return_value =
+                        #variant_arms {
+                        return Err(#cratename::maybestd::io::Error::new(
+                            #cratename::maybestd::io::ErrorKind::InvalidInput,
+                            #cratename::maybestd::format!("Unexpected variant tag: {:?}", variant_tag),
+                        ))
+                    };
+                    #init
+                    #validate
+                    Ok(return_value)
+                }
+            }
+        });
+    }
 
     Ok(quote! {
         impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
-            fn deserialize_reader<R: borsh::maybestd::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
-                let tag = <u8 as #cratename::de::BorshDeserialize>::deserialize_reader(reader)?;
+            fn deserialize_reader<R: #cratename::maybestd::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
+                #read_tag
                 <Self as #cratename::de::EnumExt>::deserialize_variant(reader, tag)
             }
         }
 
         impl #impl_generics #cratename::de::EnumExt for #name #ty_generics #where_clause {
-            fn deserialize_variant<R: borsh::maybestd::io::Read>(
+            fn deserialize_variant<R: #cratename::maybestd::io::Read>(
                 reader: &mut R,
                 variant_tag: u8,
             ) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
@@ -104,6 +154,7 @@ pub fn enum_de(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
                     ))
                 };
                 #init
+                #validate
                 Ok(return_value)
             }
         }
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
-use proc_macro2::{Ident, TokenStream};
+use core::convert::TryFrom;
+
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
-use syn::{punctuated::Punctuated, token::Comma, Variant};
+use syn::{punctuated::Punctuated, token::Comma, Error, ItemEnum, Variant};
+
+use crate::attribute_helpers::{ContainerAttrs, VariantAttrs};
 
 /// Calculates the discriminant that will be assigned by the compiler.
 /// See: https://doc.rust-lang.org/reference/items/enumerations.html#assigning-discriminant-values
@@ -16,9 +20,73 @@ pub fn discriminant_map(variants: &Punctuated<Variant, Comma>) -> HashMap<Ident,
             || quote! { #next_discriminant_if_not_specified },
             |(_, e)| quote! { #e },
         );
-        next_discriminant_if_not_specified = quote! { #this_discriminant + 1 };
+        // Parenthesized so that an expression like `1 << 3` is incremented as a whole.
+        next_discriminant_if_not_specified = quote! { (#this_discriminant) + 1 };
         map.insert(variant.ident.clone(), this_discriminant);
     }
 
     map
 }
+
+/// Returns the tags that the variants are written with. If a variant has `#[borsh(tag = N)]`, the
+/// variants are written with their `tag`, or without one with their position in the enum, and
+/// two variants with the same tag are an error. Otherwise they are written with the discriminants
+/// of [`discriminant_map`].
+pub fn tag_map(
+    input: &ItemEnum,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<HashMap<Ident, TokenStream>> {
+    let variant_attrs = input
+        .variants
+        .iter()
+        .map(|variant| VariantAttrs::parse(&variant.attrs))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let first_tag = match variant_attrs.iter().find_map(|attrs| attrs.tag.as_ref()) {
+        Some(tag) => tag,
+        None => return Ok(discriminant_map(&input.variants)),
+    };
+    if container_attrs.named_variants {
+        return Err(Error::new(
+            first_tag.span(),
+            "`tag` cannot be used on the variants of an enum with `named_variants`",
+        ));
+    }
+    if let Some((_, discriminant)) = input
+        .variants
+        .iter()
+        .find_map(|variant| variant.discriminant.as_ref())
+    {
+        return Err(Error::new_spanned(
+            discriminant,
+            "explicit discriminants cannot be combined with `#[borsh(tag = ...)]` on variants",
+        ));
+    }
+    let mut variants_by_tag = HashMap::new();
+    let mut map = HashMap::new();
+    for (position, (variant, attrs)) in input.variants.iter().zip(&variant_attrs).enumerate() {
+        let (tag, span) = match &attrs.tag {
+            Some(tag) => (tag.base10_parse::<u8>()?, tag.span()),
+            None => (
+                u8::try_from(position).map_err(|_| {
+                    Error::new(
+                        variant.ident.span(),
+                        "the variant needs a `#[borsh(tag = ...)]`, its position does not fit in a `u8`",
+                    )
+                })?,
+                variant.ident.span(),
+            ),
+        };
+        if let Some(other) = variants_by_tag.insert(tag, &variant.ident) {
+            return Err(Error::new(
+                span,
+                format!(
+                    "the tag {} of `{}` is already the tag of `{}`",
+                    tag, variant.ident, other
+                ),
+            ));
+        }
+        let tag = Literal::u8_unsuffixed(tag);
+        map.insert(variant.ident.clone(), quote! { #tag });
+    }
+    Ok(map)
+}
@@ -1,49 +1,60 @@
-use core::convert::TryFrom;
-
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
 use syn::{Fields, Ident, ItemEnum, WhereClause};
 
-use crate::{attribute_helpers::contains_skip, enum_discriminant_map::discriminant_map};
+use crate::{
+    attribute_helpers::{is_skipped, reject_optional_tail, ContainerAttrs},
+    config::DeriveConfig,
+    context::{field_index, serialize_field},
+    enum_discriminant_map::tag_map,
+};
 
-pub fn enum_ser(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2> {
+pub fn enum_ser(input: &ItemEnum, config: &DeriveConfig) -> syn::Result<TokenStream2> {
     let name = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let mut where_clause = where_clause.map_or_else(
+    let (impl_generics, ty_generics, original_where_clause) = input.generics.split_for_impl();
+    let mut where_clause = original_where_clause.map_or_else(
         || WhereClause {
             where_token: Default::default(),
             predicates: Default::default(),
         },
         Clone::clone,
     );
+    let container_attrs = ContainerAttrs::parse_enum(&input.attrs)?;
+    let cratename = config.crate_path_for(container_attrs.crate_path.as_ref());
+    let named_variants = container_attrs.named_variants;
     let mut variant_idx_body = TokenStream2::new();
+    let mut variant_tags = Vec::new();
     let mut fields_body = TokenStream2::new();
-    let discriminants = discriminant_map(&input.variants);
+    let discriminants = tag_map(input, &container_attrs)?;
     for variant in input.variants.iter() {
         let variant_ident = &variant.ident;
         let mut variant_header = TokenStream2::new();
         let mut variant_body = TokenStream2::new();
-        let discriminant_value = discriminants.get(variant_ident).unwrap();
+        let variant_name = variant_ident.to_string();
+        let discriminant_value = if named_variants {
+            quote! { #variant_name }
+        } else {
+            discriminants.get(variant_ident).unwrap().clone()
+        };
+        variant_tags.push(quote! { (#discriminant_value, #variant_name) });
+        reject_optional_tail(&variant.fields)?;
         match &variant.fields {
             Fields::Named(fields) => {
                 for field in &fields.named {
                     let field_name = field.ident.as_ref().unwrap();
-                    if contains_skip(&field.attrs) {
-                        variant_header.extend(quote! { _#field_name, });
+                    if is_skipped(field)? {
+                        variant_header.extend(quote! { #field_name: _, });
                         continue;
                     } else {
-                        let field_type = &field.ty;
-                        where_clause.predicates.push(
-                            syn::parse2(quote! {
-                                #field_type: #cratename::ser::BorshSerialize
-                            })
-                            .unwrap(),
-                        );
                         variant_header.extend(quote! { #field_name, });
                     }
-                    variant_body.extend(quote! {
-                         #cratename::BorshSerialize::serialize(#field_name, writer)?;
-                    })
+                    variant_body.extend(serialize_field(
+                        field,
+                        quote! { #field_name },
+                        &cratename,
+                        &container_attrs,
+                        &mut where_clause,
+                    )?);
                 }
                 variant_header = quote! { { #variant_header }};
                 variant_idx_body.extend(quote!(
@@ -52,28 +63,23 @@ pub fn enum_ser(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
             }
             Fields::Unnamed(fields) => {
                 for (field_idx, field) in fields.unnamed.iter().enumerate() {
-                    let field_idx =
-                        u32::try_from(field_idx).expect("up to 2^32 fields are supported");
-                    if contains_skip(&field.attrs) {
+                    let field_idx = field_index(field_idx, field)?;
+                    if is_skipped(field)? {
                         let field_ident =
                             Ident::new(format!("_id{}", field_idx).as_str(), Span::call_site());
                         variant_header.extend(quote! { #field_ident, });
                         continue;
                     } else {
-                        let field_type = &field.ty;
-                        where_clause.predicates.push(
-                            syn::parse2(quote! {
-                                #field_type: #cratename::ser::BorshSerialize
-                            })
-                            .unwrap(),
-                        );
-
                         let field_ident =
                             Ident::new(format!("id{}", field_idx).as_str(), Span::call_site());
                         variant_header.extend(quote! { #field_ident, });
-                        variant_body.extend(quote! {
-                            #cratename::BorshSerialize::serialize(#field_ident, writer)?;
-                        })
+                        variant_body.extend(serialize_field(
+                            field,
+                            quote! { #field_ident },
+                            &cratename,
+                            &container_attrs,
+                            &mut where_clause,
+                        )?);
                     }
                 }
                 variant_header = quote! { ( #variant_header )};
@@ -93,13 +99,55 @@ pub fn enum_ser(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
             }
         ))
     }
+    let (write_variant, tagged_impl) = if named_variants {
+        let write_variant = quote! {
+            let variant_name: &str = match self {
+                #variant_idx_body
+            };
+            #cratename::BorshSerialize::serialize(variant_name, writer)?;
+        };
+        (write_variant, TokenStream2::new())
+    } else {
+        let write_variant = quote! {
+            let variant_idx: u8 = match self {
+                #variant_idx_body
+            };
+            writer.write_all(&variant_idx.to_le_bytes())?;
+        };
+        let tagged_impl = quote! {
+            impl #impl_generics #cratename::ser::BorshTagged for #name #ty_generics #original_where_clause {
+                const VARIANT_TAGS: &'static [(u8, &'static str)] = &[#(#variant_tags),*];
+
+                fn borsh_tag(&self) -> u8 {
+                    match self {
+                        #variant_idx_body
+                    }
+                }
+            }
+        };
+        (write_variant, tagged_impl)
+    };
+    if let Some(context) = &container_attrs.ser_context {
+        return Ok(quote! {
+            impl #impl_generics #cratename::ser::BorshSerializeWith<#context> for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn serialize_with<W: #cratename::maybestd::io::Write>(&self, writer: &mut W, ctx: &#context) -> ::core::result::Result<(), #cratename::maybestd::io::Error> {
+                    #write_variant
+
+                    match self {
+                        #fields_body
+                    }
+                    Ok(())
+                }
+            }
+
+            #tagged_impl
+        });
+    }
     Ok(quote! {
         impl #impl_generics #cratename::ser::BorshSerialize for #name #ty_generics #where_clause {
             fn serialize<W: #cratename::maybestd::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), #cratename::maybestd::io::Error> {
-                let variant_idx: u8 = match self {
-                    #variant_idx_body
-                };
-                writer.write_all(&variant_idx.to_le_bytes())?;
+                #write_variant
 
                 match self {
                     #fields_body
@@ -107,5 +155,7 @@ pub fn enum_ser(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2>
                 Ok(())
             }
         }
+
+        #tagged_impl
     })
 }
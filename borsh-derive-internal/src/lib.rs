@@ -2,15 +2,18 @@
 // TODO: re-enable this lint when we bump msrv to 1.58
 #![allow(clippy::uninlined_format_args)]
 
-mod attribute_helpers;
+pub mod attribute_helpers;
+mod config;
+mod context;
 mod enum_de;
-mod enum_discriminant_map;
+pub mod enum_discriminant_map;
 mod enum_ser;
 mod struct_de;
 mod struct_ser;
 mod union_de;
 mod union_ser;
 
+pub use config::DeriveConfig;
 pub use enum_de::enum_de;
 pub use enum_ser::enum_ser;
 pub use struct_de::struct_de;
@@ -1,10 +1,17 @@
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use syn::{Fields, Ident, ItemStruct, WhereClause};
+use quote::{format_ident, quote};
+use syn::{Field, Fields, ItemStruct, Path, WhereClause};
 
-use crate::attribute_helpers::{contains_initialize_with, contains_skip};
+use crate::attribute_helpers::{
+    contains_initialize_with, is_skipped, optional_tail_start, ContainerAttrs,
+};
+use crate::config::DeriveConfig;
+use crate::context::{deserialize_field, optional_tail_field, skipped_field, validate_call};
 
-pub fn struct_de(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStream2> {
+pub fn struct_de(input: &ItemStruct, config: &DeriveConfig) -> syn::Result<TokenStream2> {
+    let container_attrs = ContainerAttrs::parse_struct(&input.attrs)?;
+    let cratename = config.crate_path_for(container_attrs.crate_path.as_ref());
+    let context = container_attrs.de_context.as_ref();
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let mut where_clause = where_clause.map_or_else(
@@ -15,29 +22,25 @@ pub fn struct_de(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStrea
         Clone::clone,
     );
     let init_method = contains_initialize_with(&input.attrs)?;
+    let tail_start = optional_tail_start(&input.fields)?;
+    let mut bindings = TokenStream2::new();
     let return_value = match &input.fields {
         Fields::Named(fields) => {
             let mut body = TokenStream2::new();
-            for field in &fields.named {
+            for (index, field) in fields.named.iter().enumerate() {
                 let field_name = field.ident.as_ref().unwrap();
-                let delta = if contains_skip(&field.attrs) {
-                    quote! {
-                        #field_name: Default::default(),
-                    }
-                } else {
-                    let field_type = &field.ty;
-                    where_clause.predicates.push(
-                        syn::parse2(quote! {
-                            #field_type: #cratename::BorshDeserialize
-                        })
-                        .unwrap(),
-                    );
-
-                    quote! {
-                        #field_name: #cratename::BorshDeserialize::deserialize_reader(reader)?,
-                    }
-                };
-                body.extend(delta);
+                let value = field_value(
+                    index,
+                    field,
+                    tail_start,
+                    &cratename,
+                    &container_attrs,
+                    &mut where_clause,
+                    &mut bindings,
+                )?;
+                body.extend(quote! {
+                    #field_name: #value,
+                });
             }
             quote! {
                 Self { #body }
@@ -45,11 +48,19 @@ pub fn struct_de(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStrea
         }
         Fields::Unnamed(fields) => {
             let mut body = TokenStream2::new();
-            for _ in 0..fields.unnamed.len() {
-                let delta = quote! {
-                    #cratename::BorshDeserialize::deserialize_reader(reader)?,
-                };
-                body.extend(delta);
+            for (index, field) in fields.unnamed.iter().enumerate() {
+                let value = field_value(
+                    index,
+                    field,
+                    tail_start,
+                    &cratename,
+                    &container_attrs,
+                    &mut where_clause,
+                    &mut bindings,
+                )?;
+                body.extend(quote! {
+                    #value,
+                });
             }
             quote! {
                 Self( #body )
@@ -61,23 +72,94 @@ pub fn struct_de(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStrea
             }
         }
     };
-    if let Some(method_ident) = init_method {
+    let return_value = if tail_start.is_some() {
+        quote! {
+            {
+                #bindings
+                #return_value
+            }
+        }
+    } else {
+        return_value
+    };
+    let validate = validate_call(&container_attrs, &cratename);
+    let init = if init_method.is_none() && container_attrs.validate.is_none() {
+        quote! {
+            Ok(#return_value)
+        }
+    } else {
+        let init_call = init_method.map(|method_ident| {
+            quote! {
+                return_value.#method_ident();
+            }
+        });
+        let binding = if init_call.is_some() {
+            quote! { let mut return_value }
+        } else {
+            quote! { let return_value }
+        };
+        quote! {
+            #binding = #return_value;
+            #init_call
+            #validate
+            Ok(return_value)
+        }
+    };
+    if let Some(context) = context {
         Ok(quote! {
-            impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
-                fn deserialize_reader<R: borsh::maybestd::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
-                    let mut return_value = #return_value;
-                    return_value.#method_ident();
-                    Ok(return_value)
+            impl #impl_generics #cratename::de::BorshDeserializeWith<#context> for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn deserialize_reader_with<R: #cratename::maybestd::io::Read>(reader: &mut R, ctx: &mut #context) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
+                    #init
                 }
             }
         })
     } else {
         Ok(quote! {
             impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
-                fn deserialize_reader<R: borsh::maybestd::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
-                    Ok(#return_value)
+                fn deserialize_reader<R: #cratename::maybestd::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::maybestd::io::Error> {
+                    #init
                 }
             }
         })
     }
 }
+
+/// Returns the expression of the value of a field. With `#[borsh(optional_tail)]` on the struct,
+/// the fields are read into local variables in `bindings` instead, which switch `reader` to an
+/// `OptionalTail` at `tail_start`, and the expression is the variable.
+fn field_value(
+    index: usize,
+    field: &Field,
+    tail_start: Option<usize>,
+    cratename: &Path,
+    container_attrs: &ContainerAttrs,
+    where_clause: &mut WhereClause,
+    bindings: &mut TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let value = if is_skipped(field)? {
+        skipped_field(field, where_clause)
+    } else {
+        deserialize_field(field, cratename, container_attrs, where_clause)?
+    };
+    let tail_start = match tail_start {
+        Some(tail_start) => tail_start,
+        None => return Ok(value),
+    };
+    if index == tail_start {
+        bindings.extend(quote! {
+            let mut optional_tail = #cratename::de::OptionalTail::new(reader);
+            let reader = &mut optional_tail;
+        });
+    }
+    let value = if index >= tail_start && !is_skipped(field)? {
+        optional_tail_field(field, value, where_clause)?
+    } else {
+        value
+    };
+    let variable = format_ident!("field_{}", index);
+    bindings.extend(quote! {
+        let #variable = #value;
+    });
+    Ok(quote! { #variable })
+}
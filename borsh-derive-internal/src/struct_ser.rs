@@ -1,12 +1,14 @@
-use core::convert::TryFrom;
-
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{Fields, Ident, Index, ItemStruct, WhereClause};
+use syn::{Fields, Index, ItemStruct, WhereClause};
 
-use crate::attribute_helpers::contains_skip;
+use crate::attribute_helpers::{is_skipped, optional_tail_start, ContainerAttrs};
+use crate::config::DeriveConfig;
+use crate::context::{field_index, serialize_field};
 
-pub fn struct_ser(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStream2> {
+pub fn struct_ser(input: &ItemStruct, config: &DeriveConfig) -> syn::Result<TokenStream2> {
+    let container_attrs = ContainerAttrs::parse_struct(&input.attrs)?;
+    let cratename = config.crate_path_for(container_attrs.crate_path.as_ref());
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let mut where_clause = where_clause.map_or_else(
@@ -16,42 +18,58 @@ pub fn struct_ser(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStre
         },
         Clone::clone,
     );
+    // The optional fields are always written, only their position is checked.
+    optional_tail_start(&input.fields)?;
     let mut body = TokenStream2::new();
     match &input.fields {
         Fields::Named(fields) => {
             for field in &fields.named {
-                if contains_skip(&field.attrs) {
+                if is_skipped(field)? {
                     continue;
                 }
                 let field_name = field.ident.as_ref().unwrap();
-                let delta = quote! {
-                    #cratename::BorshSerialize::serialize(&self.#field_name, writer)?;
-                };
+                let delta = serialize_field(
+                    field,
+                    quote! { &self.#field_name },
+                    &cratename,
+                    &container_attrs,
+                    &mut where_clause,
+                )?;
                 body.extend(delta);
-
-                let field_type = &field.ty;
-                where_clause.predicates.push(
-                    syn::parse2(quote! {
-                        #field_type: #cratename::ser::BorshSerialize
-                    })
-                    .unwrap(),
-                );
             }
         }
         Fields::Unnamed(fields) => {
-            for field_idx in 0..fields.unnamed.len() {
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                if is_skipped(field)? {
+                    continue;
+                }
                 let field_idx = Index {
-                    index: u32::try_from(field_idx).expect("up to 2^32 fields are supported"),
+                    index: field_index(field_idx, field)?,
                     span: Span::call_site(),
                 };
-                let delta = quote! {
-                    #cratename::BorshSerialize::serialize(&self.#field_idx, writer)?;
-                };
+                let delta = serialize_field(
+                    field,
+                    quote! { &self.#field_idx },
+                    &cratename,
+                    &container_attrs,
+                    &mut where_clause,
+                )?;
                 body.extend(delta);
             }
         }
         Fields::Unit => {}
     }
+    if let Some(context) = &container_attrs.ser_context {
+        return Ok(quote! {
+            impl #impl_generics #cratename::ser::BorshSerializeWith<#context> for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn serialize_with<W: #cratename::maybestd::io::Write>(&self, writer: &mut W, ctx: &#context) -> ::core::result::Result<(), #cratename::maybestd::io::Error> {
+                    #body
+                    Ok(())
+                }
+            }
+        });
+    }
     Ok(quote! {
         impl #impl_generics #cratename::ser::BorshSerialize for #name #ty_generics #where_clause {
             fn serialize<W: #cratename::maybestd::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), #cratename::maybestd::io::Error> {
@@ -81,7 +99,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = struct_ser(&item_struct, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = struct_ser(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::ser::BorshSerialize for A
             where
@@ -107,7 +125,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = struct_ser(&item_struct, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = struct_ser(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<K, V> borsh::ser::BorshSerialize for A<K, V>
             where
@@ -133,7 +151,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = struct_ser(&item_struct, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = struct_ser(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<K: Key, V> borsh::ser::BorshSerialize for A<K, V>
             where
@@ -150,4 +168,67 @@ mod tests {
         };
         assert_eq(expected, actual);
     }
+
+    #[test]
+    fn default_generics() {
+        let item_struct: ItemStruct = syn::parse2(quote!{
+            struct A<K, V = u8, const N: usize = 32> {
+                x: HashMap<K, V>,
+                y: [u8; N],
+            }
+        }).unwrap();
+
+        let actual = struct_ser(&item_struct, &DeriveConfig::default()).unwrap();
+        let expected = quote!{
+            impl<K, V, const N: usize> borsh::ser::BorshSerialize for A<K, V, N>
+            where
+                HashMap<K, V>: borsh::ser::BorshSerialize,
+                [u8; N]: borsh::ser::BorshSerialize
+            {
+                fn serialize<W: borsh::maybestd::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), borsh::maybestd::io::Error> {
+                    borsh::BorshSerialize::serialize(&self.x, writer)?;
+                    borsh::BorshSerialize::serialize(&self.y, writer)?;
+                    Ok(())
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
+    #[test]
+    fn crate_path() {
+        let item_struct: ItemStruct = syn::parse2(quote!{
+            struct A {
+                x: u64,
+            }
+        }).unwrap();
+
+        let config = DeriveConfig::new(syn::parse_quote!(near_sdk::borsh));
+        let actual = struct_ser(&item_struct, &config).unwrap();
+        let expected = quote!{
+            impl near_sdk::borsh::ser::BorshSerialize for A
+            where
+                u64: near_sdk::borsh::ser::BorshSerialize
+            {
+                fn serialize<W: near_sdk::borsh::maybestd::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), near_sdk::borsh::maybestd::io::Error> {
+                    near_sdk::borsh::BorshSerialize::serialize(&self.x, writer)?;
+                    Ok(())
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
+    #[test]
+    fn crate_attribute_overrides_config() {
+        let item_struct: ItemStruct = syn::parse2(quote!{
+            #[borsh(crate = "::reexport::borsh")]
+            struct A(u8);
+        }).unwrap();
+
+        let config = DeriveConfig::new(syn::parse_quote!(near_sdk::borsh));
+        let actual = struct_ser(&item_struct, &config).unwrap().to_string();
+        assert!(actual.starts_with("impl :: reexport :: borsh :: ser :: BorshSerialize for A"));
+        assert!(!actual.contains("near_sdk"));
+    }
 }
@@ -1,6 +1,11 @@
 use proc_macro2::TokenStream as TokenStream2;
-use syn::{Ident, ItemUnion};
+use syn::ItemUnion;
 
-pub fn union_de(_input: &ItemUnion, _cratename: Ident) -> syn::Result<TokenStream2> {
-    unimplemented!()
+use crate::config::DeriveConfig;
+
+pub fn union_de(input: &ItemUnion, _config: &DeriveConfig) -> syn::Result<TokenStream2> {
+    Err(syn::Error::new_spanned(
+        input.union_token,
+        "BorshDeserialize cannot be derived for unions, since the active field is not known",
+    ))
 }
@@ -1,6 +1,11 @@
 use proc_macro2::TokenStream as TokenStream2;
-use syn::{Ident, ItemUnion};
+use syn::ItemUnion;
 
-pub fn union_ser(_input: &ItemUnion, _cratename: Ident) -> syn::Result<TokenStream2> {
-    unimplemented!()
+use crate::config::DeriveConfig;
+
+pub fn union_ser(input: &ItemUnion, _config: &DeriveConfig) -> syn::Result<TokenStream2> {
+    Err(syn::Error::new_spanned(
+        input.union_token,
+        "BorshSerialize cannot be derived for unions, since the active field is not known",
+    ))
 }
@@ -9,19 +9,28 @@ use syn::{Ident, ItemEnum, ItemStruct, ItemUnion};
 use borsh_derive_internal::*;
 use borsh_schema_derive_internal::*;
 
-#[proc_macro_derive(BorshSerialize, attributes(borsh_skip))]
+/// The derives refer to the borsh crate by the name it has in the dependencies of the crate that
+/// uses them.
+fn derive_config() -> DeriveConfig {
+    DeriveConfig::new(
+        Ident::new(
+            &crate_name("borsh").unwrap_or_else(|_| "borsh".to_string()),
+            Span::call_site(),
+        )
+        .into(),
+    )
+}
+
+#[proc_macro_derive(BorshSerialize, attributes(borsh_skip, borsh))]
 pub fn borsh_serialize(input: TokenStream) -> TokenStream {
-    let cratename = Ident::new(
-        &crate_name("borsh").unwrap_or_else(|_| "borsh".to_string()),
-        Span::call_site(),
-    );
+    let config = derive_config();
 
     let res = if let Ok(input) = syn::parse::<ItemStruct>(input.clone()) {
-        struct_ser(&input, cratename)
+        struct_ser(&input, &config)
     } else if let Ok(input) = syn::parse::<ItemEnum>(input.clone()) {
-        enum_ser(&input, cratename)
+        enum_ser(&input, &config)
     } else if let Ok(input) = syn::parse::<ItemUnion>(input) {
-        union_ser(&input, cratename)
+        union_ser(&input, &config)
     } else {
         // Derive macros can only be defined on structs, enums, and unions.
         unreachable!()
@@ -32,19 +41,16 @@ pub fn borsh_serialize(input: TokenStream) -> TokenStream {
     })
 }
 
-#[proc_macro_derive(BorshDeserialize, attributes(borsh_skip, borsh_init))]
+#[proc_macro_derive(BorshDeserialize, attributes(borsh_skip, borsh_init, borsh))]
 pub fn borsh_deserialize(input: TokenStream) -> TokenStream {
-    let cratename = Ident::new(
-        &crate_name("borsh").unwrap_or_else(|_| "borsh".to_string()),
-        Span::call_site(),
-    );
+    let config = derive_config();
 
     let res = if let Ok(input) = syn::parse::<ItemStruct>(input.clone()) {
-        struct_de(&input, cratename)
+        struct_de(&input, &config)
     } else if let Ok(input) = syn::parse::<ItemEnum>(input.clone()) {
-        enum_de(&input, cratename)
+        enum_de(&input, &config)
     } else if let Ok(input) = syn::parse::<ItemUnion>(input) {
-        union_de(&input, cratename)
+        union_de(&input, &config)
     } else {
         // Derive macros can only be defined on structs, enums, and unions.
         unreachable!()
@@ -55,21 +61,18 @@ pub fn borsh_deserialize(input: TokenStream) -> TokenStream {
     })
 }
 
-#[proc_macro_derive(BorshSchema, attributes(borsh_skip))]
+#[proc_macro_derive(BorshSchema, attributes(borsh_skip, borsh))]
 pub fn borsh_schema(input: TokenStream) -> TokenStream {
-    let cratename = Ident::new(
-        &crate_name("borsh").unwrap_or_else(|_| "borsh".to_string()),
-        Span::call_site(),
-    );
+    let config = derive_config();
 
     let res = if let Ok(input) = syn::parse::<ItemStruct>(input.clone()) {
-        process_struct(&input, cratename)
+        process_struct(&input, &config)
     } else if let Ok(input) = syn::parse::<ItemEnum>(input.clone()) {
-        process_enum(&input, cratename)
-    } else if syn::parse::<ItemUnion>(input).is_ok() {
-        Err(syn::Error::new(
-            Span::call_site(),
-            "Borsh schema does not support unions yet.",
+        process_enum(&input, &config)
+    } else if let Ok(input) = syn::parse::<ItemUnion>(input) {
+        Err(syn::Error::new_spanned(
+            input.union_token,
+            "BorshSchema cannot be derived for unions, since the active field is not known",
         ))
     } else {
         // Derive macros can only be defined on structs, enums, and unions.
@@ -1,20 +1,76 @@
-use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens};
-use syn::{
-    parse_quote, AttrStyle, Attribute, Field, Fields, FieldsUnnamed, Ident, ItemEnum, ItemStruct,
-    Visibility,
+use syn::{parse_quote, Field, Fields, FieldsUnnamed, Ident, ItemEnum, ItemStruct, Visibility};
+
+use borsh_derive_internal::enum_discriminant_map::tag_map;
+
+use crate::helpers::{
+    declaration, quote_where_clause, schema_type_params, ContainerAttrs, DeriveConfig, VariantAttrs,
 };
 
-use crate::helpers::{declaration, quote_where_clause};
+/// The variants are described by structs named `<EnumName><VariantName>`, declared in the
+/// generated function. Fails if the fields refer to a type with one of these names, which the
+/// struct would shadow.
+fn check_variant_struct_names(input: &ItemEnum, name_str: &str) -> syn::Result<()> {
+    let names: Vec<(String, &Ident)> = input
+        .variants
+        .iter()
+        .map(|variant| (format!("{}{}", name_str, variant.ident), &variant.ident))
+        .collect();
+    for variant in &input.variants {
+        for field in &variant.fields {
+            if let Some((ident, (_, variant_ident))) =
+                find_ident(field.ty.to_token_stream(), &names)
+            {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "`{}` is the name of the schema struct of the variant `{}::{}`, rename the type or the variant",
+                        ident, name_str, variant_ident
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn find_ident<'a, 'b>(
+    tokens: TokenStream2,
+    names: &'a [(String, &'b Ident)],
+) -> Option<(Ident, &'a (String, &'b Ident))> {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                if let Some(name) = names.iter().find(|(name, _)| ident == name) {
+                    return Some((ident, name));
+                }
+            }
+            TokenTree::Group(group) => {
+                if let Some(found) = find_ident(group.stream(), names) {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
 
-pub fn process_enum(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStream2> {
+pub fn process_enum(input: &ItemEnum, config: &DeriveConfig) -> syn::Result<TokenStream2> {
+    let container_attrs = ContainerAttrs::parse_enum(&input.attrs)?;
+    let cratename = config.crate_path_for(container_attrs.crate_path.as_ref());
     let name = &input.ident;
     let name_str = name.to_token_stream().to_string();
     let generics = &input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     // Generate function that returns the name of the type.
-    let (declaration, where_clause_additions) =
-        declaration(&name_str, &input.generics, cratename.clone());
+    let type_params = schema_type_params(
+        generics,
+        input.variants.iter().flat_map(|variant| &variant.fields),
+        &container_attrs,
+    )?;
+    let (declaration, where_clause_additions) = declaration(&name_str, &type_params, &cratename);
 
     // Generate function that returns the schema for variants.
     // Definitions of the variants.
@@ -23,12 +79,28 @@ pub fn process_enum(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStre
     let mut anonymous_defs = TokenStream2::new();
     // Recursive calls to `add_definitions_recursively`.
     let mut add_recursive_defs = TokenStream2::new();
+    check_variant_struct_names(input, &name_str)?;
+    // The structs of the variants refer to the crate the same way as the enum.
+    let crate_path_str = cratename.to_token_stream().to_string();
     for variant in &input.variants {
         let variant_name_str = variant.ident.to_token_stream().to_string();
         let full_variant_name_str = format!("{}{}", name_str, variant_name_str);
         let full_variant_ident = Ident::new(full_variant_name_str.as_str(), Span::call_site());
+        let mut attrs = vec![parse_quote! { #[borsh(crate = #crate_path_str)] }];
+        // The structs of the variants are declared with the type parameters of the enum, which
+        // their own fields may not use.
+        if container_attrs.schema_skip_type_params {
+            attrs.push(parse_quote! { #[borsh(schema(skip_type_params))] });
+        } else if !type_params.is_empty() {
+            let type_params_str = type_params
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            attrs.push(parse_quote! { #[borsh(schema(type_params = #type_params_str))] });
+        }
         let mut anonymous_struct = ItemStruct {
-            attrs: vec![],
+            attrs,
             vis: Visibility::Inherited,
             struct_token: Default::default(),
             ident: full_variant_ident.clone(),
@@ -36,61 +108,48 @@ pub fn process_enum(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStre
             fields: variant.fields.clone(),
             semi_token: Some(Default::default()),
         };
-        let generic_params = generics
+        // The phantom data uses all lifetimes and type parameters, as the fields of a variant may
+        // not use them all. It is left out of the schema like any `PhantomData` field.
+        let lifetime_params =
+            generics
+                .lifetimes()
+                .fold(TokenStream2::new(), |acc, lifetime_def| {
+                    let lifetime = &lifetime_def.lifetime;
+                    quote! {
+                        #acc
+                        &#lifetime (),
+                    }
+                });
+        let phantom_params = generics
             .type_params()
-            .fold(TokenStream2::new(), |acc, generic| {
+            .fold(lifetime_params, |acc, generic| {
                 let ident = &generic.ident;
                 quote! {
                     #acc
                     #ident ,
                 }
             });
-        if !generic_params.is_empty() {
-            let attr = Attribute {
-                pound_token: Default::default(),
-                style: AttrStyle::Outer,
-                bracket_token: Default::default(),
-                path: parse_quote! {borsh_skip},
-                tokens: Default::default(),
+        if !phantom_params.is_empty() {
+            let phantom_field = Field {
+                attrs: vec![],
+                vis: Visibility::Inherited,
+                ident: Some(Ident::new("borsh_schema_phantom_data", Span::call_site())),
+                colon_token: None,
+                ty: parse_quote! {::core::marker::PhantomData<(#phantom_params)>},
             };
-            // Whether we should convert the struct from unit struct to regular struct.
-            let mut unit_to_regular = false;
-            match &mut anonymous_struct.fields {
-                Fields::Named(named) => {
-                    named.named.push(Field {
-                        attrs: vec![attr.clone()],
-                        vis: Visibility::Inherited,
-                        ident: Some(Ident::new("borsh_schema_phantom_data", Span::call_site())),
-                        colon_token: None,
-                        ty: parse_quote! {::core::marker::PhantomData<(#generic_params)>},
-                    });
-                }
-                Fields::Unnamed(unnamed) => {
-                    unnamed.unnamed.push(Field {
-                        attrs: vec![attr.clone()],
-                        vis: Visibility::Inherited,
-                        ident: None,
-                        colon_token: None,
-                        ty: parse_quote! {::core::marker::PhantomData<(#generic_params)>},
-                    });
-                }
-                Fields::Unit => {
-                    unit_to_regular = true;
-                }
-            }
-            if unit_to_regular {
-                let mut fields = FieldsUnnamed {
+            if let Fields::Unit = anonymous_struct.fields {
+                anonymous_struct.fields = Fields::Unnamed(FieldsUnnamed {
                     paren_token: Default::default(),
                     unnamed: Default::default(),
-                };
-                fields.unnamed.push(Field {
-                    attrs: vec![attr],
-                    vis: Visibility::Inherited,
-                    ident: None,
-                    colon_token: None,
-                    ty: parse_quote! {::core::marker::PhantomData<(#generic_params)>},
                 });
-                anonymous_struct.fields = Fields::Unnamed(fields);
+            }
+            match &mut anonymous_struct.fields {
+                Fields::Named(named) => named.named.push(phantom_field),
+                Fields::Unnamed(unnamed) => unnamed.unnamed.push(Field {
+                    ident: None,
+                    ..phantom_field
+                }),
+                Fields::Unit => unreachable!(),
             }
         }
         anonymous_defs.extend(quote! {
@@ -105,12 +164,42 @@ pub fn process_enum(input: &ItemEnum, cratename: Ident) -> syn::Result<TokenStre
         });
     }
 
+    let definition = if container_attrs.named_variants {
+        quote! {
+            let definition = #cratename::schema::Definition::NamedEnum{variants};
+        }
+    } else if input.variants.iter().any(|variant| {
+        variant.discriminant.is_some()
+            || matches!(
+                VariantAttrs::parse(&variant.attrs),
+                Ok(VariantAttrs { tag: Some(_) })
+            )
+    }) {
+        // The `#[borsh(tag = ...)]` of the variants, or their discriminant expressions evaluated
+        // by the compiler, like for serialization.
+        let discriminants = tag_map(input, &container_attrs)?;
+        let tags = input
+            .variants
+            .iter()
+            .map(|variant| discriminants[&variant.ident].clone());
+        let len = input.variants.len();
+        quote! {
+            let tags: [u8; #len] = [#(#tags),*];
+            let definition = #cratename::schema::Definition::enum_with_tags(
+                tags.iter().zip(variants).map(|(tag, (name, declaration))| (*tag, name, declaration)).collect()
+            );
+        }
+    } else {
+        quote! {
+            let definition = #cratename::schema::Definition::Enum{variants};
+        }
+    };
     let type_definitions = quote! {
         fn add_definitions_recursively(definitions: &mut #cratename::maybestd::collections::HashMap<#cratename::schema::Declaration, #cratename::schema::Definition>) {
             #anonymous_defs
             #add_recursive_defs
             let variants = #cratename::maybestd::vec![#(#variants_defs),*];
-            let definition = #cratename::schema::Definition::Enum{variants};
+            #definition
             Self::add_definition(Self::declaration(), definition, definitions);
         }
     };
@@ -144,7 +233,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = process_enum(&item_enum, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = process_enum(&item_enum, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A {
                 fn declaration() -> borsh::schema::Declaration {
@@ -157,8 +246,10 @@ mod tests {
                     >
                 ) {
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct ABacon;
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct AEggs;
                     <ABacon as borsh::BorshSchema>::add_definitions_recursively(definitions);
                     <AEggs as borsh::BorshSchema>::add_definitions_recursively(definitions);
@@ -182,7 +273,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = process_enum(&item_enum, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = process_enum(&item_enum, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A {
                 fn declaration() -> borsh::schema::Declaration {
@@ -195,6 +286,7 @@ mod tests {
                     >
                 ) {
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct ABacon;
                     <ABacon as borsh::BorshSchema>::add_definitions_recursively(definitions);
                     let variants = borsh::maybestd::vec![("Bacon".to_string(), <ABacon>::declaration())];
@@ -217,7 +309,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = process_enum(&item_enum, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = process_enum(&item_enum, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A {
                 fn declaration() -> borsh::schema::Declaration {
@@ -230,12 +322,16 @@ mod tests {
                     >
                 ) {
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct ABacon;
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct AEggs;
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct ASalad(Tomatoes, Cucumber, Oil);
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
                     struct ASausage {
                         wrapper: Wrapper,
                         filling: Filling
@@ -269,7 +365,7 @@ mod tests {
             }
         }).unwrap();
 
-        let actual = process_enum(&item_enum, Ident::new("borsh", Span::call_site())).unwrap();
+        let actual = process_enum(&item_enum, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<C, W> borsh::BorshSchema for A<C, W>
             where
@@ -287,21 +383,28 @@ mod tests {
                     >
                 ) {
                     #[derive(borsh :: BorshSchema)]
-                    struct ABacon<C, W>(#[borsh_skip] ::core::marker::PhantomData<(C, W, )>);
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "C, W"))]
+                    struct ABacon<C, W>(::core::marker::PhantomData<(C, W, )>);
                     #[derive(borsh :: BorshSchema)]
-                    struct AEggs<C, W>(#[borsh_skip] ::core::marker::PhantomData<(C, W, )>);
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "C, W"))]
+                    struct AEggs<C, W>(::core::marker::PhantomData<(C, W, )>);
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "C, W"))]
                     struct ASalad<C, W>(
                         Tomatoes,
                         C,
                         Oil,
-                        #[borsh_skip] ::core::marker::PhantomData<(C, W, )>
+                        ::core::marker::PhantomData<(C, W, )>
                     );
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "C, W"))]
                     struct ASausage<C, W> {
                         wrapper: W,
                         filling: Filling,
-                        #[borsh_skip]
                         borsh_schema_phantom_data: ::core::marker::PhantomData<(C, W, )>
                     }
                     <ABacon<C, W> as borsh::BorshSchema>::add_definitions_recursively(definitions);
@@ -338,7 +441,7 @@ mod tests {
 
         let actual = process_enum(
             &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
+            &DeriveConfig::default(),
         )
         .unwrap();
         let expected = quote!{
@@ -360,20 +463,24 @@ mod tests {
                     >
                 ) {
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "A, B"))]
                     struct SideLeft<A, B>
                     (
                         A, 
-                        #[borsh_skip] ::core::marker::PhantomData<(A, B, )>
+                        ::core::marker::PhantomData<(A, B, )>
                     )
                     where 
                         A: Display + Debug, 
                         B: Display + Debug,
                     ;
                     #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "A, B"))]
                     struct SideRight<A, B>
                     (
                         B, 
-                        #[borsh_skip] ::core::marker::PhantomData<(A, B, )>
+                        ::core::marker::PhantomData<(A, B, )>
                     )
                     where 
                         A: Display + Debug, 
@@ -392,4 +499,121 @@ mod tests {
         };
         assert_eq(expected, actual);
     }
+
+    #[test]
+    fn skipped_type_params() {
+        let item_enum: ItemEnum = syn::parse2(quote!{
+            enum E<T, U> {
+                A(u64, #[borsh_skip] T),
+                B { value: U },
+            }
+        })
+        .unwrap();
+
+        let actual = process_enum(&item_enum, &DeriveConfig::default()).unwrap();
+        let expected = quote!{
+            impl<T, U> borsh::BorshSchema for E<T, U>
+            where
+                U: borsh::BorshSchema
+            {
+                fn declaration() -> borsh::schema::Declaration {
+                    let params = borsh::maybestd::vec![<U>::declaration()];
+                    format!(r#"{}<{}>"#, "E", params.join(", "))
+                }
+                fn add_definitions_recursively(
+                    definitions: &mut borsh::maybestd::collections::HashMap<
+                        borsh::schema::Declaration,
+                        borsh::schema::Definition
+                    >
+                ) {
+                    #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "U"))]
+                    struct EA<T, U>(
+                        u64,
+                        #[borsh_skip] T,
+                        ::core::marker::PhantomData<(T, U, )>
+                    );
+                    #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    #[borsh(schema(type_params = "U"))]
+                    struct EB<T, U> {
+                        value: U,
+                        borsh_schema_phantom_data: ::core::marker::PhantomData<(T, U, )>
+                    }
+                    <EA<T, U> as borsh::BorshSchema>::add_definitions_recursively(definitions);
+                    <EB<T, U> as borsh::BorshSchema>::add_definitions_recursively(definitions);
+                    let variants = borsh::maybestd::vec![
+                        ("A".to_string(), <EA<T, U> >::declaration()),
+                        ("B".to_string(), <EB<T, U> >::declaration())
+                    ];
+                    let definition = borsh::schema::Definition::Enum { variants };
+                    Self::add_definition(Self::declaration(), definition, definitions);
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
+    #[test]
+    fn lifetimes() {
+        let item_enum: ItemEnum = syn::parse2(quote!{
+            enum A<'a> {
+                Bacon(&'a str),
+                Eggs
+            }
+        }).unwrap();
+
+        let actual = process_enum(&item_enum, &DeriveConfig::default()).unwrap();
+        let expected = quote!{
+            impl<'a> borsh::BorshSchema for A<'a> {
+                fn declaration() -> borsh::schema::Declaration {
+                    "A".to_string()
+                }
+                fn add_definitions_recursively(
+                    definitions: &mut borsh::maybestd::collections::HashMap<
+                        borsh::schema::Declaration,
+                        borsh::schema::Definition
+                    >
+                ) {
+                    #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    struct ABacon<'a>(
+                        &'a str,
+                        ::core::marker::PhantomData<(&'a (), )>
+                    );
+                    #[derive(borsh :: BorshSchema)]
+                    #[borsh(crate = "borsh")]
+                    struct AEggs<'a>(
+                        ::core::marker::PhantomData<(&'a (), )>
+                    );
+                    <ABacon<'a> as borsh::BorshSchema>::add_definitions_recursively(definitions);
+                    <AEggs<'a> as borsh::BorshSchema>::add_definitions_recursively(definitions);
+                    let variants = borsh::maybestd::vec![
+                        ("Bacon".to_string(), <ABacon<'a> >::declaration()),
+                        ("Eggs".to_string(), <AEggs<'a> >::declaration())
+                    ];
+                    let definition = borsh::schema::Definition::Enum { variants };
+                    Self::add_definition(Self::declaration(), definition, definitions);
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
+    #[test]
+    fn variant_struct_shadowing_a_field_type() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            enum A {
+                Bacon(Vec<ABacon>),
+                Eggs
+            }
+        }).unwrap();
+
+        let err = process_enum(&item_enum, &DeriveConfig::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`ABacon` is the name of the schema struct of the variant `A::Bacon`, rename the type or the variant"
+        );
+    }
 }
@@ -1,28 +1,60 @@
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens};
-use syn::{Attribute, Generics, Ident, Meta, WhereClause};
+use syn::{Field, Generics, Ident, Path, WhereClause};
 
-pub fn contains_skip(attrs: &[Attribute]) -> bool {
-    for attr in attrs.iter() {
-        if let Ok(Meta::Path(path)) = attr.parse_meta() {
-            if path.to_token_stream().to_string().as_str() == "borsh_skip" {
-                return true;
-            }
+pub use borsh_derive_internal::attribute_helpers::{
+    is_skipped, ContainerAttrs, FieldAttrs, VariantAttrs,
+};
+pub use borsh_derive_internal::DeriveConfig;
+
+/// Returns the type parameters that the schema depends on: the ones that appear in the type of
+/// a field without `#[borsh(skip)]` that is not `PhantomData`. A parameter used only by skipped
+/// fields is left out of the declaration and does not get a `BorshSchema` bound. With
+/// `skip_type_params`, none are, and with `type_params`, the listed ones are.
+pub fn schema_type_params<'a>(
+    generics: &Generics,
+    fields: impl IntoIterator<Item = &'a Field>,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<Vec<Ident>> {
+    if container_attrs.schema_skip_type_params {
+        return Ok(vec![]);
+    }
+    if let Some(type_params) = &container_attrs.schema_type_params {
+        return Ok(type_params.clone());
+    }
+    let mut used = vec![];
+    for field in fields {
+        if !is_skipped(field)? {
+            used.push(field.ty.to_token_stream());
         }
     }
-    false
+    Ok(generics
+        .type_params()
+        .map(|type_param| &type_param.ident)
+        .filter(|ident| used.iter().any(|ty| mentions(ty.clone(), ident)))
+        .cloned()
+        .collect())
+}
+
+fn mentions(tokens: TokenStream2, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(token) => token == *ident,
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
 }
 
+/// Returns the body of `declaration()` and the bounds it needs, for the `type_params` returned by
+/// [`schema_type_params`].
 pub fn declaration(
     ident_str: &str,
-    generics: &Generics,
-    cratename: Ident,
+    type_params: &[Ident],
+    cratename: &Path,
 ) -> (TokenStream2, Vec<TokenStream2>) {
     // Generate function that returns the name of the type.
     let mut declaration_params = vec![];
     let mut where_clause = vec![];
-    for type_param in generics.type_params() {
-        let type_param_name = &type_param.ident;
+    for type_param_name in type_params {
         declaration_params.push(quote! {
             <#type_param_name>::declaration()
         });
@@ -60,3 +92,38 @@ pub fn quote_where_clause(
         quote! { where #(#additions),*}
     }
 }
+
+/// Returns the declaration of a field, the call that adds its definitions, and the bound on its
+/// type, if any, following `#[borsh(schema(...))]` and `#[borsh(big_endian)]`.
+pub fn field_schema(
+    field: &Field,
+    cratename: &Path,
+) -> syn::Result<(TokenStream2, TokenStream2, Option<TokenStream2>)> {
+    let attrs = FieldAttrs::parse(&field.attrs)?;
+    let field_type = &field.ty;
+    Ok(if let Some(with) = attrs.schema_with {
+        (
+            quote! { #with::declaration() },
+            quote! { #with::add_definitions_recursively(definitions); },
+            None,
+        )
+    } else if let Some(declaration) = attrs.schema_declaration {
+        (
+            quote! { #declaration.to_string() },
+            TokenStream2::new(),
+            None,
+        )
+    } else if attrs.big_endian {
+        (
+            quote! { <#field_type as #cratename::big_endian::BigEndian>::big_endian_declaration() },
+            quote! { <#field_type as #cratename::big_endian::BigEndian>::add_big_endian_definitions(definitions); },
+            Some(quote! { #field_type: #cratename::big_endian::BigEndian }),
+        )
+    } else {
+        (
+            quote! { <#field_type as #cratename::BorshSchema>::declaration() },
+            quote! { <#field_type as #cratename::BorshSchema>::add_definitions_recursively(definitions); },
+            Some(quote! { #field_type: #cratename::BorshSchema }),
+        )
+    })
+}
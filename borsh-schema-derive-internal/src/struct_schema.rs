@@ -1,17 +1,23 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens};
-use syn::{Fields, Ident, ItemStruct};
+use syn::{Fields, ItemStruct};
 
-use crate::helpers::{contains_skip, declaration, quote_where_clause};
+use crate::helpers::{
+    declaration, field_schema, is_skipped, quote_where_clause, schema_type_params, ContainerAttrs,
+    DeriveConfig,
+};
 
-pub fn process_struct(input: &ItemStruct, cratename: Ident) -> syn::Result<TokenStream2> {
+pub fn process_struct(input: &ItemStruct, config: &DeriveConfig) -> syn::Result<TokenStream2> {
+    let container_attrs = ContainerAttrs::parse_struct(&input.attrs)?;
+    let cratename = config.crate_path_for(container_attrs.crate_path.as_ref());
     let name = &input.ident;
     let name_str = name.to_token_stream().to_string();
     let generics = &input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     // Generate function that returns the name of the type.
+    let type_params = schema_type_params(generics, &input.fields, &container_attrs)?;
     let (declaration, mut where_clause_additions) =
-        declaration(&name_str, &input.generics, cratename.clone());
+        declaration(&name_str, &type_params, &cratename);
 
     // Generate function that returns the schema of required types.
     let mut fields_vec = vec![];
@@ -20,20 +26,16 @@ pub fn process_struct(input: &ItemStruct, cratename: Ident) -> syn::Result<Token
     match &input.fields {
         Fields::Named(fields) => {
             for field in &fields.named {
-                if contains_skip(&field.attrs) {
+                if is_skipped(field)? {
                     continue;
                 }
                 let field_name = field.ident.as_ref().unwrap().to_token_stream().to_string();
-                let field_type = &field.ty;
+                let (field_declaration, add_definitions, bound) = field_schema(field, &cratename)?;
                 fields_vec.push(quote! {
-                    (#field_name.to_string(), <#field_type as #cratename::BorshSchema>::declaration())
-                });
-                add_definitions_recursively_rec.extend(quote! {
-                    <#field_type as #cratename::BorshSchema>::add_definitions_recursively(definitions);
-                });
-                where_clause_additions.push(quote! {
-                    #field_type: #cratename::BorshSchema
+                    (#field_name.to_string(), #field_declaration)
                 });
+                add_definitions_recursively_rec.extend(add_definitions);
+                where_clause_additions.extend(bound);
             }
             if !fields_vec.is_empty() {
                 struct_fields = quote! {
@@ -43,19 +45,13 @@ pub fn process_struct(input: &ItemStruct, cratename: Ident) -> syn::Result<Token
         }
         Fields::Unnamed(fields) => {
             for field in &fields.unnamed {
-                if contains_skip(&field.attrs) {
+                if is_skipped(field)? {
                     continue;
                 }
-                let field_type = &field.ty;
-                fields_vec.push(quote! {
-                    <#field_type as #cratename::BorshSchema>::declaration()
-                });
-                add_definitions_recursively_rec.extend(quote! {
-                    <#field_type as #cratename::BorshSchema>::add_definitions_recursively(definitions);
-                });
-                where_clause_additions.push(quote! {
-                    #field_type: #cratename::BorshSchema
-                });
+                let (field_declaration, add_definitions, bound) = field_schema(field, &cratename)?;
+                fields_vec.push(field_declaration);
+                add_definitions_recursively_rec.extend(add_definitions);
+                where_clause_additions.extend(bound);
             }
             if !fields_vec.is_empty() {
                 struct_fields = quote! {
@@ -108,11 +104,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A
             {
@@ -136,11 +128,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<T> borsh::BorshSchema for A<T>
             where
@@ -174,11 +162,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A
             where
@@ -215,11 +199,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<K, V> borsh::BorshSchema for A<K, V>
             where
@@ -260,11 +240,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A
             where
@@ -294,6 +270,44 @@ mod tests {
         assert_eq(expected, actual);
     }
 
+    #[test]
+    fn schema_field_overrides() {
+        let item_struct: ItemStruct = syn::parse2(quote!{
+            struct A {
+                #[borsh(with = "millis", schema(with = "millis"))]
+                x: Duration,
+                #[borsh(schema(declaration = "Vec<u8>"))]
+                y: Bytes,
+            }
+        })
+        .unwrap();
+
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
+        let expected = quote!{
+            impl borsh::BorshSchema for A
+            {
+                fn declaration() -> borsh::schema::Declaration {
+                    "A".to_string()
+                }
+                fn add_definitions_recursively(
+                    definitions: &mut borsh::maybestd::collections::HashMap<
+                        borsh::schema::Declaration,
+                        borsh::schema::Definition
+                    >
+                ) {
+                    let fields = borsh::schema::Fields::NamedFields(borsh::maybestd::vec![
+                        ("x".to_string(), millis::declaration()),
+                        ("y".to_string(), "Vec<u8>".to_string())
+                    ]);
+                    let definition = borsh::schema::Definition::Struct { fields };
+                    Self::add_definition(Self::declaration(), definition, definitions);
+                    millis::add_definitions_recursively(definitions);
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
     #[test]
     fn simple_generics() {
         let item_struct: ItemStruct = syn::parse2(quote!{
@@ -304,11 +318,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<K, V> borsh::BorshSchema for A<K, V>
             where
@@ -341,6 +351,74 @@ mod tests {
         assert_eq(expected, actual);
     }
 
+    #[test]
+    fn phantom_data_field() {
+        let item_struct: ItemStruct = syn::parse2(quote!{
+            struct Unit<T>(PhantomData<T>);
+        })
+        .unwrap();
+
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
+        let expected = quote!{
+            impl<T> borsh::BorshSchema for Unit<T>
+            {
+                fn declaration() -> borsh::schema::Declaration {
+                    "Unit".to_string()
+                }
+                fn add_definitions_recursively(
+                    definitions: &mut borsh::maybestd::collections::HashMap<
+                        borsh::schema::Declaration,
+                        borsh::schema::Definition
+                    >
+                ) {
+                    let fields = borsh::schema::Fields::Empty;
+                    let definition = borsh::schema::Definition::Struct { fields };
+                    Self::add_definition(Self::declaration(), definition, definitions);
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
+    #[test]
+    fn default_generics() {
+        let item_struct: ItemStruct = syn::parse2(quote!{
+            struct A<T = u64, const N: usize = 32>(T, [u8; N]);
+        })
+        .unwrap();
+
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
+        let expected = quote!{
+            impl<T, const N: usize> borsh::BorshSchema for A<T, N>
+            where
+                T: borsh::BorshSchema,
+                T: borsh::BorshSchema,
+                [u8; N]: borsh::BorshSchema
+            {
+                fn declaration() -> borsh::schema::Declaration {
+                    let params = borsh::maybestd::vec![<T>::declaration()];
+                    format!(r#"{}<{}>"#, "A", params.join(", "))
+                }
+                fn add_definitions_recursively(
+                    definitions: &mut borsh::maybestd::collections::HashMap<
+                        borsh::schema::Declaration,
+                        borsh::schema::Definition
+                    >
+                ) {
+                    let fields = borsh::schema::Fields::UnnamedFields(borsh::maybestd::vec![
+                        <T as borsh::BorshSchema>::declaration(),
+                        <[u8; N] as borsh::BorshSchema>::declaration()
+                    ]);
+                    let definition = borsh::schema::Definition::Struct { fields };
+                    Self::add_definition(Self::declaration(), definition, definitions);
+                    <T as borsh::BorshSchema>::add_definitions_recursively(definitions);
+                    <[u8; N] as borsh::BorshSchema>::add_definitions_recursively(definitions);
+                }
+            }
+        };
+        assert_eq(expected, actual);
+    }
+
     #[test]
     fn trailing_comma_generics() {
         let item_struct: ItemStruct = syn::parse2(quote!{
@@ -354,11 +432,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl<K, V> borsh::BorshSchema for A<K, V>
             where
@@ -399,11 +473,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A {
                 fn declaration() -> borsh::schema::Declaration {
@@ -431,11 +501,7 @@ mod tests {
         })
         .unwrap();
 
-        let actual = process_struct(
-            &item_struct,
-            Ident::new("borsh", proc_macro2::Span::call_site()),
-        )
-        .unwrap();
+        let actual = process_struct(&item_struct, &DeriveConfig::default()).unwrap();
         let expected = quote!{
             impl borsh::BorshSchema for A
             where
@@ -0,0 +1,91 @@
+//! Big-endian integers, for fields shared with a wire format that is not Borsh.
+//!
+//! Borsh writes integers in little-endian order. Fields with `#[borsh(big_endian)]` use
+//! [`BigEndian`] instead, which writes the same number of bytes, most significant first:
+//!
+//! ```
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+//! struct Header {
+//!     #[borsh(big_endian)]
+//!     magic: u32,
+//!     length: u32,
+//! }
+//!
+//! let header = Header { magic: 0xcafe_f00d, length: 1 };
+//! let data = header.try_to_vec().unwrap();
+//! assert_eq!(data, [0xca, 0xfe, 0xf0, 0x0d, 1, 0, 0, 0]);
+//! assert_eq!(Header::try_from_slice(&data).unwrap(), header);
+//! ```
+//!
+//! The schema declares such a field as `BigEndian<u32>`, an array of 4 `u8`, so that readers of
+//! the schema see the byte order and decode the bytes as they are.
+use core::mem::size_of;
+
+use crate::maybestd::{
+    collections::HashMap,
+    io::{Read, Result, Write},
+    string::ToString,
+};
+use crate::schema::{declarations, Declaration, Definition};
+use crate::BorshSchema;
+
+/// (De)serialization of integers in big-endian order, used by fields with `#[borsh(big_endian)]`.
+pub trait BigEndian: Sized {
+    fn serialize_big_endian<W: Write>(&self, writer: &mut W) -> Result<()>;
+
+    fn deserialize_big_endian<R: Read>(reader: &mut R) -> Result<Self>;
+
+    /// The declaration of the big-endian encoding in the schema, e.g. `BigEndian<u32>`.
+    fn big_endian_declaration() -> Declaration;
+
+    /// Adds the definition of [`big_endian_declaration`](Self::big_endian_declaration), an array
+    /// of `u8` as long as the integer.
+    fn add_big_endian_definitions(definitions: &mut HashMap<Declaration, Definition>);
+}
+
+macro_rules! impl_big_endian {
+    ($type: ident) => {
+        impl BigEndian for $type {
+            #[inline]
+            fn serialize_big_endian<W: Write>(&self, writer: &mut W) -> Result<()> {
+                writer.write_all(&self.to_be_bytes())
+            }
+
+            #[inline]
+            fn deserialize_big_endian<R: Read>(reader: &mut R) -> Result<Self> {
+                let mut buf = [0u8; size_of::<$type>()];
+                reader
+                    .read_exact(&mut buf)
+                    .map_err(crate::de::unexpected_eof_to_unexpected_length_of_input)?;
+                Ok($type::from_be_bytes(buf))
+            }
+
+            fn big_endian_declaration() -> Declaration {
+                concat!("BigEndian<", stringify!($type), ">").to_string()
+            }
+
+            fn add_big_endian_definitions(definitions: &mut HashMap<Declaration, Definition>) {
+                let definition = Definition::Array {
+                    length: size_of::<$type>() as u32,
+                    elements: declarations::U8.to_string(),
+                };
+                <$type as BorshSchema>::add_definition(
+                    Self::big_endian_declaration(),
+                    definition,
+                    definitions,
+                );
+            }
+        }
+    };
+}
+
+impl_big_endian!(u16);
+impl_big_endian!(u32);
+impl_big_endian!(u64);
+impl_big_endian!(u128);
+impl_big_endian!(i16);
+impl_big_endian!(i32);
+impl_big_endian!(i64);
+impl_big_endian!(i128);
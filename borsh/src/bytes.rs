@@ -0,0 +1,112 @@
+//! Fixed-size byte arrays such as hashes and keys.
+//!
+//! ```
+//! use borsh::bytes::FixedBytes;
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! let hash = FixedBytes::from([0xab; 32]);
+//! let data = hash.try_to_vec().unwrap();
+//! assert_eq!(data, [0xab; 32]);
+//! assert_eq!(FixedBytes::<32>::try_from_slice(&data).unwrap(), hash);
+//! ```
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::maybestd::{
+    collections::HashMap,
+    io::{Read, Result, Write},
+};
+use crate::schema::{Declaration, Definition};
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// `N` bytes serialized as they are, without a length prefix, and declared as `Array<u8, N>` like
+/// `[u8; N]`. Its `Debug` output is in hex, and `{:x}` formats the bytes as a hex string.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedBytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> FixedBytes<N> {
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn into_inner(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for FixedBytes<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for FixedBytes<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<FixedBytes<N>> for [u8; N] {
+    fn from(bytes: FixedBytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> Deref for FixedBytes<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for FixedBytes<N> {
+    fn deref_mut(&mut self) -> &mut [u8; N] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::LowerHex for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FixedBytes({:x})", self)
+    }
+}
+
+impl<const N: usize> BorshSerialize for FixedBytes<N> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+impl<const N: usize> BorshDeserialize for FixedBytes<N> {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self(<[u8; N]>::deserialize_reader(reader)?))
+    }
+}
+
+impl<const N: usize> BorshSchema for FixedBytes<N> {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        <[u8; N]>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        <[u8; N]>::declaration()
+    }
+}
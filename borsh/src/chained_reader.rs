@@ -0,0 +1,104 @@
+//! Reading a value from several buffers without concatenating them.
+use crate::maybestd::io::{Read, Result};
+
+/// A reader over a sequence of byte chunks, read one after another as if they were a single
+/// buffer. A value may start in one chunk and end in another, since the built-in
+/// `BorshDeserialize` implementations keep reading until they have all the bytes they need.
+///
+/// ```
+/// use borsh::{BorshDeserialize, ChainedReader};
+///
+/// let chunks: [&[u8]; 3] = [&[1, 0, 0], &[0, 2], &[0]];
+/// let mut reader = ChainedReader::new(chunks);
+/// let value = <(u32, u16)>::deserialize_reader(&mut reader).unwrap();
+/// assert_eq!(value, (1, 2));
+/// ```
+///
+/// With `std`, `std::io::Read::chain` does the same for a fixed number of readers.
+pub struct ChainedReader<I: Iterator> {
+    chunks: I,
+    current: Option<I::Item>,
+    position: usize,
+}
+
+impl<I> ChainedReader<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    pub fn new<C: IntoIterator<IntoIter = I>>(chunks: C) -> Self {
+        Self {
+            chunks: chunks.into_iter(),
+            current: None,
+            position: 0,
+        }
+    }
+}
+
+impl<I> Read for ChainedReader<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if let Some(chunk) = &self.current {
+                let remaining = &chunk.as_ref()[self.position..];
+                if !remaining.is_empty() {
+                    let len = remaining.len().min(buf.len());
+                    buf[..len].copy_from_slice(&remaining[..len]);
+                    self.position += len;
+                    return Ok(len);
+                }
+            }
+            // The current chunk is exhausted, continue with the next non-empty one.
+            match self.chunks.next() {
+                Some(chunk) => {
+                    self.current = Some(chunk);
+                    self.position = 0;
+                }
+                None => {
+                    self.current = None;
+                    return Ok(0);
+                }
+            }
+        }
+    }
+}
+
+/// A reader over a [`bytes::Buf`], which consumes the bytes it reads from the buffer.
+///
+/// The chunks of the buffer are read one after another, like with [`ChainedReader`], so this
+/// works with `Buf`s that are not contiguous, such as chains of `Bytes`.
+#[cfg(feature = "bytes")]
+pub struct BytesReader<'a, B: bytes::Buf>(pub &'a mut B);
+
+#[cfg(feature = "bytes")]
+impl<B: bytes::Buf> Read for BytesReader<'_, B> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let chunk = self.0.chunk();
+        let len = chunk.len().min(buf.len());
+        buf[..len].copy_from_slice(&chunk[..len]);
+        self.0.advance(len);
+        Ok(len)
+    }
+}
+
+/// Deserializes a `T` from the beginning of `buf` and advances `buf` past it, without copying
+/// the chunks of `buf` into a contiguous buffer first.
+///
+/// ```
+/// use bytes::{Buf, Bytes};
+///
+/// let mut buf = Bytes::from_static(&[1, 0]).chain(Bytes::from_static(&[0, 0, 2]));
+/// let value: (u32, u8) = borsh::from_buf(&mut buf).unwrap();
+/// assert_eq!(value, (1, 2));
+/// assert!(!buf.has_remaining());
+/// ```
+#[cfg(feature = "bytes")]
+pub fn from_buf<T: crate::BorshDeserialize, B: bytes::Buf>(buf: &mut B) -> Result<T> {
+    T::deserialize_reader(&mut BytesReader(buf))
+}
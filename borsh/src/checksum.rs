@@ -0,0 +1,119 @@
+//! A CRC32 checksum after the serialized bytes, to detect data corrupted at rest.
+//!
+//! ```
+//! let data = borsh::serialize_with_checksum(&("blob", 7u64)).unwrap();
+//! let value: (String, u64) = borsh::deserialize_with_checksum(&data).unwrap();
+//! assert_eq!(value, ("blob".to_string(), 7));
+//!
+//! let mut corrupted = data.clone();
+//! corrupted[0] ^= 1;
+//! assert!(borsh::deserialize_with_checksum::<(String, u64)>(&corrupted).is_err());
+//! ```
+//!
+//! The checksum is the CRC32 (IEEE) of the payload, as a little-endian `u32`. It catches
+//! accidental changes of the bytes, not deliberate ones.
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use crate::maybestd::format;
+use crate::maybestd::{
+    io::{Error, ErrorKind, Result},
+    vec::Vec,
+};
+use crate::{BorshDeserialize, BorshSerialize};
+
+const CHECKSUM_LEN: usize = 4;
+
+const ERROR_MISSING_CHECKSUM: &str = "Input is shorter than a checksum";
+
+/// The reflected IEEE polynomial, as used by zlib, PNG and Ethernet.
+const POLYNOMIAL: u32 = 0xedb8_8320;
+
+const TABLE: [u32; 256] = table();
+
+const fn table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// The CRC32 (IEEE) of `data`.
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        TABLE[((crc ^ u32::from(*byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// The checksum at the end of the input does not match its payload.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChecksumMismatch {
+    /// The checksum stored after the payload.
+    pub expected: u32,
+    /// The checksum of the payload as read.
+    pub actual: u32,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Checksum mismatch: expected {:#010x}, got {:#010x}",
+            self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChecksumMismatch {}
+
+/// With `std`, the error keeps the `ChecksumMismatch`, see [`Error::get_ref`].
+impl From<ChecksumMismatch> for Error {
+    #[cfg(feature = "std")]
+    fn from(err: ChecksumMismatch) -> Self {
+        Error::new(ErrorKind::InvalidData, err)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn from(err: ChecksumMismatch) -> Self {
+        Error::new(ErrorKind::InvalidData, format!("{}", err))
+    }
+}
+
+/// Serializes `value` followed by the CRC32 of its bytes.
+pub fn serialize_with_checksum<T: BorshSerialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut data = value.try_to_vec()?;
+    let checksum = crc32(&data);
+    data.extend_from_slice(&checksum.to_le_bytes());
+    Ok(data)
+}
+
+/// Checks the CRC32 at the end of `data`, then deserializes the payload before it. Fails with a
+/// [`ChecksumMismatch`] error if the payload is corrupted.
+pub fn deserialize_with_checksum<T: BorshDeserialize>(data: &[u8]) -> Result<T> {
+    if data.len() < CHECKSUM_LEN {
+        return Err(Error::new(ErrorKind::InvalidData, ERROR_MISSING_CHECKSUM));
+    }
+    let (payload, checksum) = data.split_at(data.len() - CHECKSUM_LEN);
+    let mut expected = [0u8; CHECKSUM_LEN];
+    expected.copy_from_slice(checksum);
+    let expected = u32::from_le_bytes(expected);
+    let actual = crc32(payload);
+    if actual != expected {
+        return Err(ChecksumMismatch { expected, actual }.into());
+    }
+    T::try_from_slice(payload)
+}
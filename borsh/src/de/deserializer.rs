@@ -0,0 +1,137 @@
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind, Read, Result},
+    string::String,
+    vec::Vec,
+};
+use crate::{BorshDeserialize, Reader};
+
+use super::{limits, unexpected_eof_to_unexpected_length_of_input};
+
+macro_rules! read_primitive {
+    ($($method: ident: $type: ty),*) => {
+        $(
+            #[doc = concat!("Reads a `", stringify!($type), "`.")]
+            #[inline]
+            pub fn $method(&mut self) -> Result<$type> {
+                <$type>::deserialize_reader(&mut self.reader)
+            }
+        )*
+    };
+}
+
+/// Reads Borsh values piece by piece, the counterpart of [`Serializer`](crate::ser::Serializer)
+/// for formats that mix Borsh primitives with other data and for `BorshDeserialize` impls that
+/// read the parts of a value by hand:
+///
+/// ```
+/// use borsh::de::Deserializer;
+/// use borsh::{BorshDeserialize, BorshSerialize};
+///
+/// #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+/// enum Message {
+///     Ping,
+///     Text { from: u64, lines: Vec<String> },
+/// }
+///
+/// let message = Message::Text { from: 7, lines: vec!["hello".into(), "world".into()] };
+/// let data = message.try_to_vec().unwrap();
+///
+/// let mut deserializer = Deserializer::new(&data[..]);
+/// assert_eq!(deserializer.read_variant_tag().unwrap(), 1);
+/// let from = deserializer.read_u64().unwrap();
+/// let len = deserializer.read_seq_header().unwrap();
+/// let lines = (0..len).map(|_| deserializer.read_str()).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(Message::Text { from, lines }, message);
+/// assert_eq!(deserializer.position(), data.len());
+/// ```
+///
+/// Each method fails with the same errors as `BorshDeserialize` for the same value. The
+/// deserializer counts the bytes it reads, so errors of a hand-written format can report where
+/// they occurred with [`position`](Self::position).
+pub struct Deserializer<R> {
+    reader: Reader<R>,
+}
+
+impl<R: Read> Deserializer<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Reader::new(reader),
+        }
+    }
+
+    /// The number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.reader.position()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    read_primitive!(
+        read_u8: u8,
+        read_u16: u16,
+        read_u32: u32,
+        read_u64: u64,
+        read_u128: u128,
+        read_i8: i8,
+        read_i16: i16,
+        read_i32: i32,
+        read_i64: i64,
+        read_i128: i128,
+        read_bool: bool,
+        read_f32: f32,
+        read_f64: f64
+    );
+
+    /// Reads a string with its length prefix, the bytes of a `String`.
+    pub fn read_str(&mut self) -> Result<String> {
+        String::deserialize_reader(&mut self.reader)
+    }
+
+    /// Reads bytes with their length prefix, the bytes of a `Vec<u8>`.
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>> {
+        Vec::<u8>::deserialize_reader(&mut self.reader)
+    }
+
+    /// Fills `buf` with the bytes as they are, without a length prefix, the bytes of a `[u8; N]`.
+    pub fn read_raw(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.reader
+            .read_exact(buf)
+            .map_err(unexpected_eof_to_unexpected_length_of_input)
+    }
+
+    /// Reads the `u32` length prefix of a sequence, whose elements the caller reads next. Fails if
+    /// the length goes over the [`Limits`](super::Limits) in effect, like the built-in sequences.
+    pub fn read_seq_header(&mut self) -> Result<u32> {
+        let len = u32::deserialize_reader(&mut self.reader)?;
+        limits::check_sequence(len as usize)?;
+        Ok(len)
+    }
+
+    /// Reads the tag of an enum variant, which the caller follows with the fields of the variant.
+    pub fn read_variant_tag(&mut self) -> Result<u8> {
+        u8::deserialize_reader(&mut self.reader)
+    }
+
+    /// Reads the tag of an `Option`, which the caller follows with the value if it is `Some`.
+    pub fn read_option_tag(&mut self) -> Result<bool> {
+        match u8::deserialize_reader(&mut self.reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            flag => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Invalid Option representation: {}. The first byte must be 0 or 1",
+                    flag
+                ),
+            )),
+        }
+    }
+
+    /// Reads any value with its `BorshDeserialize` impl.
+    pub fn deserialize<T: BorshDeserialize>(&mut self) -> Result<T> {
+        T::deserialize_reader(&mut self.reader)
+    }
+}
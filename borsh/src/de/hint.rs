@@ -4,6 +4,19 @@ pub fn cautious<T>(hint: u32) -> usize {
     core::cmp::max(core::cmp::min(hint, 4096 / el_size), 1) as usize
 }
 
+/// The most bytes allocated up front for a `#[borsh(reserve = N)]` hint.
+const MAX_RESERVED_BYTES: usize = 1024 * 1024;
+
+/// The capacity for `hint` elements when `reserve` of them were asked for: the cautious capacity,
+/// or up to `reserve` elements as long as the input claims that many and they fit into
+/// `MAX_RESERVED_BYTES`.
+#[inline]
+pub fn reserved<T>(hint: u32, reserve: usize) -> usize {
+    let el_size = core::cmp::max(core::mem::size_of::<T>(), 1);
+    let reserve = reserve.min(hint as usize).min(MAX_RESERVED_BYTES / el_size);
+    core::cmp::max(cautious::<T>(hint), reserve)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,4 +25,12 @@ mod tests {
     pub fn test_cautious_u8() {
         assert_eq!(cautious::<u8>(10), 10);
     }
+
+    #[test]
+    pub fn test_reserved() {
+        assert_eq!(reserved::<u64>(10_000, 0), 512);
+        assert_eq!(reserved::<u64>(10_000, 5_000), 5_000);
+        assert_eq!(reserved::<u64>(3_000, 5_000), 3_000);
+        assert_eq!(reserved::<u64>(u32::MAX, usize::MAX), 1024 * 1024 / 8);
+    }
 }
@@ -0,0 +1,312 @@
+//! Limits on the size of the values decoded from untrusted input.
+use core::fmt;
+
+use core::mem::size_of;
+
+use crate::maybestd::io::Result;
+#[cfg(not(feature = "std"))]
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind},
+};
+
+/// Bounds checked while deserializing with
+/// [`BorshDeserialize::try_from_slice_limited`](super::BorshDeserialize::try_from_slice_limited).
+///
+/// The default is effectively unlimited: each limit is the maximum value of its type.
+///
+/// ```
+/// use borsh::de::{Limit, LimitExceeded, Limits};
+/// use borsh::BorshDeserialize;
+///
+/// let data = borsh::to_vec(&vec![1u16, 2, 3]).unwrap();
+/// let limits = Limits {
+///     max_sequence_elements: 2,
+///     ..Limits::default()
+/// };
+/// let err = Vec::<u16>::try_from_slice_limited(&data, &limits).unwrap_err();
+/// let exceeded = err.get_ref().unwrap().downcast_ref::<LimitExceeded>().unwrap();
+/// assert_eq!(exceeded.limit, Limit::SequenceElements);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Limits {
+    /// The most bytes of input.
+    pub max_total_len: usize,
+    /// The most elements of a single sequence, set or map.
+    pub max_sequence_elements: usize,
+    /// The most bytes of a single string.
+    pub max_string_bytes: usize,
+    /// How deep sequences, sets, maps and smart pointers are nested.
+    pub max_depth: usize,
+    /// The most elements of a single sequence, set or map of a zero-sized type, e.g. `Vec<()>`.
+    /// Its elements take no input, so a length prefix alone claims billions of them.
+    pub max_zst_sequence_elements: usize,
+}
+
+impl Limits {
+    /// The limit of sequences of a zero-sized type when
+    /// [`BorshSchemaContainer::deserialize_value`](crate::schema::BorshSchemaContainer::deserialize_value),
+    /// `validate_bytes` and `skip_value_checked` run without `limits`. Unlike the typed
+    /// deserializers, they build or visit every element.
+    pub const VALUE_MAX_ZST_SEQUENCE_ELEMENTS: usize = 65_536;
+
+    /// No limits, the behavior of
+    /// [`BorshDeserialize::try_from_slice`](super::BorshDeserialize::try_from_slice).
+    pub const UNLIMITED: Limits = Limits {
+        max_total_len: usize::MAX,
+        max_sequence_elements: usize::MAX,
+        max_string_bytes: usize::MAX,
+        max_depth: usize::MAX,
+        max_zst_sequence_elements: usize::MAX,
+    };
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+/// One of the fields of [`Limits`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Limit {
+    TotalLen,
+    SequenceElements,
+    StringBytes,
+    Depth,
+    ZstSequenceElements,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Limit::TotalLen => "max_total_len",
+            Limit::SequenceElements => "max_sequence_elements",
+            Limit::StringBytes => "max_string_bytes",
+            Limit::Depth => "max_depth",
+            Limit::ZstSequenceElements => "max_zst_sequence_elements",
+        })
+    }
+}
+
+/// The error of a value over one of the [`Limits`], wrapped in an `io::Error` of kind
+/// `InvalidData`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    /// The length or depth of the input.
+    pub actual: usize,
+    /// The value of the limit.
+    pub max: usize,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Exceeded the {} limit of {}: {}",
+            self.limit, self.max, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LimitExceeded {}
+
+#[cfg(feature = "std")]
+mod active {
+    use core::cell::Cell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{Limit, LimitExceeded, Limits};
+    use crate::maybestd::io::{Error, ErrorKind, Result};
+
+    /// The limits of the deserialization running on this thread and the current depth.
+    #[derive(Clone, Copy)]
+    struct State {
+        limits: Limits,
+        depth: usize,
+    }
+
+    std::thread_local! {
+        static STATE: Cell<Option<State>> = Cell::new(None);
+    }
+
+    /// The number of deserializations with limits running on any thread. While it is zero, the
+    /// checks return without looking up `STATE`, so that deserializing without limits does not
+    /// pay for them.
+    static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+    /// The state of this thread, `None` when it does not run a deserialization with limits.
+    #[inline]
+    fn current() -> Option<State> {
+        if ACTIVE.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        STATE.with(Cell::get)
+    }
+
+    fn exceeded(limit: Limit, actual: usize, max: usize) -> Error {
+        Error::new(ErrorKind::InvalidData, LimitExceeded { limit, actual, max })
+    }
+
+    /// Runs `f` with `limits` enforced, and restores the previous limits afterwards, so that
+    /// deserializations with limits can be nested.
+    pub(crate) fn with_limits<T>(
+        limits: &Limits,
+        total_len: usize,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        if total_len > limits.max_total_len {
+            return Err(exceeded(Limit::TotalLen, total_len, limits.max_total_len));
+        }
+        struct Restore(Option<State>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                STATE.with(|state| state.set(self.0));
+                ACTIVE.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+        ACTIVE.fetch_add(1, Ordering::Relaxed);
+        let _restore = Restore(STATE.with(|state| {
+            state.replace(Some(State {
+                limits: *limits,
+                depth: 0,
+            }))
+        }));
+        f()
+    }
+
+    fn check(limit: Limit, actual: usize, max: fn(&Limits) -> usize) -> Result<()> {
+        match current() {
+            Some(state) if actual > max(&state.limits) => {
+                Err(exceeded(limit, actual, max(&state.limits)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_sequence(len: usize) -> Result<()> {
+        check(Limit::SequenceElements, len, |limits| {
+            limits.max_sequence_elements
+        })
+    }
+
+    pub(crate) fn check_string(len: usize) -> Result<()> {
+        check(Limit::StringBytes, len, |limits| limits.max_string_bytes)
+    }
+
+    pub(crate) fn check_zst_sequence(len: usize) -> Result<()> {
+        check(Limit::ZstSequenceElements, len, |limits| {
+            limits.max_zst_sequence_elements
+        })
+    }
+
+    /// Like `check_zst_sequence`, with `Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS` when no limits
+    /// are set.
+    pub(crate) fn check_zst_values(len: usize) -> Result<()> {
+        let max = match current() {
+            Some(state) => state.limits.max_zst_sequence_elements,
+            None => Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS,
+        };
+        if len > max {
+            return Err(exceeded(Limit::ZstSequenceElements, len, max));
+        }
+        Ok(())
+    }
+
+    /// One level of nesting, left when dropped.
+    pub(crate) struct Depth(bool);
+
+    impl Drop for Depth {
+        fn drop(&mut self) {
+            if self.0 {
+                STATE.with(|state| {
+                    if let Some(mut current) = state.get() {
+                        current.depth -= 1;
+                        state.set(Some(current));
+                    }
+                });
+            }
+        }
+    }
+
+    pub(crate) fn enter() -> Result<Depth> {
+        if ACTIVE.load(Ordering::Relaxed) == 0 {
+            return Ok(Depth(false));
+        }
+        STATE.with(|state| match state.get() {
+            None => Ok(Depth(false)),
+            Some(current) if current.depth >= current.limits.max_depth => Err(exceeded(
+                Limit::Depth,
+                current.depth + 1,
+                current.limits.max_depth,
+            )),
+            Some(mut current) => {
+                current.depth += 1;
+                state.set(Some(current));
+                Ok(Depth(true))
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) use active::{
+    check_sequence, check_string, check_zst_sequence, check_zst_values, enter, with_limits,
+};
+
+// Without `std` there is no thread-local state to hold the limits, so only
+// `Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS` is checked.
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn check_sequence(_len: usize) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn check_string(_len: usize) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn check_zst_sequence(_len: usize) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn check_zst_values(len: usize) -> Result<()> {
+    let max = Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS;
+    if len > max {
+        let exceeded = LimitExceeded {
+            limit: Limit::ZstSequenceElements,
+            actual: len,
+            max,
+        };
+        return Err(Error::new(ErrorKind::InvalidData, format!("{}", exceeded)));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct Depth;
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn enter() -> Result<Depth> {
+    Ok(Depth)
+}
+
+/// Checks the length of a sequence of `T`, and the limit of sequences of a zero-sized type if `T`
+/// is one.
+#[inline]
+pub(crate) fn check_sequence_of<T>(len: usize) -> Result<()> {
+    check_sequence(len)?;
+    if size_of::<T>() == 0 {
+        check_zst_sequence(len)?;
+    }
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
 use core::{
-    convert::{TryFrom, TryInto},
+    convert::TryInto,
     hash::{BuildHasher, Hash},
     mem::{forget, size_of},
 };
@@ -23,15 +23,46 @@ use crate::maybestd::{
 #[cfg(feature = "rc")]
 use crate::maybestd::{rc::Rc, sync::Arc};
 
-mod hint;
+mod deserializer;
+pub(crate) mod hint;
+pub(crate) mod limits;
+mod optional_tail;
+mod with_context;
+
+pub use deserializer::Deserializer;
+pub use limits::{Limit, LimitExceeded, Limits};
+pub use optional_tail::OptionalTail;
+pub use with_context::BorshDeserializeWith;
 
 const ERROR_NOT_ALL_BYTES_READ: &str = "Not all bytes read";
-const ERROR_UNEXPECTED_LENGTH_OF_INPUT: &str = "Unexpected length of input";
+pub(crate) const ERROR_UNEXPECTED_LENGTH_OF_INPUT: &str = "Unexpected length of input";
 const ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_ISIZE: &str = "Overflow on machine with 32 bit isize";
 const ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_USIZE: &str = "Overflow on machine with 32 bit usize";
 const ERROR_INVALID_ZERO_VALUE: &str = "Expected a non-zero value";
+const ERROR_UNINHABITED_TYPE: &str = "Cannot deserialize a value of an uninhabited type";
+#[cfg(any(feature = "canonical", feature = "indexmap", test))]
+const ERROR_DUPLICATE_KEY: &str = "Duplicate key, not allowed in the canonical encoding";
+#[cfg(feature = "canonical")]
+const ERROR_UNSORTED_KEYS: &str = "Keys out of order, not allowed in the canonical encoding";
+#[cfg(any(test, feature = "chrono"))]
+const ERROR_TIMESTAMP_OUT_OF_RANGE: &str = "Timestamp is out of range";
+#[cfg(any(test, feature = "num-bigint"))]
+const ERROR_NON_CANONICAL_MAGNITUDE: &str = "Magnitude of a big integer has trailing zero bytes";
+#[cfg(any(test, feature = "num-bigint"))]
+const ERROR_NON_CANONICAL_SIGN: &str = "Sign of a big integer does not match its magnitude";
+#[cfg(any(test, feature = "rust_decimal"))]
+const ERROR_INVALID_DECIMAL: &str = "Invalid flags of a decimal";
+#[cfg(any(test, feature = "heapless"))]
+const ERROR_CAPACITY_EXCEEDED: &str = "Length exceeds the capacity of the container";
 
 /// A data-structure that can be de-serialized from binary format by NBOR.
+///
+/// # `usize` and `isize`
+///
+/// `usize` and `isize` are read as a `u64` and an `i64` on every target, as they are written. A
+/// value that does not fit into the pointer width of the target, such as a length above
+/// `u32::MAX` written on a 64-bit target and read on wasm32, fails with an
+/// [`ErrorKind::InvalidData`] error and is never truncated.
 pub trait BorshDeserialize: Sized {
     /// Deserializes this instance from a given slice of bytes.
     /// Updates the buffer to point at the remaining bytes.
@@ -51,6 +82,18 @@ pub trait BorshDeserialize: Sized {
         Ok(result)
     }
 
+    /// Deserialize this instance from a slice of bytes, failing with a [`LimitExceeded`] error as
+    /// soon as the input goes over one of the `limits`.
+    ///
+    /// Compliant input decodes exactly as with [`try_from_slice`](Self::try_from_slice). The
+    /// limits apply to the built-in implementations of sequences, maps, strings and smart
+    /// pointers, including those nested in derived and hand-written implementations that
+    /// deserialize their fields on the same thread.
+    #[cfg(feature = "std")]
+    fn try_from_slice_limited(v: &[u8], limits: &Limits) -> Result<Self> {
+        limits::with_limits(limits, v.len(), || Self::try_from_slice(v))
+    }
+
     fn try_from_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let result = Self::deserialize_reader(reader)?;
         let mut buf = [0u8; 1];
@@ -125,7 +168,7 @@ pub trait EnumExt: BorshDeserialize {
     fn deserialize_variant<R: Read>(reader: &mut R, tag: u8) -> Result<Self>;
 }
 
-fn unexpected_eof_to_unexpected_length_of_input(e: Error) -> Error {
+pub(crate) fn unexpected_eof_to_unexpected_length_of_input(e: Error) -> Error {
     if e.kind() == ErrorKind::UnexpectedEof {
         Error::new(ErrorKind::InvalidInput, ERROR_UNEXPECTED_LENGTH_OF_INPUT)
     } else {
@@ -234,30 +277,63 @@ impl_for_nonzero_integer!(core::num::NonZeroU64);
 impl_for_nonzero_integer!(core::num::NonZeroU128);
 impl_for_nonzero_integer!(core::num::NonZeroUsize);
 
+impl<T: BorshDeserialize> BorshDeserialize for core::num::Wrapping<T> {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self(T::deserialize_reader(reader)?))
+    }
+}
+
+#[cfg(feature = "saturating")]
+#[clippy::msrv = "1.74"]
+impl<T: BorshDeserialize> BorshDeserialize for core::num::Saturating<T> {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self(T::deserialize_reader(reader)?))
+    }
+}
+
+/// Read as an `i64`. Fails with `InvalidData` if the value does not fit into the `isize` of the
+/// target, e.g. on 32-bit targets.
 impl BorshDeserialize for isize {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let i: i64 = BorshDeserialize::deserialize_reader(reader)?;
-        let i = isize::try_from(i).map_err(|_| {
-            Error::new(
-                ErrorKind::InvalidInput,
-                ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_ISIZE,
-            )
-        })?;
-        Ok(i)
+        Ok(check_signed_width(i, isize::BITS)? as isize)
     }
 }
 
+/// Read as a `u64`. Fails with `InvalidData` if the value does not fit into the `usize` of the
+/// target, e.g. on 32-bit targets.
 impl BorshDeserialize for usize {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let u: u64 = BorshDeserialize::deserialize_reader(reader)?;
-        let u = usize::try_from(u).map_err(|_| {
-            Error::new(
-                ErrorKind::InvalidInput,
-                ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_USIZE,
-            )
-        })?;
-        Ok(u)
+        Ok(check_unsigned_width(u, usize::BITS)? as usize)
+    }
+}
+
+/// Checks that `value` fits into an `isize` of `bits` bits, so that casting it does not truncate.
+fn check_signed_width(value: i64, bits: u32) -> Result<i64> {
+    if bits < 64 {
+        let max = (1i64 << (bits - 1)) - 1;
+        if value < -max - 1 || value > max {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_ISIZE,
+            ));
+        }
     }
+    Ok(value)
+}
+
+/// Checks that `value` fits into a `usize` of `bits` bits, so that casting it does not truncate.
+fn check_unsigned_width(value: u64, bits: u32) -> Result<u64> {
+    if bits < 64 && value >> bits != 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_USIZE,
+        ));
+    }
+    Ok(value)
 }
 
 // Note NaNs have a portability issue. Specifically, signalling NaNs on MIPS are quiet NaNs on x86,
@@ -287,6 +363,8 @@ macro_rules! impl_for_float {
 impl_for_float!(f32, u32);
 impl_for_float!(f64, u64);
 
+/// Only `0` and `1` are accepted. Any other byte is an error rather than `true`, so that corrupted
+/// data is not silently accepted.
 impl BorshDeserialize for bool {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
@@ -298,7 +376,7 @@ impl BorshDeserialize for bool {
         } else {
             let msg = format!("Invalid bool representation: {}", b);
 
-            Err(Error::new(ErrorKind::InvalidInput, msg))
+            Err(Error::new(ErrorKind::InvalidData, msg))
         }
     }
 }
@@ -361,10 +439,29 @@ where
     }
 }
 
+/// Reads the length-prefixed bytes of a string.
+pub(crate) fn deserialize_string_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = u32::deserialize_reader(reader)?;
+    limits::check_string(len as usize)?;
+    Ok(u8::vec_from_reader(len, reader)?.unwrap_or_default())
+}
+
 impl BorshDeserialize for String {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        String::from_utf8(Vec::<u8>::deserialize_reader(reader)?).map_err(|err| {
+        String::from_utf8(deserialize_string_bytes(reader)?).map_err(|err| {
+            let msg = err.to_string();
+            Error::new(ErrorKind::InvalidData, msg)
+        })
+    }
+}
+
+/// The trailing NUL is added back; interior NUL bytes are rejected.
+#[cfg(feature = "std")]
+impl BorshDeserialize for std::ffi::CString {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::new(deserialize_string_bytes(reader)?).map_err(|err| {
             let msg = err.to_string();
             Error::new(ErrorKind::InvalidData, msg)
         })
@@ -377,7 +474,26 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_reserve(reader, 0)
+    }
+}
+
+/// Deserialization with a hint of how many elements to allocate up front, used by fields with
+/// `#[borsh(reserve = N)]`. The hint does not change what is read.
+pub trait BorshDeserializeReserve: BorshDeserialize {
+    /// Deserializes the value, allocating room for up to `reserve` elements before reading them.
+    /// The allocation is still bounded by the length prefix and by a cap on its size in bytes.
+    fn deserialize_reader_reserve<R: Read>(reader: &mut R, reserve: usize) -> Result<Self>;
+}
+
+impl<T> BorshDeserializeReserve for Vec<T>
+where
+    T: BorshDeserialize,
+{
+    fn deserialize_reader_reserve<R: Read>(reader: &mut R, reserve: usize) -> Result<Self> {
         let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<T>(len as usize)?;
+        let _depth = limits::enter()?;
         if len == 0 {
             Ok(Vec::new())
         } else if let Some(vec_bytes) = T::vec_from_reader(len, reader)? {
@@ -394,7 +510,7 @@ where
             }
         } else {
             // TODO(16): return capacity allocation when we can safely do that.
-            let mut result = Vec::with_capacity(hint::cautious::<T>(len));
+            let mut result = Vec::with_capacity(hint::reserved::<T>(len, reserve));
             for _ in 0..len {
                 result.push(T::deserialize_reader(reader)?);
             }
@@ -403,6 +519,32 @@ where
     }
 }
 
+/// Deserializes a sequence encoded like `Vec<T>` directly into any collection that can be extended
+/// with its elements, without an intermediate `Vec`.
+///
+/// ```
+/// use borsh::maybestd::collections::HashSet;
+///
+/// let data = borsh::to_vec(&vec![3u8, 1, 3]).unwrap();
+/// let set: HashSet<u8> = borsh::de::deserialize_seq_into::<_, u8, _>(&mut &data[..]).unwrap();
+/// assert_eq!(set, [1, 3].iter().copied().collect());
+/// ```
+pub fn deserialize_seq_into<C, T, R>(reader: &mut R) -> Result<C>
+where
+    C: Extend<T> + Default,
+    T: BorshDeserialize,
+    R: Read,
+{
+    let len = u32::deserialize_reader(reader)?;
+    limits::check_sequence_of::<T>(len as usize)?;
+    let _depth = limits::enter()?;
+    let mut result = C::default();
+    for _ in 0..len {
+        result.extend(Some(T::deserialize_reader(reader)?));
+    }
+    Ok(result)
+}
+
 #[cfg(any(test, feature = "bytes"))]
 impl BorshDeserialize for bytes::Bytes {
     #[inline]
@@ -417,6 +559,8 @@ impl BorshDeserialize for bytes::BytesMut {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence(len as usize)?;
+        let _depth = limits::enter()?;
         let mut out = BytesMut::with_capacity(hint::cautious::<u8>(len));
         for _ in 0..len {
             out.put_u8(u8::deserialize_reader(reader)?);
@@ -435,6 +579,206 @@ impl BorshDeserialize for bson::oid::ObjectId {
     }
 }
 
+#[cfg(any(test, feature = "chrono"))]
+impl BorshDeserialize for chrono::DateTime<chrono::Utc> {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let secs = i64::deserialize_reader(reader)?;
+        let nanos = u32::deserialize_reader(reader)?;
+        Self::from_timestamp(secs, nanos)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, ERROR_TIMESTAMP_OUT_OF_RANGE))
+    }
+}
+
+#[cfg(any(test, feature = "chrono"))]
+impl BorshDeserialize for chrono::NaiveDateTime {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(chrono::DateTime::<chrono::Utc>::deserialize_reader(reader)?.naive_utc())
+    }
+}
+
+/// Only accepts the representations that `Decimal::serialize` produces: unused bits of the flags
+/// must be zero and the scale at most 28.
+#[cfg(any(test, feature = "rust_decimal"))]
+impl BorshDeserialize for rust_decimal::Decimal {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let bytes = <[u8; 16]>::deserialize_reader(reader)?;
+        let value = rust_decimal::Decimal::deserialize(bytes);
+        if rust_decimal::Decimal::serialize(&value) != bytes {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_INVALID_DECIMAL));
+        }
+        Ok(value)
+    }
+}
+
+/// Fails instead of truncating when the serialized length is greater than `N`.
+#[cfg(any(test, feature = "heapless"))]
+impl<T, const N: usize> BorshDeserialize for heapless::Vec<T, N>
+where
+    T: BorshDeserialize,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<T>(len as usize)?;
+        let _depth = limits::enter()?;
+        if len as u64 > N as u64 {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_CAPACITY_EXCEEDED));
+        }
+        let mut result = heapless::Vec::new();
+        for _ in 0..len {
+            // Cannot fail, the length was checked above.
+            let _ = result.push(T::deserialize_reader(reader)?);
+        }
+        Ok(result)
+    }
+}
+
+/// Fails instead of truncating when the serialized length is greater than `N`.
+#[cfg(any(test, feature = "heapless"))]
+impl<const N: usize> BorshDeserialize for heapless::String<N> {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let bytes = heapless::Vec::<u8, N>::deserialize_reader(reader)?;
+        heapless::String::from_utf8(bytes)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))
+    }
+}
+
+/// The elements are stored inline until there are more than fit in `A`.
+#[cfg(any(test, feature = "smallvec"))]
+impl<A> BorshDeserialize for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: BorshDeserialize,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<A::Item>(len as usize)?;
+        let _depth = limits::enter()?;
+        let mut result = smallvec::SmallVec::new();
+        for _ in 0..len {
+            result.push(A::Item::deserialize_reader(reader)?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(any(test, feature = "either"))]
+impl<L, R> BorshDeserialize for either::Either<L, R>
+where
+    L: BorshDeserialize,
+    R: BorshDeserialize,
+{
+    #[inline]
+    fn deserialize_reader<Rd: Read>(reader: &mut Rd) -> Result<Self> {
+        let flag: u8 = BorshDeserialize::deserialize_reader(reader)?;
+        match flag {
+            0 => Ok(either::Either::Left(L::deserialize_reader(reader)?)),
+            1 => Ok(either::Either::Right(R::deserialize_reader(reader)?)),
+            _ => {
+                let msg = format!(
+                    "Invalid Either representation: {}. The first byte must be 0 or 1",
+                    flag
+                );
+
+                Err(Error::new(ErrorKind::InvalidInput, msg))
+            }
+        }
+    }
+}
+
+/// Only accepts the canonical encoding, without trailing zero bytes.
+#[cfg(any(test, feature = "num-bigint"))]
+impl BorshDeserialize for num_bigint::BigUint {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let bytes = Vec::<u8>::deserialize_reader(reader)?;
+        if bytes.last() == Some(&0) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                ERROR_NON_CANONICAL_MAGNITUDE,
+            ));
+        }
+        Ok(Self::from_bytes_le(&bytes))
+    }
+}
+
+/// Only accepts the canonical encoding: zero has the zero sign and the other numbers have a
+/// non-empty magnitude.
+#[cfg(any(test, feature = "num-bigint"))]
+impl BorshDeserialize for num_bigint::BigInt {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let flag: u8 = BorshDeserialize::deserialize_reader(reader)?;
+        let sign = match flag {
+            0 => num_bigint::Sign::NoSign,
+            1 => num_bigint::Sign::Plus,
+            2 => num_bigint::Sign::Minus,
+            _ => {
+                let msg = format!(
+                    "Invalid BigInt representation: {}. The first byte must be 0, 1 or 2",
+                    flag
+                );
+
+                return Err(Error::new(ErrorKind::InvalidInput, msg));
+            }
+        };
+        let magnitude = num_bigint::BigUint::deserialize_reader(reader)?;
+        if (sign == num_bigint::Sign::NoSign) != (magnitude.bits() == 0) {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_NON_CANONICAL_SIGN));
+        }
+        Ok(Self::from_biguint(sign, magnitude))
+    }
+}
+
+/// The entries keep the order in which they were serialized.
+#[cfg(any(test, feature = "indexmap"))]
+impl<K, V, H> BorshDeserialize for indexmap::IndexMap<K, V, H>
+where
+    K: BorshDeserialize + Eq + Hash,
+    V: BorshDeserialize,
+    H: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<(K, V)>(len as usize)?;
+        let _depth = limits::enter()?;
+        let mut result = indexmap::IndexMap::with_hasher(H::default());
+        for _ in 0..len {
+            let key = K::deserialize_reader(reader)?;
+            let value = V::deserialize_reader(reader)?;
+            if result.insert(key, value).is_some() && cfg!(feature = "canonical") {
+                return Err(Error::new(ErrorKind::InvalidData, ERROR_DUPLICATE_KEY));
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The items keep the order in which they were serialized.
+#[cfg(any(test, feature = "indexmap"))]
+impl<T, H> BorshDeserialize for indexmap::IndexSet<T, H>
+where
+    T: BorshDeserialize + Eq + Hash,
+    H: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let vec = <Vec<T>>::deserialize_reader(reader)?;
+        let len = vec.len();
+        let result = vec.into_iter().collect::<indexmap::IndexSet<T, H>>();
+        if result.len() != len && cfg!(feature = "canonical") {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_DUPLICATE_KEY));
+        }
+        Ok(result)
+    }
+}
+
 impl<T> BorshDeserialize for Cow<'_, T>
 where
     T: ToOwned + ?Sized,
@@ -479,6 +823,27 @@ where
     }
 }
 
+/// With the `canonical` feature, rejects the keys of a map or the items of a set that are repeated
+/// or not in the increasing order in which they are serialized.
+#[cfg(feature = "canonical")]
+fn check_canonical_order<'a, K: PartialOrd + 'a>(keys: impl Iterator<Item = &'a K>) -> Result<()> {
+    let mut previous = None;
+    for key in keys {
+        if let Some(previous) = previous {
+            match K::partial_cmp(previous, key) {
+                Some(core::cmp::Ordering::Less) => {}
+                Some(core::cmp::Ordering::Equal) => {
+                    return Err(Error::new(ErrorKind::InvalidData, ERROR_DUPLICATE_KEY))
+                }
+                _ => return Err(Error::new(ErrorKind::InvalidData, ERROR_UNSORTED_KEYS)),
+            }
+        }
+        previous = Some(key);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "canonical"))]
 impl<T, H> BorshDeserialize for HashSet<T, H>
 where
     T: BorshDeserialize + Eq + Hash,
@@ -491,6 +856,23 @@ where
     }
 }
 
+/// With the `canonical` feature, the items must be in the increasing order in which they are
+/// serialized, which needs `T: PartialOrd`.
+#[cfg(feature = "canonical")]
+impl<T, H> BorshDeserialize for HashSet<T, H>
+where
+    T: BorshDeserialize + Eq + Hash + PartialOrd,
+    H: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let vec = <Vec<T>>::deserialize_reader(reader)?;
+        check_canonical_order(vec.iter())?;
+        Ok(vec.into_iter().collect::<HashSet<T, H>>())
+    }
+}
+
+#[cfg(not(feature = "canonical"))]
 impl<K, V, H> BorshDeserialize for HashMap<K, V, H>
 where
     K: BorshDeserialize + Eq + Hash,
@@ -500,6 +882,8 @@ where
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<(K, V)>(len as usize)?;
+        let _depth = limits::enter()?;
         // TODO(16): return capacity allocation when we can safely do that.
         let mut result = HashMap::with_hasher(H::default());
         for _ in 0..len {
@@ -511,6 +895,24 @@ where
     }
 }
 
+/// With the `canonical` feature, the keys must be in the increasing order in which they are
+/// serialized, which needs `K: PartialOrd`.
+#[cfg(feature = "canonical")]
+impl<K, V, H> BorshDeserialize for HashMap<K, V, H>
+where
+    K: BorshDeserialize + Eq + Hash + PartialOrd,
+    V: BorshDeserialize,
+    H: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        // TODO(16): return capacity allocation when we can safely do that.
+        let vec = <Vec<(K, V)>>::deserialize_reader(reader)?;
+        check_canonical_order(vec.iter().map(|(key, _)| key))?;
+        Ok(vec.into_iter().collect::<HashMap<K, V, H>>())
+    }
+}
+
 impl<T> BorshDeserialize for BTreeSet<T>
 where
     T: BorshDeserialize + Ord,
@@ -518,6 +920,8 @@ where
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let vec = <Vec<T>>::deserialize_reader(reader)?;
+        #[cfg(feature = "canonical")]
+        check_canonical_order(vec.iter())?;
         Ok(vec.into_iter().collect::<BTreeSet<T>>())
     }
 }
@@ -527,9 +931,12 @@ where
     K: BorshDeserialize + Ord + core::hash::Hash,
     V: BorshDeserialize,
 {
+    #[cfg(not(feature = "canonical"))]
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
         let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<(K, V)>(len as usize)?;
+        let _depth = limits::enter()?;
         let mut result = BTreeMap::new();
         for _ in 0..len {
             let key = K::deserialize_reader(reader)?;
@@ -538,6 +945,14 @@ where
         }
         Ok(result)
     }
+
+    #[cfg(feature = "canonical")]
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let vec = <Vec<(K, V)>>::deserialize_reader(reader)?;
+        check_canonical_order(vec.iter().map(|(key, _)| key))?;
+        Ok(vec.into_iter().collect::<BTreeMap<K, V>>())
+    }
 }
 
 #[cfg(feature = "std")]
@@ -607,6 +1022,7 @@ where
     T::Owned: BorshDeserialize,
 {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let _depth = limits::enter()?;
         Ok(T::Owned::deserialize_reader(reader)?.into())
     }
 }
@@ -676,7 +1092,7 @@ fn array_deserialization_doesnt_leak() {
     static DESERIALIZE_COUNT: AtomicUsize = AtomicUsize::new(0);
     static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
 
-    struct MyType(u8);
+    struct MyType(#[allow(dead_code)] u8);
     impl BorshDeserialize for MyType {
         fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
             let val = u8::deserialize_reader(reader)?;
@@ -762,6 +1178,7 @@ where
     T::Owned: BorshDeserialize,
 {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let _depth = limits::enter()?;
         Ok(T::Owned::deserialize_reader(reader)?.into())
     }
 }
@@ -774,12 +1191,48 @@ where
     T::Owned: BorshDeserialize,
 {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let _depth = limits::enter()?;
         Ok(T::Owned::deserialize_reader(reader)?.into())
     }
 }
 
 impl<T: ?Sized> BorshDeserialize for PhantomData<T> {
     fn deserialize_reader<R: Read>(_: &mut R) -> Result<Self> {
-        Ok(Self::default())
+        Ok(PhantomData)
+    }
+}
+
+impl BorshDeserialize for core::convert::Infallible {
+    fn deserialize_reader<R: Read>(_: &mut R) -> Result<Self> {
+        Err(Error::new(ErrorKind::InvalidData, ERROR_UNINHABITED_TYPE))
+    }
+}
+
+#[test]
+fn usize_width_is_checked() {
+    assert_eq!(
+        check_unsigned_width(u32::MAX as u64, 32).unwrap(),
+        u32::MAX as u64
+    );
+    assert_eq!(check_unsigned_width(u64::MAX, 64).unwrap(), u64::MAX);
+    for value in [u32::MAX as u64 + 1, u64::MAX] {
+        let err = check_unsigned_width(value, 32).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_USIZE);
+    }
+    assert!(check_unsigned_width(u16::MAX as u64 + 1, 16).is_err());
+}
+
+#[test]
+fn isize_width_is_checked() {
+    for value in [i32::MIN as i64, -1, 0, i32::MAX as i64] {
+        assert_eq!(check_signed_width(value, 32).unwrap(), value);
+    }
+    for value in [i64::MIN, i32::MIN as i64 - 1, i32::MAX as i64 + 1, i64::MAX] {
+        let err = check_signed_width(value, 32).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_ISIZE);
     }
+    assert_eq!(check_signed_width(i64::MIN, 64).unwrap(), i64::MIN);
+    assert!(check_signed_width(i16::MIN as i64 - 1, 16).is_err());
 }
@@ -0,0 +1,83 @@
+use crate::maybestd::io::{ErrorKind, Read, Result};
+
+/// Reads the fields of a struct with `#[borsh(optional_tail)]` from the first optional field on,
+/// and tells whether the input ends before each of them.
+///
+/// ```
+/// use borsh::{BorshDeserialize, BorshSerialize};
+///
+/// #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+/// struct RecordV1 {
+///     id: u32,
+/// }
+///
+/// #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+/// struct RecordV2 {
+///     id: u32,
+///     #[borsh(optional_tail)]
+///     label: String,
+///     #[borsh(optional_tail = "default_priority")]
+///     priority: u8,
+/// }
+///
+/// fn default_priority() -> u8 {
+///     5
+/// }
+///
+/// let old = RecordV1 { id: 1 }.try_to_vec().unwrap();
+/// let record = RecordV2::try_from_slice(&old).unwrap();
+/// assert_eq!(record, RecordV2 { id: 1, label: String::new(), priority: 5 });
+/// ```
+///
+/// Serialization always writes the optional fields. On deserialization, an optional field gets
+/// its default only if the input ends right before it, so a struct with an optional tail has to
+/// be the last value of the input: followed by more data, as an element of a `Vec` or a field
+/// that is not the last one, it reads the bytes of the next value as its optional fields. The
+/// schema of the struct describes the bytes it writes, with all of its optional fields.
+#[derive(Debug)]
+pub struct OptionalTail<'a, R> {
+    reader: &'a mut R,
+    peeked: Option<u8>,
+}
+
+impl<'a, R: Read> OptionalTail<'a, R> {
+    pub fn new(reader: &'a mut R) -> Self {
+        Self {
+            reader,
+            peeked: None,
+        }
+    }
+
+    /// Whether the input has ended. Otherwise the byte read to find out is kept for the next
+    /// read.
+    pub fn is_at_end(&mut self) -> Result<bool> {
+        if self.peeked.is_some() {
+            return Ok(false);
+        }
+        let mut buf = [0u8; 1];
+        loop {
+            match self.reader.read(&mut buf) {
+                Ok(0) => return Ok(true),
+                Ok(_) => {
+                    self.peeked = Some(buf[0]);
+                    return Ok(false);
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl<'a, R: Read> Read for OptionalTail<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.peeked {
+            Some(byte) if !buf.is_empty() => {
+                buf[0] = byte;
+                self.peeked = None;
+                Ok(1)
+            }
+            _ => self.reader.read(buf),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Deserialization that needs an external context, such as an interner or a protocol version.
+use core::convert::TryFrom;
+use core::hash::{BuildHasher, Hash};
+use core::mem::size_of;
+
+use crate::maybestd::{
+    boxed::Box,
+    collections::{BTreeMap, HashMap},
+    format,
+    io::{Error, ErrorKind, Read, Result},
+    string::String,
+    vec::Vec,
+};
+
+use super::{hint, limits, BorshDeserialize, ERROR_NOT_ALL_BYTES_READ};
+
+/// A data-structure that can be de-serialized from binary format with the help of a context of
+/// type `Ctx`, the counterpart of serde's `DeserializeSeed`.
+///
+/// `#[derive(BorshDeserialize)]` implements it, instead of `BorshDeserialize`, for types with
+/// `#[borsh(de_context = "Ctx")]`. The fields with `#[borsh(with_context)]` are deserialized with
+/// the context, the other fields with `BorshDeserialize`:
+///
+/// ```
+/// use borsh::de::BorshDeserializeWith;
+/// use borsh::maybestd::io::{Read, Result};
+/// use borsh::BorshDeserialize;
+///
+/// struct Interner(Vec<String>);
+///
+/// /// An index into the strings of the `Interner`.
+/// struct Symbol(usize);
+///
+/// impl BorshDeserializeWith<Interner> for Symbol {
+///     fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Interner) -> Result<Self> {
+///         let string = String::deserialize_reader(reader)?;
+///         let index = match ctx.0.iter().position(|s| *s == string) {
+///             Some(index) => index,
+///             None => {
+///                 ctx.0.push(string);
+///                 ctx.0.len() - 1
+///             }
+///         };
+///         Ok(Symbol(index))
+///     }
+/// }
+///
+/// #[derive(BorshDeserialize)]
+/// #[borsh(de_context = "Interner")]
+/// struct Transfer {
+///     #[borsh(with_context)]
+///     from: Symbol,
+///     #[borsh(with_context)]
+///     to: Symbol,
+///     amount: u64,
+/// }
+///
+/// let mut data = borsh::to_vec(&("alice", "alice", 10u64)).unwrap();
+/// let mut interner = Interner(Vec::new());
+/// let transfer = Transfer::try_from_slice_with(&data, &mut interner).unwrap();
+/// assert_eq!((transfer.from.0, transfer.to.0, transfer.amount), (0, 0, 10));
+/// assert_eq!(interner.0, ["alice"]);
+/// ```
+///
+/// It is implemented for the primitive types and `String` with any context, ignoring it, and for
+/// the standard containers of types that implement it, passing the context to each element.
+pub trait BorshDeserializeWith<Ctx: ?Sized>: Sized {
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self>;
+
+    /// Deserializes this instance from a given slice of bytes.
+    /// Updates the buffer to point at the remaining bytes.
+    fn deserialize_with(buf: &mut &[u8], ctx: &mut Ctx) -> Result<Self> {
+        Self::deserialize_reader_with(&mut *buf, ctx)
+    }
+
+    /// Deserialize this instance from a slice of bytes.
+    fn try_from_slice_with(v: &[u8], ctx: &mut Ctx) -> Result<Self> {
+        let mut v_mut = v;
+        let result = Self::deserialize_with(&mut v_mut, ctx)?;
+        if !v_mut.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ));
+        }
+        Ok(result)
+    }
+}
+
+macro_rules! impl_for_context_free {
+    ($($type: ty)+) => {
+    $(
+        impl<Ctx: ?Sized> BorshDeserializeWith<Ctx> for $type {
+            #[inline]
+            fn deserialize_reader_with<R: Read>(reader: &mut R, _ctx: &mut Ctx) -> Result<Self> {
+                <$type as BorshDeserialize>::deserialize_reader(reader)
+            }
+        }
+    )+
+    };
+}
+
+impl_for_context_free!(() bool f32 f64 i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize String);
+
+impl<T, Ctx> BorshDeserializeWith<Ctx> for Vec<T>
+where
+    T: BorshDeserializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<T>(len as usize)?;
+        let mut result = if size_of::<T>() == 0 {
+            Vec::new()
+        } else {
+            Vec::with_capacity(hint::cautious::<T>(len))
+        };
+        for _ in 0..len {
+            result.push(T::deserialize_reader_with(reader, ctx)?);
+        }
+        Ok(result)
+    }
+}
+
+impl<T, Ctx, const N: usize> BorshDeserializeWith<Ctx> for [T; N]
+where
+    T: BorshDeserializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        let mut result = Vec::with_capacity(N);
+        for _ in 0..N {
+            result.push(T::deserialize_reader_with(reader, ctx)?);
+        }
+        // The vector has exactly `N` elements.
+        Ok(<[T; N]>::try_from(result).ok().unwrap())
+    }
+}
+
+impl<T, Ctx> BorshDeserializeWith<Ctx> for Option<T>
+where
+    T: BorshDeserializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        let flag: u8 = BorshDeserialize::deserialize_reader(reader)?;
+        if flag == 0 {
+            Ok(None)
+        } else if flag == 1 {
+            Ok(Some(T::deserialize_reader_with(reader, ctx)?))
+        } else {
+            let msg = format!(
+                "Invalid Option representation: {}. The first byte must be 0 or 1",
+                flag
+            );
+
+            Err(Error::new(ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
+impl<T, E, Ctx> BorshDeserializeWith<Ctx> for core::result::Result<T, E>
+where
+    T: BorshDeserializeWith<Ctx>,
+    E: BorshDeserializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        let flag: u8 = BorshDeserialize::deserialize_reader(reader)?;
+        if flag == 0 {
+            Ok(Err(E::deserialize_reader_with(reader, ctx)?))
+        } else if flag == 1 {
+            Ok(Ok(T::deserialize_reader_with(reader, ctx)?))
+        } else {
+            let msg = format!(
+                "Invalid Result representation: {}. The first byte must be 0 or 1",
+                flag
+            );
+
+            Err(Error::new(ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
+impl<T, Ctx> BorshDeserializeWith<Ctx> for Box<T>
+where
+    T: BorshDeserializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        Ok(Box::new(T::deserialize_reader_with(reader, ctx)?))
+    }
+}
+
+impl<K, V, H, Ctx> BorshDeserializeWith<Ctx> for HashMap<K, V, H>
+where
+    K: BorshDeserializeWith<Ctx> + Eq + Hash,
+    V: BorshDeserializeWith<Ctx>,
+    H: BuildHasher + Default,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<(K, V)>(len as usize)?;
+        let mut result = HashMap::with_hasher(H::default());
+        for _ in 0..len {
+            let key = K::deserialize_reader_with(reader, ctx)?;
+            let value = V::deserialize_reader_with(reader, ctx)?;
+            result.insert(key, value);
+        }
+        Ok(result)
+    }
+}
+
+impl<K, V, Ctx> BorshDeserializeWith<Ctx> for BTreeMap<K, V>
+where
+    K: BorshDeserializeWith<Ctx> + Ord,
+    V: BorshDeserializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits::check_sequence_of::<(K, V)>(len as usize)?;
+        let mut result = BTreeMap::new();
+        for _ in 0..len {
+            let key = K::deserialize_reader_with(reader, ctx)?;
+            let value = V::deserialize_reader_with(reader, ctx)?;
+            result.insert(key, value);
+        }
+        Ok(result)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+      impl<$($name,)+ Ctx> BorshDeserializeWith<Ctx> for ($($name,)+)
+      where $($name: BorshDeserializeWith<Ctx>,)+ Ctx: ?Sized
+      {
+        #[inline]
+        fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Ctx) -> Result<Self> {
+            Ok(($($name::deserialize_reader_with(reader, ctx)?,)+))
+        }
+      }
+    };
+}
+
+impl_tuple!(T0);
+impl_tuple!(T0 T1);
+impl_tuple!(T0 T1 T2);
+impl_tuple!(T0 T1 T2 T3);
+impl_tuple!(T0 T1 T2 T3 T4);
+impl_tuple!(T0 T1 T2 T3 T4 T5);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
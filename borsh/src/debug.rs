@@ -0,0 +1,375 @@
+//! Debugging helpers for Borsh data.
+use core::fmt::Write as _;
+
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind, Result},
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::schema::value::{check_length, decode_length, decode_primitive, MAX_DEPTH};
+use crate::schema::{BorshSchema, BorshSchemaContainer, Declaration, Definition, Fields, Value};
+use crate::BorshDeserialize;
+
+const ERROR_MAX_DEPTH_EXCEEDED: &str = "Exceeded the maximum nesting depth of the schema";
+
+/// How many bytes are shown on a line of the dump; longer values are cut with `..`.
+const BYTES_PER_LINE: usize = 8;
+
+/// Renders `bytes` as a dump annotated with the schema of `T`, see [`annotate_with_schema`].
+pub fn annotate<T: BorshSchema + ?Sized>(bytes: &[u8]) -> String {
+    annotate_with_schema(&T::schema_container(), bytes)
+}
+
+/// Renders `bytes` as a dump annotated with `container`: one line per primitive value, length
+/// prefix and tag, with its offset, its bytes in hex, its path in the value and its declaration.
+///
+/// The dump stops at the first place where the bytes do not match the schema, such as an invalid
+/// tag or the end of the input, with a line marked with `!!` that shows the bytes from there and the
+/// error. Bytes left over after the value are reported the same way.
+///
+/// ```
+/// use borsh::{BorshSchema, BorshSerialize};
+///
+/// #[derive(BorshSerialize, BorshSchema)]
+/// struct Point {
+///     x: u16,
+///     label: Option<String>,
+/// }
+///
+/// let data = Point { x: 7, label: None }.try_to_vec().unwrap();
+/// assert_eq!(
+///     borsh::debug::annotate::<Point>(&data[..2]),
+///     "00000000  07 00                       x: u16 = 7\n\
+///      00000002                              !! label.tag: Option<string>: Unexpected length of input\n"
+/// );
+/// ```
+///
+/// The output is meant for people and its format may change.
+pub fn annotate_with_schema(container: &BorshSchemaContainer, bytes: &[u8]) -> String {
+    let mut annotator = Annotator {
+        container,
+        bytes,
+        offset: 0,
+        line_end: 0,
+        out: String::new(),
+    };
+    match annotator.walk(&container.declaration, &mut String::new(), 0) {
+        Ok(()) if annotator.offset < bytes.len() => {
+            let left = bytes.len() - annotator.offset;
+            annotator.line(
+                annotator.offset,
+                bytes.len(),
+                &format!("!! {} bytes left over after the value", left),
+            );
+        }
+        Ok(()) => {}
+        Err((path, declaration, error)) => {
+            annotator.line(
+                annotator.offset,
+                bytes.len(),
+                &format!("!! {}: {}: {}", display_path(&path), declaration, error),
+            );
+        }
+    }
+    annotator.out
+}
+
+/// The path, declaration and error of the item where the data stopped matching the schema.
+type Failure = (String, Declaration, Error);
+
+struct Annotator<'a> {
+    container: &'a BorshSchemaContainer,
+    bytes: &'a [u8],
+    /// Offset of the next byte to read.
+    offset: usize,
+    /// Offset where the last line of the dump ends.
+    line_end: usize,
+    out: String,
+}
+
+impl Annotator<'_> {
+    fn walk(
+        &mut self,
+        declaration: &Declaration,
+        path: &mut String,
+        depth: usize,
+    ) -> core::result::Result<(), Failure> {
+        let fail = |path: &str, error: Error| (path.to_string(), declaration.clone(), error);
+        if depth > MAX_DEPTH {
+            return Err(fail(
+                path,
+                Error::new(ErrorKind::InvalidData, ERROR_MAX_DEPTH_EXCEEDED),
+            ));
+        }
+        let depth = depth + 1;
+        let definition = match self.container.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => {
+                let value = self
+                    .read(|buf| decode_primitive(declaration, buf))
+                    .map_err(|error| fail(path, error))?;
+                self.leaf(path, declaration, &display(&value));
+                return Ok(());
+            }
+        };
+        match definition {
+            Definition::Array { length, elements } => {
+                self.walk_elements(*length as u64, elements, path, depth)?;
+            }
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                if *length_width == 0 {
+                    let mut index = 0u64;
+                    while self.offset < self.bytes.len() {
+                        let start = self.offset;
+                        self.walk_element(index, elements, path, depth)?;
+                        if self.offset == start {
+                            break;
+                        }
+                        index += 1;
+                    }
+                } else {
+                    let len_path = format!("{}.len", display_path(path));
+                    let length = self
+                        .read(|buf| {
+                            let length = decode_length(*length_width, buf)?;
+                            check_length(length, length_range)?;
+                            Ok(length)
+                        })
+                        .map_err(|error| fail(&len_path, error))?;
+                    self.leaf(&len_path, declaration, &length.to_string());
+                    self.walk_elements(length, elements, path, depth)?;
+                }
+            }
+            Definition::Tuple { elements } => {
+                for (index, element) in elements.iter().enumerate() {
+                    self.walk_field(&index.to_string(), element, path, depth)?;
+                }
+            }
+            Definition::Enum { variants } => {
+                let tag_path = format!("{}.tag", display_path(path));
+                let (tag, (variant, variant_declaration)) = self
+                    .read(|buf| {
+                        let tag = u8::deserialize(buf)?;
+                        let variant = variants.get(tag as usize).ok_or_else(|| {
+                            Error::new(
+                                ErrorKind::InvalidInput,
+                                format!("Unexpected variant tag: {}", tag),
+                            )
+                        })?;
+                        Ok((tag, variant))
+                    })
+                    .map_err(|error| fail(&tag_path, error))?;
+                self.leaf(&tag_path, declaration, &format!("{} ({})", tag, variant));
+                self.walk_field(variant, variant_declaration, path, depth)?;
+            }
+            Definition::TaggedEnum { variants } => {
+                let tag_path = format!("{}.tag", display_path(path));
+                let (tag, variant, variant_declaration) = self
+                    .read(|buf| {
+                        let tag = u8::deserialize(buf)?;
+                        variants
+                            .iter()
+                            .find(|(variant_tag, _, _)| *variant_tag == tag)
+                            .ok_or_else(|| {
+                                Error::new(
+                                    ErrorKind::InvalidInput,
+                                    format!("Unexpected variant tag: {}", tag),
+                                )
+                            })
+                    })
+                    .map_err(|error| fail(&tag_path, error))?;
+                self.leaf(&tag_path, declaration, &format!("{} ({})", tag, variant));
+                self.walk_field(variant, variant_declaration, path, depth)?;
+            }
+            Definition::U32TaggedEnum { variants } => {
+                let tag_path = format!("{}.tag", display_path(path));
+                let (tag, variant, variant_declaration) = self
+                    .read(|buf| {
+                        let tag = u32::deserialize(buf)?;
+                        variants
+                            .iter()
+                            .find(|(variant_tag, _, _)| *variant_tag == tag)
+                            .ok_or_else(|| {
+                                Error::new(
+                                    ErrorKind::InvalidInput,
+                                    format!("Unexpected variant tag: {}", tag),
+                                )
+                            })
+                    })
+                    .map_err(|error| fail(&tag_path, error))?;
+                self.leaf(&tag_path, declaration, &format!("{} ({})", tag, variant));
+                self.walk_field(variant, variant_declaration, path, depth)?;
+            }
+            Definition::NamedEnum { variants } => {
+                let tag_path = format!("{}.tag", display_path(path));
+                let (variant, variant_declaration) = self
+                    .read(|buf| {
+                        let name = String::deserialize(buf)?;
+                        variants
+                            .iter()
+                            .find(|(variant, _)| *variant == name)
+                            .ok_or_else(|| {
+                                Error::new(
+                                    ErrorKind::InvalidInput,
+                                    format!("Unexpected variant name: {:?}", name),
+                                )
+                            })
+                    })
+                    .map_err(|error| fail(&tag_path, error))?;
+                self.leaf(&tag_path, declaration, &format!("{:?}", variant));
+                self.walk_field(variant, variant_declaration, path, depth)?;
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    for (name, field) in fields {
+                        self.walk_field(name, field, path, depth)?;
+                    }
+                }
+                Fields::UnnamedFields(fields) => {
+                    for (index, field) in fields.iter().enumerate() {
+                        self.walk_field(&index.to_string(), field, path, depth)?;
+                    }
+                }
+                Fields::Empty => {}
+            },
+            Definition::Option { inner } => {
+                let tag_path = format!("{}.tag", display_path(path));
+                let flag = self
+                    .read(|buf| match u8::deserialize(buf)? {
+                        flag @ (0 | 1) => Ok(flag),
+                        flag => Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "Invalid Option representation: {}. The first byte must be 0 or 1",
+                                flag
+                            ),
+                        )),
+                    })
+                    .map_err(|error| fail(&tag_path, error))?;
+                if flag == 0 {
+                    self.leaf(&tag_path, declaration, "0 (None)");
+                } else {
+                    self.leaf(&tag_path, declaration, "1 (Some)");
+                    self.walk(inner, path, depth)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn walk_elements(
+        &mut self,
+        length: u64,
+        elements: &Declaration,
+        path: &mut String,
+        depth: usize,
+    ) -> core::result::Result<(), Failure> {
+        for index in 0..length {
+            let start = self.offset;
+            self.walk_element(index, elements, path, depth)?;
+            if self.offset == start {
+                // The elements take no bytes, the other ones would be the same.
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn walk_element(
+        &mut self,
+        index: u64,
+        elements: &Declaration,
+        path: &mut String,
+        depth: usize,
+    ) -> core::result::Result<(), Failure> {
+        let len = path.len();
+        let _ = write!(path, "[{}]", index);
+        let result = self.walk(elements, path, depth);
+        path.truncate(len);
+        result
+    }
+
+    fn walk_field(
+        &mut self,
+        name: &str,
+        declaration: &Declaration,
+        path: &mut String,
+        depth: usize,
+    ) -> core::result::Result<(), Failure> {
+        let len = path.len();
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(name);
+        let result = self.walk(declaration, path, depth);
+        path.truncate(len);
+        result
+    }
+
+    /// Reads with `f` from the current offset and moves past the bytes it read.
+    fn read<T>(&mut self, f: impl FnOnce(&mut &[u8]) -> Result<T>) -> Result<T> {
+        let mut buf = &self.bytes[self.offset..];
+        let before = buf.len();
+        let value = f(&mut buf)?;
+        self.offset += before - buf.len();
+        Ok(value)
+    }
+
+    /// Adds the line of the item that ends at the current offset.
+    fn leaf(&mut self, path: &str, declaration: &str, value: &str) {
+        self.line(
+            self.line_end,
+            self.offset,
+            &format!("{}: {} = {}", display_path(path), declaration, value),
+        );
+    }
+
+    fn line(&mut self, start: usize, end: usize, text: &str) {
+        let mut hex = self.bytes[start..end]
+            .iter()
+            .take(BYTES_PER_LINE)
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if end - start > BYTES_PER_LINE {
+            hex.push_str(" ..");
+        }
+        let _ = writeln!(self.out, "{:08x}  {:<26}  {}", start, hex, text);
+        self.line_end = end;
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "<root>"
+    } else {
+        path
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Nil => "()".to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::U8(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::U128(v) => v.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::I128(v) => v.to_string(),
+        Value::F32(v) => v.to_string(),
+        Value::F64(v) => v.to_string(),
+        Value::String(v) => format!("{:?}", v),
+        // Only primitives are displayed.
+        other => format!("{:?}", other),
+    }
+}
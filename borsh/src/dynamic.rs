@@ -0,0 +1,475 @@
+//! Serialization of trait objects through a registry of tagged implementations.
+//!
+//! Each implementation of a trait is registered with a `u32` tag that is stable across versions,
+//! and `Box<dyn Trait>` is serialized as the tag followed by the concrete value. Registrations are
+//! collected at link time, so implementations can be registered by any crate linked into the
+//! binary.
+//!
+//! ```
+//! use borsh::dynamic::BorshSerializeDyn;
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! trait Shape: BorshSerializeDyn {
+//!     fn area(&self) -> f64;
+//! }
+//!
+//! #[derive(BorshSerialize, BorshDeserialize)]
+//! struct Square(f64);
+//!
+//! impl Shape for Square {
+//!     fn area(&self) -> f64 {
+//!         self.0 * self.0
+//!     }
+//! }
+//!
+//! borsh::register_type!(Shape);
+//! borsh::register_type!(Shape, Square = 1);
+//!
+//! let shape: Box<dyn Shape> = Box::new(Square(2.0));
+//! let data = shape.try_to_vec().unwrap();
+//! assert_eq!(data[..4], 1u32.to_le_bytes());
+//! assert_eq!(Box::<dyn Shape>::try_from_slice(&data).unwrap().area(), 4.0);
+//! ```
+use core::any::{type_name, Any, TypeId};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+use crate::maybestd::{
+    boxed::Box,
+    collections::{hash_map::Entry, HashMap},
+    format,
+    io::{Error, ErrorKind, Read, Result, Write},
+    string::String,
+};
+use crate::schema::{BorshSchema, Declaration, Definition};
+use crate::{BorshDeserialize, BorshSerialize};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Object-safe counterpart of `BorshSerialize`, implemented for every `BorshSerialize` type.
+///
+/// Make it a supertrait of the traits whose objects are stored in [`TaggedBox`].
+pub trait BorshSerializeDyn {
+    fn serialize_dyn(&self, writer: &mut dyn Write) -> Result<()>;
+
+    /// The `TypeId` of the concrete type behind the trait object.
+    fn concrete_type_id(&self) -> TypeId;
+
+    /// The name of the concrete type behind the trait object, for error messages.
+    fn concrete_type_name(&self) -> &'static str;
+}
+
+impl<T: BorshSerialize + 'static> BorshSerializeDyn for T {
+    fn serialize_dyn(&self, mut writer: &mut dyn Write) -> Result<()> {
+        self.serialize(&mut writer)
+    }
+
+    fn concrete_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn concrete_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+}
+
+/// `BorshDeserialize` from a `dyn Read`, implemented for every `BorshDeserialize` type. Used by
+/// [`register_type!`](crate::register_type).
+pub trait BorshDeserializeDyn: Sized {
+    fn deserialize_dyn(reader: &mut dyn Read) -> Result<Self>;
+}
+
+impl<T: BorshDeserialize> BorshDeserializeDyn for T {
+    fn deserialize_dyn(mut reader: &mut dyn Read) -> Result<Self> {
+        T::deserialize_reader(&mut reader)
+    }
+}
+
+/// A registered implementation of a trait, created by [`register_type!`](crate::register_type).
+pub struct Registration {
+    pub tag: u32,
+    pub trait_id: fn() -> TypeId,
+    pub trait_name: &'static str,
+    pub type_id: fn() -> TypeId,
+    pub type_name: &'static str,
+    /// Deserializes the implementation and returns it as a `Box<dyn Trait>` inside `Box<dyn Any>`.
+    pub deserialize: fn(&mut dyn Read) -> Result<Box<dyn Any>>,
+}
+
+inventory::collect!(Registration);
+
+/// Registers implementations of a trait with their tags, so that `Box<dyn Trait>` and
+/// [`TaggedBox<dyn Trait>`](TaggedBox) can be serialized:
+///
+/// ```text
+/// borsh::register_type!(Handler);
+/// borsh::register_type!(Handler, Logger = 1, Forwarder = 2);
+/// ```
+///
+/// With the trait alone, it implements `BorshSerialize` for `dyn Trait` and `BorshDeserialize`
+/// for `Box<dyn Trait>`, which dispatches on the tag. This is done once, in the crate of the
+/// trait, while the implementations can be registered from any crate.
+///
+/// A tag can only be used once per trait, and a type can only be registered once per trait. The
+/// tags listed in one invocation are checked when it is compiled; conflicts between invocations
+/// are reported by [`validate_registry`] and fail the (de)serialization of the trait objects.
+#[macro_export]
+macro_rules! register_type {
+    ($trait: path $(,)?) => {
+        impl $crate::BorshSerialize for dyn $trait {
+            fn serialize<W: $crate::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> $crate::maybestd::io::Result<()> {
+                $crate::dynamic::serialize_tagged::<dyn $trait, W>(self, writer)
+            }
+        }
+
+        impl $crate::BorshDeserialize for $crate::maybestd::boxed::Box<dyn $trait> {
+            fn deserialize_reader<R: $crate::maybestd::io::Read>(
+                reader: &mut R,
+            ) -> $crate::maybestd::io::Result<Self> {
+                $crate::dynamic::deserialize_tagged::<dyn $trait, R>(reader)
+            }
+        }
+    };
+    ($trait: path, $($type: ty = $tag: expr),+ $(,)?) => {
+        // Fails to compile, with an index out of bounds, if a tag is listed twice.
+        const _: () = [()][$crate::dynamic::has_duplicate_tag(&[$($tag),+]) as usize];
+        $(
+            $crate::dynamic::inventory::submit! {
+                $crate::dynamic::Registration {
+                    tag: $tag,
+                    trait_id: ::core::any::TypeId::of::<dyn $trait>,
+                    trait_name: ::core::stringify!($trait),
+                    type_id: ::core::any::TypeId::of::<$type>,
+                    type_name: ::core::stringify!($type),
+                    deserialize: {
+                        fn deserialize(
+                            reader: &mut dyn $crate::maybestd::io::Read,
+                        ) -> $crate::maybestd::io::Result<
+                            $crate::maybestd::boxed::Box<dyn ::core::any::Any>,
+                        > {
+                            let value: $crate::maybestd::boxed::Box<dyn $trait> =
+                                $crate::maybestd::boxed::Box::new(
+                                    <$type as $crate::dynamic::BorshDeserializeDyn>::deserialize_dyn(
+                                        reader,
+                                    )?,
+                                );
+                            Ok($crate::maybestd::boxed::Box::new(value))
+                        }
+                        deserialize
+                    },
+                }
+            }
+        )+
+    };
+}
+
+/// Whether a tag appears twice in `tags`, for the compile-time check of
+/// [`register_type!`](crate::register_type).
+#[doc(hidden)]
+pub const fn has_duplicate_tag(tags: &[u32]) -> bool {
+    let mut i = 0;
+    while i < tags.len() {
+        let mut j = i + 1;
+        while j < tags.len() {
+            if tags[i] == tags[j] {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Implements `BorshSchema` for `dyn Trait`, and so for `Box<dyn Trait>` and
+/// [`TaggedBox<dyn Trait>`](TaggedBox), as a [`Definition::U32TaggedEnum`](crate::schema::Definition::U32TaggedEnum)
+/// with a variant for each of the listed implementations:
+///
+/// ```
+/// use borsh::dynamic::{BorshSerializeDyn, TaggedBox};
+/// use borsh::schema::Definition;
+/// use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+///
+/// trait Shape: BorshSerializeDyn {}
+///
+/// #[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+/// struct Square(f64);
+///
+/// impl Shape for Square {}
+///
+/// borsh::register_type!(Shape, Square = 1);
+/// borsh::borsh_trait_object_schema!(Shape => [Square]);
+///
+/// let container = TaggedBox::<dyn Shape>::schema_container();
+/// assert_eq!(container.declaration, "dyn Shape");
+/// assert_eq!(
+///     container.definitions["dyn Shape"],
+///     Definition::U32TaggedEnum {
+///         variants: vec![(1, "Square".to_string(), "Square".to_string())]
+///     }
+/// );
+/// ```
+///
+/// Each variant is named after the declaration of the implementation and has the tag it is
+/// registered with by [`register_type!`](crate::register_type). Adding the definitions panics if
+/// one of the implementations is not registered for the trait.
+#[macro_export]
+macro_rules! borsh_trait_object_schema {
+    ($trait: path => [$($type: ty),+ $(,)?]) => {
+        impl $crate::schema::BorshSchema for dyn $trait {
+            fn add_definitions_recursively(
+                definitions: &mut $crate::maybestd::collections::HashMap<
+                    $crate::schema::Declaration,
+                    $crate::schema::Definition,
+                >,
+            ) {
+                let variants = $crate::maybestd::vec![$((
+                    $crate::dynamic::registered_tag::<dyn $trait, $type>().unwrap_or_else(|| {
+                        ::core::panic!(
+                            "{} is not registered for {}",
+                            ::core::stringify!($type),
+                            ::core::stringify!($trait)
+                        )
+                    }),
+                    <$type as $crate::schema::BorshSchema>::declaration(),
+                    <$type as $crate::schema::BorshSchema>::declaration(),
+                )),+];
+                let definition = $crate::schema::Definition::U32TaggedEnum { variants };
+                <Self as $crate::schema::BorshSchema>::add_definition(
+                    <Self as $crate::schema::BorshSchema>::declaration(),
+                    definition,
+                    definitions,
+                );
+                $(
+                    <$type as $crate::schema::BorshSchema>::add_definitions_recursively(definitions);
+                )+
+            }
+
+            fn declaration() -> $crate::schema::Declaration {
+                $crate::maybestd::format!("dyn {}", ::core::stringify!($trait))
+            }
+        }
+    };
+}
+
+struct Registry {
+    by_tag: HashMap<(TypeId, u32), &'static Registration>,
+    by_type: HashMap<(TypeId, TypeId), u32>,
+    /// The first conflict between the registrations of each trait.
+    conflicts: HashMap<TypeId, String>,
+}
+
+impl Registry {
+    /// Fails with the conflict between the registrations of the trait object type `T`, if any.
+    fn check<T: ?Sized + 'static>(&self) -> Result<()> {
+        match self.conflicts.get(&TypeId::of::<T>()) {
+            Some(conflict) => Err(Error::new(ErrorKind::InvalidInput, conflict.as_str())),
+            None => Ok(()),
+        }
+    }
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = Registry {
+            by_tag: HashMap::new(),
+            by_type: HashMap::new(),
+            conflicts: HashMap::new(),
+        };
+        for registration in inventory::iter::<Registration> {
+            let trait_id = (registration.trait_id)();
+            let conflict = match registry.by_tag.entry((trait_id, registration.tag)) {
+                Entry::Occupied(occ) => Some(format!(
+                    "Tag {} of {} is registered for both {} and {}",
+                    registration.tag,
+                    registration.trait_name,
+                    occ.get().type_name,
+                    registration.type_name
+                )),
+                Entry::Vacant(vac) => {
+                    vac.insert(registration);
+                    None
+                }
+            };
+            let type_id = (registration.type_id)();
+            let conflict = conflict.or_else(|| {
+                registry
+                    .by_type
+                    .insert((trait_id, type_id), registration.tag)
+                    .map(|_| {
+                        format!(
+                            "{} is registered for {} more than once",
+                            registration.type_name, registration.trait_name
+                        )
+                    })
+            });
+            if let Some(conflict) = conflict {
+                registry.conflicts.entry(trait_id).or_insert(conflict);
+            }
+        }
+        registry
+    })
+}
+
+/// Checks that no tag or type is registered twice for the same trait.
+///
+/// Such conflicts fail the (de)serialization of the objects of the trait; call this at startup to
+/// detect them early.
+pub fn validate_registry() -> Result<()> {
+    match registry().conflicts.values().next() {
+        Some(conflict) => Err(Error::new(ErrorKind::InvalidInput, conflict.as_str())),
+        None => Ok(()),
+    }
+}
+
+/// The tag that `U` is registered with for the trait object type `T`, such as `dyn Trait`.
+pub fn registered_tag<T, U>() -> Option<u32>
+where
+    T: ?Sized + 'static,
+    U: 'static,
+{
+    registry()
+        .by_type
+        .get(&(TypeId::of::<T>(), TypeId::of::<U>()))
+        .copied()
+}
+
+/// Serializes a trait object as the tag of its concrete type followed by the concrete value. Used
+/// by [`register_type!`](crate::register_type) and [`TaggedBox`].
+pub fn serialize_tagged<T, W>(value: &T, writer: &mut W) -> Result<()>
+where
+    T: ?Sized + BorshSerializeDyn + 'static,
+    W: Write,
+{
+    let registry = registry();
+    registry.check::<T>()?;
+    let tag = registry
+        .by_type
+        .get(&(TypeId::of::<T>(), value.concrete_type_id()))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} is not registered for {}",
+                    value.concrete_type_name(),
+                    type_name::<T>()
+                ),
+            )
+        })?;
+    tag.serialize(writer)?;
+    value.serialize_dyn(writer)
+}
+
+/// Deserializes a trait object written by [`serialize_tagged`], dispatching on the tag to the
+/// registered implementation.
+pub fn deserialize_tagged<T, R>(reader: &mut R) -> Result<Box<T>>
+where
+    T: ?Sized + 'static,
+    R: Read,
+{
+    let registry = registry();
+    registry.check::<T>()?;
+    let tag = u32::deserialize_reader(reader)?;
+    let registration = registry
+        .by_tag
+        .get(&(TypeId::of::<T>(), tag))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unknown tag {} for {}", tag, type_name::<T>()),
+            )
+        })?;
+    let value = (registration.deserialize)(reader)?;
+    match value.downcast::<Box<T>>() {
+        Ok(value) => Ok(*value),
+        Err(_) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "{} is registered with a different trait than {}",
+                registration.type_name,
+                type_name::<T>()
+            ),
+        )),
+    }
+}
+
+/// A boxed trait object serialized as the `u32` tag of its concrete type, followed by the concrete
+/// value. The concrete type must be registered with [`register_type!`](crate::register_type).
+///
+/// It is encoded like `Box<dyn Trait>` after `register_type!(Trait)`, and also serializes the
+/// objects of traits for which the ser/de implementations were not generated.
+pub struct TaggedBox<T: ?Sized>(pub Box<T>);
+
+impl<T: ?Sized> TaggedBox<T> {
+    pub fn new(value: Box<T>) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> Box<T> {
+        self.0
+    }
+}
+
+impl<T: ?Sized> From<Box<T>> for TaggedBox<T> {
+    fn from(value: Box<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: ?Sized> Deref for TaggedBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for TaggedBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for TaggedBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TaggedBox").field(&self.0).finish()
+    }
+}
+
+/// Declared like `T`, see [`borsh_trait_object_schema!`](crate::borsh_trait_object_schema).
+impl<T> BorshSchema for TaggedBox<T>
+where
+    T: ?Sized + BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+impl<T> BorshSerialize for TaggedBox<T>
+where
+    T: ?Sized + BorshSerializeDyn + 'static,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        serialize_tagged(&*self.0, writer)
+    }
+}
+
+impl<T> BorshDeserialize for TaggedBox<T>
+where
+    T: ?Sized + 'static,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        deserialize_tagged(reader).map(Self)
+    }
+}
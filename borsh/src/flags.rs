@@ -0,0 +1,127 @@
+//! Support for flag types generated by the [`bitflags`](https://docs.rs/bitflags) crate.
+//!
+//! A flags value is serialized as its underlying integer `bits()`, and its schema is the schema of
+//! that integer. Since `bitflags!` types are defined in user crates, the impls are generated with
+//! the [`impl_bitflags!`](crate::impl_bitflags) macro:
+//!
+//! ```
+//! use bitflags::bitflags;
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! bitflags! {
+//!     #[derive(Debug, PartialEq)]
+//!     struct Permissions: u8 {
+//!         const READ = 0b001;
+//!         const WRITE = 0b010;
+//!     }
+//! }
+//! borsh::impl_bitflags!(Permissions);
+//!
+//! let data = (Permissions::READ | Permissions::WRITE).try_to_vec().unwrap();
+//! assert_eq!(data, vec![0b011]);
+//! assert_eq!(
+//!     Permissions::try_from_slice(&[0b111]).unwrap(),
+//!     Permissions::READ | Permissions::WRITE
+//! );
+//! ```
+//!
+//! By default, unknown bits are dropped on deserialization, like `from_bits_truncate` does. Use
+//! `impl_bitflags!(Permissions, strict)` to reject them instead.
+use ::bitflags::Flags;
+
+use crate::maybestd::io::{Error, ErrorKind, Read, Result, Write};
+use crate::schema::Declaration;
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+const ERROR_UNKNOWN_FLAGS: &str = "Unknown bits set in flags";
+
+/// Serializes the underlying bits of the flags.
+pub fn serialize<F, W>(flags: &F, writer: &mut W) -> Result<()>
+where
+    F: Flags,
+    F::Bits: BorshSerialize,
+    W: Write,
+{
+    flags.bits().serialize(writer)
+}
+
+/// Deserializes the underlying bits of the flags, dropping the bits that do not correspond to any
+/// defined flag.
+pub fn deserialize<F, R>(reader: &mut R) -> Result<F>
+where
+    F: Flags,
+    F::Bits: BorshDeserialize,
+    R: Read,
+{
+    Ok(F::from_bits_truncate(F::Bits::deserialize_reader(reader)?))
+}
+
+/// Deserializes the underlying bits of the flags, returning an error if any of the bits does not
+/// correspond to a defined flag.
+pub fn deserialize_strict<F, R>(reader: &mut R) -> Result<F>
+where
+    F: Flags,
+    F::Bits: BorshDeserialize,
+    R: Read,
+{
+    F::from_bits(F::Bits::deserialize_reader(reader)?)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, ERROR_UNKNOWN_FLAGS))
+}
+
+/// The declaration of the flags, which is the declaration of the underlying integer.
+pub fn declaration<F>() -> Declaration
+where
+    F: Flags,
+    F::Bits: BorshSchema,
+{
+    F::Bits::declaration()
+}
+
+/// Implements `BorshSerialize`, `BorshDeserialize` and `BorshSchema` for a type generated with
+/// the `bitflags!` macro. See the [`flags`](crate::flags) module for an example.
+///
+/// `impl_bitflags!(MyFlags)` drops unknown bits on deserialization, while
+/// `impl_bitflags!(MyFlags, strict)` returns an error for them.
+#[macro_export]
+macro_rules! impl_bitflags {
+    ($type: ty) => {
+        $crate::impl_bitflags!(@impl $type, deserialize);
+    };
+    ($type: ty, strict) => {
+        $crate::impl_bitflags!(@impl $type, deserialize_strict);
+    };
+    (@impl $type: ty, $deserialize: ident) => {
+        impl $crate::BorshSerialize for $type {
+            #[inline]
+            fn serialize<W: $crate::maybestd::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> $crate::maybestd::io::Result<()> {
+                $crate::flags::serialize(self, writer)
+            }
+        }
+
+        impl $crate::BorshDeserialize for $type {
+            #[inline]
+            fn deserialize_reader<R: $crate::maybestd::io::Read>(
+                reader: &mut R,
+            ) -> $crate::maybestd::io::Result<Self> {
+                $crate::flags::$deserialize(reader)
+            }
+        }
+
+        impl $crate::BorshSchema for $type {
+            fn add_definitions_recursively(
+                _definitions: &mut $crate::maybestd::collections::HashMap<
+                    $crate::schema::Declaration,
+                    $crate::schema::Definition,
+                >,
+            ) {
+            }
+
+            fn declaration() -> $crate::schema::Declaration {
+                $crate::flags::declaration::<$type>()
+            }
+        }
+    };
+}
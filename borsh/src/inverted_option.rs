@@ -0,0 +1,106 @@
+//! An `Option` with the tags swapped, for protocols that encode `None` as `1` and `Some` as `0`.
+//!
+//! ```
+//! use borsh::{BorshDeserialize, BorshSerialize, InvertedOption};
+//!
+//! let value = InvertedOption(Some(7u8));
+//! let data = value.try_to_vec().unwrap();
+//! assert_eq!(data, vec![0, 7]);
+//! assert_eq!(InvertedOption(None::<u8>).try_to_vec().unwrap(), vec![1]);
+//! assert_eq!(InvertedOption::<u8>::try_from_slice(&data).unwrap(), value);
+//! ```
+use core::ops::{Deref, DerefMut};
+
+use crate::maybestd::{
+    collections::HashMap,
+    format,
+    io::{Error, ErrorKind, Read, Result, Write},
+    string::ToString,
+    vec,
+};
+use crate::schema::{Declaration, Definition};
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// The tag of `Some`.
+const SOME_TAG: u8 = 0;
+/// The tag of `None`.
+const NONE_TAG: u8 = 1;
+
+/// An `Option<T>` serialized with the tag `1` for `None` and `0` for `Some`, the opposite of
+/// `Option<T>`. Its schema is an enum with the variants `Some` and `None` in the order of their
+/// tags.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct InvertedOption<T>(pub Option<T>);
+
+impl<T> From<Option<T>> for InvertedOption<T> {
+    fn from(option: Option<T>) -> Self {
+        Self(option)
+    }
+}
+
+impl<T> From<InvertedOption<T>> for Option<T> {
+    fn from(option: InvertedOption<T>) -> Self {
+        option.0
+    }
+}
+
+impl<T> Deref for InvertedOption<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Option<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for InvertedOption<T> {
+    fn deref_mut(&mut self) -> &mut Option<T> {
+        &mut self.0
+    }
+}
+
+impl<T: BorshSerialize> BorshSerialize for InvertedOption<T> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match &self.0 {
+            None => NONE_TAG.serialize(writer),
+            Some(value) => {
+                SOME_TAG.serialize(writer)?;
+                value.serialize(writer)
+            }
+        }
+    }
+}
+
+impl<T: BorshDeserialize> BorshDeserialize for InvertedOption<T> {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        match u8::deserialize_reader(reader)? {
+            NONE_TAG => Ok(Self(None)),
+            SOME_TAG => Ok(Self(Some(T::deserialize_reader(reader)?))),
+            flag => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Invalid InvertedOption representation: {}. The first byte must be 0 or 1",
+                    flag
+                ),
+            )),
+        }
+    }
+}
+
+impl<T: BorshSchema> BorshSchema for InvertedOption<T> {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Enum {
+            variants: vec![
+                ("Some".to_string(), T::declaration()),
+                ("None".to_string(), <()>::declaration()),
+            ],
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"InvertedOption<{}>"#, T::declaration())
+    }
+}
@@ -7,16 +7,51 @@ extern crate alloc;
 
 pub use borsh_derive::{BorshDeserialize, BorshSchema, BorshSerialize};
 
+pub mod big_endian;
+pub mod bytes;
+pub mod chained_reader;
+pub mod checksum;
 pub mod de;
+pub mod debug;
+#[cfg(feature = "dyn")]
+#[clippy::msrv = "1.70"]
+pub mod dynamic;
+#[cfg(feature = "bitflags")]
+pub mod flags;
+pub mod inverted_option;
+pub mod nan;
+pub mod reader;
+pub mod recursive;
 pub mod schema;
 pub mod schema_helpers;
+pub mod seq_with_len;
 pub mod ser;
+pub mod strings;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+pub mod varint;
+pub mod versioned;
+pub mod wire_format;
 
+pub use chained_reader::ChainedReader;
+#[cfg(feature = "bytes")]
+pub use chained_reader::{from_buf, BytesReader};
+pub use checksum::{deserialize_with_checksum, serialize_with_checksum};
 pub use de::BorshDeserialize;
+pub use inverted_option::InvertedOption;
+pub use reader::{PositionedError, Reader};
 pub use schema::BorshSchema;
-pub use schema_helpers::{try_from_slice_with_schema, try_to_vec_with_schema};
-pub use ser::helpers::{to_vec, to_writer};
-pub use ser::BorshSerialize;
+pub use schema_helpers::{
+    declaration_of, schema_container_of, try_from_slice_with_schema, try_to_vec_with_schema,
+};
+pub use seq_with_len::SeqWithLen;
+pub use ser::helpers::{
+    assert_canonical_roundtrip, is_canonical, serialize_map_ordered, serialize_slice,
+    serialized_len, to_vec, to_vec_in, to_writer, to_writer_buffered,
+};
+pub use ser::{BorshSerialize, BorshTagged};
+pub use strings::{LossyString, RawString};
+pub use versioned::Versioned;
 
 /// A facade around all the types we need from the `std`, `core`, and `alloc`
 /// crates. This avoids elaborate import wrangling having to happen in every
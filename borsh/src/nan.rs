@@ -0,0 +1,77 @@
+//! Serialization of floats that allows NaN, encoded with a single canonical bit pattern.
+//!
+//! `f32` and `f64` do not serialize NaN, since its bit patterns are not portable. Fields with
+//! `#[borsh(canonical_nan)]` use [`CanonicalNan`] instead, which writes every NaN as the quiet NaN
+//! [`F32_CANONICAL_NAN`] or [`F64_CANONICAL_NAN`], so that equal values always have the same
+//! encoding:
+//!
+//! ```
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(BorshSerialize, BorshDeserialize)]
+//! struct Sample {
+//!     #[borsh(canonical_nan)]
+//!     value: f64,
+//! }
+//!
+//! let a = Sample { value: f64::NAN }.try_to_vec().unwrap();
+//! let b = Sample { value: -f64::NAN }.try_to_vec().unwrap();
+//! assert_eq!(a, b);
+//! assert!(Sample::try_from_slice(&a).unwrap().value.is_nan());
+//! ```
+//!
+//! The sign and the payload of a NaN, and whether it is signaling, are not preserved.
+//! Deserialization only accepts the canonical pattern, so every value still has a single encoding.
+use core::convert::TryInto;
+use core::mem::size_of;
+
+use crate::maybestd::io::{Error, ErrorKind, Read, Result, Write};
+
+const ERROR_NON_CANONICAL_NAN: &str = "NaN does not have the canonical bit pattern";
+
+/// The bit pattern that every `f32` NaN is serialized as: the positive quiet NaN without payload.
+pub const F32_CANONICAL_NAN: u32 = 0x7fc0_0000;
+/// The bit pattern that every `f64` NaN is serialized as: the positive quiet NaN without payload.
+pub const F64_CANONICAL_NAN: u64 = 0x7ff8_0000_0000_0000;
+
+/// (De)serialization of floats with NaN encoded as a canonical bit pattern, used by fields with
+/// `#[borsh(canonical_nan)]`. Values other than NaN are encoded like with `BorshSerialize`.
+pub trait CanonicalNan: Sized {
+    fn serialize_canonical_nan<W: Write>(&self, writer: &mut W) -> Result<()>;
+
+    /// Fails on NaN with a bit pattern other than the canonical one.
+    fn deserialize_canonical_nan<R: Read>(reader: &mut R) -> Result<Self>;
+}
+
+macro_rules! impl_for_float {
+    ($type: ident, $int_type: ident, $canonical: ident) => {
+        impl CanonicalNan for $type {
+            #[inline]
+            fn serialize_canonical_nan<W: Write>(&self, writer: &mut W) -> Result<()> {
+                let bits = if self.is_nan() {
+                    $canonical
+                } else {
+                    self.to_bits()
+                };
+                writer.write_all(&bits.to_le_bytes())
+            }
+
+            #[inline]
+            fn deserialize_canonical_nan<R: Read>(reader: &mut R) -> Result<Self> {
+                let mut buf = [0u8; size_of::<$type>()];
+                reader
+                    .read_exact(&mut buf)
+                    .map_err(crate::de::unexpected_eof_to_unexpected_length_of_input)?;
+                let bits = $int_type::from_le_bytes(buf.try_into().unwrap());
+                let res = $type::from_bits(bits);
+                if res.is_nan() && bits != $canonical {
+                    return Err(Error::new(ErrorKind::InvalidData, ERROR_NON_CANONICAL_NAN));
+                }
+                Ok(res)
+            }
+        }
+    };
+}
+
+impl_for_float!(f32, u32, F32_CANONICAL_NAN);
+impl_for_float!(f64, u64, F64_CANONICAL_NAN);
@@ -0,0 +1,157 @@
+//! A reader that keeps track of how many bytes were read, to report where deserialization failed.
+use core::fmt;
+
+use crate::de::ERROR_UNEXPECTED_LENGTH_OF_INPUT;
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind, Read, Result},
+};
+use crate::BorshDeserialize;
+
+/// Wraps a reader and counts the bytes read through it.
+///
+/// The position is the number of bytes read from the inner reader. [`Reader::deserialize`] reads
+/// the value through the reader, so on error the position is where the value stopped being read,
+/// unless an implementation reads ahead of the bytes it uses, e.g. through a `BufReader` of its
+/// own.
+///
+/// ```
+/// use borsh::{BorshDeserialize, BorshSerialize, Reader};
+///
+/// #[derive(BorshSerialize, BorshDeserialize, Debug)]
+/// struct Header {
+///     version: u8,
+///     length: u32,
+/// }
+///
+/// let data = Header { version: 1, length: 10 }.try_to_vec().unwrap();
+/// let mut reader = Reader::new(&data[..3]);
+/// let err = reader.deserialize::<Header>().unwrap_err();
+/// assert_eq!(err.position, 3);
+/// ```
+///
+/// On a slice, it also borrows bytes from the input, for hand-written parsers:
+///
+/// ```
+/// use borsh::{BorshSerialize, Reader};
+///
+/// let mut data = b"BRSH".to_vec();
+/// 7u32.serialize(&mut data).unwrap();
+/// data.extend_from_slice(&[0xaa; 3]);
+///
+/// let mut reader = Reader::new(data.as_slice());
+/// assert_eq!(reader.read_exact_n(4).unwrap(), b"BRSH");
+/// assert_eq!(reader.read_u32().unwrap(), 7);
+/// assert_eq!(reader.position(), 8);
+/// assert_eq!(reader.rest(), [0xaa; 3]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Reader<R> {
+    inner: R,
+    position: usize,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_position(inner, 0)
+    }
+
+    /// Starts counting at `position`, for a reader that does not start at the beginning of the
+    /// input.
+    pub fn with_position(inner: R, position: usize) -> Self {
+        Self { inner, position }
+    }
+
+    /// The number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        u8::deserialize_reader(self)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16> {
+        u16::deserialize_reader(self)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        u32::deserialize_reader(self)
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        u64::deserialize_reader(self)
+    }
+
+    /// Deserializes a value, returning the position at which it failed with the error.
+    pub fn deserialize<T: BorshDeserialize>(&mut self) -> core::result::Result<T, PositionedError> {
+        T::deserialize_reader(self).map_err(|error| PositionedError {
+            position: self.position,
+            error,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<'a> Reader<&'a [u8]> {
+    /// The number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// The bytes left to read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.inner
+    }
+
+    /// Reads the next `n` bytes, borrowed from the input.
+    pub fn read_exact_n(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.inner.len() < n {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                ERROR_UNEXPECTED_LENGTH_OF_INPUT,
+            ));
+        }
+        let (bytes, rest) = self.inner.split_at(n);
+        self.inner = rest;
+        self.position += n;
+        Ok(bytes)
+    }
+}
+
+impl<R: Read> Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// An error of [`Reader::deserialize`], with the number of bytes read when it occurred.
+#[derive(Debug)]
+pub struct PositionedError {
+    pub position: usize,
+    pub error: Error,
+}
+
+impl fmt::Display for PositionedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.error, self.position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PositionedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<PositionedError> for Error {
+    fn from(err: PositionedError) -> Self {
+        Error::new(err.error.kind(), format!("{}", err))
+    }
+}
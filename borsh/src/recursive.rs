@@ -0,0 +1,147 @@
+//! Serializing, deserializing and dropping long chains of boxed values without recursion.
+//!
+//! A type that links to another value of itself through a trailing `Option<Box<Self>>` field,
+//! such as a linked list or a chain of unary expressions, serializes each link followed by the
+//! next. The straightforward impls recurse once per link, and so does the `Drop` glue of `Box`,
+//! which overflows the stack on chains that are only a few thousand links deep. The functions of
+//! this module walk the chain in a loop instead, and write and read the same bytes as the
+//! recursive impls:
+//!
+//! ```
+//! use borsh::maybestd::io::{Read, Result, Write};
+//! use borsh::recursive::{self, Chain};
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! struct Node {
+//!     value: u32,
+//!     next: Option<Box<Node>>,
+//! }
+//!
+//! impl Chain for Node {
+//!     fn next(&self) -> Option<&Self> {
+//!         self.next.as_deref()
+//!     }
+//!
+//!     fn take_next(&mut self) -> Option<Box<Self>> {
+//!         self.next.take()
+//!     }
+//!
+//!     fn set_next(&mut self, next: Option<Box<Self>>) {
+//!         self.next = next;
+//!     }
+//! }
+//!
+//! impl BorshSerialize for Node {
+//!     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+//!         recursive::serialize_chain(self, writer, |node, writer| node.value.serialize(writer))
+//!     }
+//! }
+//!
+//! impl BorshDeserialize for Node {
+//!     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+//!         recursive::deserialize_chain(reader, |reader| {
+//!             Ok(Node { value: u32::deserialize_reader(reader)?, next: None })
+//!         })
+//!     }
+//! }
+//!
+//! impl Drop for Node {
+//!     fn drop(&mut self) {
+//!         recursive::drop_iteratively(self);
+//!     }
+//! }
+//!
+//! let mut list = Node { value: 0, next: None };
+//! for value in 1..100_000 {
+//!     list = Node { value, next: Some(Box::new(list)) };
+//! }
+//! let data = list.try_to_vec().unwrap();
+//! assert_eq!(data.len(), 100_000 * 5);
+//! assert_eq!(Node::try_from_slice(&data).unwrap().value, 99_999);
+//! ```
+use crate::de::limits;
+use crate::maybestd::{
+    boxed::Box,
+    format,
+    io::{Error, ErrorKind, Read, Result, Write},
+    vec::Vec,
+};
+use crate::{BorshDeserialize, BorshSerialize};
+
+/// A value whose last field is an optional link to the next value of the chain.
+pub trait Chain: Sized {
+    /// The next link, if any.
+    fn next(&self) -> Option<&Self>;
+
+    /// Takes the next link out, leaving `None` in its place.
+    fn take_next(&mut self) -> Option<Box<Self>>;
+
+    /// Replaces the next link.
+    fn set_next(&mut self, next: Option<Box<Self>>);
+}
+
+/// Writes each link of the chain that starts at `value`: `write_head` writes the fields of the
+/// link before the next one, followed by the tag of the `Option` of the next link.
+pub fn serialize_chain<T, W, F>(value: &T, writer: &mut W, mut write_head: F) -> Result<()>
+where
+    T: Chain,
+    W: Write,
+    F: FnMut(&T, &mut W) -> Result<()>,
+{
+    let mut link = Some(value);
+    while let Some(current) = link {
+        write_head(current, writer)?;
+        link = current.next();
+        link.is_some().serialize(writer)?;
+    }
+    Ok(())
+}
+
+/// Reads the chain written by [`serialize_chain`]: `read_head` reads the fields of a link before
+/// the next one and returns it without a next link, which is then read from the tag of its
+/// `Option`.
+///
+/// The links are read in a loop and kept until the last one, then linked from the last to the
+/// first. The chain counts as a single level of nesting for
+/// [`Limits::max_depth`](crate::de::Limits::max_depth).
+pub fn deserialize_chain<T, R, F>(reader: &mut R, mut read_head: F) -> Result<T>
+where
+    T: Chain,
+    R: Read,
+    F: FnMut(&mut R) -> Result<T>,
+{
+    let _depth = limits::enter()?;
+    let mut links = Vec::new();
+    loop {
+        links.push(read_head(reader)?);
+        match u8::deserialize_reader(reader)? {
+            0 => break,
+            1 => {}
+            flag => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Invalid Option representation: {}. The first byte must be 0 or 1",
+                        flag
+                    ),
+                ))
+            }
+        }
+    }
+    let mut chain = links.pop().expect("at least one link was read");
+    while let Some(mut link) = links.pop() {
+        link.set_next(Some(Box::new(chain)));
+        chain = link;
+    }
+    Ok(chain)
+}
+
+/// Unlinks the chain after `value` one link at a time, so that dropping it does not recurse.
+/// Call it from the `Drop` impl of `T`, which the drop of each unlinked link then calls with no
+/// next link.
+pub fn drop_iteratively<T: Chain>(value: &mut T) {
+    let mut next = value.take_next();
+    while let Some(mut link) = next {
+        next = link.take_next();
+    }
+}
@@ -0,0 +1,354 @@
+//! Generation of C declarations that describe the layout of Borsh data.
+use core::fmt::Write as _;
+
+use super::codegen::type_name;
+use super::{declarations, BorshSchemaContainer, Declaration, Definition, Fields};
+use crate::maybestd::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+const STRING_TYPE: &str = "BorshString";
+
+const C_KEYWORDS: [&str; 34] = [
+    "auto", "bool", "break", "case", "char", "const", "continue", "default", "do", "double",
+    "else", "enum", "extern", "float", "for", "goto", "if", "inline", "int", "long", "register",
+    "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef",
+    "union", "unsigned", "void", "while",
+];
+
+/// Generates C declarations approximating the layout of the data described by `container`, for
+/// code that reads Borsh data through FFI.
+///
+/// Every definition becomes a `struct`, named after its declaration like with
+/// [`generate_rust`](super::generate_rust). Primitives become the `stdint.h` types, with `bool` as
+/// `uint8_t` and `u128` and `i128` as `uint8_t[16]`, and arrays become C arrays. Enums become a
+/// `tag` field followed by a `union` of the variants. Strings and sequences are a length field and
+/// a pointer, since their elements are stored inline in Borsh data but cannot be in a C struct.
+///
+/// C structs have padding and Borsh data does not, so the structs describe the fields and their
+/// order rather than the byte offsets. The output only depends on the schema.
+///
+/// ```
+/// use borsh::schema::to_c_header;
+/// use borsh::BorshSchema;
+///
+/// #[derive(BorshSchema)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// assert!(to_c_header(&Point::schema_container()).contains(
+///     "struct Point {\n    int32_t x;\n    int32_t y;\n};"
+/// ));
+/// ```
+pub fn to_c_header(container: &BorshSchemaContainer) -> String {
+    let generator = CGenerator {
+        container,
+        emitted: BTreeSet::new(),
+        visiting: BTreeSet::new(),
+        uses_string: false,
+        body: String::new(),
+    };
+    generator.generate()
+}
+
+struct CGenerator<'a> {
+    container: &'a BorshSchemaContainer,
+    emitted: BTreeSet<&'a Declaration>,
+    /// Definitions being emitted, to stop at definitions that contain themselves by value.
+    visiting: BTreeSet<&'a Declaration>,
+    uses_string: bool,
+    body: String,
+}
+
+impl<'a> CGenerator<'a> {
+    fn generate(mut self) -> String {
+        // Arrays are written inline where they are used, like in C.
+        let mut declarations: Vec<&Declaration> = self
+            .container
+            .definitions
+            .iter()
+            .filter(|(declaration, definition)| {
+                !matches!(definition, Definition::Array { .. })
+                    || **declaration == self.container.declaration
+            })
+            .map(|(declaration, _)| declaration)
+            .collect();
+        declarations.sort();
+        for declaration in &declarations {
+            self.emit(declaration);
+        }
+
+        let mut out = String::new();
+        out.push_str("/* Generated by borsh::schema::to_c_header. */\n");
+        out.push_str("#include <stdint.h>\n");
+        let undefined = self.undefined();
+        if !undefined.is_empty() {
+            out.push_str("\n/* Types to define by hand:\n");
+            for declaration in undefined {
+                let _ = writeln!(out, " * - {} as {}", declaration, type_name(declaration));
+            }
+            out.push_str(" */\n");
+        }
+        if self.uses_string || !declarations.is_empty() {
+            out.push('\n');
+        }
+        if self.uses_string {
+            let _ = writeln!(out, "typedef struct {0} {0};", STRING_TYPE);
+        }
+        for declaration in &declarations {
+            let _ = writeln!(out, "typedef struct {0} {0};", type_name(declaration));
+        }
+        if self.uses_string {
+            let _ = write!(
+                out,
+                "\n/* string */\nstruct {} {{\n    uint32_t len;\n    uint8_t *data; /* UTF-8, not NUL-terminated */\n}};\n",
+                STRING_TYPE
+            );
+        }
+        out.push_str(&self.body);
+        out
+    }
+
+    /// The declarations without a definition that are not primitives.
+    fn undefined(&self) -> BTreeSet<&'a Declaration> {
+        let mut declarations = Vec::new();
+        declarations.push(&self.container.declaration);
+        for definition in self.container.definitions.values() {
+            declarations.extend(super::order::dependencies(definition));
+        }
+        declarations
+            .into_iter()
+            .filter(|declaration| {
+                !self.container.definitions.contains_key(*declaration)
+                    && primitive(declaration).is_none()
+            })
+            .collect()
+    }
+
+    /// Emits the definition of `declaration` after the definitions it contains by value.
+    fn emit(&mut self, declaration: &'a Declaration) {
+        let definition = match self.container.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return,
+        };
+        if self.emitted.contains(declaration) || !self.visiting.insert(declaration) {
+            return;
+        }
+        for mut dependency in by_value(definition) {
+            while let Some(Definition::Array { elements, .. }) =
+                self.container.definitions.get(dependency)
+            {
+                dependency = elements;
+            }
+            self.emit(dependency);
+        }
+        self.visiting.remove(declaration);
+        self.emitted.insert(declaration);
+
+        let name = type_name(declaration);
+        let mut body = format!("\n/* {} */\nstruct {} {{\n", declaration, name);
+        match definition {
+            Definition::Array { .. } => {
+                let (base, suffix) = self.c_type(declaration);
+                let _ = writeln!(body, "    {} items{};", base, suffix);
+            }
+            Definition::Sequence {
+                length_width,
+                elements,
+                ..
+            } => {
+                let (base, suffix) = self.c_type(elements);
+                let items = if suffix.is_empty() {
+                    format!("{} *items", base)
+                } else {
+                    format!("{} (*items){}", base, suffix)
+                };
+                if *length_width == 0 {
+                    let _ = writeln!(
+                        body,
+                        "    uint64_t len; /* not serialized, the items continue until the end of the data */"
+                    );
+                } else {
+                    let _ = writeln!(body, "    uint{}_t len;", *length_width as u32 * 8);
+                }
+                let _ = writeln!(body, "    {}; /* serialized inline after len */", items);
+            }
+            Definition::Tuple { elements } => {
+                for (index, element) in elements.iter().enumerate() {
+                    self.field(&mut body, &format!("_{}", index), element);
+                }
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    for (field, element) in fields {
+                        self.field(&mut body, &identifier(field), element);
+                    }
+                }
+                Fields::UnnamedFields(fields) => {
+                    for (index, element) in fields.iter().enumerate() {
+                        self.field(&mut body, &format!("_{}", index), element);
+                    }
+                }
+                Fields::Empty => {
+                    body.push_str("    uint8_t _empty; /* no fields, not serialized */\n");
+                }
+            },
+            Definition::Option { inner } => {
+                body.push_str("    uint8_t is_some;\n");
+                self.field(&mut body, "value", inner);
+            }
+            Definition::Enum { variants } => {
+                let tags: Vec<String> = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(tag, (variant, _))| format!("{}: {}", tag, variant))
+                    .collect();
+                let _ = writeln!(body, "    uint8_t tag; /* {} */", tags.join(", "));
+                self.union(&mut body, variants);
+            }
+            Definition::TaggedEnum { variants } => {
+                let tags: Vec<String> = variants
+                    .iter()
+                    .map(|(tag, variant, _)| format!("{}: {}", tag, variant))
+                    .collect();
+                let _ = writeln!(body, "    uint8_t tag; /* {} */", tags.join(", "));
+                let variants: Vec<(String, Declaration)> = variants
+                    .iter()
+                    .map(|(_, variant, payload)| (variant.clone(), payload.clone()))
+                    .collect();
+                self.union(&mut body, &variants);
+            }
+            Definition::U32TaggedEnum { variants } => {
+                let tags: Vec<String> = variants
+                    .iter()
+                    .map(|(tag, variant, _)| format!("{}: {}", tag, variant))
+                    .collect();
+                let _ = writeln!(body, "    uint32_t tag; /* {} */", tags.join(", "));
+                let variants: Vec<(String, Declaration)> = variants
+                    .iter()
+                    .map(|(_, variant, payload)| (variant.clone(), payload.clone()))
+                    .collect();
+                self.union(&mut body, &variants);
+            }
+            Definition::NamedEnum { variants } => {
+                self.uses_string = true;
+                let names: Vec<&str> = variants.iter().map(|(variant, _)| &variant[..]).collect();
+                let _ = writeln!(
+                    body,
+                    "    {} tag; /* the name of the variant: {} */",
+                    STRING_TYPE,
+                    names.join(", ")
+                );
+                self.union(&mut body, variants);
+            }
+        }
+        body.push_str("};\n");
+        self.body.push_str(&body);
+    }
+
+    fn union(&mut self, body: &mut String, variants: &[(String, Declaration)]) {
+        let mut members = String::new();
+        for (variant, payload) in variants {
+            if let Some(Definition::Struct {
+                fields: Fields::Empty,
+            }) = self.container.definitions.get(payload)
+            {
+                continue;
+            }
+            if payload == declarations::NIL {
+                continue;
+            }
+            let (base, suffix) = self.c_type(payload);
+            let _ = writeln!(
+                members,
+                "        {} {}{};",
+                base,
+                identifier(variant),
+                suffix
+            );
+        }
+        if !members.is_empty() {
+            let _ = write!(
+                body,
+                "    union {{\n{}    }} value; /* the variant of tag */\n",
+                members
+            );
+        }
+    }
+
+    fn field(&mut self, body: &mut String, name: &str, declaration: &Declaration) {
+        if declaration == declarations::NIL {
+            let _ = writeln!(body, "    /* {}: nil, not serialized */", name);
+            return;
+        }
+        let (base, suffix) = self.c_type(declaration);
+        let _ = writeln!(body, "    {} {}{};", base, name, suffix);
+    }
+
+    /// The C type of a declaration, as the type and the array dimensions that follow the name.
+    fn c_type(&mut self, declaration: &Declaration) -> (String, String) {
+        if declaration == declarations::STRING {
+            self.uses_string = true;
+            return (STRING_TYPE.to_string(), String::new());
+        }
+        if let Some(c_type) = primitive(declaration) {
+            return c_type;
+        }
+        match self.container.definitions.get(declaration) {
+            Some(Definition::Array { length, elements }) => {
+                let (base, suffix) = self.c_type(elements);
+                (base, format!("[{}]{}", length, suffix))
+            }
+            _ => (type_name(declaration), String::new()),
+        }
+    }
+}
+
+/// The declarations that a definition contains by value, as opposed to through a pointer.
+fn by_value(definition: &Definition) -> Vec<&Declaration> {
+    match definition {
+        Definition::Sequence { .. } => Vec::new(),
+        _ => super::order::dependencies(definition),
+    }
+}
+
+fn primitive(declaration: &str) -> Option<(String, String)> {
+    let (base, suffix) = match declaration {
+        declarations::BOOL | declarations::U8 => ("uint8_t", ""),
+        declarations::U16 => ("uint16_t", ""),
+        declarations::U32 => ("uint32_t", ""),
+        declarations::U64 => ("uint64_t", ""),
+        declarations::I8 => ("int8_t", ""),
+        declarations::I16 => ("int16_t", ""),
+        declarations::I32 => ("int32_t", ""),
+        declarations::I64 => ("int64_t", ""),
+        declarations::U128 | declarations::I128 => ("uint8_t", "[16]"),
+        declarations::F32 => ("float", ""),
+        declarations::F64 => ("double", ""),
+        declarations::STRING => (STRING_TYPE, ""),
+        declarations::NIL => ("void", ""),
+        _ => return None,
+    };
+    Some((base.to_string(), suffix.to_string()))
+}
+
+/// Turns the name of a field or variant into a C identifier.
+fn identifier(name: &str) -> String {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    if C_KEYWORDS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    }
+    identifier
+}
@@ -0,0 +1,375 @@
+//! Generation of Rust type definitions from a schema.
+use core::fmt::Write as _;
+
+use super::order::dependencies;
+use super::{declarations, BorshSchemaContainer, Declaration, Definition, Fields, VariantName};
+use crate::maybestd::{
+    collections::{BTreeSet, HashMap},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+const PRIMITIVES: [(&str, &str); 15] = [
+    (declarations::NIL, "()"),
+    (declarations::BOOL, "bool"),
+    (declarations::U8, "u8"),
+    (declarations::U16, "u16"),
+    (declarations::U32, "u32"),
+    (declarations::U64, "u64"),
+    (declarations::U128, "u128"),
+    (declarations::I8, "i8"),
+    (declarations::I16, "i16"),
+    (declarations::I32, "i32"),
+    (declarations::I64, "i64"),
+    (declarations::I128, "i128"),
+    (declarations::F32, "f32"),
+    (declarations::F64, "f64"),
+    (declarations::STRING, "String"),
+];
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "box", "try", "yield",
+];
+
+/// Generates the Rust source of types with the same Borsh encoding as the types described by
+/// `container`: a struct or an enum deriving `BorshSerialize`, `BorshDeserialize` and
+/// `BorshSchema` for each struct and enum definition.
+///
+/// Definitions with the shape and declaration of the types of the standard library, such as
+/// `Option<u64>`, `Vec<T>`, `VecDeque<T>`, `HashMap<K, V>` or `Result<T, E>`, become those types,
+/// and sequences with other length prefixes become [`SeqWithLen`](crate::SeqWithLen). Enum
+/// variants whose tags are not their positions get `#[borsh(tag = N)]`. The generated types are
+/// named after their declarations, with the declarations of generic types like `Pair<u8>` turned
+/// into identifiers like `PairU8`, so for types without generics the schema of the generated code
+/// is the one of `container`.
+///
+/// ```
+/// use borsh::schema::generate_rust;
+/// use borsh::BorshSchema;
+///
+/// #[derive(BorshSchema)]
+/// struct Account {
+///     owner: [u8; 32],
+///     balance: Option<u64>,
+/// }
+///
+/// let code = generate_rust(&Account::schema_container());
+/// assert!(code.contains("pub struct Account {\n    pub owner: [u8; 32],\n    pub balance: Option<u64>,\n}"));
+/// ```
+///
+/// Declarations without a definition that are not primitives, sequences without a length prefix
+/// or with a restricted range of lengths, and enums with `u32` tags have no Rust counterpart. They
+/// are used by name and listed in a comment at the top, for the caller to define.
+pub fn generate_rust(container: &BorshSchemaContainer) -> String {
+    let generator = Generator::new(container);
+    let mut items: Vec<(String, &Declaration)> = container
+        .definitions
+        .iter()
+        .filter(|(declaration, definition)| generator.is_item(declaration, definition))
+        .map(|(declaration, _)| (type_name(declaration), declaration))
+        .collect();
+    items.sort();
+
+    let mut out = String::new();
+    out.push_str("// Generated by borsh::schema::generate_rust.\n");
+    let undefined = generator.undefined();
+    if !undefined.is_empty() {
+        out.push_str("// Types to define by hand:\n");
+        for declaration in undefined {
+            let _ = writeln!(out, "// - {} as {}", declaration, type_name(declaration));
+        }
+    }
+    out.push_str("use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};\n");
+    for (name, declaration) in items {
+        out.push('\n');
+        generator.item(&mut out, &name, declaration);
+    }
+    out
+}
+
+struct Generator<'a> {
+    container: &'a BorshSchemaContainer,
+    /// Struct definitions that are the payload of a single enum variant, generated inside the
+    /// enum.
+    inlined: BTreeSet<&'a Declaration>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(container: &'a BorshSchemaContainer) -> Self {
+        let mut uses: HashMap<&Declaration, usize> = HashMap::new();
+        *uses.entry(&container.declaration).or_default() += 1;
+        for definition in container.definitions.values() {
+            for dependency in dependencies(definition) {
+                *uses.entry(dependency).or_default() += 1;
+            }
+        }
+        let mut inlined = BTreeSet::new();
+        for definition in container.definitions.values() {
+            for (_, _, payload) in variants(definition).unwrap_or_default() {
+                if uses[payload] == 1 {
+                    if let Some(Definition::Struct { .. }) = container.definitions.get(payload) {
+                        inlined.insert(payload);
+                    }
+                }
+            }
+        }
+        Self { container, inlined }
+    }
+
+    /// Whether the definition becomes a struct or an enum of its own.
+    fn is_item(&self, declaration: &Declaration, definition: &Definition) -> bool {
+        match definition {
+            Definition::Struct { .. } => {
+                !self.inlined.contains(declaration) && self.std_type(declaration).is_none()
+            }
+            Definition::Enum { .. }
+            | Definition::NamedEnum { .. }
+            | Definition::TaggedEnum { .. } => self.std_type(declaration).is_none(),
+            _ => false,
+        }
+    }
+
+    /// The declarations that are used by name because they cannot be generated.
+    fn undefined(&self) -> BTreeSet<&Declaration> {
+        let mut declarations = vec![&self.container.declaration];
+        for definition in self.container.definitions.values() {
+            declarations.extend(dependencies(definition));
+        }
+        declarations
+            .into_iter()
+            .filter(
+                |declaration| match self.container.definitions.get(*declaration) {
+                    None => !PRIMITIVES.iter().any(|(name, _)| name == declaration),
+                    Some(Definition::Sequence { .. }) => self.std_type(declaration).is_none(),
+                    Some(Definition::U32TaggedEnum { .. }) => true,
+                    Some(_) => false,
+                },
+            )
+            .collect()
+    }
+
+    /// The Rust type of a declaration.
+    fn rust_type(&self, declaration: &Declaration) -> String {
+        if let Some((_, rust)) = PRIMITIVES.iter().find(|(name, _)| name == declaration) {
+            return rust.to_string();
+        }
+        self.std_type(declaration)
+            .unwrap_or_else(|| type_name(declaration))
+    }
+
+    /// The Rust type of a definition that does not become an item of its own, if it has one.
+    fn std_type(&self, declaration: &Declaration) -> Option<String> {
+        let generic = |name: &str| declaration.starts_with(&format!("{}<", name));
+        Some(match self.container.definitions.get(declaration)? {
+            Definition::Option { inner } => format!("Option<{}>", self.rust_type(inner)),
+            Definition::Array { length, elements } => {
+                format!("[{}; {}]", self.rust_type(elements), length)
+            }
+            Definition::Tuple { elements } => {
+                let elements: Vec<String> = elements.iter().map(|e| self.rust_type(e)).collect();
+                if elements.len() == 1 {
+                    format!("({},)", elements[0])
+                } else {
+                    format!("({})", elements.join(", "))
+                }
+            }
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let prefix = match length_width {
+                    1 => "u8",
+                    2 => "u16",
+                    4 => "u32",
+                    8 => "u64",
+                    _ => return None,
+                };
+                let max = if *length_width == 8 {
+                    u64::MAX
+                } else {
+                    (1u64 << (*length_width * 8)) - 1
+                };
+                if *length_range != (0..=max) {
+                    return None;
+                }
+                let pair = || match self.container.definitions.get(elements) {
+                    Some(Definition::Tuple { elements }) if elements.len() == 2 => {
+                        Some((self.rust_type(&elements[0]), self.rust_type(&elements[1])))
+                    }
+                    _ => None,
+                };
+                let elements = self.rust_type(elements);
+                if *length_width != 4 || generic("SeqWithLen") {
+                    format!("borsh::SeqWithLen<{}, {}>", prefix, elements)
+                } else if generic("HashMap") || generic("BTreeMap") {
+                    let (key, value) = pair()?;
+                    let map = if generic("HashMap") {
+                        "HashMap"
+                    } else {
+                        "BTreeMap"
+                    };
+                    format!("borsh::maybestd::collections::{}<{}, {}>", map, key, value)
+                } else if generic("HashSet") {
+                    format!("borsh::maybestd::collections::HashSet<{}>", elements)
+                } else if generic("BTreeSet") {
+                    format!("borsh::maybestd::collections::BTreeSet<{}>", elements)
+                } else if generic("VecDeque") || generic("LinkedList") {
+                    let list = if generic("VecDeque") {
+                        "VecDeque"
+                    } else {
+                        "LinkedList"
+                    };
+                    format!("borsh::maybestd::collections::{}<{}>", list, elements)
+                } else {
+                    // Including `BinaryHeap`, whose elements would need to be `Ord`.
+                    format!("Vec<{}>", elements)
+                }
+            }
+            Definition::Enum { variants } if generic("Result") => match &variants[..] {
+                [(err, err_type), (ok, ok_type)] if err == "Err" && ok == "Ok" => format!(
+                    "Result<{}, {}>",
+                    self.rust_type(ok_type),
+                    self.rust_type(err_type)
+                ),
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    fn item(&self, out: &mut String, name: &str, declaration: &Declaration) {
+        out.push_str("#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]\n");
+        match &self.container.definitions[declaration] {
+            Definition::Struct { fields } => {
+                let _ = write!(out, "pub struct {}", name);
+                self.fields(out, fields, "pub ", "");
+                if !matches!(fields, Fields::NamedFields(_)) {
+                    out.push(';');
+                }
+                out.push('\n');
+            }
+            definition => {
+                let variants = variants(definition).expect("Only structs and enums are items");
+                if let Definition::NamedEnum { .. } = definition {
+                    out.push_str("#[borsh(named_variants)]\n");
+                }
+                let _ = writeln!(out, "pub enum {} {{", name);
+                for (tag, variant, payload) in variants {
+                    // Not an explicit discriminant, which variants with fields only have from
+                    // Rust 1.66.
+                    if let Some(tag) = tag {
+                        let _ = writeln!(out, "    #[borsh(tag = {})]", tag);
+                    }
+                    let _ = write!(out, "    {}", identifier(variant));
+                    match self.container.definitions.get(payload) {
+                        Some(Definition::Struct { fields }) if self.inlined.contains(payload) => {
+                            self.fields(out, fields, "", "    ");
+                        }
+                        _ => {
+                            let _ = write!(out, "({})", self.rust_type(payload));
+                        }
+                    }
+                    out.push_str(",\n");
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+
+    /// Writes the fields of a struct or of an enum variant, after its name.
+    fn fields(&self, out: &mut String, fields: &Fields, visibility: &str, indent: &str) {
+        match fields {
+            Fields::NamedFields(fields) => {
+                out.push_str(" {\n");
+                for (field, declaration) in fields {
+                    let _ = writeln!(
+                        out,
+                        "{}    {}{}: {},",
+                        indent,
+                        visibility,
+                        identifier(field),
+                        self.rust_type(declaration)
+                    );
+                }
+                let _ = write!(out, "{}}}", indent);
+            }
+            Fields::UnnamedFields(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|declaration| format!("{}{}", visibility, self.rust_type(declaration)))
+                    .collect();
+                let _ = write!(out, "({})", fields.join(", "));
+            }
+            Fields::Empty => {}
+        }
+    }
+}
+
+/// The variants of an enum definition, with their tags when they are not their positions.
+fn variants(definition: &Definition) -> Option<Vec<(Option<u8>, &VariantName, &Declaration)>> {
+    match definition {
+        Definition::Enum { variants } | Definition::NamedEnum { variants } => Some(
+            variants
+                .iter()
+                .map(|(variant, payload)| (None, variant, payload))
+                .collect(),
+        ),
+        Definition::TaggedEnum { variants } => Some(
+            variants
+                .iter()
+                .map(|(tag, variant, payload)| (Some(*tag), variant, payload))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Turns a declaration into a type name: `Pair<u8, Vec<u8>>` becomes `PairU8VecU8`.
+pub(super) fn type_name(declaration: &str) -> String {
+    let mut name = String::new();
+    for part in declaration
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|part| !part.is_empty())
+    {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.extend(chars);
+        }
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Turns the name of a field or variant into an identifier, escaping keywords.
+fn identifier(name: &str) -> String {
+    // The derive keeps the `r#` of raw identifiers in field names.
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    let mut identifier: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    match identifier.as_str() {
+        "self" | "Self" | "super" | "crate" => identifier.push('_'),
+        _ if KEYWORDS.contains(&identifier.as_str()) => identifier.insert_str(0, "r#"),
+        _ => {}
+    }
+    identifier
+}
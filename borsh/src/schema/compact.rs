@@ -0,0 +1,267 @@
+//! A smaller encoding of `BorshSchemaContainer` that stores every declaration once.
+use core::convert::TryFrom;
+use core::ops::RangeInclusive;
+
+use super::order::dependencies;
+use super::{BorshSchemaContainer, Declaration, Definition, FieldName, Fields, VariantName};
+use crate as borsh; // For `#[derive(BorshSerialize, BorshDeserialize)]`.
+use crate::maybestd::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{Error, ErrorKind, Result},
+    string::String,
+    vec::Vec,
+};
+use crate::{BorshDeserialize, BorshSerialize};
+
+const ERROR_INVALID_DECLARATION_INDEX: &str = "Declaration index is out of range";
+const ERROR_DUPLICATE_DEFINITION: &str = "Declaration is defined more than once";
+
+/// Index into `CompactContainer::declarations`.
+type Index = u32;
+
+/// `BorshSchemaContainer` with the declarations replaced by indices into a sorted table.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct CompactContainer {
+    declarations: Vec<Declaration>,
+    declaration: Index,
+    /// Sorted by index.
+    definitions: Vec<(Index, CompactDefinition)>,
+}
+
+/// `Definition` with the declarations replaced by indices, in the same order of variants.
+#[derive(BorshSerialize, BorshDeserialize)]
+enum CompactDefinition {
+    Array {
+        length: u32,
+        elements: Index,
+    },
+    Sequence {
+        length_width: u8,
+        #[borsh(with = "super::length_range")]
+        length_range: RangeInclusive<u64>,
+        elements: Index,
+    },
+    Tuple {
+        elements: Vec<Index>,
+    },
+    Enum {
+        variants: Vec<(VariantName, Index)>,
+    },
+    Struct {
+        fields: CompactFields,
+    },
+    Option {
+        inner: Index,
+    },
+    NamedEnum {
+        variants: Vec<(VariantName, Index)>,
+    },
+    TaggedEnum {
+        variants: Vec<(u8, VariantName, Index)>,
+    },
+    U32TaggedEnum {
+        variants: Vec<(u32, VariantName, Index)>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+enum CompactFields {
+    NamedFields(Vec<(FieldName, Index)>),
+    UnnamedFields(Vec<Index>),
+    Empty,
+}
+
+impl BorshSchemaContainer {
+    /// Serializes the container in a compact form, where every declaration is stored once in a
+    /// sorted table and referred to by its `u32` index. Schemas repeat declarations such as
+    /// `Tuple<u64, string>` many times, so this is usually much smaller than `try_to_vec`.
+    ///
+    /// The result is deterministic and is decoded by [`from_compact_bytes`](Self::from_compact_bytes).
+    pub fn to_compact_bytes(&self) -> Result<Vec<u8>> {
+        let mut table = BTreeSet::new();
+        table.insert(&self.declaration);
+        for (declaration, definition) in &self.definitions {
+            table.insert(declaration);
+            table.extend(dependencies(definition));
+        }
+        let indices = table
+            .iter()
+            .enumerate()
+            .map(|(index, declaration)| {
+                let index = Index::try_from(index).map_err(|_| ErrorKind::InvalidInput)?;
+                Ok((*declaration, index))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let index = |declaration: &Declaration| indices[declaration];
+
+        let mut definitions = self
+            .definitions
+            .iter()
+            .map(|(declaration, definition)| {
+                (index(declaration), compact_definition(definition, &index))
+            })
+            .collect::<Vec<_>>();
+        definitions.sort_by_key(|(index, _)| *index);
+
+        CompactContainer {
+            declarations: table.into_iter().cloned().collect(),
+            declaration: index(&self.declaration),
+            definitions,
+        }
+        .try_to_vec()
+    }
+
+    /// Decodes a container serialized by [`to_compact_bytes`](Self::to_compact_bytes).
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<Self> {
+        let compact = CompactContainer::try_from_slice(bytes)?;
+        let table = &compact.declarations;
+        let declaration = |index: Index| {
+            table
+                .get(index as usize)
+                .cloned()
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, ERROR_INVALID_DECLARATION_INDEX))
+        };
+
+        let mut definitions = HashMap::with_capacity(compact.definitions.len());
+        for (index, definition) in &compact.definitions {
+            let definition = expand_definition(definition, &declaration)?;
+            if definitions
+                .insert(declaration(*index)?, definition)
+                .is_some()
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    ERROR_DUPLICATE_DEFINITION,
+                ));
+            }
+        }
+        Ok(BorshSchemaContainer {
+            declaration: declaration(compact.declaration)?,
+            definitions,
+        })
+    }
+}
+
+fn compact_definition(
+    definition: &Definition,
+    index: &impl Fn(&Declaration) -> Index,
+) -> CompactDefinition {
+    let named = |items: &[(String, Declaration)]| {
+        items
+            .iter()
+            .map(|(name, declaration)| (name.clone(), index(declaration)))
+            .collect()
+    };
+    match definition {
+        Definition::Array { length, elements } => CompactDefinition::Array {
+            length: *length,
+            elements: index(elements),
+        },
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => CompactDefinition::Sequence {
+            length_width: *length_width,
+            length_range: length_range.clone(),
+            elements: index(elements),
+        },
+        Definition::Tuple { elements } => CompactDefinition::Tuple {
+            elements: elements.iter().map(index).collect(),
+        },
+        Definition::Enum { variants } => CompactDefinition::Enum {
+            variants: named(variants),
+        },
+        Definition::Struct { fields } => CompactDefinition::Struct {
+            fields: match fields {
+                Fields::NamedFields(fields) => CompactFields::NamedFields(named(fields)),
+                Fields::UnnamedFields(fields) => {
+                    CompactFields::UnnamedFields(fields.iter().map(index).collect())
+                }
+                Fields::Empty => CompactFields::Empty,
+            },
+        },
+        Definition::Option { inner } => CompactDefinition::Option {
+            inner: index(inner),
+        },
+        Definition::NamedEnum { variants } => CompactDefinition::NamedEnum {
+            variants: named(variants),
+        },
+        Definition::TaggedEnum { variants } => CompactDefinition::TaggedEnum {
+            variants: variants
+                .iter()
+                .map(|(tag, name, declaration)| (*tag, name.clone(), index(declaration)))
+                .collect(),
+        },
+        Definition::U32TaggedEnum { variants } => CompactDefinition::U32TaggedEnum {
+            variants: variants
+                .iter()
+                .map(|(tag, name, declaration)| (*tag, name.clone(), index(declaration)))
+                .collect(),
+        },
+    }
+}
+
+fn expand_definition(
+    definition: &CompactDefinition,
+    declaration: &impl Fn(Index) -> Result<Declaration>,
+) -> Result<Definition> {
+    let named = |items: &[(String, Index)]| {
+        items
+            .iter()
+            .map(|(name, index)| Ok((name.clone(), declaration(*index)?)))
+            .collect::<Result<Vec<_>>>()
+    };
+    let unnamed = |items: &[Index]| {
+        items
+            .iter()
+            .map(|index| declaration(*index))
+            .collect::<Result<Vec<_>>>()
+    };
+    Ok(match definition {
+        CompactDefinition::Array { length, elements } => Definition::Array {
+            length: *length,
+            elements: declaration(*elements)?,
+        },
+        CompactDefinition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => Definition::Sequence {
+            length_width: *length_width,
+            length_range: length_range.clone(),
+            elements: declaration(*elements)?,
+        },
+        CompactDefinition::Tuple { elements } => Definition::Tuple {
+            elements: unnamed(elements)?,
+        },
+        CompactDefinition::Enum { variants } => Definition::Enum {
+            variants: named(variants)?,
+        },
+        CompactDefinition::Struct { fields } => Definition::Struct {
+            fields: match fields {
+                CompactFields::NamedFields(fields) => Fields::NamedFields(named(fields)?),
+                CompactFields::UnnamedFields(fields) => Fields::UnnamedFields(unnamed(fields)?),
+                CompactFields::Empty => Fields::Empty,
+            },
+        },
+        CompactDefinition::Option { inner } => Definition::Option {
+            inner: declaration(*inner)?,
+        },
+        CompactDefinition::NamedEnum { variants } => Definition::NamedEnum {
+            variants: named(variants)?,
+        },
+        CompactDefinition::TaggedEnum { variants } => Definition::TaggedEnum {
+            variants: variants
+                .iter()
+                .map(|(tag, name, index)| Ok((*tag, name.clone(), declaration(*index)?)))
+                .collect::<Result<Vec<_>>>()?,
+        },
+        CompactDefinition::U32TaggedEnum { variants } => Definition::U32TaggedEnum {
+            variants: variants
+                .iter()
+                .map(|(tag, name, index)| Ok((*tag, name.clone(), declaration(*index)?)))
+                .collect::<Result<Vec<_>>>()?,
+        },
+    })
+}
@@ -0,0 +1,79 @@
+//! Detection of different definitions added under the same declaration.
+use core::fmt;
+
+use super::{Declaration, Definition};
+use crate::maybestd::boxed::Box;
+
+/// Two different definitions added under the same declaration, such as two types with the same
+/// name in different modules, or the variant `A::BC` and the variant `AB::C` of derived enums,
+/// whose structs are both declared as `ABC`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConflictingDefinitions {
+    pub declaration: Declaration,
+    /// The definition that was added first, and is kept.
+    pub existing: Box<Definition>,
+    pub conflicting: Box<Definition>,
+}
+
+impl fmt::Display for ConflictingDefinitions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Different types are declared as {}: {:?} and {:?}",
+            self.declaration, self.existing, self.conflicting
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConflictingDefinitions {}
+
+#[cfg(feature = "std")]
+mod collect {
+    use core::cell::RefCell;
+
+    use super::ConflictingDefinitions;
+    use crate::maybestd::vec::Vec;
+
+    std::thread_local! {
+        /// The conflicts of the `try_schema_container` calls running on this thread, innermost last.
+        static CONFLICTS: RefCell<Vec<Option<ConflictingDefinitions>>> = RefCell::new(Vec::new());
+    }
+
+    /// Runs `f`, returning the first conflict that it reported instead of panicking.
+    pub(crate) fn collecting<T>(f: impl FnOnce() -> T) -> (T, Option<ConflictingDefinitions>) {
+        struct Pop;
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                CONFLICTS.with(|conflicts| conflicts.borrow_mut().pop());
+            }
+        }
+        CONFLICTS.with(|conflicts| conflicts.borrow_mut().push(None));
+        let pop = Pop;
+        let result = f();
+        let conflict =
+            CONFLICTS.with(|conflicts| conflicts.borrow_mut().last_mut().and_then(Option::take));
+        drop(pop);
+        (result, conflict)
+    }
+
+    /// Records the conflict for the innermost `collecting` call, returning `false` if there is
+    /// none.
+    pub(crate) fn report(conflict: ConflictingDefinitions) -> bool {
+        CONFLICTS.with(|conflicts| match conflicts.borrow_mut().last_mut() {
+            Some(first) => {
+                first.get_or_insert(conflict);
+                true
+            }
+            None => false,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) use collect::{collecting, report};
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn report(_conflict: ConflictingDefinitions) -> bool {
+    false
+}
@@ -0,0 +1,102 @@
+//! Checking that the schema of a type describes the bytes that the type serializes to.
+use core::fmt;
+
+use super::{BorshSchema, Declaration};
+use crate::maybestd::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use crate::BorshSerialize;
+
+/// The serialized bytes of a value do not match the schema of its type, see
+/// [`check_consistency`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyError {
+    /// The offset in the serialized bytes of the start of the value of `declaration`.
+    pub offset: usize,
+    /// The declaration whose definition does not describe the bytes at `offset`.
+    pub declaration: Declaration,
+    /// The declarations of the values that contain the value of `declaration`, from the type
+    /// that was checked down to `declaration`.
+    pub path: Vec<Declaration>,
+    /// Why the bytes do not match the definition.
+    pub message: String,
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at byte {} of {} ({})",
+            self.message,
+            self.offset,
+            self.declaration,
+            self.path.join(" > ")
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConsistencyError {}
+
+/// Checks that the schema of `T` describes the bytes that `value` serializes to: they decode with
+/// [`BorshSchemaContainer::deserialize_value`](super::BorshSchemaContainer::deserialize_value)
+/// and no bytes are left over.
+///
+/// ```
+/// use borsh::schema::check_consistency;
+/// use borsh::{BorshSchema, BorshSerialize};
+///
+/// #[derive(BorshSerialize, BorshSchema)]
+/// struct Entry {
+///     key: u32,
+///     values: Option<Vec<String>>,
+/// }
+///
+/// check_consistency(&Entry { key: 1, values: Some(vec!["a".to_string()]) }).unwrap();
+/// ```
+///
+/// Derived impls always agree, the check is meant for tests of the types whose `BorshSerialize` or
+/// `BorshSchema` impl is written by hand. Only the bytes of `value` are checked, so the values
+/// should cover every variant and both `None` and `Some`.
+pub fn check_consistency<T>(value: &T) -> Result<(), ConsistencyError>
+where
+    T: BorshSerialize + BorshSchema + ?Sized,
+{
+    let container = T::schema_container();
+    let bytes = value.try_to_vec().map_err(|err| ConsistencyError {
+        offset: 0,
+        declaration: container.declaration.clone(),
+        path: vec![container.declaration.clone()],
+        message: format!("Failed to serialize: {}", err),
+    })?;
+    let mut cursor = bytes.as_slice();
+    match container.deserialize_value_traced(&mut cursor) {
+        Ok(_) if cursor.is_empty() => Ok(()),
+        Ok(_) => Err(ConsistencyError {
+            offset: bytes.len() - cursor.len(),
+            declaration: container.declaration.clone(),
+            path: vec![container.declaration.clone()],
+            message: format!(
+                "The schema describes {} of the {} serialized bytes",
+                bytes.len() - cursor.len(),
+                bytes.len()
+            ),
+        }),
+        Err((err, mut failure)) => {
+            failure.declarations.reverse();
+            Err(ConsistencyError {
+                offset: bytes.len() - failure.remaining,
+                declaration: failure
+                    .declarations
+                    .last()
+                    .cloned()
+                    .unwrap_or_else(|| container.declaration.clone()),
+                path: failure.declarations,
+                message: err.to_string(),
+            })
+        }
+    }
+}
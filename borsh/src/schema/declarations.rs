@@ -0,0 +1,27 @@
+//! The declarations of the primitive types, which have no definition in a schema container.
+//!
+//! `usize` and `isize` are declared as [`U64`] and [`I64`], and `str`, `String` and the other
+//! string types as [`STRING`].
+
+/// The unit type `()`, which takes no bytes.
+pub const NIL: &str = "nil";
+pub const BOOL: &str = "bool";
+pub const U8: &str = "u8";
+pub const U16: &str = "u16";
+pub const U32: &str = "u32";
+pub const U64: &str = "u64";
+pub const U128: &str = "u128";
+pub const I8: &str = "i8";
+pub const I16: &str = "i16";
+pub const I32: &str = "i32";
+pub const I64: &str = "i64";
+pub const I128: &str = "i128";
+pub const F32: &str = "f32";
+pub const F64: &str = "f64";
+/// UTF-8 text with a `u32` length prefix.
+pub const STRING: &str = "string";
+
+/// All the primitive declarations.
+pub const ALL: [&str; 15] = [
+    NIL, BOOL, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64, STRING,
+];
@@ -0,0 +1,61 @@
+//! Merging of definitions that have the same structure under different declarations.
+use super::order::dependencies_mut;
+use super::{BorshSchemaContainer, Declaration};
+use crate::maybestd::{
+    collections::{hash_map::Entry, HashMap},
+    vec::Vec,
+};
+use crate::BorshSerialize;
+
+impl BorshSchemaContainer {
+    /// Keeps a single declaration for each group of definitions that are identical once the
+    /// declarations they refer to are merged, and makes every reference use it. Field and variant
+    /// names are part of the definition, so only the declarations are ignored.
+    ///
+    /// This shrinks containers that describe the same shape under many names, e.g. before sending
+    /// them. The kept declaration is the one of the container when it is in the group, otherwise the
+    /// smallest one, so the remaining declarations may not match the names of the Rust types.
+    /// Definitions that only match through recursion are not merged.
+    pub fn dedup_structurally(&mut self) {
+        loop {
+            // The declaration of the container goes first so that it is always kept.
+            let mut declarations: Vec<&Declaration> = self.definitions.keys().collect();
+            declarations.sort();
+            if let Some(position) = declarations.iter().position(|d| **d == self.declaration) {
+                let declaration = declarations.remove(position);
+                declarations.insert(0, declaration);
+            }
+
+            // Definitions are compared through their serialization, which is cheap to hash.
+            let mut kept: HashMap<Vec<u8>, &Declaration> = HashMap::new();
+            let mut replacements = HashMap::new();
+            for declaration in declarations {
+                let key = self.definitions[declaration]
+                    .try_to_vec()
+                    .expect("Serializing to a Vec cannot fail");
+                match kept.entry(key) {
+                    Entry::Occupied(occ) => {
+                        replacements.insert(declaration.clone(), (*occ.get()).clone());
+                    }
+                    Entry::Vacant(vac) => {
+                        vac.insert(declaration);
+                    }
+                }
+            }
+            if replacements.is_empty() {
+                return;
+            }
+
+            for declaration in replacements.keys() {
+                self.definitions.remove(declaration);
+            }
+            for definition in self.definitions.values_mut() {
+                for dependency in dependencies_mut(definition) {
+                    if let Some(replacement) = replacements.get(dependency) {
+                        *dependency = replacement.clone();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Encoding of a [`Value`] as Borsh data with a schema, the inverse of its decoding.
+use core::convert::TryFrom;
+
+use super::value::{check_length, ERROR_MAX_DEPTH_EXCEEDED, MAX_DEPTH};
+use super::{declarations, BorshSchemaContainer, Declaration, Definition, Fields, Value};
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind, Result},
+    vec::Vec,
+};
+use crate::BorshSerialize;
+
+const ERROR_NAN: &str = "For portability reasons we do not allow to serialize NaNs";
+
+impl BorshSchemaContainer {
+    /// Encodes `value`, in the form produced by
+    /// [`deserialize_value`](Self::deserialize_value), as Borsh data of the container's type.
+    ///
+    /// ```
+    /// use borsh::{BorshSchema, BorshSerialize};
+    ///
+    /// #[derive(BorshSerialize, BorshSchema)]
+    /// struct Entry {
+    ///     key: u32,
+    ///     values: Option<Vec<String>>,
+    /// }
+    ///
+    /// let container = Entry::schema_container();
+    /// let data = Entry { key: 1, values: Some(vec!["a".to_string()]) }.try_to_vec().unwrap();
+    /// let value = container.value_from_slice(&data).unwrap();
+    /// assert_eq!(container.serialize_value(&value).unwrap(), data);
+    /// ```
+    ///
+    /// Fails if `value` does not match the definitions, e.g. a variant that the enum does not
+    /// have or a sequence length outside of its range, and on NaN floats.
+    pub fn serialize_value(&self, value: &Value) -> Result<Vec<u8>> {
+        let mut result = Vec::new();
+        self.encode_value(&self.declaration, value, &mut result, 0)?;
+        Ok(result)
+    }
+
+    fn encode_value(
+        &self,
+        declaration: &Declaration,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_MAX_DEPTH_EXCEEDED));
+        }
+        let depth = depth + 1;
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return encode_primitive(declaration, value, out),
+        };
+        match (definition, value) {
+            (Definition::Array { length, elements }, Value::Array(values)) => {
+                if values.len() as u64 != *length as u64 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Expected {} elements for {}, found {}",
+                            length,
+                            declaration,
+                            values.len()
+                        ),
+                    ));
+                }
+                for value in values {
+                    self.encode_value(elements, value, out, depth)?;
+                }
+            }
+            (
+                Definition::Sequence {
+                    length_width,
+                    length_range,
+                    elements,
+                },
+                Value::Sequence(values),
+            ) => {
+                let length = values.len() as u64;
+                check_length(length, length_range)?;
+                encode_length(*length_width, length, out)?;
+                for value in values {
+                    self.encode_value(elements, value, out, depth)?;
+                }
+            }
+            (Definition::Tuple { elements }, Value::Tuple(values))
+            | (
+                Definition::Struct {
+                    fields: Fields::UnnamedFields(elements),
+                },
+                Value::TupleStruct(values),
+            ) => {
+                if values.len() != elements.len() {
+                    return Err(mismatch(value, declaration));
+                }
+                for (element, value) in elements.iter().zip(values) {
+                    self.encode_value(element, value, out, depth)?;
+                }
+            }
+            (Definition::Enum { variants }, Value::Enum { variant, value }) => {
+                let tag = variants
+                    .iter()
+                    .position(|(name, _)| name == variant)
+                    .ok_or_else(|| unknown_variant(variant, declaration))?;
+                out.push(u8::try_from(tag).map_err(|_| ErrorKind::InvalidInput)?);
+                self.encode_value(&variants[tag].1, value, out, depth)?;
+            }
+            (Definition::TaggedEnum { variants }, Value::Enum { variant, value }) => {
+                let (tag, _, variant_declaration) = variants
+                    .iter()
+                    .find(|(_, name, _)| name == variant)
+                    .ok_or_else(|| unknown_variant(variant, declaration))?;
+                out.push(*tag);
+                self.encode_value(variant_declaration, value, out, depth)?;
+            }
+            (Definition::U32TaggedEnum { variants }, Value::Enum { variant, value }) => {
+                let (tag, _, variant_declaration) = variants
+                    .iter()
+                    .find(|(_, name, _)| name == variant)
+                    .ok_or_else(|| unknown_variant(variant, declaration))?;
+                tag.serialize(out)?;
+                self.encode_value(variant_declaration, value, out, depth)?;
+            }
+            (Definition::NamedEnum { variants }, Value::Enum { variant, value }) => {
+                let (name, variant_declaration) = variants
+                    .iter()
+                    .find(|(name, _)| name == variant)
+                    .ok_or_else(|| unknown_variant(variant, declaration))?;
+                name.serialize(out)?;
+                self.encode_value(variant_declaration, value, out, depth)?;
+            }
+            (
+                Definition::Struct {
+                    fields: Fields::NamedFields(fields),
+                },
+                Value::Struct(values),
+            ) => {
+                if values.len() != fields.len() {
+                    return Err(mismatch(value, declaration));
+                }
+                for ((name, field), (value_name, value)) in fields.iter().zip(values) {
+                    if name != value_name {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "Expected field {} of {}, found {}",
+                                name, declaration, value_name
+                            ),
+                        ));
+                    }
+                    self.encode_value(field, value, out, depth)?;
+                }
+            }
+            (
+                Definition::Struct {
+                    fields: Fields::Empty,
+                },
+                Value::UnitStruct,
+            ) => {}
+            (Definition::Option { inner }, Value::Option(value)) => match value {
+                None => out.push(0),
+                Some(value) => {
+                    out.push(1);
+                    self.encode_value(inner, value, out, depth)?;
+                }
+            },
+            _ => return Err(mismatch(value, declaration)),
+        }
+        Ok(())
+    }
+}
+
+fn encode_primitive(declaration: &str, value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    match (declaration, value) {
+        (declarations::NIL, Value::Nil) => Ok(()),
+        (declarations::BOOL, Value::Bool(value)) => value.serialize(out),
+        (declarations::U8, Value::U8(value)) => value.serialize(out),
+        (declarations::U16, Value::U16(value)) => value.serialize(out),
+        (declarations::U32, Value::U32(value)) => value.serialize(out),
+        (declarations::U64, Value::U64(value)) => value.serialize(out),
+        (declarations::U128, Value::U128(value)) => value.serialize(out),
+        (declarations::I8, Value::I8(value)) => value.serialize(out),
+        (declarations::I16, Value::I16(value)) => value.serialize(out),
+        (declarations::I32, Value::I32(value)) => value.serialize(out),
+        (declarations::I64, Value::I64(value)) => value.serialize(out),
+        (declarations::I128, Value::I128(value)) => value.serialize(out),
+        // Unlike the `BorshSerialize` impls, a value built by hand with a NaN is an error rather
+        // than a panic.
+        (declarations::F32, Value::F32(value)) if value.is_nan() => {
+            Err(Error::new(ErrorKind::InvalidInput, ERROR_NAN))
+        }
+        (declarations::F32, Value::F32(value)) => value.serialize(out),
+        (declarations::F64, Value::F64(value)) if value.is_nan() => {
+            Err(Error::new(ErrorKind::InvalidInput, ERROR_NAN))
+        }
+        (declarations::F64, Value::F64(value)) => value.serialize(out),
+        (declarations::STRING, Value::String(value)) => value.serialize(out),
+        _ if declarations::ALL.contains(&declaration) => Err(mismatch(value, declaration)),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Declaration {} is not defined in the schema", declaration),
+        )),
+    }
+}
+
+fn encode_length(width: u8, length: u64, out: &mut Vec<u8>) -> Result<()> {
+    // The length is already checked against the range of the sequence.
+    match width {
+        0 => Ok(()),
+        1 => (length as u8).serialize(out),
+        2 => (length as u16).serialize(out),
+        4 => (length as u32).serialize(out),
+        8 => length.serialize(out),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Unsupported sequence length width: {}", width),
+        )),
+    }
+}
+
+pub(super) fn unknown_variant(name: &str, declaration: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Unknown variant {} of {}", name, declaration),
+    )
+}
+
+fn mismatch(value: &Value, declaration: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Expected {}, found {:?}", declaration, value),
+    )
+}
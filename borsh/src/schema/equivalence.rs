@@ -0,0 +1,343 @@
+//! Comparison of schema containers by the shape of their definitions rather than their names.
+use core::fmt;
+
+use super::{BorshSchemaContainer, Declaration, Definition, Fields};
+use crate::maybestd::{
+    collections::HashSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// The first place where two containers compared with [`structural_difference`] differ.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StructuralDifference {
+    /// The path from the root of the containers to the differing definitions, such as
+    /// `items[].owner`, or `<root>` for the roots themselves.
+    pub path: String,
+    /// The declaration at `path` in the first container.
+    pub left: Declaration,
+    /// The declaration at `path` in the second container.
+    pub right: Declaration,
+    /// How the definitions differ.
+    pub reason: String,
+}
+
+impl fmt::Display for StructuralDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} and {} differ: {}",
+            self.path, self.left, self.right, self.reason
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StructuralDifference {}
+
+/// Whether `a` and `b` describe the same encoding, see [`structural_difference`].
+///
+/// ```
+/// use borsh::schema::structurally_equal;
+/// use borsh::BorshSchema;
+///
+/// #[derive(BorshSchema)]
+/// struct Account {
+///     owner: [u8; 32],
+///     balance: u64,
+/// }
+///
+/// mod v2 {
+///     #[derive(borsh::BorshSchema)]
+///     pub struct Wallet {
+///         pub owner: [u8; 32],
+///         pub balance: u64,
+///     }
+/// }
+///
+/// assert!(structurally_equal(
+///     &Account::schema_container(),
+///     &v2::Wallet::schema_container()
+/// ));
+/// ```
+pub fn structurally_equal(a: &BorshSchemaContainer, b: &BorshSchemaContainer) -> bool {
+    structural_difference(a, b).is_none()
+}
+
+/// Compares the definitions reachable from the declarations of `a` and `b`, and returns the first
+/// place where they differ, or `None` if they describe the same encoding.
+///
+/// The declarations of definitions are only names for the nodes of the graph, so a renamed type or
+/// a generic type declared with other parameters is equal to the original. Everything else must
+/// match: the kinds of definitions, lengths, length prefixes, the order of fields and variants, and
+/// the names of fields and variants, which conversions from and to other formats rely on.
+/// Declarations without a definition are primitives and are compared by name.
+///
+/// Recursive definitions are compared once per pair of declarations, so the comparison always
+/// terminates.
+pub fn structural_difference(
+    a: &BorshSchemaContainer,
+    b: &BorshSchemaContainer,
+) -> Option<StructuralDifference> {
+    let mut compared = HashSet::new();
+    let mut stack = vec![(String::new(), &a.declaration, &b.declaration)];
+    while let Some((path, left, right)) = stack.pop() {
+        if !compared.insert((left, right)) {
+            continue;
+        }
+        let children = match compare(a, b, left, right) {
+            Ok(children) => children,
+            Err(reason) => {
+                return Some(StructuralDifference {
+                    path: if path.is_empty() {
+                        "<root>".to_string()
+                    } else {
+                        path
+                    },
+                    left: left.clone(),
+                    right: right.clone(),
+                    reason,
+                })
+            }
+        };
+        // Reversed, so that the children are compared in order.
+        for (suffix, left, right) in children.into_iter().rev() {
+            stack.push((join(&path, &suffix), left, right));
+        }
+    }
+    None
+}
+
+/// The path suffix and the declarations of a pair of children to compare.
+type Children<'a> = Vec<(String, &'a Declaration, &'a Declaration)>;
+
+/// Compares the definitions of `left` and `right` without their children, which are returned.
+fn compare<'a>(
+    a: &'a BorshSchemaContainer,
+    b: &'a BorshSchemaContainer,
+    left: &'a Declaration,
+    right: &'a Declaration,
+) -> Result<Children<'a>, String> {
+    let (left_definition, right_definition) =
+        match (a.definitions.get(left), b.definitions.get(right)) {
+            (None, None) if left == right => return Ok(Vec::new()),
+            (None, None) => return Err("Different primitives".to_string()),
+            (None, Some(_)) | (Some(_), None) => {
+                return Err("Only one of them is defined".to_string())
+            }
+            (Some(left_definition), Some(right_definition)) => (left_definition, right_definition),
+        };
+    let mut children = Vec::new();
+    match (left_definition, right_definition) {
+        (
+            Definition::Array {
+                length: left_length,
+                elements: left_elements,
+            },
+            Definition::Array {
+                length: right_length,
+                elements: right_elements,
+            },
+        ) => {
+            if left_length != right_length {
+                return Err(format!("Lengths {} and {}", left_length, right_length));
+            }
+            children.push(("[]".to_string(), left_elements, right_elements));
+        }
+        (
+            Definition::Sequence {
+                length_width: left_width,
+                length_range: left_range,
+                elements: left_elements,
+            },
+            Definition::Sequence {
+                length_width: right_width,
+                length_range: right_range,
+                elements: right_elements,
+            },
+        ) => {
+            if left_width != right_width {
+                return Err(format!(
+                    "Length prefixes of {} and {} bytes",
+                    left_width, right_width
+                ));
+            }
+            if left_range != right_range {
+                return Err(format!(
+                    "Length ranges {:?} and {:?}",
+                    left_range, right_range
+                ));
+            }
+            children.push(("[]".to_string(), left_elements, right_elements));
+        }
+        (
+            Definition::Tuple {
+                elements: left_elements,
+            },
+            Definition::Tuple {
+                elements: right_elements,
+            },
+        ) => {
+            if left_elements.len() != right_elements.len() {
+                return Err(format!(
+                    "{} and {} elements",
+                    left_elements.len(),
+                    right_elements.len()
+                ));
+            }
+            for (index, (left, right)) in left_elements.iter().zip(right_elements).enumerate() {
+                children.push((index.to_string(), left, right));
+            }
+        }
+        (
+            Definition::Enum {
+                variants: left_variants,
+            },
+            Definition::Enum {
+                variants: right_variants,
+            },
+        )
+        | (
+            Definition::NamedEnum {
+                variants: left_variants,
+            },
+            Definition::NamedEnum {
+                variants: right_variants,
+            },
+        ) => {
+            compare_named(
+                left_variants
+                    .iter()
+                    .map(|(name, declaration)| (name, declaration)),
+                right_variants
+                    .iter()
+                    .map(|(name, declaration)| (name, declaration)),
+                "variant",
+                &mut children,
+            )?;
+        }
+        (
+            Definition::TaggedEnum {
+                variants: left_variants,
+            },
+            Definition::TaggedEnum {
+                variants: right_variants,
+            },
+        ) => {
+            let left_tags: Vec<u8> = left_variants.iter().map(|(tag, _, _)| *tag).collect();
+            let right_tags: Vec<u8> = right_variants.iter().map(|(tag, _, _)| *tag).collect();
+            if left_tags != right_tags {
+                return Err(format!("Tags {:?} and {:?}", left_tags, right_tags));
+            }
+            compare_named(
+                left_variants
+                    .iter()
+                    .map(|(_, name, declaration)| (name, declaration)),
+                right_variants
+                    .iter()
+                    .map(|(_, name, declaration)| (name, declaration)),
+                "variant",
+                &mut children,
+            )?;
+        }
+        (
+            Definition::U32TaggedEnum {
+                variants: left_variants,
+            },
+            Definition::U32TaggedEnum {
+                variants: right_variants,
+            },
+        ) => {
+            let left_tags: Vec<u32> = left_variants.iter().map(|(tag, _, _)| *tag).collect();
+            let right_tags: Vec<u32> = right_variants.iter().map(|(tag, _, _)| *tag).collect();
+            if left_tags != right_tags {
+                return Err(format!("Tags {:?} and {:?}", left_tags, right_tags));
+            }
+            compare_named(
+                left_variants
+                    .iter()
+                    .map(|(_, name, declaration)| (name, declaration)),
+                right_variants
+                    .iter()
+                    .map(|(_, name, declaration)| (name, declaration)),
+                "variant",
+                &mut children,
+            )?;
+        }
+        (
+            Definition::Struct {
+                fields: left_fields,
+            },
+            Definition::Struct {
+                fields: right_fields,
+            },
+        ) => match (left_fields, right_fields) {
+            (Fields::NamedFields(left_fields), Fields::NamedFields(right_fields)) => {
+                compare_named(
+                    left_fields
+                        .iter()
+                        .map(|(name, declaration)| (name, declaration)),
+                    right_fields
+                        .iter()
+                        .map(|(name, declaration)| (name, declaration)),
+                    "field",
+                    &mut children,
+                )?;
+            }
+            (Fields::UnnamedFields(left_fields), Fields::UnnamedFields(right_fields)) => {
+                if left_fields.len() != right_fields.len() {
+                    return Err(format!(
+                        "{} and {} fields",
+                        left_fields.len(),
+                        right_fields.len()
+                    ));
+                }
+                for (index, (left, right)) in left_fields.iter().zip(right_fields).enumerate() {
+                    children.push((index.to_string(), left, right));
+                }
+            }
+            (Fields::Empty, Fields::Empty) => {}
+            _ => return Err("Different kinds of fields".to_string()),
+        },
+        (Definition::Option { inner: left_inner }, Definition::Option { inner: right_inner }) => {
+            children.push(("Some".to_string(), left_inner, right_inner));
+        }
+        _ => return Err("Different kinds of definitions".to_string()),
+    }
+    Ok(children)
+}
+
+/// Compares the names of fields or variants, in order, and adds their declarations to
+/// `children`.
+fn compare_named<'a>(
+    left: impl ExactSizeIterator<Item = (&'a String, &'a Declaration)>,
+    right: impl ExactSizeIterator<Item = (&'a String, &'a Declaration)>,
+    kind: &str,
+    children: &mut Children<'a>,
+) -> Result<(), String> {
+    if left.len() != right.len() {
+        return Err(format!("{} and {} {}s", left.len(), right.len(), kind));
+    }
+    for ((left_name, left), (right_name, right)) in left.zip(right) {
+        if left_name != right_name {
+            return Err(format!(
+                "The {} {:?} is {:?} in the other one",
+                kind, left_name, right_name
+            ));
+        }
+        children.push((left_name.clone(), left, right));
+    }
+    Ok(())
+}
+
+fn join(path: &str, suffix: &str) -> String {
+    if path.is_empty() {
+        suffix.to_string()
+    } else if suffix == "[]" {
+        format!("{}{}", path, suffix)
+    } else {
+        format!("{}.{}", path, suffix)
+    }
+}
@@ -0,0 +1,338 @@
+//! Generation of random values of a schema, for property tests of Borsh implementations.
+use rand::Rng;
+
+use super::{declarations, BorshSchemaContainer, Declaration, Definition, Fields, Value};
+use crate::maybestd::{
+    boxed::Box,
+    collections::HashMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Bounds of the values produced by [`generate_value`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GenConfig {
+    /// How deep definitions are nested. Recursive definitions pick `None`, the shortest sequence
+    /// and the variant that ends the recursion soonest once they reach it.
+    pub max_depth: usize,
+    /// The most elements of a sequence, unless its definition requires more.
+    pub max_sequence_length: usize,
+    /// The most characters of a string.
+    pub max_string_length: usize,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_sequence_length: 8,
+            max_string_length: 16,
+        }
+    }
+}
+
+/// Generates a random value of the container's type.
+///
+/// ```
+/// use borsh::schema::{generate_value, GenConfig};
+/// use borsh::{BorshDeserialize, BorshSchema};
+/// use rand::SeedableRng;
+///
+/// #[derive(BorshDeserialize, BorshSchema)]
+/// enum Message {
+///     Ping,
+///     Text { from: u64, lines: Vec<String> },
+/// }
+///
+/// let container = Message::schema_container();
+/// let mut rng = rand::rngs::SmallRng::seed_from_u64(7);
+/// for _ in 0..10 {
+///     let value = generate_value(&container, &mut rng, &GenConfig::default());
+///     let data = container.serialize_value(&value).unwrap();
+///     Message::try_from_slice(&data).unwrap();
+/// }
+/// ```
+///
+/// The values cover the whole range of the primitives, including infinite and subnormal floats
+/// but not NaN, and strings of any characters. Maps and sets are sequences of entries in the
+/// schema, so their keys can repeat and are not sorted.
+///
+/// # Panics
+///
+/// If the container's type has no value of finite depth, e.g. a struct that contains itself
+/// without an `Option` or a sequence in between, or if it contains a declaration without a
+/// definition.
+pub fn generate_value<R: Rng + ?Sized>(
+    container: &BorshSchemaContainer,
+    rng: &mut R,
+    config: &GenConfig,
+) -> Value {
+    let mut generator = Generator {
+        container,
+        rng,
+        config,
+        min_depths: min_depths(container),
+    };
+    assert!(
+        generator.min_depth(&container.declaration).is_some(),
+        "{} has no value of finite depth in the schema",
+        container.declaration
+    );
+    generator.value(&container.declaration, 0)
+}
+
+/// The depth of the shallowest value of every definition, for the ones that have a value of
+/// finite depth.
+fn min_depths(container: &BorshSchemaContainer) -> HashMap<&str, usize> {
+    let mut depths = HashMap::new();
+    // The depths only decrease, so this stops after at most one pass per definition.
+    loop {
+        let mut changed = false;
+        for (declaration, definition) in &container.definitions {
+            let depth = match definition_depth(container, &depths, definition) {
+                Some(depth) => depth,
+                None => continue,
+            };
+            let shallower = match depths.get(declaration.as_str()) {
+                Some(old) => depth < *old,
+                None => true,
+            };
+            if shallower {
+                depths.insert(declaration.as_str(), depth);
+                changed = true;
+            }
+        }
+        if !changed {
+            return depths;
+        }
+    }
+}
+
+fn definition_depth(
+    container: &BorshSchemaContainer,
+    depths: &HashMap<&str, usize>,
+    definition: &Definition,
+) -> Option<usize> {
+    let depth = |declaration: &Declaration| declaration_depth(container, depths, declaration);
+    let inner = match definition {
+        Definition::Array { length: 0, .. } => Some(0),
+        Definition::Array { elements, .. } => depth(elements),
+        Definition::Sequence {
+            length_range,
+            elements,
+            ..
+        } => {
+            if *length_range.start() == 0 {
+                Some(0)
+            } else {
+                depth(elements)
+            }
+        }
+        Definition::Tuple { elements } => deepest(elements.iter().map(depth)),
+        Definition::Enum { variants } => shallowest(variants.iter().map(|(_, d)| depth(d))),
+        Definition::TaggedEnum { variants } => {
+            shallowest(variants.iter().map(|(_, _, d)| depth(d)))
+        }
+        Definition::U32TaggedEnum { variants } => {
+            shallowest(variants.iter().map(|(_, _, d)| depth(d)))
+        }
+        Definition::NamedEnum { variants } => shallowest(variants.iter().map(|(_, d)| depth(d))),
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => deepest(fields.iter().map(|(_, d)| depth(d))),
+            Fields::UnnamedFields(fields) => deepest(fields.iter().map(depth)),
+            Fields::Empty => Some(0),
+        },
+        Definition::Option { .. } => Some(0),
+    };
+    inner.map(|depth| depth + 1)
+}
+
+/// The depth of a value with all the `depths`, if they are all finite.
+fn deepest(mut depths: impl Iterator<Item = Option<usize>>) -> Option<usize> {
+    depths.try_fold(0, |max, depth| depth.map(|depth| max.max(depth)))
+}
+
+/// The depth of a value with one of the `depths`, if one of them is finite.
+fn shallowest(depths: impl Iterator<Item = Option<usize>>) -> Option<usize> {
+    depths.flatten().min()
+}
+
+fn declaration_depth(
+    container: &BorshSchemaContainer,
+    depths: &HashMap<&str, usize>,
+    declaration: &str,
+) -> Option<usize> {
+    if container.definitions.contains_key(declaration) {
+        depths.get(declaration).copied()
+    } else if declarations::ALL.contains(&declaration) {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+struct Generator<'a, R: ?Sized> {
+    container: &'a BorshSchemaContainer,
+    rng: &'a mut R,
+    config: &'a GenConfig,
+    min_depths: HashMap<&'a str, usize>,
+}
+
+impl<'a, R: Rng + ?Sized> Generator<'a, R> {
+    fn min_depth(&self, declaration: &str) -> Option<usize> {
+        declaration_depth(self.container, &self.min_depths, declaration)
+    }
+
+    /// Whether a value of `declaration` nested at `depth` fits into `max_depth`.
+    fn fits(&self, declaration: &str, depth: usize) -> bool {
+        match self.min_depth(declaration) {
+            Some(min_depth) => depth + min_depth <= self.config.max_depth,
+            None => false,
+        }
+    }
+
+    fn value(&mut self, declaration: &str, depth: usize) -> Value {
+        let container = self.container;
+        let definition = match container.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return self.primitive(declaration),
+        };
+        let depth = depth + 1;
+        match definition {
+            Definition::Array { length, elements } => {
+                Value::Array((0..*length).map(|_| self.value(elements, depth)).collect())
+            }
+            Definition::Sequence {
+                length_range,
+                elements,
+                ..
+            } => {
+                let start = *length_range.start();
+                let length = if start == 0 && !self.fits(elements, depth) {
+                    0
+                } else {
+                    let end = (*length_range.end())
+                        .min(start.max(self.config.max_sequence_length as u64));
+                    self.rng.random_range(start..=end)
+                };
+                Value::Sequence((0..length).map(|_| self.value(elements, depth)).collect())
+            }
+            Definition::Tuple { elements } => Value::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.value(element, depth))
+                    .collect(),
+            ),
+            Definition::Enum { variants } => {
+                let index = self.variant(variants.iter().map(|(_, d)| d), depth);
+                let (variant, variant_declaration) = &variants[index];
+                self.variant_value(variant, variant_declaration, depth)
+            }
+            Definition::TaggedEnum { variants } => {
+                let index = self.variant(variants.iter().map(|(_, _, d)| d), depth);
+                let (_, variant, variant_declaration) = &variants[index];
+                self.variant_value(variant, variant_declaration, depth)
+            }
+            Definition::U32TaggedEnum { variants } => {
+                let index = self.variant(variants.iter().map(|(_, _, d)| d), depth);
+                let (_, variant, variant_declaration) = &variants[index];
+                self.variant_value(variant, variant_declaration, depth)
+            }
+            Definition::NamedEnum { variants } => {
+                let index = self.variant(variants.iter().map(|(_, d)| d), depth);
+                let (variant, variant_declaration) = &variants[index];
+                self.variant_value(variant, variant_declaration, depth)
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => Value::Struct(
+                    fields
+                        .iter()
+                        .map(|(name, field)| (name.clone(), self.value(field, depth)))
+                        .collect(),
+                ),
+                Fields::UnnamedFields(fields) => Value::TupleStruct(
+                    fields
+                        .iter()
+                        .map(|field| self.value(field, depth))
+                        .collect(),
+                ),
+                Fields::Empty => Value::UnitStruct,
+            },
+            Definition::Option { inner } => {
+                if self.fits(inner, depth) && self.rng.random_bool(0.5) {
+                    Value::Option(Some(Box::new(self.value(inner, depth))))
+                } else {
+                    Value::Option(None)
+                }
+            }
+        }
+    }
+
+    /// Picks a random variant among the ones that fit into `max_depth`, or the shallowest one if
+    /// none does.
+    fn variant<'d>(
+        &mut self,
+        declarations: impl Iterator<Item = &'d Declaration>,
+        depth: usize,
+    ) -> usize {
+        let declarations: Vec<&Declaration> = declarations.collect();
+        let fitting: Vec<usize> = (0..declarations.len())
+            .filter(|index| self.fits(declarations[*index], depth))
+            .collect();
+        if fitting.is_empty() {
+            (0..declarations.len())
+                .filter_map(|index| Some((self.min_depth(declarations[index])?, index)))
+                .min()
+                .map(|(_, index)| index)
+                .expect("the enum has a value of finite depth")
+        } else {
+            fitting[self.rng.random_range(0..fitting.len())]
+        }
+    }
+
+    fn variant_value(&mut self, variant: &str, declaration: &str, depth: usize) -> Value {
+        Value::Enum {
+            variant: variant.to_string(),
+            value: Box::new(self.value(declaration, depth)),
+        }
+    }
+
+    fn primitive(&mut self, declaration: &str) -> Value {
+        match declaration {
+            declarations::NIL => Value::Nil,
+            declarations::BOOL => Value::Bool(self.rng.random()),
+            declarations::U8 => Value::U8(self.rng.random()),
+            declarations::U16 => Value::U16(self.rng.random()),
+            declarations::U32 => Value::U32(self.rng.random()),
+            declarations::U64 => Value::U64(self.rng.random()),
+            declarations::U128 => Value::U128(self.rng.random()),
+            declarations::I8 => Value::I8(self.rng.random()),
+            declarations::I16 => Value::I16(self.rng.random()),
+            declarations::I32 => Value::I32(self.rng.random()),
+            declarations::I64 => Value::I64(self.rng.random()),
+            declarations::I128 => Value::I128(self.rng.random()),
+            // Random bits rather than `random::<f32>()`, which only produces `0.0..1.0`.
+            declarations::F32 => loop {
+                let value = f32::from_bits(self.rng.random());
+                if !value.is_nan() {
+                    break Value::F32(value);
+                }
+            },
+            declarations::F64 => loop {
+                let value = f64::from_bits(self.rng.random());
+                if !value.is_nan() {
+                    break Value::F64(value);
+                }
+            },
+            declarations::STRING => {
+                let length = self.rng.random_range(0..=self.config.max_string_length);
+                Value::String(
+                    (0..length)
+                        .map(|_| self.rng.random::<char>())
+                        .collect::<String>(),
+                )
+            }
+            _ => panic!("Declaration {} is not defined in the schema", declaration),
+        }
+    }
+}
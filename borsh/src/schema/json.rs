@@ -0,0 +1,279 @@
+//! Conversion between Borsh data and JSON with a schema, without the Rust type that produced it.
+use core::convert::TryFrom;
+
+use serde_json::{Map, Number, Value as Json};
+
+use super::encode::unknown_variant;
+use super::value::{ERROR_MAX_DEPTH_EXCEEDED, MAX_DEPTH};
+use super::{declarations, BorshSchemaContainer, Declaration, Definition, Fields, Value};
+use crate::maybestd::{
+    boxed::Box,
+    format,
+    io::{Error, ErrorKind, Result},
+    string::ToString,
+    vec::Vec,
+};
+
+impl BorshSchemaContainer {
+    /// Decodes a value of the container's type from the beginning of `buf` into JSON and advances
+    /// `buf` past it.
+    ///
+    /// Structs become objects, tuples, arrays and sequences become arrays, `Option` becomes `null`
+    /// or the value, and enums use the externally-tagged form `{"Variant": value}`. Integers that
+    /// do not fit into the JSON numbers of `serde_json` (`u128` and `i128` outside of the `u64` and
+    /// `i64` ranges) become strings.
+    pub fn deserialize_to_json(&self, buf: &mut &[u8]) -> Result<Json> {
+        Ok(self.deserialize_value(buf)?.to_json())
+    }
+
+    /// Encodes JSON in the form produced by [`deserialize_to_json`](Self::deserialize_to_json)
+    /// as Borsh data of the container's type.
+    ///
+    /// The JSON is converted to a [`Value`] and encoded by
+    /// [`serialize_value`](Self::serialize_value).
+    pub fn serialize_json(&self, json: &Json) -> Result<Vec<u8>> {
+        let value = self.value_from_json(&self.declaration, json, 0)?;
+        self.serialize_value(&value)
+    }
+
+    /// The `Value` of `declaration` that `json` describes. Only the shape of `json` is checked
+    /// here, the lengths, ranges and numbers are checked by `serialize_value`.
+    fn value_from_json(
+        &self,
+        declaration: &Declaration,
+        json: &Json,
+        depth: usize,
+    ) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_MAX_DEPTH_EXCEEDED));
+        }
+        let depth = depth + 1;
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return primitive_from_json(declaration, json),
+        };
+        let items = |elements: &Declaration| {
+            expect_array(json, declaration)?
+                .iter()
+                .map(|item| self.value_from_json(elements, item, depth))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(match definition {
+            Definition::Array { elements, .. } => Value::Array(items(elements)?),
+            Definition::Sequence { elements, .. } => Value::Sequence(items(elements)?),
+            Definition::Tuple { elements } => {
+                Value::Tuple(self.unnamed_from_json(declaration, elements, json, depth)?)
+            }
+            Definition::Enum { variants } | Definition::NamedEnum { variants } => self
+                .variant_from_json(declaration, json, depth, |name| {
+                    variants
+                        .iter()
+                        .find(|(variant, _)| variant == name)
+                        .map(|(_, declaration)| declaration)
+                })?,
+            Definition::TaggedEnum { variants } => {
+                self.variant_from_json(declaration, json, depth, |name| {
+                    variants
+                        .iter()
+                        .find(|(_, variant, _)| variant == name)
+                        .map(|(_, _, declaration)| declaration)
+                })?
+            }
+            Definition::U32TaggedEnum { variants } => {
+                self.variant_from_json(declaration, json, depth, |name| {
+                    variants
+                        .iter()
+                        .find(|(_, variant, _)| variant == name)
+                        .map(|(_, _, declaration)| declaration)
+                })?
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    let object = json
+                        .as_object()
+                        .ok_or_else(|| mismatch(json, declaration))?;
+                    let values = fields
+                        .iter()
+                        .map(|(name, field)| {
+                            let value = object.get(name).ok_or_else(|| {
+                                Error::new(
+                                    ErrorKind::InvalidData,
+                                    format!("Missing field {} of {}", name, declaration),
+                                )
+                            })?;
+                            Ok((name.clone(), self.value_from_json(field, value, depth)?))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Value::Struct(values)
+                }
+                Fields::UnnamedFields(fields) => {
+                    Value::TupleStruct(self.unnamed_from_json(declaration, fields, json, depth)?)
+                }
+                Fields::Empty => {
+                    if !json.is_null() {
+                        return Err(mismatch(json, declaration));
+                    }
+                    Value::UnitStruct
+                }
+            },
+            Definition::Option { inner } => Value::Option(if json.is_null() {
+                None
+            } else {
+                Some(Box::new(self.value_from_json(inner, json, depth)?))
+            }),
+        })
+    }
+
+    /// The `Value::Enum` of the variant named in `json`, whose declaration `find` looks up among
+    /// the variants of `declaration`.
+    fn variant_from_json<'a>(
+        &self,
+        declaration: &Declaration,
+        json: &Json,
+        depth: usize,
+        find: impl Fn(&str) -> Option<&'a Declaration>,
+    ) -> Result<Value> {
+        let (name, value) = expect_variant(json, declaration)?;
+        let variant_declaration = find(name).ok_or_else(|| unknown_variant(name, declaration))?;
+        Ok(Value::Enum {
+            variant: name.to_string(),
+            value: Box::new(self.value_from_json(variant_declaration, value, depth)?),
+        })
+    }
+
+    fn unnamed_from_json(
+        &self,
+        declaration: &Declaration,
+        elements: &[Declaration],
+        json: &Json,
+        depth: usize,
+    ) -> Result<Vec<Value>> {
+        let items = expect_array(json, declaration)?;
+        if items.len() != elements.len() {
+            return Err(mismatch(json, declaration));
+        }
+        elements
+            .iter()
+            .zip(items)
+            .map(|(element, item)| self.value_from_json(element, item, depth))
+            .collect()
+    }
+}
+
+impl Value {
+    /// Converts the value to JSON, as described in
+    /// [`BorshSchemaContainer::deserialize_to_json`].
+    pub fn to_json(&self) -> Json {
+        match self {
+            Value::Nil | Value::UnitStruct => Json::Null,
+            Value::Bool(v) => Json::Bool(*v),
+            Value::U8(v) => Json::from(*v),
+            Value::U16(v) => Json::from(*v),
+            Value::U32(v) => Json::from(*v),
+            Value::U64(v) => Json::from(*v),
+            Value::U128(v) => match u64::try_from(*v) {
+                Ok(v) => Json::from(v),
+                Err(_) => Json::String(v.to_string()),
+            },
+            Value::I8(v) => Json::from(*v),
+            Value::I16(v) => Json::from(*v),
+            Value::I32(v) => Json::from(*v),
+            Value::I64(v) => Json::from(*v),
+            Value::I128(v) => match i64::try_from(*v) {
+                Ok(v) => Json::from(v),
+                Err(_) => Json::String(v.to_string()),
+            },
+            // Deserialization rejects NaN, the only value without a JSON number.
+            Value::F32(v) => Number::from_f64(*v as f64).map_or(Json::Null, Json::Number),
+            Value::F64(v) => Number::from_f64(*v).map_or(Json::Null, Json::Number),
+            Value::String(v) => Json::String(v.clone()),
+            Value::Array(values)
+            | Value::Sequence(values)
+            | Value::Tuple(values)
+            | Value::TupleStruct(values) => {
+                Json::Array(values.iter().map(Value::to_json).collect())
+            }
+            Value::Enum { variant, value } => {
+                let mut object = Map::new();
+                object.insert(variant.clone(), value.to_json());
+                Json::Object(object)
+            }
+            Value::Struct(fields) => Json::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.to_json()))
+                    .collect(),
+            ),
+            Value::Option(value) => value.as_ref().map_or(Json::Null, |value| value.to_json()),
+        }
+    }
+}
+
+/// A primitive value, or `Value::Nil` for a declaration that is not defined, which
+/// `serialize_value` rejects.
+fn primitive_from_json(declaration: &str, json: &Json) -> Result<Value> {
+    macro_rules! integer {
+        ($variant: ident) => {
+            Value::$variant(
+                match json {
+                    Json::Number(number) => number.to_string().parse().ok(),
+                    Json::String(string) => string.parse().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| mismatch(json, declaration))?,
+            )
+        };
+    }
+    Ok(match declaration {
+        declarations::NIL => match json {
+            Json::Null => Value::Nil,
+            _ => return Err(mismatch(json, declaration)),
+        },
+        declarations::BOOL => {
+            Value::Bool(json.as_bool().ok_or_else(|| mismatch(json, declaration))?)
+        }
+        declarations::U8 => integer!(U8),
+        declarations::U16 => integer!(U16),
+        declarations::U32 => integer!(U32),
+        declarations::U64 => integer!(U64),
+        declarations::U128 => integer!(U128),
+        declarations::I8 => integer!(I8),
+        declarations::I16 => integer!(I16),
+        declarations::I32 => integer!(I32),
+        declarations::I64 => integer!(I64),
+        declarations::I128 => integer!(I128),
+        declarations::F32 => {
+            Value::F32(json.as_f64().ok_or_else(|| mismatch(json, declaration))? as f32)
+        }
+        declarations::F64 => Value::F64(json.as_f64().ok_or_else(|| mismatch(json, declaration))?),
+        declarations::STRING => Value::String(
+            json.as_str()
+                .ok_or_else(|| mismatch(json, declaration))?
+                .to_string(),
+        ),
+        _ => Value::Nil,
+    })
+}
+
+fn expect_array<'a>(json: &'a Json, declaration: &str) -> Result<&'a Vec<Json>> {
+    json.as_array().ok_or_else(|| mismatch(json, declaration))
+}
+
+/// Accepts `{"Variant": value}`, and `"Variant"` for variants without fields.
+fn expect_variant<'a>(json: &'a Json, declaration: &str) -> Result<(&'a str, &'a Json)> {
+    match json {
+        Json::Object(object) if object.len() == 1 => {
+            let (name, value) = object.iter().next().unwrap();
+            Ok((name, value))
+        }
+        Json::String(name) => Ok((name, &Json::Null)),
+        _ => Err(mismatch(json, declaration)),
+    }
+}
+
+fn mismatch(json: &Json, declaration: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Expected {}, found {}", declaration, json),
+    )
+}
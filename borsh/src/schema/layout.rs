@@ -0,0 +1,154 @@
+//! Sizes and offsets of the values of definitions whose encoding has a fixed size.
+use super::{declarations, BorshSchemaContainer, Declaration, Definition, Fields};
+
+/// How deep [`fixed_size`] goes into nested definitions, which also stops it on recursive
+/// definitions.
+const MAX_DEPTH: usize = 512;
+
+/// Returns the byte offset of the named field `field_name` within the encoding of the struct
+/// `declaration`. The offset is only known when all the fields before it have a fixed size, so
+/// `None` is returned if one of them does not, or if `declaration` is not a struct with named
+/// fields that has a field `field_name`.
+///
+/// ```
+/// use borsh::schema::field_offset;
+/// use borsh::BorshSchema;
+///
+/// #[derive(BorshSchema)]
+/// struct Header {
+///     version: u8,
+///     timestamp: u64,
+///     payload: Vec<u8>,
+///     checksum: u32,
+/// }
+///
+/// let container = Header::schema_container();
+/// assert_eq!(field_offset(&container, "Header", "timestamp"), Some(1));
+/// assert_eq!(field_offset(&container, "Header", "payload"), Some(9));
+/// assert_eq!(field_offset(&container, "Header", "checksum"), None);
+/// ```
+pub fn field_offset(
+    container: &BorshSchemaContainer,
+    declaration: &str,
+    field_name: &str,
+) -> Option<u64> {
+    let fields = match container.definitions.get(declaration)? {
+        Definition::Struct {
+            fields: Fields::NamedFields(fields),
+        } => fields,
+        _ => return None,
+    };
+    let mut offset = 0u64;
+    for (name, field) in fields {
+        if name == field_name {
+            return Some(offset);
+        }
+        offset = offset.checked_add(fixed_size(container, field, 0)?)?;
+    }
+    None
+}
+
+/// Returns the number of bytes every value of `declaration` is encoded with, or `None` if the
+/// size depends on the value.
+pub(super) fn fixed_size(
+    container: &BorshSchemaContainer,
+    declaration: &str,
+    depth: usize,
+) -> Option<u64> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let depth = depth + 1;
+    let definition = match container.definitions.get(declaration) {
+        Some(definition) => definition,
+        None => return primitive_size(declaration),
+    };
+    match definition {
+        Definition::Array { length, elements } => {
+            fixed_size(container, elements, depth)?.checked_mul(*length as u64)
+        }
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => {
+            // Only a sequence of one allowed length with a length prefix has a fixed size.
+            if *length_width == 0 || length_range.start() != length_range.end() {
+                return None;
+            }
+            fixed_size(container, elements, depth)?
+                .checked_mul(*length_range.start())?
+                .checked_add(*length_width as u64)
+        }
+        Definition::Tuple { elements } => sum_sizes(container, elements.iter(), depth),
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => {
+                sum_sizes(container, fields.iter().map(|(_, field)| field), depth)
+            }
+            Fields::UnnamedFields(fields) => sum_sizes(container, fields.iter(), depth),
+            Fields::Empty => Some(0),
+        },
+        Definition::Enum { variants } => variants_size(
+            container,
+            1,
+            variants.iter().map(|(_, variant)| variant),
+            depth,
+        ),
+        Definition::TaggedEnum { variants } => variants_size(
+            container,
+            1,
+            variants.iter().map(|(_, _, variant)| variant),
+            depth,
+        ),
+        Definition::U32TaggedEnum { variants } => variants_size(
+            container,
+            4,
+            variants.iter().map(|(_, _, variant)| variant),
+            depth,
+        ),
+        Definition::Option { .. } | Definition::NamedEnum { .. } => None,
+    }
+}
+
+/// The size of the tag of `tag_width` bytes followed by a variant, when all variants have the same
+/// size.
+fn variants_size<'a>(
+    container: &BorshSchemaContainer,
+    tag_width: u64,
+    variants: impl Iterator<Item = &'a Declaration>,
+    depth: usize,
+) -> Option<u64> {
+    let mut size = None;
+    for variant in variants {
+        let variant_size = fixed_size(container, variant, depth)?;
+        if matches!(size, Some(size) if size != variant_size) {
+            return None;
+        }
+        size = Some(variant_size);
+    }
+    size?.checked_add(tag_width)
+}
+
+fn sum_sizes<'a>(
+    container: &BorshSchemaContainer,
+    declarations: impl Iterator<Item = &'a Declaration>,
+    depth: usize,
+) -> Option<u64> {
+    let mut size = 0u64;
+    for declaration in declarations {
+        size = size.checked_add(fixed_size(container, declaration, depth)?)?;
+    }
+    Some(size)
+}
+
+fn primitive_size(declaration: &str) -> Option<u64> {
+    Some(match declaration {
+        declarations::NIL => 0,
+        declarations::BOOL | declarations::U8 | declarations::I8 => 1,
+        declarations::U16 | declarations::I16 => 2,
+        declarations::U32 | declarations::I32 | declarations::F32 => 4,
+        declarations::U64 | declarations::I64 | declarations::F64 => 8,
+        declarations::U128 | declarations::I128 => 16,
+        _ => return None,
+    })
+}
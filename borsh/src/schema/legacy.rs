@@ -0,0 +1,68 @@
+//! Decoding of schema containers serialized before `Definition::Sequence` recorded the width and
+//! the range of its length prefix, i.e. by borsh 0.10 and earlier.
+use super::{BorshSchemaContainer, Declaration, Definition, Fields, VariantName};
+use crate as borsh; // For `#[derive(BorshDeserialize)]`.
+use crate::maybestd::{collections::HashMap, io::Result, vec::Vec};
+use crate::BorshDeserialize;
+
+#[derive(BorshDeserialize)]
+struct LegacyContainer {
+    declaration: Declaration,
+    definitions: HashMap<Declaration, LegacyDefinition>,
+}
+
+/// `Definition` as it was serialized by borsh 0.10, with the same tags.
+#[derive(BorshDeserialize)]
+enum LegacyDefinition {
+    Array {
+        length: u32,
+        elements: Declaration,
+    },
+    Sequence {
+        elements: Declaration,
+    },
+    Tuple {
+        elements: Vec<Declaration>,
+    },
+    Enum {
+        variants: Vec<(VariantName, Declaration)>,
+    },
+    Struct {
+        fields: Fields,
+    },
+}
+
+impl BorshSchemaContainer {
+    /// Decodes a container serialized by borsh 0.10 and earlier, whose `Definition::Sequence` only
+    /// holds the declaration of the elements.
+    ///
+    /// Those sequences always had a `u32` length prefix, so they are decoded with
+    /// [`Definition::DEFAULT_LENGTH_WIDTH`] and [`Definition::DEFAULT_LENGTH_RANGE`]. The other
+    /// definitions are unchanged. Re-serializing the result with `try_to_vec` gives the current
+    /// encoding.
+    pub fn from_legacy_bytes(bytes: &[u8]) -> Result<Self> {
+        let legacy = LegacyContainer::try_from_slice(bytes)?;
+        Ok(BorshSchemaContainer {
+            declaration: legacy.declaration,
+            definitions: legacy
+                .definitions
+                .into_iter()
+                .map(|(declaration, definition)| (declaration, upgrade(definition)))
+                .collect(),
+        })
+    }
+}
+
+fn upgrade(definition: LegacyDefinition) -> Definition {
+    match definition {
+        LegacyDefinition::Array { length, elements } => Definition::Array { length, elements },
+        LegacyDefinition::Sequence { elements } => Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements,
+        },
+        LegacyDefinition::Tuple { elements } => Definition::Tuple { elements },
+        LegacyDefinition::Enum { variants } => Definition::Enum { variants },
+        LegacyDefinition::Struct { fields } => Definition::Struct { fields },
+    }
+}
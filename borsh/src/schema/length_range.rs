@@ -0,0 +1,31 @@
+//! The encoding of `Definition::Sequence::length_range`, for `#[borsh(with = "length_range")]`.
+//!
+//! The bounds are written as two `u64`s, the start first, without implementing Borsh for
+//! `RangeInclusive` itself.
+use core::ops::RangeInclusive;
+
+use super::{Declaration, Definition};
+use crate::maybestd::{
+    collections::HashMap,
+    io::{Read, Result, Write},
+};
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+pub(crate) fn serialize<W: Write>(range: &RangeInclusive<u64>, writer: &mut W) -> Result<()> {
+    range.start().serialize(writer)?;
+    range.end().serialize(writer)
+}
+
+pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<RangeInclusive<u64>> {
+    let start = u64::deserialize_reader(reader)?;
+    let end = u64::deserialize_reader(reader)?;
+    Ok(RangeInclusive::new(start, end))
+}
+
+pub(crate) fn declaration() -> Declaration {
+    <(u64, u64)>::declaration()
+}
+
+pub(crate) fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+    <(u64, u64)>::add_definitions_recursively(definitions);
+}
@@ -0,0 +1,1388 @@
+//! Since Borsh is not a self-descriptive format we have a way to describe types serialized with Borsh so that
+//! we can deserialize serialized blobs without having Rust types available. Additionally, this can be used to
+//! serialize content provided in a different format, e.g. JSON object `{"user": "alice", "message": "Message"}`
+//! can be serialized by JS code into Borsh format such that it can be deserialized into `struct UserMessage {user: String, message: String}`
+//! on Rust side.
+//!
+//! The important components are: `BorshSchema` trait, `Definition` and `Declaration` types, and `BorshSchemaContainer` struct.
+//! * `BorshSchema` trait allows any type that implements it to be self-descriptive, i.e. generate it's own schema;
+//! * `Declaration` is used to describe the type identifier, e.g. `HashMap<u64, String>`;
+//! * `Definition` is used to describe the structure of the type;
+//! * `BorshSchemaContainer` is used to store all declarations and defintions that are needed to work with a single type.
+//! * `Value` is what `BorshSchemaContainer::deserialize_value` decodes Borsh data into when the Rust type is not available.
+
+#![allow(dead_code)] // Unclear why rust check complains on fields of `Definition` variants.
+use crate as borsh; // For `#[derive(BorshSerialize, BorshDeserialize)]`.
+use crate::maybestd::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use crate::maybestd::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    collections::{hash_map::Entry, HashMap, HashSet},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use crate::{BorshDeserialize, BorshSchema as BorshSchemaMacro, BorshSerialize};
+use core::marker::PhantomData;
+use core::ops::RangeInclusive;
+
+#[cfg(feature = "rc")]
+use crate::maybestd::{rc::Rc, sync::Arc};
+
+#[cfg(feature = "codegen")]
+mod c_header;
+#[cfg(feature = "codegen")]
+mod codegen;
+mod compact;
+mod conflict;
+mod consistency;
+pub mod declarations;
+mod dedup;
+mod encode;
+mod equivalence;
+#[cfg(feature = "rand")]
+mod generate;
+#[cfg(feature = "json")]
+mod json;
+mod layout;
+mod legacy;
+mod length_range;
+mod order;
+mod reachable;
+mod rename;
+mod skip;
+mod stream;
+mod validate;
+pub(crate) mod value;
+#[cfg(feature = "codegen")]
+pub use c_header::to_c_header;
+#[cfg(feature = "codegen")]
+pub use codegen::generate_rust;
+pub use conflict::ConflictingDefinitions;
+pub use consistency::{check_consistency, ConsistencyError};
+pub use equivalence::{structural_difference, structurally_equal, StructuralDifference};
+#[cfg(feature = "rand")]
+pub use generate::{generate_value, GenConfig};
+pub use layout::field_offset;
+pub use order::CycleError;
+pub use skip::{skip_value, skip_value_checked};
+pub use stream::{SchemaReader, SchemaWriter};
+pub use validate::{assert_transparent_wrappers_consistent, ValidationError};
+pub use value::Value;
+
+/// The type that we use to represent the declaration of the Borsh type.
+pub type Declaration = String;
+/// The type that we use for the name of the variant.
+pub type VariantName = String;
+/// The name of the field in the struct (can be used to convert JSON to Borsh using the schema).
+pub type FieldName = String;
+/// The type that we use to represent the definition of the Borsh type.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize, BorshSchemaMacro)]
+pub enum Definition {
+    /// A fixed-size array with the length known at the compile time and the same-type elements.
+    Array { length: u32, elements: Declaration },
+    /// A sequence of elements of length known at the run time and the same-type elements.
+    Sequence {
+        /// How many bytes the little-endian length prefix takes: 1, 2, 4 or 8. Width 0 means that
+        /// there is no length prefix and the elements continue until the end of the input.
+        length_width: u8,
+        /// The range of lengths the sequence is allowed to have.
+        #[borsh(with = "length_range", schema(with = "length_range"))]
+        length_range: RangeInclusive<u64>,
+        elements: Declaration,
+    },
+    /// A fixed-size tuple with the length known at the compile time and the elements of different
+    /// types.
+    Tuple { elements: Vec<Declaration> },
+    /// A tagged union, a.k.a enum. Tagged-unions have variants with associated structures.
+    Enum {
+        variants: Vec<(VariantName, Declaration)>,
+    },
+    /// A structure, structurally similar to a tuple.
+    Struct { fields: Fields },
+    /// An optional value, encoded as a `u8` tag (`0` for absent, `1` for present) followed by the
+    /// value when it is present. Kept as the last variant so that the tags of the other variants
+    /// do not change for already serialized containers.
+    Option { inner: Declaration },
+    /// A tagged union whose tag is the name of the variant, encoded as a string. Used by enums
+    /// with `#[borsh(named_variants)]`.
+    NamedEnum {
+        variants: Vec<(VariantName, Declaration)>,
+    },
+    /// A tagged union whose variants have `u8` tags other than their positions, like enums with
+    /// explicit discriminants. The variants are listed in declaration order with their tags.
+    TaggedEnum {
+        variants: Vec<(u8, VariantName, Declaration)>,
+    },
+    /// A tagged union with a little-endian `u32` tag, like
+    /// [`TaggedBox<dyn Trait>`](crate::dynamic::TaggedBox) whose variants are the registered
+    /// implementations of the trait.
+    U32TaggedEnum {
+        variants: Vec<(u32, VariantName, Declaration)>,
+    },
+}
+
+impl Definition {
+    /// Width of the `u32` length prefix used by the built-in sequences.
+    pub const DEFAULT_LENGTH_WIDTH: u8 = 4;
+    /// Lengths representable by the `u32` length prefix used by the built-in sequences.
+    pub const DEFAULT_LENGTH_RANGE: RangeInclusive<u64> = RangeInclusive::new(0, u32::MAX as u64);
+
+    /// The definition of an enum whose variants have the given tags: `Definition::Enum` when each
+    /// tag is the position of its variant, as for enums without explicit discriminants, and
+    /// `Definition::TaggedEnum` otherwise.
+    pub fn enum_with_tags(variants: Vec<(u8, VariantName, Declaration)>) -> Self {
+        let positional = variants
+            .iter()
+            .enumerate()
+            .all(|(position, (tag, _, _))| *tag as usize == position);
+        if positional {
+            Definition::Enum {
+                variants: variants
+                    .into_iter()
+                    .map(|(_, name, declaration)| (name, declaration))
+                    .collect(),
+            }
+        } else {
+            Definition::TaggedEnum { variants }
+        }
+    }
+}
+
+/// The collection representing the fields of a struct.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize, BorshSchemaMacro)]
+pub enum Fields {
+    /// The struct with named fields.
+    NamedFields(Vec<(FieldName, Declaration)>),
+    /// The struct with unnamed fields, structurally identical to a tuple.
+    UnnamedFields(Vec<Declaration>),
+    /// The struct with no fields.
+    Empty,
+}
+
+/// All schema information needed to deserialize a single type.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize, BorshSchemaMacro)]
+pub struct BorshSchemaContainer {
+    /// Declaration of the type.
+    pub declaration: Declaration,
+    /// All definitions needed to deserialize the given type.
+    pub definitions: HashMap<Declaration, Definition>,
+}
+
+/// The declaration and the definition of the type that can be used to (de)serialize Borsh without
+/// the Rust type that produced it.
+pub trait BorshSchema {
+    /// Recursively, using DFS, add type definitions required for this type. For primitive types
+    /// this is an empty map. Type definition explains how to serialize/deserialize a type.
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>);
+
+    /// Helper method to add a single type definition to the map.
+    fn add_definition(
+        declaration: Declaration,
+        definition: Definition,
+        definitions: &mut HashMap<Declaration, Definition>,
+    ) {
+        match definitions.entry(declaration) {
+            Entry::Occupied(occ) => {
+                let existing_def = occ.get();
+                if existing_def != &definition
+                    && conflict::report(ConflictingDefinitions {
+                        declaration: occ.key().clone(),
+                        existing: Box::new(existing_def.clone()),
+                        conflicting: Box::new(definition.clone()),
+                    })
+                {
+                    return;
+                }
+                assert_eq!(existing_def, &definition, "Redefining type schema for the same type name. Types with the same names are not supported.");
+            }
+            Entry::Vacant(vac) => {
+                vac.insert(definition);
+            }
+        }
+    }
+    /// Get the name of the type without brackets.
+    fn declaration() -> Declaration;
+
+    fn schema_container() -> BorshSchemaContainer {
+        let mut definitions = HashMap::new();
+        Self::add_definitions_recursively(&mut definitions);
+        BorshSchemaContainer {
+            declaration: Self::declaration(),
+            definitions,
+        }
+    }
+
+    /// Like [`schema_container`](Self::schema_container), but returns the first pair of different
+    /// definitions added under the same declaration as an error instead of panicking.
+    #[cfg(feature = "std")]
+    fn try_schema_container() -> Result<BorshSchemaContainer, ConflictingDefinitions> {
+        match conflict::collecting(Self::schema_container) {
+            (_, Some(conflict)) => Err(conflict),
+            (container, None) => Ok(container),
+        }
+    }
+}
+
+/// The definition of the variant `variant` of the enum declared as `enum_declaration` in
+/// `container`, or `None` if there is no such enum or variant, or the variant is a primitive.
+///
+/// Derived enums describe each variant as a struct declared as `<EnumName><VariantName>`, followed
+/// by the type parameters of the enum like the declaration of the enum, so its fields can be found
+/// with:
+///
+/// ```
+/// use borsh::schema::{enum_variant_definition, Definition, Fields};
+/// use borsh::BorshSchema;
+///
+/// #[derive(BorshSchema)]
+/// enum Shape {
+///     Circle { radius: u32 },
+///     Square(u32),
+/// }
+///
+/// let container = Shape::schema_container();
+/// assert_eq!(
+///     enum_variant_definition(&container, "Shape", "Circle"),
+///     Some(&Definition::Struct {
+///         fields: Fields::NamedFields(vec![("radius".to_string(), "u32".to_string())])
+///     })
+/// );
+/// ```
+pub fn enum_variant_definition<'a>(
+    container: &'a BorshSchemaContainer,
+    enum_declaration: &str,
+    variant: &str,
+) -> Option<&'a Definition> {
+    let declaration = match container.definitions.get(enum_declaration)? {
+        Definition::Enum { variants } | Definition::NamedEnum { variants } => variants
+            .iter()
+            .find(|(name, _)| name == variant)
+            .map(|(_, declaration)| declaration),
+        Definition::TaggedEnum { variants } => variants
+            .iter()
+            .find(|(_, name, _)| name == variant)
+            .map(|(_, _, declaration)| declaration),
+        Definition::U32TaggedEnum { variants } => variants
+            .iter()
+            .find(|(_, name, _)| name == variant)
+            .map(|(_, _, declaration)| declaration),
+        _ => None,
+    }?;
+    container.definitions.get(declaration)
+}
+
+/// The length of `definition` if it is an array of bytes, such as the definition of `[u8; 32]` or
+/// [`FixedBytes<32>`](crate::bytes::FixedBytes), which tools can show as hex strings.
+///
+/// ```
+/// use borsh::schema::{is_fixed_bytes, BorshSchema};
+///
+/// let container = <[u8; 32]>::schema_container();
+/// assert_eq!(is_fixed_bytes(&container.definitions["Array<u8, 32>"]), Some(32));
+/// ```
+pub fn is_fixed_bytes(definition: &Definition) -> Option<u32> {
+    match definition {
+        Definition::Array { length, elements } if elements == declarations::U8 => Some(*length),
+        _ => None,
+    }
+}
+
+/// Implements `BorshSchema` for a type by using the declaration and the definitions of another
+/// type with the same wire format:
+///
+/// ```
+/// use borsh::schema::BorshSchema;
+///
+/// struct Timestamp(u64);
+///
+/// borsh::impl_borsh_schema_as!(Timestamp => u64);
+///
+/// assert_eq!(Timestamp::declaration(), "u64");
+/// ```
+///
+/// A derived struct with a single field, such as a fixed-point `struct Amount(u128)`, is already
+/// serialized like its field. With the schema of the field too, the wrapper is transparent, like
+/// `Wrapping<T>` and `Saturating<T>` are.
+///
+/// Like any trait implementation, it has to be in the crate that defines the type.
+#[macro_export]
+macro_rules! impl_borsh_schema_as {
+    ($($type: ty => $inner: ty),+ $(,)?) => {
+        $(
+            impl $crate::schema::BorshSchema for $type {
+                fn add_definitions_recursively(
+                    definitions: &mut $crate::maybestd::collections::HashMap<
+                        $crate::schema::Declaration,
+                        $crate::schema::Definition,
+                    >,
+                ) {
+                    <$inner as $crate::schema::BorshSchema>::add_definitions_recursively(definitions);
+                }
+
+                fn declaration() -> $crate::schema::Declaration {
+                    <$inner as $crate::schema::BorshSchema>::declaration()
+                }
+            }
+        )+
+    };
+}
+
+impl<T> BorshSchema for Box<T>
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+#[cfg(feature = "rc")]
+impl<T> BorshSchema for Rc<T>
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+#[cfg(feature = "rc")]
+impl<T> BorshSchema for Arc<T>
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+/// Declared like `T`, since the wrapper only changes the arithmetic.
+impl<T: BorshSchema> BorshSchema for core::num::Wrapping<T> {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+/// Declared like `T`, since the wrapper only changes the arithmetic.
+#[cfg(feature = "saturating")]
+#[clippy::msrv = "1.74"]
+impl<T: BorshSchema> BorshSchema for core::num::Saturating<T> {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+/// Declared like `T`, for the view types that serialize borrowed data.
+impl<T> BorshSchema for &T
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+impl<T> BorshSchema for &mut T
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+/// Declared like `T`, so `Cow<'_, str>` is a `string` whether it is borrowed or owned.
+impl<T> BorshSchema for Cow<'_, T>
+where
+    T: BorshSchema + ToOwned + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+impl BorshSchema for () {
+    fn add_definitions_recursively(_definitions: &mut HashMap<Declaration, Definition>) {}
+
+    fn declaration() -> Declaration {
+        declarations::NIL.to_string()
+    }
+}
+
+macro_rules! impl_for_primitives {
+    ($($type: ident : $name: ident)+) => {
+    $(
+        impl BorshSchema for $type {
+            fn add_definitions_recursively(_definitions: &mut HashMap<Declaration, Definition>) {}
+            fn declaration() -> Declaration {
+                declarations::$name.to_string()
+            }
+        }
+    )+
+    };
+}
+
+impl_for_primitives!(bool: BOOL f32: F32 f64: F64);
+impl_for_primitives!(i8: I8 i16: I16 i32: I32 i64: I64 i128: I128);
+impl_for_primitives!(u8: U8 u16: U16 u32: U32 u64: U64 u128: U128);
+impl_for_primitives!(String: STRING);
+impl_for_primitives!(str: STRING);
+impl_for_primitives!(isize: I64);
+impl_for_primitives!(usize: U64);
+
+impl<T, const N: usize> BorshSchema for [T; N]
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Array {
+            length: N as u32,
+            elements: T::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+    fn declaration() -> Declaration {
+        format!(r#"Array<{}, {}>"#, T::declaration(), N)
+    }
+}
+
+impl<T> BorshSchema for Option<T>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Option {
+            inner: T::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"Option<{}>"#, T::declaration())
+    }
+}
+
+impl<T, E> BorshSchema for core::result::Result<T, E>
+where
+    T: BorshSchema,
+    E: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        // The same tags as in serialization, 0 for `Err` and 1 for `Ok`.
+        let definition = Definition::Enum {
+            variants: vec![
+                ("Err".to_string(), E::declaration()),
+                ("Ok".to_string(), T::declaration()),
+            ],
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+        E::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"Result<{}, {}>"#, T::declaration(), E::declaration())
+    }
+}
+
+impl<T> BorshSchema for Vec<T>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: T::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"Vec<{}>"#, T::declaration())
+    }
+}
+
+macro_rules! impl_for_sequences {
+    ($($type: ident)+) => {
+    $(
+        /// Declared under its own name so that generated code can use the same collection, the
+        /// data is interchangeable with the one of `Vec<T>`.
+        impl<T> BorshSchema for $type<T>
+        where
+            T: BorshSchema,
+        {
+            fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+                let definition = Definition::Sequence {
+                    length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                    length_range: Definition::DEFAULT_LENGTH_RANGE,
+                    elements: T::declaration(),
+                };
+                Self::add_definition(Self::declaration(), definition, definitions);
+                T::add_definitions_recursively(definitions);
+            }
+
+            fn declaration() -> Declaration {
+                format!(concat!(stringify!($type), "<{}>"), T::declaration())
+            }
+        }
+    )+
+    };
+}
+
+impl_for_sequences!(VecDeque LinkedList BinaryHeap);
+
+macro_rules! impl_for_c_strings {
+    ($($type: ty),+) => {
+    $(
+        /// Declared as `Vec<u8>`, the trailing NUL is not serialized.
+        #[cfg(feature = "std")]
+        impl BorshSchema for $type {
+            fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+                Vec::<u8>::add_definitions_recursively(definitions);
+            }
+
+            fn declaration() -> Declaration {
+                Vec::<u8>::declaration()
+            }
+        }
+    )+
+    };
+}
+
+impl_for_c_strings!(std::ffi::CStr, std::ffi::CString);
+
+impl<T> BorshSchema for [T]
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: T::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"Vec<{}>"#, T::declaration())
+    }
+}
+
+// The hasher does not affect the serialized data, so it is left out of the declaration.
+impl<K, V, H> BorshSchema for HashMap<K, V, H>
+where
+    K: BorshSchema,
+    V: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: <(K, V)>::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        <(K, V)>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"HashMap<{}, {}>"#, K::declaration(), V::declaration())
+    }
+}
+
+impl<T, H> BorshSchema for HashSet<T, H>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: <T>::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        <T>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"HashSet<{}>"#, T::declaration())
+    }
+}
+
+impl<K, V> BorshSchema for BTreeMap<K, V>
+where
+    K: BorshSchema,
+    V: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: <(K, V)>::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        <(K, V)>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"BTreeMap<{}, {}>"#, K::declaration(), V::declaration())
+    }
+}
+
+impl<T> BorshSchema for BTreeSet<T>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: <T>::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        <T>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"BTreeSet<{}>"#, T::declaration())
+    }
+}
+
+/// The seconds and nanoseconds since the Unix epoch, see the `BorshSerialize` impl.
+#[cfg(any(test, feature = "chrono"))]
+fn timestamp_definition() -> Definition {
+    Definition::Struct {
+        fields: Fields::NamedFields(vec![
+            ("secs".to_string(), i64::declaration()),
+            ("nanos".to_string(), u32::declaration()),
+        ]),
+    }
+}
+
+#[cfg(any(test, feature = "chrono"))]
+impl BorshSchema for chrono::DateTime<chrono::Utc> {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        Self::add_definition(Self::declaration(), timestamp_definition(), definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "DateTime<Utc>".to_string()
+    }
+}
+
+#[cfg(any(test, feature = "chrono"))]
+impl BorshSchema for chrono::NaiveDateTime {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        Self::add_definition(Self::declaration(), timestamp_definition(), definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "NaiveDateTime".to_string()
+    }
+}
+
+/// Declared as a struct of the flags and the three parts of the mantissa, the 16 bytes of its
+/// internal representation.
+#[cfg(any(test, feature = "rust_decimal"))]
+impl BorshSchema for rust_decimal::Decimal {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let fields = ["flags", "lo", "mid", "hi"]
+            .iter()
+            .map(|field| (field.to_string(), u32::declaration()))
+            .collect();
+        let definition = Definition::Struct {
+            fields: Fields::NamedFields(fields),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "Decimal".to_string()
+    }
+}
+
+/// Declared as `Vec<T>`, so that std peers read the same schema.
+#[cfg(any(test, feature = "heapless"))]
+impl<T, const N: usize> BorshSchema for heapless::Vec<T, N>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        Vec::<T>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        Vec::<T>::declaration()
+    }
+}
+
+/// Declared as `string`, so that std peers read the same schema.
+#[cfg(any(test, feature = "heapless"))]
+impl<const N: usize> BorshSchema for heapless::String<N> {
+    fn add_definitions_recursively(_definitions: &mut HashMap<Declaration, Definition>) {}
+
+    fn declaration() -> Declaration {
+        String::declaration()
+    }
+}
+
+/// Declared as `Vec<T>`, the inline capacity is not part of the schema.
+#[cfg(any(test, feature = "smallvec"))]
+impl<A> BorshSchema for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        Vec::<A::Item>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        Vec::<A::Item>::declaration()
+    }
+}
+
+#[cfg(any(test, feature = "either"))]
+impl<L, R> BorshSchema for either::Either<L, R>
+where
+    L: BorshSchema,
+    R: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Enum {
+            variants: vec![
+                ("Left".to_string(), L::declaration()),
+                ("Right".to_string(), R::declaration()),
+            ],
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        L::add_definitions_recursively(definitions);
+        R::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"Either<{}, {}>"#, L::declaration(), R::declaration())
+    }
+}
+
+#[cfg(any(test, feature = "num-bigint"))]
+impl BorshSchema for num_bigint::BigUint {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Struct {
+            fields: Fields::NamedFields(vec![(
+                "magnitude_le".to_string(),
+                Vec::<u8>::declaration(),
+            )]),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        Vec::<u8>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "BigUint".to_string()
+    }
+}
+
+#[cfg(any(test, feature = "num-bigint"))]
+impl BorshSchema for num_bigint::BigInt {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Enum {
+            variants: vec![
+                ("Zero".to_string(), num_bigint::BigUint::declaration()),
+                ("Positive".to_string(), num_bigint::BigUint::declaration()),
+                ("Negative".to_string(), num_bigint::BigUint::declaration()),
+            ],
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        num_bigint::BigUint::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "BigInt".to_string()
+    }
+}
+
+// Like for `HashMap`, the hasher is left out of the declaration.
+#[cfg(any(test, feature = "indexmap"))]
+impl<K, V, H> BorshSchema for indexmap::IndexMap<K, V, H>
+where
+    K: BorshSchema,
+    V: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: <(K, V)>::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        <(K, V)>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"IndexMap<{}, {}>"#, K::declaration(), V::declaration())
+    }
+}
+
+#[cfg(any(test, feature = "indexmap"))]
+impl<T, H> BorshSchema for indexmap::IndexSet<T, H>
+where
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: <T>::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        <T>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"IndexSet<{}>"#, T::declaration())
+    }
+}
+
+// Because it's a zero-sized marker, its type parameter doesn't need to be
+// included in the schema and so it's not bound to `BorshSchema`
+impl<T> BorshSchema for PhantomData<T> {
+    fn add_definitions_recursively(_definitions: &mut HashMap<Declaration, Definition>) {}
+
+    fn declaration() -> Declaration {
+        <()>::declaration()
+    }
+}
+
+// Uninhabited, so it is an enum without variants.
+impl BorshSchema for core::convert::Infallible {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Enum { variants: vec![] };
+        Self::add_definition(Self::declaration(), definition, definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "never".to_string()
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+    impl<$($name),+> BorshSchema for ($($name,)+)
+    where
+        $($name: BorshSchema),+
+    {
+        fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+            let elements = vec![$($name::declaration()),+];
+
+            let definition = Definition::Tuple { elements };
+            Self::add_definition(Self::declaration(), definition, definitions);
+            $(
+                $name::add_definitions_recursively(definitions);
+            )+
+        }
+
+        fn declaration() -> Declaration {
+            let params = vec![$($name::declaration()),+];
+            format!(r#"Tuple<{}>"#, params.join(", "))
+        }
+    }
+    };
+}
+
+impl_tuple!(T0);
+impl_tuple!(T0, T1);
+impl_tuple!(T0, T1, T2);
+impl_tuple!(T0, T1, T2, T3);
+impl_tuple!(T0, T1, T2, T3, T4);
+impl_tuple!(T0, T1, T2, T3, T4, T5);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18);
+impl_tuple!(
+    T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19
+);
+impl_tuple!(
+    T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19, T20
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maybestd::collections::HashMap;
+
+    macro_rules! map(
+    () => { HashMap::new() };
+    { $($key:expr => $value:expr),+ } => {
+        {
+            let mut m = HashMap::new();
+            $(
+                m.insert($key.to_string(), $value);
+            )+
+            m
+        }
+     };
+    );
+
+    #[test]
+    fn simple_option() {
+        let actual_name = Option::<u64>::declaration();
+        let mut actual_defs = map!();
+        Option::<u64>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Option<u64>", actual_name);
+        assert_eq!(
+            map! {"Option<u64>" => Definition::Option { inner: "u64".to_string() }},
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn option_string() {
+        // What a generator maps to a nullable type, e.g. `string | null` in TypeScript.
+        let actual_name = Option::<String>::declaration();
+        let mut actual_defs = map!();
+        Option::<String>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Option<string>", actual_name);
+        assert_eq!(
+            map! {"Option<string>" => Definition::Option { inner: "string".to_string() }},
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_option() {
+        let actual_name = Option::<Option<u64>>::declaration();
+        let mut actual_defs = map!();
+        Option::<Option<u64>>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Option<Option<u64>>", actual_name);
+        assert_eq!(
+            map! {
+            "Option<u64>" => Definition::Option { inner: "u64".to_string() },
+            "Option<Option<u64>>" => Definition::Option { inner: "Option<u64>".to_string() }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_vec() {
+        let actual_name = Vec::<u64>::declaration();
+        let mut actual_defs = map!();
+        Vec::<u64>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Vec<u64>", actual_name);
+        assert_eq!(
+            map! {
+            "Vec<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_vec() {
+        let actual_name = Vec::<Vec<u64>>::declaration();
+        let mut actual_defs = map!();
+        Vec::<Vec<u64>>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Vec<Vec<u64>>", actual_name);
+        assert_eq!(
+            map! {
+            "Vec<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            },
+            "Vec<Vec<u64>>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "Vec<u64>".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_vec_deque() {
+        let actual_name = VecDeque::<u64>::declaration();
+        let mut actual_defs = map!();
+        VecDeque::<u64>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("VecDeque<u64>", actual_name);
+        assert_eq!(
+            map! {
+            "VecDeque<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_vec_deque() {
+        let actual_name = VecDeque::<VecDeque<u64>>::declaration();
+        let mut actual_defs = map!();
+        VecDeque::<VecDeque<u64>>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("VecDeque<VecDeque<u64>>", actual_name);
+        assert_eq!(
+            map! {
+            "VecDeque<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            },
+            "VecDeque<VecDeque<u64>>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "VecDeque<u64>".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_linked_list() {
+        let actual_name = LinkedList::<u64>::declaration();
+        let mut actual_defs = map!();
+        LinkedList::<u64>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("LinkedList<u64>", actual_name);
+        assert_eq!(
+            map! {
+            "LinkedList<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_linked_list() {
+        let actual_name = LinkedList::<LinkedList<u64>>::declaration();
+        let mut actual_defs = map!();
+        LinkedList::<LinkedList<u64>>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("LinkedList<LinkedList<u64>>", actual_name);
+        assert_eq!(
+            map! {
+            "LinkedList<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            },
+            "LinkedList<LinkedList<u64>>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "LinkedList<u64>".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_binary_heap() {
+        let actual_name = BinaryHeap::<u64>::declaration();
+        let mut actual_defs = map!();
+        BinaryHeap::<u64>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("BinaryHeap<u64>", actual_name);
+        assert_eq!(
+            map! {
+            "BinaryHeap<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_binary_heap() {
+        let actual_name = BinaryHeap::<Vec<u64>>::declaration();
+        let mut actual_defs = map!();
+        BinaryHeap::<Vec<u64>>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("BinaryHeap<Vec<u64>>", actual_name);
+        assert_eq!(
+            map! {
+            "Vec<u64>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u64".to_string()
+            },
+            "BinaryHeap<Vec<u64>>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "Vec<u64>".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_tuple() {
+        let actual_name = <(u64, String)>::declaration();
+        let mut actual_defs = map!();
+        <(u64, String)>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Tuple<u64, string>", actual_name);
+        assert_eq!(
+            map! {
+                "Tuple<u64, string>" => Definition::Tuple { elements: vec![ "u64".to_string(), "string".to_string()]}
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_tuple() {
+        let actual_name = <(u64, (u8, bool), String)>::declaration();
+        let mut actual_defs = map!();
+        <(u64, (u8, bool), String)>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Tuple<u64, Tuple<u8, bool>, string>", actual_name);
+        assert_eq!(
+            map! {
+                "Tuple<u64, Tuple<u8, bool>, string>" => Definition::Tuple { elements: vec![
+                    "u64".to_string(),
+                    "Tuple<u8, bool>".to_string(),
+                    "string".to_string(),
+                ]},
+                "Tuple<u8, bool>" => Definition::Tuple { elements: vec![ "u8".to_string(), "bool".to_string()]}
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_map() {
+        let actual_name = HashMap::<u64, String>::declaration();
+        let mut actual_defs = map!();
+        HashMap::<u64, String>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("HashMap<u64, string>", actual_name);
+        assert_eq!(
+            map! {
+                "HashMap<u64, string>" => Definition::Sequence {
+                    length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                    length_range: Definition::DEFAULT_LENGTH_RANGE,
+                    elements: "Tuple<u64, string>".to_string()
+                } ,
+                "Tuple<u64, string>" => Definition::Tuple { elements: vec![ "u64".to_string(), "string".to_string()]}
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_set() {
+        let actual_name = HashSet::<String>::declaration();
+        let mut actual_defs = map!();
+        HashSet::<String>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("HashSet<string>", actual_name);
+        assert_eq!(
+            map! {
+                "HashSet<string>" => Definition::Sequence {
+                    length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                    length_range: Definition::DEFAULT_LENGTH_RANGE,
+                    elements: "string".to_string()
+                }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn b_tree_map() {
+        let actual_name = BTreeMap::<u64, String>::declaration();
+        let mut actual_defs = map!();
+        BTreeMap::<u64, String>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("BTreeMap<u64, string>", actual_name);
+        assert_eq!(
+            map! {
+                "BTreeMap<u64, string>" => Definition::Sequence {
+                    length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                    length_range: Definition::DEFAULT_LENGTH_RANGE,
+                    elements: "Tuple<u64, string>".to_string()
+                } ,
+                "Tuple<u64, string>" => Definition::Tuple { elements: vec![ "u64".to_string(), "string".to_string()]}
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn b_tree_set() {
+        let actual_name = BTreeSet::<String>::declaration();
+        let mut actual_defs = map!();
+        BTreeSet::<String>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("BTreeSet<string>", actual_name);
+        assert_eq!(
+            map! {
+                "BTreeSet<string>" => Definition::Sequence {
+                    length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                    length_range: Definition::DEFAULT_LENGTH_RANGE,
+                    elements: "string".to_string()
+                }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn simple_array() {
+        let actual_name = <[u64; 32]>::declaration();
+        let mut actual_defs = map!();
+        <[u64; 32]>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Array<u64, 32>", actual_name);
+        assert_eq!(
+            map! {"Array<u64, 32>" => Definition::Array { length: 32, elements: "u64".to_string()}},
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn nested_array() {
+        let actual_name = <[[[u64; 9]; 10]; 32]>::declaration();
+        let mut actual_defs = map!();
+        <[[[u64; 9]; 10]; 32]>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!("Array<Array<Array<u64, 9>, 10>, 32>", actual_name);
+        assert_eq!(
+            map! {
+            "Array<u64, 9>" =>
+                Definition::Array { length: 9, elements: "u64".to_string() },
+            "Array<Array<u64, 9>, 10>" =>
+                Definition::Array { length: 10, elements: "Array<u64, 9>".to_string() },
+            "Array<Array<Array<u64, 9>, 10>, 32>" =>
+                Definition::Array { length: 32, elements: "Array<Array<u64, 9>, 10>".to_string() }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn string() {
+        let actual_name = str::declaration();
+        assert_eq!("string", actual_name);
+        let actual_name = String::declaration();
+        assert_eq!("string", actual_name);
+        let mut actual_defs = map!();
+        String::add_definitions_recursively(&mut actual_defs);
+        assert_eq!(map! {}, actual_defs);
+    }
+
+    #[test]
+    fn boxed_schema() {
+        let boxed_declaration = Box::<str>::declaration();
+        assert_eq!("string", boxed_declaration);
+        let boxed_declaration = Box::<[u8]>::declaration();
+        assert_eq!("Vec<u8>", boxed_declaration);
+    }
+
+    #[test]
+    #[cfg(feature = "rc")]
+    fn rc_schema() {
+        assert_eq!("string", Arc::<str>::declaration());
+        assert_eq!("Vec<u8>", Arc::<[u8]>::declaration());
+        assert_eq!("string", Rc::<str>::declaration());
+        assert_eq!("Vec<u8>", Rc::<[u8]>::declaration());
+        let mut actual_defs = map!();
+        Arc::<[u8]>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!(
+            map! {
+            "Vec<u8>" => Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: Definition::DEFAULT_LENGTH_RANGE,
+                elements: "u8".to_string()
+            }
+            },
+            actual_defs
+        );
+    }
+
+    #[test]
+    fn phantom_data_schema() {
+        let phantom_declaration = PhantomData::<String>::declaration();
+        assert_eq!("nil", phantom_declaration);
+        let phantom_declaration = PhantomData::<Vec<u8>>::declaration();
+        assert_eq!("nil", phantom_declaration);
+    }
+
+    #[test]
+    fn infallible_schema() {
+        assert_eq!("never", core::convert::Infallible::declaration());
+        assert_eq!(
+            "Result<u64, never>",
+            Result::<u64, core::convert::Infallible>::declaration()
+        );
+        let mut actual_defs = map!();
+        Result::<u64, core::convert::Infallible>::add_definitions_recursively(&mut actual_defs);
+        assert_eq!(
+            map! {
+            "Result<u64, never>" => Definition::Enum {
+                variants: vec![
+                    ("Err".to_string(), "never".to_string()),
+                    ("Ok".to_string(), "u64".to_string()),
+                ]
+            },
+            "never" => Definition::Enum { variants: vec![] }
+            },
+            actual_defs
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Ordering of the definitions of a schema so that every type comes after the types it uses.
+use core::fmt;
+
+use super::{BorshSchemaContainer, Declaration, Definition, Fields};
+use crate::maybestd::{collections::HashSet, vec, vec::Vec};
+
+/// Returned by [`BorshSchemaContainer::topological_order`] when the definitions refer to each
+/// other recursively and therefore cannot be ordered.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CycleError {
+    /// The declarations forming the cycle, each one using the next and the last one using the
+    /// first.
+    pub cycle: Vec<Declaration>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Recursive definitions: {}", self.cycle.join(" -> "))?;
+        if let Some(first) = self.cycle.first() {
+            write!(f, " -> {}", first)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CycleError {}
+
+impl BorshSchemaContainer {
+    /// Returns the declarations of all definitions in the container, ordered so that each one comes
+    /// after the declarations its definition uses. Primitive types, which have no definitions, are
+    /// not included.
+    pub fn topological_order(&self) -> Result<Vec<Declaration>, CycleError> {
+        // Sort the starting points so that the order does not depend on the `HashMap` iteration.
+        let mut declarations: Vec<&Declaration> = self.definitions.keys().collect();
+        declarations.sort();
+
+        let mut order = Vec::with_capacity(declarations.len());
+        let mut done = HashSet::new();
+        let mut stack = Vec::new();
+        for declaration in declarations {
+            self.visit(declaration, &mut done, &mut stack, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    fn visit<'a>(
+        &'a self,
+        declaration: &'a Declaration,
+        done: &mut HashSet<&'a Declaration>,
+        stack: &mut Vec<&'a Declaration>,
+        order: &mut Vec<Declaration>,
+    ) -> Result<(), CycleError> {
+        if done.contains(declaration) {
+            return Ok(());
+        }
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return Ok(()),
+        };
+        if let Some(position) = stack.iter().position(|d| *d == declaration) {
+            return Err(CycleError {
+                cycle: stack[position..].iter().map(|d| (*d).clone()).collect(),
+            });
+        }
+        stack.push(declaration);
+        for dependency in dependencies(definition) {
+            self.visit(dependency, done, stack, order)?;
+        }
+        stack.pop();
+        done.insert(declaration);
+        order.push(declaration.clone());
+        Ok(())
+    }
+}
+
+/// The declarations used by a definition, in the order they appear in it.
+pub(super) fn dependencies(definition: &Definition) -> Vec<&Declaration> {
+    match definition {
+        Definition::Array { elements, .. } | Definition::Sequence { elements, .. } => {
+            vec![elements]
+        }
+        Definition::Tuple { elements } => elements.iter().collect(),
+        Definition::Enum { variants } | Definition::NamedEnum { variants } => {
+            variants.iter().map(|(_, d)| d).collect()
+        }
+        Definition::TaggedEnum { variants } => variants.iter().map(|(_, _, d)| d).collect(),
+        Definition::U32TaggedEnum { variants } => variants.iter().map(|(_, _, d)| d).collect(),
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => fields.iter().map(|(_, d)| d).collect(),
+            Fields::UnnamedFields(fields) => fields.iter().collect(),
+            Fields::Empty => Vec::new(),
+        },
+        Definition::Option { inner } => vec![inner],
+    }
+}
+
+/// Mutable access to the declarations used by a definition, in the order they appear in it.
+pub(super) fn dependencies_mut(definition: &mut Definition) -> Vec<&mut Declaration> {
+    match definition {
+        Definition::Array { elements, .. } | Definition::Sequence { elements, .. } => {
+            vec![elements]
+        }
+        Definition::Tuple { elements } => elements.iter_mut().collect(),
+        Definition::Enum { variants } | Definition::NamedEnum { variants } => {
+            variants.iter_mut().map(|(_, d)| d).collect()
+        }
+        Definition::TaggedEnum { variants } => variants.iter_mut().map(|(_, _, d)| d).collect(),
+        Definition::U32TaggedEnum { variants } => variants.iter_mut().map(|(_, _, d)| d).collect(),
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => fields.iter_mut().map(|(_, d)| d).collect(),
+            Fields::UnnamedFields(fields) => fields.iter_mut().collect(),
+            Fields::Empty => Vec::new(),
+        },
+        Definition::Option { inner } => vec![inner],
+    }
+}
@@ -0,0 +1,55 @@
+//! Removal of the definitions that the declaration of a container does not use.
+use super::order::dependencies;
+use super::{BorshSchemaContainer, Declaration};
+use crate::maybestd::{
+    collections::{HashMap, HashSet},
+    vec,
+    vec::Vec,
+};
+
+impl BorshSchemaContainer {
+    /// Removes the definitions that are not used, directly or through other definitions, by the
+    /// declaration of the container. Useful after building or merging containers by hand.
+    pub fn retain_reachable(&mut self) {
+        let reachable = self.reachable_declarations();
+        let unreachable = self
+            .definitions
+            .keys()
+            .filter(|declaration| !reachable.contains(declaration))
+            .cloned()
+            .collect::<Vec<_>>();
+        for declaration in unreachable {
+            self.definitions.remove(&declaration);
+        }
+    }
+
+    /// Returns a copy of the container without the definitions that are not used by its
+    /// declaration, see [`retain_reachable`](Self::retain_reachable).
+    pub fn reachable_subset(&self) -> BorshSchemaContainer {
+        let reachable = self.reachable_declarations();
+        BorshSchemaContainer {
+            declaration: self.declaration.clone(),
+            definitions: self
+                .definitions
+                .iter()
+                .filter(|(declaration, _)| reachable.contains(*declaration))
+                .map(|(declaration, definition)| (declaration.clone(), definition.clone()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    /// The declarations that have a definition and are reachable from the declaration of the
+    /// container. Primitive and undefined declarations end the walk.
+    fn reachable_declarations(&self) -> HashSet<&Declaration> {
+        let mut reachable = HashSet::new();
+        let mut stack = vec![&self.declaration];
+        while let Some(declaration) = stack.pop() {
+            if let Some((declaration, definition)) = self.definitions.get_key_value(declaration) {
+                if reachable.insert(declaration) {
+                    stack.extend(dependencies(definition));
+                }
+            }
+        }
+        reachable
+    }
+}
@@ -0,0 +1,64 @@
+//! Renaming of a declaration throughout a container.
+use super::order::{dependencies, dependencies_mut};
+use super::BorshSchemaContainer;
+use crate::maybestd::string::ToString;
+
+impl BorshSchemaContainer {
+    /// Renames the declaration `from` to `to`: the key of its definition, every reference to it in
+    /// the other definitions and the declaration of the container, e.g. to keep the `Config` of
+    /// two crates apart before merging their containers.
+    ///
+    /// ```
+    /// use borsh::BorshSchema;
+    ///
+    /// #[derive(BorshSchema)]
+    /// struct Config {
+    ///     retries: u8,
+    /// }
+    ///
+    /// #[derive(BorshSchema)]
+    /// struct Settings {
+    ///     config: Config,
+    ///     fallback: Option<Config>,
+    /// }
+    ///
+    /// let mut container = Settings::schema_container();
+    /// container.rename_declaration("Config", "net::Config");
+    /// assert!(container.definitions.contains_key("net::Config"));
+    /// assert!(!container.definitions.contains_key("Config"));
+    /// ```
+    ///
+    /// Only the exact declaration is renamed. Declarations that contain it, like `Option<Config>`
+    /// above, keep their name and refer to `to`.
+    ///
+    /// # Panics
+    ///
+    /// If `to` is already a declaration of the container, defined or used, as the two would no
+    /// longer be told apart.
+    pub fn rename_declaration(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+        let used = self.declaration == to
+            || self.definitions.contains_key(to)
+            || self
+                .definitions
+                .values()
+                .any(|definition| dependencies(definition).into_iter().any(|d| d == to));
+        assert!(!used, "{} is already a declaration of the container", to);
+
+        if let Some(definition) = self.definitions.remove(from) {
+            self.definitions.insert(to.to_string(), definition);
+        }
+        for definition in self.definitions.values_mut() {
+            for dependency in dependencies_mut(definition) {
+                if dependency == from {
+                    *dependency = to.to_string();
+                }
+            }
+        }
+        if self.declaration == from {
+            self.declaration = to.to_string();
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Skipping over Borsh data with a schema, without decoding it.
+use core::convert::TryFrom;
+
+use super::layout::fixed_size;
+use super::value::{
+    check_length, check_zero_sized_length, decode_length, ERROR_MAX_DEPTH_EXCEEDED,
+    ERROR_UNEXPECTED_LENGTH_OF_INPUT, ERROR_ZERO_SIZED_ELEMENTS, MAX_DEPTH,
+};
+use super::{declarations, BorshSchemaContainer, Definition, Fields};
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind, Result},
+    string::{String, ToString},
+};
+use crate::BorshDeserialize;
+
+/// Advances `bytes` past one value of `declaration`, without decoding it into a value, to get to
+/// the data that follows:
+///
+/// ```
+/// use borsh::schema::skip_value;
+/// use borsh::{BorshSchema, BorshSerialize};
+///
+/// #[derive(BorshSerialize, BorshSchema)]
+/// struct Entry {
+///     payload: Vec<u64>,
+///     name: String,
+/// }
+///
+/// let data = Entry { payload: vec![1, 2, 3], name: "last".to_string() }.try_to_vec().unwrap();
+/// let container = Entry::schema_container();
+/// let mut cursor = &data[..];
+/// skip_value(&container, "Vec<u64>", &mut cursor).unwrap();
+/// assert_eq!(cursor, &"last".to_string().try_to_vec().unwrap()[..]);
+/// ```
+///
+/// Only length prefixes, tags and `Option` flags are read. Values of a fixed size, including
+/// sequences of them, are skipped at once without looking at their bytes, so invalid contents such
+/// as a `bool` other than `0` or `1` are not detected. [`skip_value_checked`] detects them.
+pub fn skip_value(
+    container: &BorshSchemaContainer,
+    declaration: &str,
+    bytes: &mut &[u8],
+) -> Result<()> {
+    skip(container, declaration, bytes, Contents::Skipped, 0)
+}
+
+/// Like [`skip_value`], and also checks the contents that [`BorshSchemaContainer::deserialize_value`]
+/// checks: that every `bool` is `0` or `1`, every string is UTF-8 and sequences of elements without
+/// bytes are within [`Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS`](crate::de::Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS)
+/// or the limit set by `try_from_slice_limited`.
+/// Values of a fixed size that contain no `bool` are still skipped at once.
+pub fn skip_value_checked(
+    container: &BorshSchemaContainer,
+    declaration: &str,
+    bytes: &mut &[u8],
+) -> Result<()> {
+    skip(container, declaration, bytes, Contents::Checked, 0)
+}
+
+/// Whether the bytes of `bool`s and strings are checked or skipped.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Contents {
+    Skipped,
+    Checked,
+}
+
+fn skip(
+    container: &BorshSchemaContainer,
+    declaration: &str,
+    bytes: &mut &[u8],
+    contents: Contents,
+    depth: usize,
+) -> Result<()> {
+    if depth > MAX_DEPTH {
+        return Err(Error::new(ErrorKind::InvalidData, ERROR_MAX_DEPTH_EXCEEDED));
+    }
+    let depth = depth + 1;
+    if let Some(size) = fixed_size(container, declaration, 0) {
+        if skipped_at_once(container, declaration, size, contents) {
+            return advance(bytes, size);
+        }
+    }
+    let definition = match container.definitions.get(declaration) {
+        Some(definition) => definition,
+        None if declaration == declarations::BOOL => return bool::deserialize(bytes).map(drop),
+        None if declaration == declarations::STRING => {
+            let length = u32::deserialize(bytes)?;
+            let start = *bytes;
+            advance(bytes, length as u64)?;
+            if contents == Contents::Checked {
+                core::str::from_utf8(&start[..length as usize])
+                    .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))?;
+            }
+            return Ok(());
+        }
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Declaration {} is not defined in the schema", declaration),
+            ))
+        }
+    };
+    match definition {
+        Definition::Array { length, elements } => {
+            for _ in 0..*length {
+                skip(container, elements, bytes, contents, depth)?;
+            }
+        }
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => {
+            if *length_width == 0 {
+                let mut length = 0u64;
+                while !bytes.is_empty() {
+                    let remaining = bytes.len();
+                    skip(container, elements, bytes, contents, depth)?;
+                    if bytes.len() == remaining {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            ERROR_ZERO_SIZED_ELEMENTS,
+                        ));
+                    }
+                    length += 1;
+                }
+                check_length(length, length_range)?;
+            } else {
+                let length = decode_length(*length_width, bytes)?;
+                check_length(length, length_range)?;
+                if contents == Contents::Checked {
+                    check_zero_sized_length(container, elements, length)?;
+                }
+                match fixed_size(container, elements, 0) {
+                    Some(size) if skipped_at_once(container, elements, size, contents) => {
+                        let total = length.checked_mul(size).ok_or_else(|| {
+                            Error::new(ErrorKind::InvalidInput, ERROR_UNEXPECTED_LENGTH_OF_INPUT)
+                        })?;
+                        advance(bytes, total)?;
+                    }
+                    _ => {
+                        for _ in 0..length {
+                            skip(container, elements, bytes, contents, depth)?;
+                        }
+                    }
+                }
+            }
+        }
+        Definition::Tuple { elements } => {
+            for element in elements {
+                skip(container, element, bytes, contents, depth)?;
+            }
+        }
+        Definition::Enum { variants } => {
+            let tag = u8::deserialize(bytes)?;
+            let (_, variant) = variants
+                .get(tag as usize)
+                .ok_or_else(|| unexpected_tag(tag as u32))?;
+            skip(container, variant, bytes, contents, depth)?;
+        }
+        Definition::TaggedEnum { variants } => {
+            let tag = u8::deserialize(bytes)?;
+            let (_, _, variant) = variants
+                .iter()
+                .find(|(variant_tag, _, _)| *variant_tag == tag)
+                .ok_or_else(|| unexpected_tag(tag as u32))?;
+            skip(container, variant, bytes, contents, depth)?;
+        }
+        Definition::U32TaggedEnum { variants } => {
+            let tag = u32::deserialize(bytes)?;
+            let (_, _, variant) = variants
+                .iter()
+                .find(|(variant_tag, _, _)| *variant_tag == tag)
+                .ok_or_else(|| unexpected_tag(tag))?;
+            skip(container, variant, bytes, contents, depth)?;
+        }
+        Definition::NamedEnum { variants } => {
+            let name = String::deserialize(bytes)?;
+            let (_, variant) = variants
+                .iter()
+                .find(|(variant, _)| *variant == name)
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unexpected variant name: {:?}", name),
+                    )
+                })?;
+            skip(container, variant, bytes, contents, depth)?;
+        }
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => {
+                for (_, field) in fields {
+                    skip(container, field, bytes, contents, depth)?;
+                }
+            }
+            Fields::UnnamedFields(fields) => {
+                for field in fields {
+                    skip(container, field, bytes, contents, depth)?;
+                }
+            }
+            Fields::Empty => {}
+        },
+        Definition::Option { inner } => match u8::deserialize(bytes)? {
+            0 => {}
+            1 => skip(container, inner, bytes, contents, depth)?,
+            flag => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Invalid Option representation: {}. The first byte must be 0 or 1",
+                        flag
+                    ),
+                ))
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Whether a value of `declaration`, of a fixed `size`, has no contents to check. Only values
+/// without bytes and primitives other than `bool` are known to have none without looking further.
+fn skipped_at_once(
+    container: &BorshSchemaContainer,
+    declaration: &str,
+    size: u64,
+    contents: Contents,
+) -> bool {
+    contents == Contents::Skipped
+        || size == 0
+        || (!container.definitions.contains_key(declaration) && declaration != declarations::BOOL)
+}
+
+fn advance(bytes: &mut &[u8], size: u64) -> Result<()> {
+    match usize::try_from(size) {
+        Ok(size) if size <= bytes.len() => {
+            *bytes = &bytes[size..];
+            Ok(())
+        }
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            ERROR_UNEXPECTED_LENGTH_OF_INPUT,
+        )),
+    }
+}
+
+fn unexpected_tag(tag: u32) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Unexpected variant tag: {}", tag),
+    )
+}
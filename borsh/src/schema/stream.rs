@@ -0,0 +1,166 @@
+//! Writing and reading the Borsh encoding of a schema container one definition at a time.
+use core::convert::TryFrom;
+
+use super::{Declaration, Definition};
+use crate::maybestd::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    string::{String, ToString},
+};
+use crate::{BorshDeserialize, BorshSerialize};
+
+const ERROR_UNSORTED_DEFINITIONS: &str =
+    "Definitions must be written in increasing order of their declarations";
+const ERROR_TOO_MANY_DEFINITIONS: &str = "More definitions written than announced";
+const ERROR_TOO_FEW_DEFINITIONS: &str = "Fewer definitions written than announced";
+
+/// Writes the Borsh encoding of a [`BorshSchemaContainer`](super::BorshSchemaContainer) without
+/// holding its definitions, for schemas too large to build in memory.
+///
+/// ```
+/// use borsh::schema::{Definition, SchemaWriter};
+/// use borsh::{BorshSchema, BorshSerialize};
+///
+/// let container = <Vec<[u8; 2]>>::schema_container();
+/// let mut writer = SchemaWriter::new(Vec::new(), "Vec<Array<u8, 2>>", 2).unwrap();
+/// writer
+///     .write_definition("Array<u8, 2>", &Definition::Array { length: 2, elements: "u8".into() })
+///     .unwrap();
+/// writer
+///     .write_definition(
+///         "Vec<Array<u8, 2>>",
+///         &Definition::Sequence {
+///             length_width: 4,
+///             length_range: 0..=u32::MAX as u64,
+///             elements: "Array<u8, 2>".into(),
+///         },
+///     )
+///     .unwrap();
+/// assert_eq!(writer.finish().unwrap(), container.try_to_vec().unwrap());
+/// ```
+///
+/// The number of definitions comes first in the encoding, so it is given upfront. The definitions
+/// are written in increasing order of their declarations, the order in which the `HashMap` of a
+/// container is serialized, so that the bytes are the same as those of the container.
+#[derive(Debug)]
+pub struct SchemaWriter<W> {
+    writer: W,
+    remaining: u32,
+    last: Option<Declaration>,
+}
+
+impl<W: Write> SchemaWriter<W> {
+    /// Writes the declaration of the container and the number of its definitions, `len`.
+    pub fn new(mut writer: W, declaration: &str, len: usize) -> Result<Self> {
+        let remaining = u32::try_from(len).map_err(|_| ErrorKind::InvalidInput)?;
+        declaration.serialize(&mut writer)?;
+        remaining.serialize(&mut writer)?;
+        Ok(Self {
+            writer,
+            remaining,
+            last: None,
+        })
+    }
+
+    /// Writes the next definition. Fails if `declaration` does not come after the previous one,
+    /// or if all of the announced definitions have been written.
+    pub fn write_definition(&mut self, declaration: &str, definition: &Definition) -> Result<()> {
+        if self.remaining == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                ERROR_TOO_MANY_DEFINITIONS,
+            ));
+        }
+        if let Some(last) = &self.last {
+            if last.as_str() >= declaration {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    ERROR_UNSORTED_DEFINITIONS,
+                ));
+            }
+        }
+        declaration.serialize(&mut self.writer)?;
+        definition.serialize(&mut self.writer)?;
+        self.remaining -= 1;
+        self.last = Some(declaration.to_string());
+        Ok(())
+    }
+
+    /// Returns the writer, after checking that all of the announced definitions were written.
+    pub fn finish(self) -> Result<W> {
+        if self.remaining != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                ERROR_TOO_FEW_DEFINITIONS,
+            ));
+        }
+        Ok(self.writer)
+    }
+}
+
+/// Reads the Borsh encoding of a [`BorshSchemaContainer`](super::BorshSchemaContainer) one
+/// definition at a time, for consumers that scan the definitions without keeping them.
+///
+/// ```
+/// use borsh::schema::SchemaReader;
+/// use borsh::{BorshSchema, BorshSerialize};
+///
+/// let data = <Option<(u8, String)>>::schema_container().try_to_vec().unwrap();
+/// let mut reader = SchemaReader::new(data.as_slice()).unwrap();
+/// assert_eq!(reader.declaration(), "Option<Tuple<u8, string>>");
+/// assert_eq!(reader.remaining(), 2);
+/// let declarations: Vec<String> = reader.map(|entry| entry.unwrap().0).collect();
+/// assert_eq!(declarations, ["Option<Tuple<u8, string>>", "Tuple<u8, string>"]);
+/// ```
+///
+/// The iterator yields the definitions in the order they were written. After an error, it ends.
+#[derive(Debug)]
+pub struct SchemaReader<R> {
+    reader: R,
+    declaration: Declaration,
+    remaining: u32,
+}
+
+impl<R: Read> SchemaReader<R> {
+    /// Reads the declaration of the container and the number of its definitions.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let declaration = String::deserialize_reader(&mut reader)?;
+        let remaining = u32::deserialize_reader(&mut reader)?;
+        Ok(Self {
+            reader,
+            declaration,
+            remaining,
+        })
+    }
+
+    /// The declaration of the container.
+    pub fn declaration(&self) -> &str {
+        &self.declaration
+    }
+
+    /// The number of definitions not read yet.
+    pub fn remaining(&self) -> usize {
+        self.remaining as usize
+    }
+
+    /// Returns the reader, positioned after the last definition read.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for SchemaReader<R> {
+    type Item = Result<(Declaration, Definition)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let entry = <(Declaration, Definition)>::deserialize_reader(&mut self.reader);
+        self.remaining = if entry.is_ok() { self.remaining - 1 } else { 0 };
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining()))
+    }
+}
@@ -0,0 +1,124 @@
+//! Checks that the definitions of a container are complete and consistent.
+use core::fmt;
+
+use super::order::dependencies;
+use super::{declarations, BorshSchemaContainer, Declaration};
+use crate::maybestd::{collections::HashSet, vec, vec::Vec};
+
+/// The wrappers whose schema is the one of the type they wrap.
+const TRANSPARENT_WRAPPERS: [&str; 6] = ["Box", "Rc", "Arc", "Cow", "Wrapping", "Saturating"];
+
+/// An error of [`BorshSchemaContainer::validate`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ValidationError {
+    /// A declaration is neither a primitive nor defined in the container.
+    UndefinedDeclaration {
+        declaration: Declaration,
+        /// The declaration whose definition uses it, or `None` if it is the declaration of the
+        /// container.
+        used_by: Option<Declaration>,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UndefinedDeclaration {
+                declaration,
+                used_by: Some(used_by),
+            } => write!(
+                f,
+                "Declaration {} used by {} is not defined in the schema",
+                declaration, used_by
+            ),
+            ValidationError::UndefinedDeclaration {
+                declaration,
+                used_by: None,
+            } => write!(
+                f,
+                "Declaration {} is not defined in the schema",
+                declaration
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+impl BorshSchemaContainer {
+    /// Checks that every declaration reachable from the declaration of the container is a
+    /// primitive, see [`declarations`], or has a definition. Containers of derived types are always
+    /// valid, unless a field is declared with `#[borsh(schema(declaration = "..."))]` as a type
+    /// that nothing defines.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![(&self.declaration, None)];
+        while let Some((declaration, used_by)) = stack.pop() {
+            if !visited.insert(declaration) {
+                continue;
+            }
+            match self.definitions.get(declaration) {
+                Some(definition) => {
+                    // Reversed, so that the first undefined declaration in a definition is reported.
+                    for dependency in dependencies(definition).into_iter().rev() {
+                        stack.push((dependency, Some(declaration)));
+                    }
+                }
+                None if declarations::ALL.contains(&declaration.as_str()) => {}
+                None => {
+                    return Err(ValidationError::UndefinedDeclaration {
+                        declaration: declaration.clone(),
+                        used_by: used_by.cloned(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Panics if a declaration of `container` names one of the wrappers that are declared like the type
+/// they wrap, such as `Box<u64>` or `Vec<Arc<string>>`, which means that an implementation of
+/// `BorshSchema` declares them as a type of their own. The wrappers are `Box`, `Rc`, `Arc`, `Cow`,
+/// `Wrapping` and `Saturating`.
+///
+/// ```
+/// use borsh::schema::assert_transparent_wrappers_consistent;
+/// use borsh::BorshSchema;
+///
+/// #[derive(BorshSchema)]
+/// struct Node {
+///     value: Box<u64>,
+///     next: Option<Box<[u8; 4]>>,
+/// }
+///
+/// assert_transparent_wrappers_consistent(&Node::schema_container());
+/// ```
+pub fn assert_transparent_wrappers_consistent(container: &BorshSchemaContainer) {
+    let mut declarations: Vec<&Declaration> = vec![&container.declaration];
+    for (declaration, definition) in &container.definitions {
+        declarations.push(declaration);
+        declarations.extend(dependencies(definition));
+    }
+    for declaration in declarations {
+        if let Some(wrapper) = TRANSPARENT_WRAPPERS
+            .iter()
+            .find(|wrapper| names_wrapper(declaration, wrapper))
+        {
+            panic!(
+                "Declaration {} names {}, which should be declared like the type it wraps",
+                declaration, wrapper
+            );
+        }
+    }
+}
+
+/// Whether `declaration` contains `wrapper<` where `wrapper` is not the end of a longer name.
+fn names_wrapper(declaration: &str, wrapper: &str) -> bool {
+    declaration.match_indices(wrapper).any(|(start, _)| {
+        let before = declaration[..start].chars().next_back();
+        let after = declaration[start + wrapper.len()..].chars().next();
+        !matches!(before, Some(c) if c.is_alphanumeric() || c == '_') && after == Some('<')
+    })
+}
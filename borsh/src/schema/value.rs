@@ -0,0 +1,360 @@
+//! Decoding of Borsh data with a schema, without the Rust type that produced it.
+use core::convert::TryFrom;
+
+use super::layout::fixed_size;
+use super::skip::skip_value_checked;
+use super::{
+    declarations, BorshSchemaContainer, Declaration, Definition, FieldName, Fields, VariantName,
+};
+use crate::de::limits;
+use crate::maybestd::{
+    boxed::Box,
+    format,
+    io::{Error, ErrorKind, Result},
+    string::String,
+    vec::Vec,
+};
+use crate::BorshDeserialize;
+
+const ERROR_NOT_ALL_BYTES_READ: &str = "Not all bytes read";
+pub(super) const ERROR_UNEXPECTED_LENGTH_OF_INPUT: &str = "Unexpected length of input";
+pub(super) const ERROR_MAX_DEPTH_EXCEEDED: &str =
+    "Exceeded the maximum nesting depth of the schema";
+pub(super) const ERROR_ZERO_SIZED_ELEMENTS: &str =
+    "Sequence without a length prefix has elements that take no bytes";
+
+/// How deep the decoder goes into nested definitions before giving up. Protects against
+/// recursive schemas and malicious input exhausting the stack.
+pub(crate) const MAX_DEPTH: usize = 512;
+
+/// Where decoding failed: the declarations of the values being decoded, from the innermost one
+/// out, and the number of bytes left at the start of the innermost one.
+#[derive(Default)]
+pub(crate) struct Failure {
+    pub declarations: Vec<Declaration>,
+    pub remaining: usize,
+}
+
+/// A value decoded using the schema.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    /// The unit type, declared as `nil`.
+    Nil,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    String(String),
+    /// Elements of `Definition::Array`.
+    Array(Vec<Value>),
+    /// Elements of `Definition::Sequence`.
+    Sequence(Vec<Value>),
+    /// Elements of `Definition::Tuple`.
+    Tuple(Vec<Value>),
+    /// The variant of `Definition::Enum`, `Definition::NamedEnum`, `Definition::TaggedEnum` or
+    /// `Definition::U32TaggedEnum` and its associated value.
+    Enum {
+        variant: VariantName,
+        value: Box<Value>,
+    },
+    /// A struct with `Fields::NamedFields`.
+    Struct(Vec<(FieldName, Value)>),
+    /// A struct with `Fields::UnnamedFields`.
+    TupleStruct(Vec<Value>),
+    /// A struct with `Fields::Empty`.
+    UnitStruct,
+    /// The value of `Definition::Option`.
+    Option(Option<Box<Value>>),
+}
+
+impl BorshSchemaContainer {
+    /// Decodes a value of the container's type from the beginning of `buf` and advances `buf` past
+    /// it.
+    pub fn deserialize_value(&self, buf: &mut &[u8]) -> Result<Value> {
+        self.decode(&self.declaration, buf, 0, &mut None)
+    }
+
+    /// Like [`deserialize_value`](Self::deserialize_value), and on error also returns where
+    /// decoding failed.
+    pub(crate) fn deserialize_value_traced(
+        &self,
+        buf: &mut &[u8],
+    ) -> core::result::Result<Value, (Error, Failure)> {
+        let mut failure = None;
+        self.decode(&self.declaration, buf, 0, &mut failure)
+            .map_err(|err| (err, failure.unwrap_or_default()))
+    }
+
+    /// Decodes a value of the container's type from `v`, returning an error if not all bytes
+    /// were read.
+    pub fn value_from_slice(&self, v: &[u8]) -> Result<Value> {
+        let mut v_mut = v;
+        let result = self.deserialize_value(&mut v_mut)?;
+        if !v_mut.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ));
+        }
+        Ok(result)
+    }
+
+    /// Checks that `bytes` are exactly one value of the container's type, as
+    /// [`value_from_slice`](Self::value_from_slice) does, without building the [`Value`].
+    pub fn validate_bytes(&self, bytes: &[u8]) -> Result<()> {
+        let mut buf = bytes;
+        skip_value_checked(self, &self.declaration, &mut buf)?;
+        if !buf.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ));
+        }
+        Ok(())
+    }
+
+    fn decode(
+        &self,
+        declaration: &Declaration,
+        buf: &mut &[u8],
+        depth: usize,
+        failure: &mut Option<Failure>,
+    ) -> Result<Value> {
+        let remaining = buf.len();
+        let result = self.decode_definition(declaration, buf, depth, failure);
+        if result.is_err() {
+            // The innermost declaration records where it started, the outer ones add themselves.
+            let failure = failure.get_or_insert_with(|| Failure {
+                declarations: Vec::new(),
+                remaining,
+            });
+            failure.declarations.push(declaration.clone());
+        }
+        result
+    }
+
+    fn decode_definition(
+        &self,
+        declaration: &Declaration,
+        buf: &mut &[u8],
+        depth: usize,
+        failure: &mut Option<Failure>,
+    ) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_MAX_DEPTH_EXCEEDED));
+        }
+        let depth = depth + 1;
+        let definition = match self.definitions.get(declaration) {
+            Some(definition) => definition,
+            None => return decode_primitive(declaration, buf),
+        };
+        match definition {
+            Definition::Array { length, elements } => {
+                let mut values = Vec::new();
+                for _ in 0..*length {
+                    values.push(self.decode(elements, buf, depth, failure)?);
+                }
+                Ok(Value::Array(values))
+            }
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let mut values = Vec::new();
+                if *length_width == 0 {
+                    while !buf.is_empty() {
+                        let remaining = buf.len();
+                        values.push(self.decode(elements, buf, depth, failure)?);
+                        if buf.len() == remaining {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                ERROR_ZERO_SIZED_ELEMENTS,
+                            ));
+                        }
+                    }
+                    check_length(values.len() as u64, length_range)?;
+                } else {
+                    let length = decode_length(*length_width, buf)?;
+                    check_length(length, length_range)?;
+                    check_zero_sized_length(self, elements, length)?;
+                    for _ in 0..length {
+                        values.push(self.decode(elements, buf, depth, failure)?);
+                    }
+                }
+                Ok(Value::Sequence(values))
+            }
+            Definition::Tuple { elements } => {
+                let mut values = Vec::with_capacity(elements.len());
+                for element in elements {
+                    values.push(self.decode(element, buf, depth, failure)?);
+                }
+                Ok(Value::Tuple(values))
+            }
+            Definition::Enum { variants } => {
+                let tag = u8::deserialize(buf)?;
+                let (variant, variant_declaration) =
+                    variants.get(tag as usize).ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unexpected variant tag: {}", tag),
+                        )
+                    })?;
+                Ok(Value::Enum {
+                    variant: variant.clone(),
+                    value: Box::new(self.decode(variant_declaration, buf, depth, failure)?),
+                })
+            }
+            Definition::TaggedEnum { variants } => {
+                let tag = u8::deserialize(buf)?;
+                let (_, variant, variant_declaration) = variants
+                    .iter()
+                    .find(|(variant_tag, _, _)| *variant_tag == tag)
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unexpected variant tag: {}", tag),
+                        )
+                    })?;
+                Ok(Value::Enum {
+                    variant: variant.clone(),
+                    value: Box::new(self.decode(variant_declaration, buf, depth, failure)?),
+                })
+            }
+            Definition::U32TaggedEnum { variants } => {
+                let tag = u32::deserialize(buf)?;
+                let (_, variant, variant_declaration) = variants
+                    .iter()
+                    .find(|(variant_tag, _, _)| *variant_tag == tag)
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unexpected variant tag: {}", tag),
+                        )
+                    })?;
+                Ok(Value::Enum {
+                    variant: variant.clone(),
+                    value: Box::new(self.decode(variant_declaration, buf, depth, failure)?),
+                })
+            }
+            Definition::NamedEnum { variants } => {
+                let name = String::deserialize(buf)?;
+                let (variant, variant_declaration) = variants
+                    .iter()
+                    .find(|(variant, _)| *variant == name)
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unexpected variant name: {:?}", name),
+                        )
+                    })?;
+                Ok(Value::Enum {
+                    variant: variant.clone(),
+                    value: Box::new(self.decode(variant_declaration, buf, depth, failure)?),
+                })
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    let mut values = Vec::with_capacity(fields.len());
+                    for (name, field) in fields {
+                        values.push((name.clone(), self.decode(field, buf, depth, failure)?));
+                    }
+                    Ok(Value::Struct(values))
+                }
+                Fields::UnnamedFields(fields) => {
+                    let mut values = Vec::with_capacity(fields.len());
+                    for field in fields {
+                        values.push(self.decode(field, buf, depth, failure)?);
+                    }
+                    Ok(Value::TupleStruct(values))
+                }
+                Fields::Empty => Ok(Value::UnitStruct),
+            },
+            Definition::Option { inner } => match u8::deserialize(buf)? {
+                0 => Ok(Value::Option(None)),
+                1 => Ok(Value::Option(Some(Box::new(
+                    self.decode(inner, buf, depth, failure)?,
+                )))),
+                flag => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Invalid Option representation: {}. The first byte must be 0 or 1",
+                        flag
+                    ),
+                )),
+            },
+        }
+    }
+}
+
+pub(crate) fn decode_primitive(declaration: &str, buf: &mut &[u8]) -> Result<Value> {
+    Ok(match declaration {
+        declarations::NIL => Value::Nil,
+        declarations::BOOL => Value::Bool(BorshDeserialize::deserialize(buf)?),
+        declarations::U8 => Value::U8(BorshDeserialize::deserialize(buf)?),
+        declarations::U16 => Value::U16(BorshDeserialize::deserialize(buf)?),
+        declarations::U32 => Value::U32(BorshDeserialize::deserialize(buf)?),
+        declarations::U64 => Value::U64(BorshDeserialize::deserialize(buf)?),
+        declarations::U128 => Value::U128(BorshDeserialize::deserialize(buf)?),
+        declarations::I8 => Value::I8(BorshDeserialize::deserialize(buf)?),
+        declarations::I16 => Value::I16(BorshDeserialize::deserialize(buf)?),
+        declarations::I32 => Value::I32(BorshDeserialize::deserialize(buf)?),
+        declarations::I64 => Value::I64(BorshDeserialize::deserialize(buf)?),
+        declarations::I128 => Value::I128(BorshDeserialize::deserialize(buf)?),
+        declarations::F32 => Value::F32(BorshDeserialize::deserialize(buf)?),
+        declarations::F64 => Value::F64(BorshDeserialize::deserialize(buf)?),
+        declarations::STRING => Value::String(BorshDeserialize::deserialize(buf)?),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Declaration {} is not defined in the schema", declaration),
+            ))
+        }
+    })
+}
+
+pub(crate) fn decode_length(width: u8, buf: &mut &[u8]) -> Result<u64> {
+    Ok(match width {
+        1 => u8::deserialize(buf)? as u64,
+        2 => u16::deserialize(buf)? as u64,
+        4 => u32::deserialize(buf)? as u64,
+        8 => u64::deserialize(buf)?,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unsupported sequence length width: {}", width),
+            ))
+        }
+    })
+}
+
+pub(crate) fn check_length(length: u64, range: &core::ops::RangeInclusive<u64>) -> Result<()> {
+    if !range.contains(&length) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Sequence length {} is outside of the allowed range {}..={}",
+                length,
+                range.start(),
+                range.end()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the length of a sequence of `elements` against the limit of sequences of a zero-sized
+/// type if they take no bytes, as their length prefix alone claims them.
+pub(crate) fn check_zero_sized_length(
+    container: &BorshSchemaContainer,
+    elements: &str,
+    length: u64,
+) -> Result<()> {
+    if fixed_size(container, elements, 0) == Some(0) {
+        limits::check_zst_values(usize::try_from(length).unwrap_or(usize::MAX))?;
+    }
+    Ok(())
+}
@@ -2,7 +2,7 @@ use crate::maybestd::{
     io::{Error, ErrorKind, Result},
     vec::Vec,
 };
-use crate::schema::BorshSchemaContainer;
+use crate::schema::{BorshSchemaContainer, Declaration};
 use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
 
 /// Deserialize this instance from a slice of bytes, but assume that at the beginning we have
@@ -27,3 +27,21 @@ pub fn try_to_vec_with_schema<T: BorshSerialize + BorshSchema>(value: &T) -> Res
     res.extend(value.try_to_vec()?);
     Ok(res)
 }
+
+/// The schema container of the type of `value`, for generic code that has a value but does not
+/// name its type.
+///
+/// ```
+/// use borsh::{schema_container_of, BorshSchema};
+///
+/// let value = vec![(1u8, "one".to_string())];
+/// assert_eq!(schema_container_of(&value), Vec::<(u8, String)>::schema_container());
+/// ```
+pub fn schema_container_of<T: BorshSchema + ?Sized>(_value: &T) -> BorshSchemaContainer {
+    T::schema_container()
+}
+
+/// The declaration of the type of `value`, like [`schema_container_of`].
+pub fn declaration_of<T: BorshSchema + ?Sized>(_value: &T) -> Declaration {
+    T::declaration()
+}
@@ -0,0 +1,169 @@
+//! Sequences with a length prefix other than the default `u32`.
+//!
+//! ```
+//! use borsh::{BorshDeserialize, BorshSerialize, SeqWithLen};
+//!
+//! let seq = SeqWithLen::<u16, u8>::new(vec![1, 2, 3]);
+//! let data = seq.try_to_vec().unwrap();
+//! assert_eq!(data, vec![3, 0, 1, 2, 3]);
+//! assert_eq!(SeqWithLen::<u16, u8>::try_from_slice(&data).unwrap(), seq);
+//! ```
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::ops::{Deref, DerefMut};
+
+use crate::de::{hint, limits};
+use crate::maybestd::{
+    collections::HashMap,
+    format,
+    io::{Error, ErrorKind, Read, Result, Write},
+    vec::Vec,
+};
+use crate::schema::{Declaration, Definition};
+use crate::ser::serialize_slice;
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+const ERROR_LENGTH_OVERFLOW: &str = "Sequence is too long for its length prefix";
+const ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_USIZE: &str = "Overflow on machine with 32 bit usize";
+
+/// An unsigned integer that can be used as the length prefix of [`SeqWithLen`].
+pub trait LengthPrefix: BorshSerialize + BorshDeserialize + BorshSchema + Copy {
+    /// How many bytes the prefix takes.
+    const WIDTH: u8;
+    /// The largest length the prefix can hold.
+    const MAX_LEN: u64;
+
+    /// Converts the length of a sequence into the prefix, if it fits.
+    fn from_len(len: usize) -> Option<Self>;
+
+    /// Converts the prefix into a length, if it fits into `usize`.
+    fn to_len(self) -> Option<usize>;
+}
+
+macro_rules! impl_length_prefix {
+    ($type: ident) => {
+        impl LengthPrefix for $type {
+            const WIDTH: u8 = size_of::<$type>() as u8;
+            const MAX_LEN: u64 = $type::MAX as u64;
+
+            #[inline]
+            fn from_len(len: usize) -> Option<Self> {
+                $type::try_from(len).ok()
+            }
+
+            #[inline]
+            fn to_len(self) -> Option<usize> {
+                usize::try_from(self).ok()
+            }
+        }
+    };
+}
+
+impl_length_prefix!(u8);
+impl_length_prefix!(u16);
+impl_length_prefix!(u32);
+impl_length_prefix!(u64);
+
+/// A `Vec<T>` serialized with an `L` length prefix instead of the default `u32` one.
+///
+/// Serialization returns an error if the vector has more than `L::MAX` elements.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SeqWithLen<L, T> {
+    items: Vec<T>,
+    length: PhantomData<L>,
+}
+
+impl<L, T> SeqWithLen<L, T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            length: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<L, T> From<Vec<T>> for SeqWithLen<L, T> {
+    fn from(items: Vec<T>) -> Self {
+        Self::new(items)
+    }
+}
+
+impl<L, T> Deref for SeqWithLen<L, T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.items
+    }
+}
+
+impl<L, T> DerefMut for SeqWithLen<L, T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.items
+    }
+}
+
+impl<L, T> BorshSerialize for SeqWithLen<L, T>
+where
+    L: LengthPrefix,
+    T: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        L::from_len(self.items.len())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, ERROR_LENGTH_OVERFLOW))?
+            .serialize(writer)?;
+        serialize_slice(&self.items, writer)
+    }
+}
+
+impl<L, T> BorshDeserialize for SeqWithLen<L, T>
+where
+    L: LengthPrefix,
+    T: BorshDeserialize,
+{
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = L::deserialize_reader(reader)?.to_len().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                ERROR_OVERFLOW_ON_MACHINE_WITH_32_BIT_USIZE,
+            )
+        })?;
+        limits::check_sequence_of::<T>(len)?;
+        let _depth = limits::enter()?;
+        let mut items = if size_of::<T>() == 0 {
+            Vec::new()
+        } else {
+            Vec::with_capacity(hint::cautious::<T>(u32::try_from(len).unwrap_or(u32::MAX)))
+        };
+        for _ in 0..len {
+            items.push(T::deserialize_reader(reader)?);
+        }
+        Ok(Self::new(items))
+    }
+}
+
+impl<L, T> BorshSchema for SeqWithLen<L, T>
+where
+    L: LengthPrefix,
+    T: BorshSchema,
+{
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: L::WIDTH,
+            length_range: 0..=L::MAX_LEN,
+            elements: T::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!(r#"SeqWithLen<{}, {}>"#, L::declaration(), T::declaration())
+    }
+}
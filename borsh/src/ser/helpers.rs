@@ -1,8 +1,16 @@
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
 use crate::maybestd::{
-    io::{Result, Write},
+    io::{Error, ErrorKind, Result, Write},
     vec::Vec,
 };
-use crate::BorshSerialize;
+use crate::{BorshDeserialize, BorshSerialize};
+
+/// The capacity of the buffer used by [`to_writer_buffered`].
+const BUFFER_CAPACITY: usize = 8 * 1024;
+
+const ERROR_NOT_CANONICAL: &str = "The bytes are not the canonical encoding of the value";
 
 /// Serialize an object into a vector of bytes.
 pub fn to_vec<T>(value: &T) -> Result<Vec<u8>>
@@ -12,6 +20,27 @@ where
     value.try_to_vec()
 }
 
+/// Serializes an object at the end of `buf`, without clearing it first. Reusing one buffer for
+/// many values avoids the allocation that [`to_vec`] makes for each of them:
+///
+/// ```
+/// let mut buf = Vec::new();
+/// for value in [1u32, 2, 3] {
+///     buf.clear();
+///     borsh::to_vec_in(&value, &mut buf).unwrap();
+///     assert_eq!(buf, borsh::to_vec(&value).unwrap());
+/// }
+/// ```
+///
+/// The appended bytes are the same as the output of [`to_vec`]. On error, `buf` is truncated back
+/// to its original length, see [`BorshSerialize::append_to_vec`].
+pub fn to_vec_in<T>(value: &T, buf: &mut Vec<u8>) -> Result<()>
+where
+    T: BorshSerialize + ?Sized,
+{
+    value.append_to_vec(buf).map(|_| ())
+}
+
 /// Serializes an object directly into a `Writer`.
 pub fn to_writer<T, W: Write>(mut writer: W, value: &T) -> Result<()>
 where
@@ -19,3 +48,200 @@ where
 {
     value.serialize(&mut writer)
 }
+
+/// Serializes an object into a `Writer` through an internal buffer, so that the writer receives a
+/// few large writes instead of one write per primitive. Use it for writers that are expensive to
+/// call, such as files and sockets that are not wrapped in a `BufWriter`.
+///
+/// The bytes written are the same as with [`to_writer`]. All buffered bytes are written before
+/// returning, but the writer itself is not flushed.
+pub fn to_writer_buffered<T, W: Write>(writer: W, value: &T) -> Result<()>
+where
+    T: BorshSerialize + ?Sized,
+{
+    let mut writer = BufferedWriter::new(writer);
+    value.serialize(&mut writer)?;
+    writer.flush_buffer()
+}
+
+/// Serializes `slice` with its length prefix, the same bytes as a `Vec<T>` with the same elements.
+///
+/// Slices of `u8` are written with a single `write_all`, and slices of values that always take the
+/// same number of bytes, such as integers, floats, `bool` and arrays of them, are encoded into a
+/// buffer on the stack and written in chunks instead of one write per element. Serializing a
+/// `Vec<T>` or a `[T]` takes the same paths.
+///
+/// ```
+/// let values = [1u32, 2, 3];
+/// let mut data = Vec::new();
+/// borsh::serialize_slice(&values, &mut data).unwrap();
+/// assert_eq!(data, borsh::to_vec(&values.to_vec()).unwrap());
+/// ```
+pub fn serialize_slice<T: BorshSerialize, W: Write>(slice: &[T], writer: &mut W) -> Result<()> {
+    slice.serialize(writer)
+}
+
+/// Serializes the entries of a map in the order given by `cmp`, for protocols whose canonical
+/// form orders the entries by something other than their keys:
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// let balances: HashMap<String, u64> =
+///     vec![("a".to_string(), 5), ("b".to_string(), 9)].into_iter().collect();
+/// let mut data = Vec::new();
+/// borsh::serialize_map_ordered(&balances, &mut data, |(_, a), (_, b)| b.cmp(a)).unwrap();
+/// assert_eq!(data, borsh::to_vec(&vec![("b", 9u64), ("a", 5)]).unwrap());
+/// ```
+///
+/// The bytes have the layout of a `HashMap<K, V>` or a `BTreeMap<K, V>`, and deserialize into one
+/// regardless of the order, except with the `canonical` feature, which only accepts keys in
+/// ascending order. Entries that `cmp` finds equal keep the order of iteration.
+pub fn serialize_map_ordered<'a, K, V, M, F, W>(map: M, writer: &mut W, mut cmp: F) -> Result<()>
+where
+    K: BorshSerialize + 'a,
+    V: BorshSerialize + 'a,
+    M: IntoIterator<Item = (&'a K, &'a V)>,
+    F: FnMut(&(&'a K, &'a V), &(&'a K, &'a V)) -> Ordering,
+    W: Write,
+{
+    let mut entries = map.into_iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| cmp(a, b));
+    u32::try_from(entries.len())
+        .map_err(|_| ErrorKind::InvalidInput)?
+        .serialize(writer)?;
+    for (key, value) in entries {
+        key.serialize(writer)?;
+        value.serialize(writer)?;
+    }
+    Ok(())
+}
+
+/// Returns the number of bytes that `value` serializes to, counted without storing them:
+///
+/// ```
+/// let value = (1u8, vec![2u32, 3], "four".to_string());
+/// assert_eq!(borsh::serialized_len(&value), borsh::to_vec(&value).unwrap().len());
+/// ```
+///
+/// # Panics
+///
+/// If `value` fails to serialize, for example because it has a collection with more than
+/// `u32::MAX` elements.
+pub fn serialized_len<T>(value: &T) -> usize
+where
+    T: BorshSerialize + ?Sized,
+{
+    let mut counter = LengthCounter(0);
+    value
+        .serialize(&mut counter)
+        .expect("the value to count the serialized length of fails to serialize");
+    counter.0
+}
+
+/// Checks that `bytes` is the canonical encoding of a `T`: it deserializes as a `T` without bytes
+/// left over, and serializing the result gives back exactly `bytes`.
+///
+/// Borsh deserialization accepts some inputs that serialization never produces, such as the
+/// entries of a `HashMap` in an order other than sorted by key. Rejecting them makes sure that each
+/// value has a single encoding, as required for hashing or content-addressed storage.
+pub fn is_canonical<T>(bytes: &[u8]) -> bool
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    match T::try_from_slice(bytes) {
+        Ok(value) => matches!(value.try_to_vec(), Ok(reserialized) if reserialized == bytes),
+        Err(_) => false,
+    }
+}
+
+/// Deserializes a `T` from `bytes` and fails unless serializing it gives back exactly `bytes`, the
+/// check of [`is_canonical`] for data that crosses a trust boundary, such as signed bytes:
+///
+/// ```
+/// use borsh::maybestd::collections::BTreeMap;
+///
+/// let map: BTreeMap<u8, bool> = vec![(1, true), (2, false)].into_iter().collect();
+/// let data = borsh::to_vec(&map).unwrap();
+/// assert_eq!(borsh::assert_canonical_roundtrip::<BTreeMap<u8, bool>>(&data).unwrap(), map);
+///
+/// // The same entries with the keys in the wrong order.
+/// let unsorted = borsh::to_vec(&vec![(2u8, false), (1u8, true)]).unwrap();
+/// assert!(borsh::assert_canonical_roundtrip::<BTreeMap<u8, bool>>(&unsorted).is_err());
+/// ```
+///
+/// The `canonical` feature rejects most non-canonical inputs during deserialization already, this
+/// check covers the other ones, such as the types with their own `BorshDeserialize`
+/// implementation.
+pub fn assert_canonical_roundtrip<T>(bytes: &[u8]) -> Result<T>
+where
+    T: BorshSerialize + BorshDeserialize + PartialEq,
+{
+    let value = T::try_from_slice(bytes)?;
+    if value.try_to_vec()? != bytes {
+        return Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_CANONICAL));
+    }
+    Ok(value)
+}
+
+/// Collects small writes into a buffer and passes writes that do not fit into the buffer straight
+/// to the inner writer.
+struct BufferedWriter<W> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> BufferedWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buffer: Vec::with_capacity(BUFFER_CAPACITY),
+        }
+    }
+
+    fn flush_buffer(&mut self) -> Result<()> {
+        if !self.buffer.is_empty() {
+            self.inner.write_all(&self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for BufferedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.buffer.len() + buf.len() > BUFFER_CAPACITY {
+            self.flush_buffer()?;
+        }
+        if buf.len() >= BUFFER_CAPACITY {
+            self.inner.write(buf)
+        } else {
+            self.buffer.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.flush_buffer()?;
+        self.inner.flush()
+    }
+}
+
+/// A writer that drops the bytes and only keeps their number, for [`serialized_len`].
+struct LengthCounter(usize);
+
+impl Write for LengthCounter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.0 += buf.len();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::maybestd::io::{Result, Write};
+
+/// A writer that forwards to another writer and counts the bytes and the calls that go through
+/// it, to find out how the serialization of a value is split into writes:
+///
+/// ```
+/// use borsh::ser::InstrumentedWriter;
+/// use borsh::BorshSerialize;
+///
+/// let mut writer = InstrumentedWriter::new(Vec::new());
+/// (7u32, vec![1u8, 2, 3]).serialize(&mut writer).unwrap();
+/// assert_eq!(writer.bytes_written(), 11);
+/// // The `u32`, the length of the vector and its bytes.
+/// assert_eq!(writer.calls(), 3);
+/// assert_eq!(writer.into_inner().len(), 11);
+/// ```
+///
+/// Each call to `write` or `write_all` counts once, even when the inner writer takes several
+/// calls to write the bytes. Counts can be compared before and after serializing a field to see
+/// what it contributes.
+#[derive(Debug, Default)]
+pub struct InstrumentedWriter<W> {
+    inner: W,
+    bytes_written: u64,
+    calls: u64,
+}
+
+impl<W> InstrumentedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            bytes_written: 0,
+            calls: 0,
+        }
+    }
+
+    /// How many bytes were written to the inner writer.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// How many times `write` or `write_all` was called.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Sets both counts back to zero.
+    pub fn reset(&mut self) {
+        self.bytes_written = 0;
+        self.calls = 0;
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for InstrumentedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.calls += 1;
+        let written = self.inner.write(buf)?;
+        self.bytes_written += written as u64;
+        Ok(written)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.calls += 1;
+        self.inner.write_all(buf)?;
+        self.bytes_written += buf.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
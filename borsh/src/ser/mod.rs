@@ -15,8 +15,17 @@ use crate::maybestd::{
 use crate::maybestd::{rc::Rc, sync::Arc};
 
 pub(crate) mod helpers;
+mod instrumented;
+mod serializer;
+mod with_context;
+
+pub use instrumented::InstrumentedWriter;
+pub use serializer::Serializer;
+pub use with_context::BorshSerializeWith;
 
 const DEFAULT_SERIALIZER_CAPACITY: usize = 1024;
+/// The size of the buffer that slices of fixed-size values are encoded into before being written.
+const SLICE_CHUNK_LEN: usize = 1024;
 
 /// A data-structure that can be serialized into binary format by NBOR.
 ///
@@ -40,16 +49,35 @@ const DEFAULT_SERIALIZER_CAPACITY: usize = 1024;
 /// let mut buffer_slice_enough_for_the_data = &mut buffer[1024..1024 + single_serialized_buffer_len];
 /// x.serialize(&mut buffer_slice_enough_for_the_data).unwrap();
 /// ```
+///
+/// # `usize` and `isize`
+///
+/// `usize` and `isize` are written as a `u64` and an `i64` on every target, so the bytes do not
+/// depend on the pointer width of the producer. Values above `u32::MAX` (or outside of the `i32`
+/// range) fail to deserialize on 32-bit targets such as wasm32, see
+/// [`BorshDeserialize`](crate::BorshDeserialize#usize-and-isize); data meant for them should
+/// keep such values in range or use `u64`.
 pub trait BorshSerialize {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()>;
 
     /// Serialize this instance into a vector of bytes.
     fn try_to_vec(&self) -> Result<Vec<u8>> {
         let mut result = Vec::with_capacity(DEFAULT_SERIALIZER_CAPACITY);
-        self.serialize(&mut result)?;
+        self.append_to_vec(&mut result)?;
         Ok(result)
     }
 
+    /// Serialize this instance at the end of `buf` and return the number of bytes it added. On
+    /// error, `buf` is truncated back to its original length.
+    fn append_to_vec(&self, buf: &mut Vec<u8>) -> Result<usize> {
+        let start = buf.len();
+        if let Err(err) = self.serialize(buf) {
+            buf.truncate(start);
+            return Err(err);
+        }
+        Ok(buf.len() - start)
+    }
+
     #[inline]
     #[doc(hidden)]
     fn u8_slice(slice: &[Self]) -> Option<&[u8]>
@@ -59,6 +87,68 @@ pub trait BorshSerialize {
         let _ = slice;
         None
     }
+
+    /// The number of bytes that every value of the type serializes to, if it is the same for all
+    /// of them. Slices of such values are encoded into a buffer on the stack and written in large
+    /// chunks.
+    #[inline]
+    #[doc(hidden)]
+    fn fixed_size() -> Option<usize>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// The tag of an enum value, implemented by `#[derive(BorshSerialize)]` on enums.
+///
+/// ```
+/// use borsh::{BorshSerialize, BorshTagged};
+///
+/// #[derive(BorshSerialize)]
+/// #[repr(u8)]
+/// enum Message {
+///     Ping,
+///     Text(String),
+///     Close = 10,
+/// }
+///
+/// assert_eq!(Message::VARIANT_TAGS, &[(0, "Ping"), (1, "Text"), (10, "Close")]);
+/// assert_eq!(Message::variant_name(10), Some("Close"));
+/// assert_eq!(Message::variant_name(2), None);
+/// assert_eq!(Message::variant_count(), 3);
+/// let message = Message::Text("hi".to_string());
+/// assert_eq!(message.borsh_tag(), message.try_to_vec().unwrap()[0]);
+/// ```
+///
+/// The constant needs neither a value nor a schema container, and is available without `std`.
+/// It matches the variants of the `BorshSchema` of the enum. It is not implemented for enums with
+/// `#[borsh(named_variants)]`, since they are tagged by the variant name.
+pub trait BorshTagged {
+    /// The tag and the name of each variant, in declaration order.
+    const VARIANT_TAGS: &'static [(u8, &'static str)];
+
+    /// The tag that is written before the fields of this value.
+    fn borsh_tag(&self) -> u8;
+
+    /// The tag and the name of each variant, in declaration order. Same as `VARIANT_TAGS`.
+    fn variant_tags() -> &'static [(u8, &'static str)] {
+        Self::VARIANT_TAGS
+    }
+
+    /// The number of variants, e.g. the size of a dispatch table indexed by variant.
+    fn variant_count() -> usize {
+        Self::VARIANT_TAGS.len()
+    }
+
+    /// The name of the variant with the tag `tag`, if there is one.
+    fn variant_name(tag: u8) -> Option<&'static str> {
+        Self::VARIANT_TAGS
+            .iter()
+            .find(|(variant_tag, _)| *variant_tag == tag)
+            .map(|(_, name)| *name)
+    }
 }
 
 impl BorshSerialize for u8 {
@@ -81,6 +171,11 @@ macro_rules! impl_for_integer {
                 let bytes = self.to_le_bytes();
                 writer.write_all(&bytes)
             }
+
+            #[inline]
+            fn fixed_size() -> Option<usize> {
+                Some(core::mem::size_of::<$type>())
+            }
         }
     };
 }
@@ -118,12 +213,32 @@ impl_for_nonzero_integer!(core::num::NonZeroU64);
 impl_for_nonzero_integer!(core::num::NonZeroU128);
 impl_for_nonzero_integer!(core::num::NonZeroUsize);
 
+/// Serialized like `T`.
+impl<T: BorshSerialize> BorshSerialize for core::num::Wrapping<T> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+/// Serialized like `T`.
+#[cfg(feature = "saturating")]
+#[clippy::msrv = "1.74"]
+impl<T: BorshSerialize> BorshSerialize for core::num::Saturating<T> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+/// Written as an `i64`.
 impl BorshSerialize for isize {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         BorshSerialize::serialize(&(*self as i64), writer)
     }
 }
 
+/// Written as a `u64`.
 impl BorshSerialize for usize {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         BorshSerialize::serialize(&(*self as u64), writer)
@@ -143,6 +258,11 @@ macro_rules! impl_for_float {
                 );
                 writer.write_all(&self.to_bits().to_le_bytes())
             }
+
+            #[inline]
+            fn fixed_size() -> Option<usize> {
+                Some(core::mem::size_of::<$type>())
+            }
         }
     };
 }
@@ -155,6 +275,11 @@ impl BorshSerialize for bool {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         (u8::from(*self)).serialize(writer)
     }
+
+    #[inline]
+    fn fixed_size() -> Option<usize> {
+        Some(1)
+    }
 }
 
 impl<T> BorshSerialize for core::ops::Range<T>
@@ -218,11 +343,42 @@ impl BorshSerialize for String {
     }
 }
 
+/// Serialized like `Vec<u8>`, without the trailing NUL.
+#[cfg(feature = "std")]
+impl BorshSerialize for std::ffi::CStr {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.to_bytes().serialize(writer)
+    }
+}
+
+/// Serialized like `Vec<u8>`, without the trailing NUL.
+#[cfg(feature = "std")]
+impl BorshSerialize for std::ffi::CString {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_bytes().serialize(writer)
+    }
+}
+
 /// Helper method that is used to serialize a slice of data (without the length marker).
 #[inline]
-fn serialize_slice<T: BorshSerialize, W: Write>(data: &[T], writer: &mut W) -> Result<()> {
+pub(crate) fn serialize_slice<T: BorshSerialize, W: Write>(
+    data: &[T],
+    writer: &mut W,
+) -> Result<()> {
     if let Some(u8_slice) = T::u8_slice(data) {
         writer.write_all(u8_slice)?;
+    } else if let Some(size @ 1..=SLICE_CHUNK_LEN) = T::fixed_size() {
+        let mut chunk = [0u8; SLICE_CHUNK_LEN];
+        for items in data.chunks(SLICE_CHUNK_LEN / size) {
+            let mut buf = &mut chunk[..];
+            for item in items {
+                item.serialize(&mut buf)?;
+            }
+            let len = SLICE_CHUNK_LEN - buf.len();
+            writer.write_all(&chunk[..len])?;
+        }
     } else {
         for item in data {
             item.serialize(writer)?;
@@ -295,6 +451,162 @@ impl BorshSerialize for bson::oid::ObjectId {
     }
 }
 
+/// Serialized as the `i64` number of seconds since the Unix epoch followed by the `u32` number of
+/// nanoseconds since that second. The nanoseconds go above 999_999_999 only for leap seconds.
+#[cfg(any(test, feature = "chrono"))]
+impl BorshSerialize for chrono::DateTime<chrono::Utc> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.timestamp().serialize(writer)?;
+        self.timestamp_subsec_nanos().serialize(writer)
+    }
+}
+
+/// Serialized like `DateTime<Utc>`, as if the time was in UTC.
+#[cfg(any(test, feature = "chrono"))]
+impl BorshSerialize for chrono::NaiveDateTime {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.and_utc().serialize(writer)
+    }
+}
+
+/// Serialized as the 16 bytes of its internal representation, see `Decimal::serialize`: the flags
+/// with the scale and the sign, followed by the low, middle and high 32 bits of the mantissa, all
+/// little-endian.
+#[cfg(any(test, feature = "rust_decimal"))]
+impl BorshSerialize for rust_decimal::Decimal {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&rust_decimal::Decimal::serialize(self))
+    }
+}
+
+/// Serialized like `Vec<T>`.
+#[cfg(any(test, feature = "heapless"))]
+impl<T, const N: usize> BorshSerialize for heapless::Vec<T, N>
+where
+    T: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_slice().serialize(writer)
+    }
+}
+
+/// Serialized like `String`.
+#[cfg(any(test, feature = "heapless"))]
+impl<const N: usize> BorshSerialize for heapless::String<N> {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_str().serialize(writer)
+    }
+}
+
+/// Serialized like `Vec<T>`.
+#[cfg(any(test, feature = "smallvec"))]
+impl<A> BorshSerialize for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_slice().serialize(writer)
+    }
+}
+
+/// Serialized as the `u8` tag of the variant, 0 for `Left` and 1 for `Right`, followed by its
+/// value.
+#[cfg(any(test, feature = "either"))]
+impl<L, R> BorshSerialize for either::Either<L, R>
+where
+    L: BorshSerialize,
+    R: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            either::Either::Left(l) => {
+                0u8.serialize(writer)?;
+                l.serialize(writer)
+            }
+            either::Either::Right(r) => {
+                1u8.serialize(writer)?;
+                r.serialize(writer)
+            }
+        }
+    }
+}
+
+/// Serialized as the little-endian bytes of the number, like `Vec<u8>`. The bytes have no
+/// trailing zeros, so zero is serialized without any bytes.
+#[cfg(any(test, feature = "num-bigint"))]
+impl BorshSerialize for num_bigint::BigUint {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        if self.bits() == 0 {
+            return Vec::<u8>::new().serialize(writer);
+        }
+        self.to_bytes_le().serialize(writer)
+    }
+}
+
+/// Serialized as a `u8` sign, 0 for zero, 1 for positive and 2 for negative numbers, followed by
+/// the magnitude serialized like `BigUint`.
+#[cfg(any(test, feature = "num-bigint"))]
+impl BorshSerialize for num_bigint::BigInt {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let sign: u8 = match self.sign() {
+            num_bigint::Sign::NoSign => 0,
+            num_bigint::Sign::Plus => 1,
+            num_bigint::Sign::Minus => 2,
+        };
+        sign.serialize(writer)?;
+        self.magnitude().serialize(writer)
+    }
+}
+
+/// Serialized like `HashMap<K, V>`, but in iteration order instead of sorted by key, since the
+/// order of an `IndexMap` is part of its value.
+#[cfg(any(test, feature = "indexmap"))]
+impl<K, V, H> BorshSerialize for indexmap::IndexMap<K, V, H>
+where
+    K: BorshSerialize,
+    V: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        u32::try_from(self.len())
+            .map_err(|_| ErrorKind::InvalidInput)?
+            .serialize(writer)?;
+        for (key, value) in self {
+            key.serialize(writer)?;
+            value.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialized like `HashSet<T>`, but in iteration order instead of sorted.
+#[cfg(any(test, feature = "indexmap"))]
+impl<T, H> BorshSerialize for indexmap::IndexSet<T, H>
+where
+    T: BorshSerialize,
+{
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        u32::try_from(self.len())
+            .map_err(|_| ErrorKind::InvalidInput)?
+            .serialize(writer)?;
+        for item in self {
+            item.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
 impl<T> BorshSerialize for VecDeque<T>
 where
     T: BorshSerialize,
@@ -489,14 +801,13 @@ where
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         if N == 0 {
             return Ok(());
-        } else if let Some(u8_slice) = T::u8_slice(self) {
-            writer.write_all(u8_slice)?;
-        } else {
-            for el in self.iter() {
-                el.serialize(writer)?;
-            }
         }
-        Ok(())
+        serialize_slice(self, writer)
+    }
+
+    #[inline]
+    fn fixed_size() -> Option<usize> {
+        T::fixed_size()?.checked_mul(N)
     }
 }
 
@@ -560,3 +871,9 @@ impl<T: ?Sized> BorshSerialize for PhantomData<T> {
         Ok(())
     }
 }
+
+impl BorshSerialize for core::convert::Infallible {
+    fn serialize<W: Write>(&self, _: &mut W) -> Result<()> {
+        match *self {}
+    }
+}
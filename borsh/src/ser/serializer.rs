@@ -0,0 +1,134 @@
+use core::convert::TryFrom;
+
+use crate::maybestd::io::{Error, ErrorKind, Result, Write};
+use crate::BorshSerialize;
+
+const ERROR_SEQUENCE_TOO_LONG: &str = "Sequences can have at most u32::MAX elements";
+
+macro_rules! serialize_primitive {
+    ($($method: ident: $type: ty),*) => {
+        $(
+            #[doc = concat!("Writes a `", stringify!($type), "`.")]
+            #[inline]
+            pub fn $method(&mut self, value: $type) -> Result<()> {
+                value.serialize(&mut self.writer)
+            }
+        )*
+    };
+}
+
+/// Writes Borsh values piece by piece, for formats that mix Borsh primitives with other data and
+/// for `BorshSerialize` impls that write the parts of a value by hand:
+///
+/// ```
+/// use borsh::ser::Serializer;
+/// use borsh::BorshSerialize;
+///
+/// #[derive(BorshSerialize)]
+/// enum Message {
+///     Ping,
+///     Text { from: u64, lines: Vec<String> },
+/// }
+///
+/// let mut serializer = Serializer::new(Vec::new());
+/// serializer.serialize_variant(1).unwrap();
+/// serializer.serialize_u64(7).unwrap();
+/// serializer.serialize_seq_header(2).unwrap();
+/// serializer.serialize_str("hello").unwrap();
+/// serializer.serialize_str("world").unwrap();
+///
+/// let message = Message::Text { from: 7, lines: vec!["hello".into(), "world".into()] };
+/// assert_eq!(serializer.into_inner(), message.try_to_vec().unwrap());
+/// ```
+///
+/// Each method writes the same bytes as `BorshSerialize` for the same value, so the
+/// `BorshSerialize` impls of the crate can all be written with them: a struct is its fields one
+/// after the other, an enum is [`serialize_variant`](Self::serialize_variant) followed by the
+/// fields of the variant, and a collection is
+/// [`serialize_seq_header`](Self::serialize_seq_header) followed by its elements.
+#[derive(Debug, Default)]
+pub struct Serializer<W> {
+    writer: W,
+}
+
+impl<W: Write> Serializer<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    serialize_primitive!(
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_bool: bool
+    );
+
+    /// Writes an `f32`. Panics on NaN, like its `BorshSerialize` impl.
+    #[inline]
+    pub fn serialize_f32(&mut self, value: f32) -> Result<()> {
+        value.serialize(&mut self.writer)
+    }
+
+    /// Writes an `f64`. Panics on NaN, like its `BorshSerialize` impl.
+    #[inline]
+    pub fn serialize_f64(&mut self, value: f64) -> Result<()> {
+        value.serialize(&mut self.writer)
+    }
+
+    /// Writes a string with its length prefix, the bytes of a `String`.
+    pub fn serialize_str(&mut self, value: &str) -> Result<()> {
+        value.serialize(&mut self.writer)
+    }
+
+    /// Writes bytes with their length prefix, the bytes of a `Vec<u8>`.
+    pub fn serialize_bytes(&mut self, value: &[u8]) -> Result<()> {
+        value.serialize(&mut self.writer)
+    }
+
+    /// Writes bytes as they are, without a length prefix, the bytes of a `[u8; N]`.
+    pub fn serialize_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(bytes)
+    }
+
+    /// Writes the `u32` length prefix of a sequence of `len` elements, which the caller writes
+    /// next. Fails if `len` does not fit in a `u32`.
+    pub fn serialize_seq_header(&mut self, len: usize) -> Result<()> {
+        let len = u32::try_from(len)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, ERROR_SEQUENCE_TOO_LONG))?;
+        len.serialize(&mut self.writer)
+    }
+
+    /// Writes the tag of an enum variant, which the caller follows with the fields of the variant.
+    pub fn serialize_variant(&mut self, tag: u8) -> Result<()> {
+        tag.serialize(&mut self.writer)
+    }
+
+    /// Writes the tag of an `Option`, which the caller follows with the value if it is `Some`.
+    pub fn serialize_option_tag(&mut self, is_some: bool) -> Result<()> {
+        u8::from(is_some).serialize(&mut self.writer)
+    }
+
+    /// Writes any value with its `BorshSerialize` impl.
+    pub fn serialize<T: BorshSerialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut self.writer)
+    }
+}
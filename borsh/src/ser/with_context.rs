@@ -0,0 +1,250 @@
+//! Serialization that needs an external context, such as an interner or a protocol version.
+use core::convert::TryFrom;
+use core::hash::BuildHasher;
+
+use crate::maybestd::{
+    boxed::Box,
+    collections::{BTreeMap, HashMap},
+    io::{ErrorKind, Result, Write},
+    string::String,
+    vec::Vec,
+};
+
+use super::BorshSerialize;
+
+/// A data-structure that can be serialized into binary format with the help of a context of type
+/// `Ctx`, the counterpart of [`BorshDeserializeWith`](crate::de::BorshDeserializeWith).
+///
+/// `#[derive(BorshSerialize)]` implements it, instead of `BorshSerialize`, for types with
+/// `#[borsh(ser_context = "Ctx")]`, or `#[borsh(context = "Ctx")]` which derives both traits with
+/// the same context. The fields with `#[borsh(with_context)]` are serialized with the context,
+/// the other fields with `BorshSerialize`:
+///
+/// ```
+/// use borsh::maybestd::io::{Result, Write};
+/// use borsh::ser::BorshSerializeWith;
+/// use borsh::BorshSerialize;
+///
+/// struct Interner(Vec<String>);
+///
+/// /// An index into the strings of the `Interner`, serialized as the string.
+/// struct Symbol(usize);
+///
+/// impl BorshSerializeWith<Interner> for Symbol {
+///     fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Interner) -> Result<()> {
+///         ctx.0[self.0].serialize(writer)
+///     }
+/// }
+///
+/// #[derive(BorshSerialize)]
+/// #[borsh(ser_context = "Interner")]
+/// struct Transfer {
+///     #[borsh(with_context)]
+///     from: Symbol,
+///     amount: u64,
+/// }
+///
+/// let interner = Interner(vec!["alice".to_string()]);
+/// let transfer = Transfer { from: Symbol(0), amount: 10 };
+/// let data = transfer.try_to_vec_with(&interner).unwrap();
+/// assert_eq!(data, ("alice", 10u64).try_to_vec().unwrap());
+/// ```
+///
+/// It is implemented for the primitive types and strings with any context, ignoring it, and for
+/// the standard containers of types that implement it, passing the context to each element.
+pub trait BorshSerializeWith<Ctx: ?Sized> {
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()>;
+
+    /// Serialize this instance into a vector of bytes.
+    fn try_to_vec_with(&self, ctx: &Ctx) -> Result<Vec<u8>> {
+        let mut result = Vec::with_capacity(super::DEFAULT_SERIALIZER_CAPACITY);
+        self.serialize_with(&mut result, ctx)?;
+        Ok(result)
+    }
+}
+
+macro_rules! impl_for_context_free {
+    ($($type: ty)+) => {
+    $(
+        impl<Ctx: ?Sized> BorshSerializeWith<Ctx> for $type {
+            #[inline]
+            fn serialize_with<W: Write>(&self, writer: &mut W, _ctx: &Ctx) -> Result<()> {
+                <$type as BorshSerialize>::serialize(self, writer)
+            }
+        }
+    )+
+    };
+}
+
+impl_for_context_free!(() bool f32 f64 i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize str String);
+
+/// Writes the `u32` length prefix of a sequence.
+fn serialize_len<W: Write>(len: usize, writer: &mut W) -> Result<()> {
+    u32::try_from(len)
+        .map_err(|_| ErrorKind::InvalidInput)?
+        .serialize(writer)
+}
+
+impl<T, Ctx> BorshSerializeWith<Ctx> for [T]
+where
+    T: BorshSerializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        serialize_len(self.len(), writer)?;
+        for item in self {
+            item.serialize_with(writer, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, Ctx> BorshSerializeWith<Ctx> for Vec<T>
+where
+    T: BorshSerializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        self.as_slice().serialize_with(writer, ctx)
+    }
+}
+
+impl<T, Ctx, const N: usize> BorshSerializeWith<Ctx> for [T; N]
+where
+    T: BorshSerializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        for item in self {
+            item.serialize_with(writer, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, Ctx> BorshSerializeWith<Ctx> for Option<T>
+where
+    T: BorshSerializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        match self {
+            None => 0u8.serialize(writer),
+            Some(value) => {
+                1u8.serialize(writer)?;
+                value.serialize_with(writer, ctx)
+            }
+        }
+    }
+}
+
+impl<T, E, Ctx> BorshSerializeWith<Ctx> for core::result::Result<T, E>
+where
+    T: BorshSerializeWith<Ctx>,
+    E: BorshSerializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        match self {
+            Err(e) => {
+                0u8.serialize(writer)?;
+                e.serialize_with(writer, ctx)
+            }
+            Ok(v) => {
+                1u8.serialize(writer)?;
+                v.serialize_with(writer, ctx)
+            }
+        }
+    }
+}
+
+impl<T, Ctx> BorshSerializeWith<Ctx> for Box<T>
+where
+    T: BorshSerializeWith<Ctx> + ?Sized,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        self.as_ref().serialize_with(writer, ctx)
+    }
+}
+
+impl<T, Ctx> BorshSerializeWith<Ctx> for &T
+where
+    T: BorshSerializeWith<Ctx> + ?Sized,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        (*self).serialize_with(writer, ctx)
+    }
+}
+
+impl<K, V, H, Ctx> BorshSerializeWith<Ctx> for HashMap<K, V, H>
+where
+    K: BorshSerializeWith<Ctx> + PartialOrd,
+    V: BorshSerializeWith<Ctx>,
+    H: BuildHasher,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        let mut vec = self.iter().collect::<Vec<_>>();
+        vec.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+        serialize_len(vec.len(), writer)?;
+        for (key, value) in vec {
+            key.serialize_with(writer, ctx)?;
+            value.serialize_with(writer, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, Ctx> BorshSerializeWith<Ctx> for BTreeMap<K, V>
+where
+    K: BorshSerializeWith<Ctx>,
+    V: BorshSerializeWith<Ctx>,
+    Ctx: ?Sized,
+{
+    #[inline]
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+        serialize_len(self.len(), writer)?;
+        for (key, value) in self {
+            key.serialize_with(writer, ctx)?;
+            value.serialize_with(writer, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($idx:tt $name:ident)+) => {
+      impl<$($name,)+ Ctx> BorshSerializeWith<Ctx> for ($($name,)+)
+      where $($name: BorshSerializeWith<Ctx>,)+ Ctx: ?Sized
+      {
+        #[inline]
+        fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Ctx) -> Result<()> {
+            $(self.$idx.serialize_with(writer, ctx)?;)+
+            Ok(())
+        }
+      }
+    };
+}
+
+impl_tuple!(0 T0);
+impl_tuple!(0 T0 1 T1);
+impl_tuple!(0 T0 1 T1 2 T2);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11);
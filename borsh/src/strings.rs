@@ -0,0 +1,114 @@
+//! Strings that can be deserialized from bytes that are not valid UTF-8.
+//!
+//! Both types have the same wire format and schema as `String`, so a field can switch between
+//! `String`, [`LossyString`] and [`RawString`] without changing the data it reads.
+//!
+//! ```
+//! use borsh::{BorshDeserialize, LossyString, RawString};
+//!
+//! let data = [3, 0, 0, 0, b'a', 0xff, b'b'];
+//! assert!(String::try_from_slice(&data).is_err());
+//! assert_eq!(LossyString::try_from_slice(&data).unwrap().0, "a\u{FFFD}b");
+//! assert_eq!(RawString::try_from_slice(&data).unwrap().0, vec![b'a', 0xff, b'b']);
+//! ```
+use core::str::Utf8Error;
+
+use crate::de::deserialize_string_bytes;
+use crate::maybestd::{
+    collections::HashMap,
+    io::{Read, Result, Write},
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::schema::{declarations, Declaration, Definition};
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// A `String` that replaces invalid UTF-8 sequences with U+FFFD on deserialization, like
+/// `String::from_utf8_lossy` does.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct LossyString(pub String);
+
+/// The bytes of a string, kept exactly as they were read whether or not they are valid UTF-8.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct RawString(pub Vec<u8>);
+
+impl RawString {
+    /// Validates the bytes as UTF-8, for when they are needed as a string. Deserialization
+    /// does not validate them, so a large string only pays for the validation when it is used.
+    pub fn as_str_checked(&self) -> core::result::Result<&str, Utf8Error> {
+        core::str::from_utf8(&self.0)
+    }
+}
+
+impl From<String> for LossyString {
+    fn from(string: String) -> Self {
+        Self(string)
+    }
+}
+
+impl From<LossyString> for String {
+    fn from(string: LossyString) -> Self {
+        string.0
+    }
+}
+
+impl From<String> for RawString {
+    fn from(string: String) -> Self {
+        Self(string.into_bytes())
+    }
+}
+
+impl From<Vec<u8>> for RawString {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl BorshSerialize for LossyString {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for LossyString {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let bytes = deserialize_string_bytes(reader)?;
+        // Only allocate a new string when there is something to replace.
+        let string = match String::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        };
+        Ok(Self(string))
+    }
+}
+
+impl BorshSerialize for RawString {
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for RawString {
+    #[inline]
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        deserialize_string_bytes(reader).map(Self)
+    }
+}
+
+macro_rules! impl_string_schema {
+    ($type: ident) => {
+        impl BorshSchema for $type {
+            fn add_definitions_recursively(_definitions: &mut HashMap<Declaration, Definition>) {}
+
+            fn declaration() -> Declaration {
+                declarations::STRING.to_string()
+            }
+        }
+    };
+}
+
+impl_string_schema!(LossyString);
+impl_string_schema!(RawString);
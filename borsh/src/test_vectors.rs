@@ -0,0 +1,234 @@
+//! Fixed values with their expected Borsh bytes, to check this and other implementations of
+//! Borsh against the same fixtures.
+//!
+//! ```
+//! borsh::test_vectors::check_all().unwrap();
+//!
+//! let json = borsh::test_vectors::to_json().unwrap();
+//! assert_eq!(json[0]["name"], "unit");
+//! ```
+//!
+//! Each vector pairs a value of a built-in or derived type with the bytes it serializes to.
+//! [`check_all`] fails as soon as the output of one of them changes. [`to_json`] exports the
+//! vectors for implementations in other languages, which decode `bytes` and compare with `value`,
+//! then encode `value` and compare with `bytes`:
+//!
+//! ```json
+//! {
+//!   "name": "option_some",
+//!   "declaration": "Option<u32>",
+//!   "schema": "0b0000004f7074696f6e3c7533323e01000000...",
+//!   "value": 7,
+//!   "bytes": "0107000000"
+//! }
+//! ```
+//!
+//! `schema` is the Borsh encoding of the [`BorshSchemaContainer`] of the type, and `value` is its
+//! JSON form, see [`BorshSchemaContainer::deserialize_to_json`]. Byte strings are lowercase hex.
+#![allow(dead_code)] // The fields of enum variants are reported as unread, like in `schema`.
+use core::fmt::Write as _;
+
+use serde_json::{json, Value as Json};
+
+use crate as borsh; // For `#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]`.
+use crate::maybestd::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    format,
+    io::{Error, ErrorKind, Result},
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::schema::BorshSchemaContainer;
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// A value with the bytes it serializes to.
+#[derive(Clone, Copy, Debug)]
+pub struct TestVector {
+    /// Unique among [`VECTORS`].
+    pub name: &'static str,
+    /// The expected Borsh encoding of the value.
+    pub bytes: &'static [u8],
+    /// Serializes the value.
+    pub serialize: fn() -> Result<Vec<u8>>,
+    /// Deserializes the given bytes, and tells whether the result equals the value.
+    pub deserializes_to_value: fn(&[u8]) -> Result<bool>,
+    /// The schema of the type of the value.
+    pub schema: fn() -> BorshSchemaContainer,
+}
+
+impl TestVector {
+    /// Checks that the value serializes to `bytes`, and that `bytes` deserialize to the value.
+    pub fn check(&self) -> Result<()> {
+        let bytes = (self.serialize)()?;
+        if bytes != self.bytes {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Test vector {} serialized to {}, expected {}",
+                    self.name,
+                    hex(&bytes),
+                    hex(self.bytes)
+                ),
+            ));
+        }
+        if !(self.deserializes_to_value)(self.bytes)? {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Test vector {} did not deserialize to its value", self.name),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The vector in the JSON form described in the [module documentation](self).
+    pub fn to_json(&self) -> Result<Json> {
+        let schema = (self.schema)();
+        let value = schema.deserialize_to_json(&mut &self.bytes[..])?;
+        Ok(json!({
+            "name": self.name,
+            "declaration": schema.declaration,
+            "schema": hex(&schema.try_to_vec()?),
+            "value": value,
+            "bytes": hex(self.bytes),
+        }))
+    }
+}
+
+/// Checks all of the [`VECTORS`], see [`TestVector::check`].
+pub fn check_all() -> Result<()> {
+    VECTORS.iter().try_for_each(TestVector::check)
+}
+
+/// All of the [`VECTORS`] as a JSON array, see [`TestVector::to_json`].
+pub fn to_json() -> Result<Json> {
+    VECTORS
+        .iter()
+        .map(TestVector::to_json)
+        .collect::<Result<Vec<_>>>()
+        .map(Json::Array)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        write!(result, "{:02x}", byte).expect("Writing to a String cannot fail");
+    }
+    result
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Pair(u8, String);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Empty;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+enum Inner {
+    A,
+    B(u8),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+enum Outer {
+    X,
+    Y(Inner),
+    Z {
+        inner: Option<Inner>,
+        tags: Vec<String>,
+    },
+}
+
+macro_rules! vectors {
+    ($($name: literal: $type: ty = $value: expr => $bytes: expr,)*) => {
+        &[$(
+            TestVector {
+                name: $name,
+                bytes: &$bytes,
+                serialize: || {
+                    let value: $type = $value;
+                    value.try_to_vec()
+                },
+                deserializes_to_value: |bytes| {
+                    let value: $type = $value;
+                    Ok(<$type as BorshDeserialize>::try_from_slice(bytes)? == value)
+                },
+                schema: <$type as BorshSchema>::schema_container,
+            },
+        )*]
+    };
+}
+
+/// The test vectors, in the order of [`to_json`].
+pub static VECTORS: &[TestVector] = vectors! {
+    "unit": () = () => [],
+    "bool_false": bool = false => [0],
+    "bool_true": bool = true => [1],
+    "u8_max": u8 = u8::MAX => [0xff],
+    "u16_max": u16 = u16::MAX => [0xff, 0xff],
+    "u32_byte_order": u32 = 0x0102_0304 => [4, 3, 2, 1],
+    "u32_max": u32 = u32::MAX => [0xff; 4],
+    "u64_max": u64 = u64::MAX => [0xff; 8],
+    "u128_max": u128 = u128::MAX => [0xff; 16],
+    "i8_min": i8 = i8::MIN => [0x80],
+    "i16_min": i16 = i16::MIN => [0, 0x80],
+    "i32_min": i32 = i32::MIN => [0, 0, 0, 0x80],
+    "i32_minus_one": i32 = -1 => [0xff; 4],
+    "i64_min": i64 = i64::MIN => [0, 0, 0, 0, 0, 0, 0, 0x80],
+    "i128_min": i128 = i128::MIN => [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80],
+    "usize_as_u64": usize = 1 => [1, 0, 0, 0, 0, 0, 0, 0],
+    "f32_one_and_a_half": f32 = 1.5 => [0, 0, 0xc0, 0x3f],
+    "f64_negative_zero": f64 = -0.0 => [0, 0, 0, 0, 0, 0, 0, 0x80],
+    "f64_max": f64 = f64::MAX => [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xef, 0x7f],
+    "string_empty": String = String::new() => [0, 0, 0, 0],
+    "string_ascii": String = "borsh".to_string() => [5, 0, 0, 0, b'b', b'o', b'r', b's', b'h'],
+    "string_unicode": String = "é€😀".to_string()
+        => [9, 0, 0, 0, 0xc3, 0xa9, 0xe2, 0x82, 0xac, 0xf0, 0x9f, 0x98, 0x80],
+    "cow_str": Cow<'static, str> = Cow::Borrowed("x") => [1, 0, 0, 0, b'x'],
+    "vec_empty": Vec<u8> = Vec::new() => [0, 0, 0, 0],
+    "vec_u32": Vec<u32> = vec![1, 0xdead_beef] => [2, 0, 0, 0, 1, 0, 0, 0, 0xef, 0xbe, 0xad, 0xde],
+    "vec_deque": VecDeque<u8> = vec![1, 2].into_iter().collect()
+        => [2, 0, 0, 0, 1, 2],
+    "array_u8": [u8; 3] = [1, 2, 3] => [1, 2, 3],
+    "array_empty": [u32; 0] = [] => [],
+    "option_none": Option<u32> = None => [0],
+    "option_some": Option<u32> = Some(7) => [1, 7, 0, 0, 0],
+    "result_ok": core::result::Result<u8, String> = Ok(1) => [1, 1],
+    "result_err": core::result::Result<u8, String> = Err("no".to_string())
+        => [0, 2, 0, 0, 0, b'n', b'o'],
+    "tuple": (u8, u16, String) = (1, 0x0203, "a".to_string()) => [1, 3, 2, 1, 0, 0, 0, b'a'],
+    "box": Box<u16> = Box::new(0x0102) => [2, 1],
+    "hash_map_empty": HashMap<u8, u8> = HashMap::new() => [0, 0, 0, 0],
+    "hash_map_sorted_keys": HashMap<u8, String> = vec![
+        (3, "c".to_string()),
+        (1, "a".to_string()),
+        (2, "b".to_string()),
+    ]
+    .into_iter()
+    .collect() => [
+        3, 0, 0, 0,
+        1, 1, 0, 0, 0, b'a',
+        2, 1, 0, 0, 0, b'b',
+        3, 1, 0, 0, 0, b'c',
+    ],
+    "hash_set_sorted": HashSet<u16> = vec![300, 2].into_iter().collect()
+        => [2, 0, 0, 0, 2, 0, 0x2c, 0x01],
+    "btree_map": BTreeMap<String, u8> = vec![("b".to_string(), 2), ("a".to_string(), 1)].into_iter().collect()
+        => [2, 0, 0, 0, 1, 0, 0, 0, b'a', 1, 1, 0, 0, 0, b'b', 2],
+    "btree_set_signed": BTreeSet<i8> = vec![1, -1].into_iter().collect()
+        => [2, 0, 0, 0, 0xff, 1],
+    "struct_named": Point = Point { x: -1, y: 2 } => [0xff, 0xff, 0xff, 0xff, 2, 0, 0, 0],
+    "struct_tuple": Pair = Pair(1, "z".to_string()) => [1, 1, 0, 0, 0, b'z'],
+    "struct_unit": Empty = Empty => [],
+    "enum_unit_variant": Outer = Outer::X => [0],
+    "enum_nested": Outer = Outer::Y(Inner::B(9)) => [1, 1, 9],
+    "enum_struct_variant": Outer = Outer::Z { inner: Some(Inner::A), tags: vec!["t".to_string()] }
+        => [2, 1, 0, 1, 0, 0, 0, 1, 0, 0, 0, b't'],
+};
@@ -0,0 +1,149 @@
+//! LEB128 variable-length encoding of integers, for fields that are usually small.
+//!
+//! Each byte holds 7 bits of the value, least significant first, with the high bit set when more
+//! bytes follow: `0` to `127` take one byte, `128` to `16383` two bytes, and so on. Signed
+//! integers are zigzag-encoded first, so that small negative values are short as well.
+//!
+//! The module plugs into `#[borsh(with = "borsh::varint")]`:
+//!
+//! ```
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+//! struct Entry {
+//!     #[borsh(with = "borsh::varint")]
+//!     count: u64,
+//!     #[borsh(with = "borsh::varint")]
+//!     delta: i32,
+//! }
+//!
+//! let entry = Entry { count: 300, delta: -2 };
+//! let data = entry.try_to_vec().unwrap();
+//! assert_eq!(data, [0xac, 0x02, 0x03]);
+//! assert_eq!(Entry::try_from_slice(&data).unwrap(), entry);
+//! ```
+//!
+//! Every value has a single encoding: deserialization rejects encodings with unnecessary trailing
+//! bytes and values that do not fit into the integer type.
+use crate::maybestd::io::{Error, ErrorKind, Read, Result, Write};
+use crate::BorshDeserialize;
+
+const ERROR_NON_CANONICAL_VARINT: &str = "Varint has unnecessary trailing bytes";
+const ERROR_VARINT_OVERFLOW: &str = "Varint does not fit into the integer type";
+
+/// The most bytes a varint takes, for `u128`.
+const MAX_LEN: usize = 19;
+
+/// An integer that can be encoded as a varint.
+pub trait Varint: Sized {
+    fn serialize_varint<W: Write>(&self, writer: &mut W) -> Result<()>;
+
+    fn deserialize_varint<R: Read>(reader: &mut R) -> Result<Self>;
+}
+
+macro_rules! impl_unsigned {
+    ($type: ident) => {
+        impl Varint for $type {
+            #[inline]
+            fn serialize_varint<W: Write>(&self, writer: &mut W) -> Result<()> {
+                write_u128(writer, *self as u128)
+            }
+
+            #[inline]
+            fn deserialize_varint<R: Read>(reader: &mut R) -> Result<Self> {
+                Ok(read_u128(reader, $type::BITS)? as $type)
+            }
+        }
+    };
+}
+
+impl_unsigned!(u16);
+impl_unsigned!(u32);
+impl_unsigned!(u64);
+impl_unsigned!(u128);
+
+macro_rules! impl_signed {
+    ($type: ident, $unsigned: ident) => {
+        impl Varint for $type {
+            #[inline]
+            fn serialize_varint<W: Write>(&self, writer: &mut W) -> Result<()> {
+                let zigzag = ((*self << 1) ^ (*self >> ($type::BITS - 1))) as $unsigned;
+                zigzag.serialize_varint(writer)
+            }
+
+            #[inline]
+            fn deserialize_varint<R: Read>(reader: &mut R) -> Result<Self> {
+                let zigzag = $unsigned::deserialize_varint(reader)?;
+                Ok((zigzag >> 1) as $type ^ -((zigzag & 1) as $type))
+            }
+        }
+    };
+}
+
+impl_signed!(i16, u16);
+impl_signed!(i32, u32);
+impl_signed!(i64, u64);
+impl_signed!(i128, u128);
+
+/// Serializes `value` as a varint, for `#[borsh(with = "borsh::varint")]`.
+#[inline]
+pub fn serialize<T: Varint, W: Write>(value: &T, writer: &mut W) -> Result<()> {
+    value.serialize_varint(writer)
+}
+
+/// Deserializes a varint, for `#[borsh(with = "borsh::varint")]`.
+#[inline]
+pub fn deserialize<T: Varint, R: Read>(reader: &mut R) -> Result<T> {
+    T::deserialize_varint(reader)
+}
+
+/// Writes `value` as a varint.
+pub fn write_varint<W: Write>(writer: &mut W, value: u64) -> Result<()> {
+    write_u128(writer, value as u128)
+}
+
+/// Reads a varint that fits into a `u64`.
+pub fn read_varint<R: Read>(reader: &mut R) -> Result<u64> {
+    Ok(read_u128(reader, u64::BITS)? as u64)
+}
+
+fn write_u128<W: Write>(writer: &mut W, mut value: u128) -> Result<()> {
+    let mut buf = [0u8; MAX_LEN];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])
+}
+
+/// Reads a varint of a value that fits into `bits` bits.
+fn read_u128<R: Read>(reader: &mut R, bits: u32) -> Result<u128> {
+    let mut value = 0u128;
+    let mut shift = 0;
+    loop {
+        let byte = u8::deserialize_reader(reader)?;
+        let group = (byte & 0x7f) as u128;
+        if shift >= bits || (bits - shift < 7 && group >> (bits - shift) != 0) {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_VARINT_OVERFLOW));
+        }
+        value |= group << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    ERROR_NON_CANONICAL_VARINT,
+                ));
+            }
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
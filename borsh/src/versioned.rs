@@ -0,0 +1,57 @@
+//! A value with the version of its encoding, for types whose layout changes over time.
+use crate as borsh; // For `#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]`.
+use crate::maybestd::io::{Read, Result};
+use crate::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// A value preceded by the `u32` version of its encoding.
+///
+/// The version is read first, so that the layout of `inner` can depend on it:
+///
+/// ```
+/// use borsh::{BorshDeserialize, BorshSerialize, Versioned};
+///
+/// #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+/// struct Config {
+///     name: String,
+///     retries: u8,
+/// }
+///
+/// let data = Versioned::new(1, "old".to_string()).try_to_vec().unwrap();
+/// let config = Versioned::deserialize_by_version(&mut data.as_slice(), |version, reader| {
+///     match version {
+///         1 => Ok(Config { name: String::deserialize_reader(reader)?, retries: 3 }),
+///         _ => Config::deserialize_reader(reader),
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!(config.inner, Config { name: "old".to_string(), retries: 3 });
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct Versioned<T> {
+    pub version: u32,
+    pub inner: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(version: u32, inner: T) -> Self {
+        Self { version, inner }
+    }
+
+    /// Reads the version, then `inner` with `f`, which receives the version and the reader
+    /// positioned after it.
+    pub fn deserialize_by_version<R, F>(reader: &mut R, f: F) -> Result<Self>
+    where
+        R: Read,
+        F: FnOnce(u32, &mut R) -> Result<T>,
+    {
+        let version = u32::deserialize_reader(reader)?;
+        let inner = f(version, reader)?;
+        Ok(Self { version, inner })
+    }
+}
+
+/// Returns the version at the beginning of serialized `Versioned` data, without reading the rest
+/// of `buf`.
+pub fn peek_version(buf: &[u8]) -> Result<u32> {
+    u32::deserialize(&mut &buf[..])
+}
@@ -0,0 +1,182 @@
+//! Test vectors that pin the serialized bytes of types, so that changes of the wire format are
+//! caught by tests.
+//!
+//! ```
+//! use borsh::BorshSerialize;
+//!
+//! #[derive(BorshSerialize, borsh::BorshDeserialize)]
+//! struct Greeting {
+//!     id: u8,
+//!     text: String,
+//! }
+//!
+//! let sample = Greeting { id: 10, text: "hello".to_string() };
+//! // Prints the hex to paste into the test when it is first written.
+//! println!("{}", borsh::wire_format::to_hex(&sample.try_to_vec().unwrap()));
+//! borsh::assert_wire_format!(Greeting, sample, "0a 05000000 68656c6c6f");
+//! ```
+use core::fmt::Write as _;
+
+use crate::maybestd::{
+    format,
+    io::{Error, ErrorKind, Result},
+    string::String,
+    vec::Vec,
+};
+use crate::{BorshDeserialize, BorshSerialize};
+
+/// How many bytes are shown on a line of a diff.
+const BYTES_PER_LINE: usize = 16;
+/// How many lines of a diff are shown from the first difference.
+const DIFF_LINES: usize = 4;
+
+/// Formats bytes as lowercase hex, two digits per byte without separators.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+/// Parses hex digits into bytes. Whitespace and `_` between bytes are ignored, so that long test
+/// vectors can be grouped by field.
+pub fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let digits: Vec<u8> = hex
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'_')
+        .map(|c| match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid hex digit {:?}", c as char),
+            )),
+        })
+        .collect::<Result<_>>()?;
+    let pairs = digits.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Odd number of hex digits",
+        ));
+    }
+    Ok(pairs.map(|pair| (pair[0] << 4) | pair[1]).collect())
+}
+
+/// Describes where `actual` differs from `expected`, or returns `None` if they are equal.
+///
+/// The description has the offset of the first difference and the lines of both from there in
+/// hex, followed by the complete actual bytes, to update the test vector with if the change of the
+/// wire format is intended.
+pub fn diff(expected: &[u8], actual: &[u8]) -> Option<String> {
+    let offset = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+    if offset == expected.len() && offset == actual.len() {
+        return None;
+    }
+    let mut out = format!(
+        "Serialized bytes differ at byte {} (expected {} bytes, got {} bytes)\n",
+        offset,
+        expected.len(),
+        actual.len()
+    );
+    let first_line = offset / BYTES_PER_LINE * BYTES_PER_LINE;
+    for start in (first_line..expected.len().max(actual.len()))
+        .step_by(BYTES_PER_LINE)
+        .take(DIFF_LINES)
+    {
+        let line = |bytes: &[u8]| {
+            let end = bytes.len().min(start + BYTES_PER_LINE);
+            bytes.get(start..end).map(to_hex).unwrap_or_default()
+        };
+        let _ = writeln!(out, "{:08x} - {}", start, line(expected));
+        let _ = writeln!(out, "{:08x} + {}", start, line(actual));
+        if start == first_line {
+            let _ = writeln!(
+                out,
+                "{:11}{:>width$}",
+                "",
+                "^^",
+                width = (offset - start + 1) * 2
+            );
+        }
+    }
+    let _ = write!(out, "actual: {}", to_hex(actual));
+    Some(out)
+}
+
+/// Checks that `value` serializes to the bytes of `expected_hex`, and that these bytes deserialize
+/// to a value that serializes to them again. Fails with an `InvalidData` error whose message is
+/// the [`diff`] of the bytes. See [`assert_wire_format!`](crate::assert_wire_format).
+pub fn check_wire_format<T>(value: &T, expected_hex: &str) -> Result<()>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    check_bytes(value, &from_hex(expected_hex)?)
+}
+
+fn check_bytes<T>(value: &T, expected: &[u8]) -> Result<()>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    if let Some(diff) = diff(expected, &value.try_to_vec()?) {
+        return Err(Error::new(ErrorKind::InvalidData, diff));
+    }
+    let decoded = T::try_from_slice(expected)?;
+    if let Some(diff) = diff(expected, &decoded.try_to_vec()?) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The expected bytes do not decode to the same value: {}",
+                diff
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks `value` against the bytes stored in the file at `path`, like [`check_wire_format`].
+///
+/// If the file does not exist it is created with the bytes of `value`, so that the snapshot is
+/// recorded by the first run of the test and checked by the following ones. Delete the file to
+/// record it again after an intended change of the wire format.
+#[cfg(feature = "std")]
+pub fn check_snapshot_file<T, P>(value: &T, path: P) -> Result<()>
+where
+    T: BorshSerialize + BorshDeserialize,
+    P: AsRef<std::path::Path>,
+{
+    let path = path.as_ref();
+    match std::fs::read(path) {
+        Ok(expected) => check_bytes(value, &expected)
+            .map_err(|err| Error::new(err.kind(), format!("{}: {}", path.display(), err))),
+        Err(err) if err.kind() == ErrorKind::NotFound => std::fs::write(path, value.try_to_vec()?),
+        Err(err) => Err(err),
+    }
+}
+
+/// Asserts that a value of a type serializes to the bytes of a hex string, and that these bytes
+/// deserialize back, see [`wire_format::check_wire_format`](crate::wire_format::check_wire_format):
+///
+/// ```
+/// borsh::assert_wire_format!(Option<u16>, Some(0x0102), "01 0201");
+/// ```
+///
+/// Panics with the offset of the first difference and the lines of hex around it otherwise.
+#[macro_export]
+macro_rules! assert_wire_format {
+    ($type: ty, $value: expr, $hex: expr $(,)?) => {
+        if let Err(err) = $crate::wire_format::check_wire_format::<$type>(&$value, $hex) {
+            ::core::panic!(
+                "Wire format of {} changed: {}",
+                ::core::stringify!($type),
+                err
+            );
+        }
+    };
+}
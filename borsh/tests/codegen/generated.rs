@@ -0,0 +1,43 @@
+// Generated by borsh::schema::generate_rust.
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum Action {
+    Idle,
+    Move {
+        to: Position,
+        speed: Option<u16>,
+    },
+    Say(String, [u8; 2]),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+#[borsh(named_variants)]
+pub enum Mode {
+    Fast,
+    Safe(u8),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct Position(pub i32, pub i32);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum Priority {
+    #[borsh(tag = 10)]
+    Low,
+    #[borsh(tag = 11)]
+    High(u8),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct World {
+    pub actions: Vec<Action>,
+    pub names: borsh::maybestd::collections::HashMap<u32, String>,
+    pub scores: borsh::maybestd::collections::BTreeMap<String, (u8, i64)>,
+    pub last: Result<Position, String>,
+    pub mode: Mode,
+    pub short: borsh::SeqWithLen<u8, bool>,
+    pub queue: borsh::maybestd::collections::VecDeque<u16>,
+    pub priority: Priority,
+    pub r#type: u128,
+}
@@ -0,0 +1,9 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct Sample {
+    #[borsh(big_endian, canonical_nan)]
+    value: f64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct Sample {
+    #[borsh(big_endian)]
+    value: f64,
+}
+
+fn main() {}
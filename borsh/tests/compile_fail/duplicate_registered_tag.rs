@@ -0,0 +1,18 @@
+use borsh::dynamic::BorshSerializeDyn;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+trait Handler: BorshSerializeDyn {}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct First;
+
+impl Handler for First {}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Second;
+
+impl Handler for Second {}
+
+borsh::register_type!(Handler, First = 1, Second = 1);
+
+fn main() {}
@@ -0,0 +1,18 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+enum Explicit {
+    #[borsh(tag = 1)]
+    A,
+    #[borsh(tag = 1)]
+    B,
+}
+
+#[derive(BorshSerialize)]
+enum Positional {
+    A,
+    #[borsh(tag = 0)]
+    B,
+}
+
+fn main() {}
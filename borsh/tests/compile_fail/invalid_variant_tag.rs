@@ -0,0 +1,23 @@
+use borsh::{BorshDeserialize, BorshSchema};
+
+#[derive(BorshDeserialize)]
+enum TooLarge {
+    #[borsh(tag = 256)]
+    A,
+}
+
+#[derive(BorshSchema)]
+enum WithDiscriminant {
+    #[borsh(tag = 1)]
+    A,
+    B = 2,
+}
+
+#[derive(BorshDeserialize)]
+#[borsh(named_variants)]
+enum Named {
+    #[borsh(tag = 1)]
+    A,
+}
+
+fn main() {}
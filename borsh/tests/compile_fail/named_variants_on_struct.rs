@@ -0,0 +1,9 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+#[borsh(named_variants)]
+struct A {
+    x: u64,
+}
+
+fn main() {}
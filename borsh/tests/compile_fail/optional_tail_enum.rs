@@ -0,0 +1,12 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+enum Record {
+    V1 {
+        id: u32,
+        #[borsh(optional_tail)]
+        score: u64,
+    },
+}
+
+fn main() {}
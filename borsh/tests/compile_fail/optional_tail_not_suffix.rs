@@ -0,0 +1,11 @@
+use borsh::BorshDeserialize;
+
+#[derive(BorshDeserialize)]
+struct Record {
+    id: u32,
+    #[borsh(optional_tail)]
+    score: u64,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct NoSchema(u8);
+
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(require_schema)]
+struct Account {
+    id: u64,
+    extra: NoSchema,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(require_schema)]
+enum Message {
+    Ping,
+    Data(Vec<NoSchema>),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct A {
+    x: u64,
+    #[borsh_skip]
+    #[borsh(skip)]
+    y: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+union IntOrFloat {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
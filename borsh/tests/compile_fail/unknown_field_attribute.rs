@@ -0,0 +1,10 @@
+use borsh::BorshDeserialize;
+
+#[derive(BorshDeserialize)]
+struct A {
+    x: u64,
+    #[borsh(skip_me)]
+    y: u64,
+}
+
+fn main() {}
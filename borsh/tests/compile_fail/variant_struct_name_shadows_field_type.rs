@@ -0,0 +1,14 @@
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+struct ShapeCircle {
+    radius: u32,
+}
+
+#[derive(BorshSchema)]
+enum Shape {
+    Circle(ShapeCircle),
+    Square(u32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct Counter {
+    #[borsh(skip, with = "borsh::varint")]
+    count: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use borsh::BorshDeserialize;
+
+#[derive(BorshDeserialize)]
+struct Transfer {
+    #[borsh(with_context)]
+    from: u64,
+    amount: u64,
+}
+
+fn main() {}
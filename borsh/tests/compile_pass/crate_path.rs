@@ -0,0 +1,34 @@
+// A crate that depends on borsh through a re-export, like the crates of frameworks built on it.
+mod framework {
+    pub use ::borsh;
+}
+
+use framework::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+#[borsh(crate = "framework::borsh")]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+#[borsh(crate = "crate::framework::borsh")]
+enum Shape<T> {
+    Empty,
+    Dot(Point),
+    Line { from: Point, to: T },
+}
+
+fn main() {
+    let shape = Shape::Line {
+        from: Point { x: 1, y: 2 },
+        to: Point { x: 3, y: 4 },
+    };
+    let data = shape.try_to_vec().unwrap();
+    assert_eq!(Shape::<Point>::try_from_slice(&data).unwrap(), shape);
+    assert_eq!(Shape::<Point>::declaration(), "Shape<Point>");
+    assert!(Shape::<Point>::schema_container()
+        .definitions
+        .contains_key("ShapeLine<Point>"));
+}
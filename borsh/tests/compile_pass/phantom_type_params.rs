@@ -0,0 +1,16 @@
+use std::marker::PhantomData;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// Implements none of the borsh traits.
+struct NotBorsh;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+struct S<T>(PhantomData<T>);
+
+fn main() {
+    let data = S::<NotBorsh>(PhantomData).try_to_vec().unwrap();
+    S::<NotBorsh>::try_from_slice(&data).unwrap();
+    assert_eq!(S::<NotBorsh>::declaration(), "S");
+    let _ = S::<NotBorsh>::schema_container();
+}
@@ -0,0 +1,31 @@
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+struct Ref<'a, T> {
+    data: &'a [T],
+}
+
+#[derive(BorshSchema)]
+struct Bounded<'a, 'b: 'a, T: 'a>
+where
+    T: Clone,
+{
+    data: &'a [T],
+    name: &'b str,
+}
+
+#[derive(BorshSchema)]
+enum Message<'a, T> {
+    Borrowed(&'a T),
+    Text { text: &'a str },
+    Empty,
+}
+
+fn main() {
+    // Lifetimes do not change the wire format, so they are not part of the declarations.
+    assert_eq!(Ref::<u8>::declaration(), "Ref<u8>");
+    assert_eq!(Bounded::<u64>::declaration(), "Bounded<u64>");
+    assert_eq!(Message::<u8>::declaration(), "Message<u8>");
+    let container = Message::<u8>::schema_container();
+    assert!(container.definitions.contains_key("MessageEmpty<u8>"));
+}
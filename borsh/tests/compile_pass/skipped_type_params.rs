@@ -0,0 +1,32 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// Implements none of the borsh traits.
+#[derive(Default)]
+struct NotBorsh;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+struct S<T> {
+    real: u64,
+    #[borsh_skip]
+    hidden: T,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+enum E<T, U> {
+    A(u64, #[borsh_skip] T),
+    B {
+        value: U,
+        #[borsh_skip]
+        hidden: T,
+    },
+}
+
+fn main() {
+    let data = S { real: 1, hidden: NotBorsh }.try_to_vec().unwrap();
+    S::<NotBorsh>::try_from_slice(&data).unwrap();
+    let _ = S::<NotBorsh>::schema_container();
+
+    let data = E::<NotBorsh, u8>::A(1, NotBorsh).try_to_vec().unwrap();
+    E::<NotBorsh, u8>::try_from_slice(&data).unwrap();
+    let _ = E::<NotBorsh, u8>::schema_container();
+}
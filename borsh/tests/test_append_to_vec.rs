@@ -0,0 +1,85 @@
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct Entry {
+    key: String,
+    value: Vec<u32>,
+}
+
+#[test]
+fn test_append_to_vec_lengths() {
+    let mut buf = Vec::new();
+    let lengths = vec![
+        42u64.append_to_vec(&mut buf).unwrap(),
+        Entry {
+            key: "abc".to_string(),
+            value: vec![1, 2],
+        }
+        .append_to_vec(&mut buf)
+        .unwrap(),
+        Some(true).append_to_vec(&mut buf).unwrap(),
+    ];
+    assert_eq!(lengths, vec![8, 4 + 3 + 4 + 2 * 4, 2]);
+    assert_eq!(lengths.iter().sum::<usize>(), buf.len());
+
+    let mut offset = 0;
+    for (length, expected) in lengths.iter().zip([
+        42u64.try_to_vec().unwrap(),
+        Entry {
+            key: "abc".to_string(),
+            value: vec![1, 2],
+        }
+        .try_to_vec()
+        .unwrap(),
+        Some(true).try_to_vec().unwrap(),
+    ]) {
+        assert_eq!(buf[offset..offset + length], expected[..]);
+        offset += length;
+    }
+}
+
+#[test]
+fn test_append_to_vec_error_keeps_buffer() {
+    struct Failing;
+
+    impl BorshSerialize for Failing {
+        fn serialize<W: borsh::maybestd::io::Write>(
+            &self,
+            writer: &mut W,
+        ) -> borsh::maybestd::io::Result<()> {
+            writer.write_all(&[1, 2, 3])?;
+            Err(borsh::maybestd::io::ErrorKind::InvalidInput.into())
+        }
+    }
+
+    let mut buf = vec![9];
+    assert!(Failing.append_to_vec(&mut buf).is_err());
+    assert_eq!(buf, vec![9]);
+}
+
+#[test]
+fn test_to_vec_in_reuses_buffer() {
+    let entries: Vec<Entry> = (0..100)
+        .map(|i| Entry {
+            key: i.to_string(),
+            value: (0..i).collect(),
+        })
+        .collect();
+
+    let mut buf = Vec::new();
+    for entry in &entries {
+        buf.clear();
+        borsh::to_vec_in(entry, &mut buf).unwrap();
+        assert_eq!(buf, entry.try_to_vec().unwrap());
+    }
+
+    let mut appended = vec![7u8];
+    for entry in &entries {
+        borsh::to_vec_in(entry, &mut appended).unwrap();
+    }
+    let mut expected = vec![7u8];
+    for entry in &entries {
+        expected.extend(entry.try_to_vec().unwrap());
+    }
+    assert_eq!(appended, expected);
+}
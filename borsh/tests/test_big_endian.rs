@@ -0,0 +1,86 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use borsh::schema::{BorshSchemaContainer, Definition, Fields, Value};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Header {
+    #[borsh(big_endian)]
+    magic: u32,
+    length: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+enum Frame {
+    Empty,
+    Data(#[borsh(big_endian)] i64, u16),
+}
+
+#[test]
+fn test_byte_order() {
+    let header = Header {
+        magic: 0x0102_0304,
+        length: 0x0102_0304,
+    };
+    let data = header.try_to_vec().unwrap();
+    assert_eq!(data, [1, 2, 3, 4, 4, 3, 2, 1]);
+    assert_eq!(data[..4], 0x0102_0304u32.to_be_bytes());
+    assert_ne!(data[..4], data[4..]);
+    assert_eq!(Header::try_from_slice(&data).unwrap(), header);
+}
+
+#[test]
+fn test_enum_fields() {
+    let frame = Frame::Data(-2, 5);
+    let data = frame.try_to_vec().unwrap();
+    assert_eq!(
+        data,
+        [1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 5, 0]
+    );
+    assert_eq!(Frame::try_from_slice(&data).unwrap(), frame);
+    assert_eq!(
+        Frame::try_from_slice(&data[..5]).unwrap_err().to_string(),
+        "Unexpected length of input"
+    );
+}
+
+#[test]
+fn test_schema() {
+    let container: BorshSchemaContainer = Header::schema_container();
+    assert_eq!(
+        container.definitions["Header"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("magic".to_string(), "BigEndian<u32>".to_string()),
+                ("length".to_string(), "u32".to_string()),
+            ])
+        }
+    );
+    assert_eq!(
+        container.definitions["BigEndian<u32>"],
+        Definition::Array {
+            length: 4,
+            elements: "u8".to_string()
+        }
+    );
+
+    let data = Header {
+        magic: 0x0102_0304,
+        length: 7,
+    }
+    .try_to_vec()
+    .unwrap();
+    assert_eq!(
+        container.value_from_slice(&data).unwrap(),
+        Value::Struct(vec![
+            (
+                "magic".to_string(),
+                Value::Array(vec![Value::U8(1), Value::U8(2), Value::U8(3), Value::U8(4)])
+            ),
+            ("length".to_string(), Value::U32(7)),
+        ])
+    );
+    assert!(Frame::schema_container()
+        .definitions
+        .contains_key("BigEndian<i64>"));
+}
@@ -0,0 +1,82 @@
+use bitflags::bitflags;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+bitflags! {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Permissions: u16 {
+        const READ = 0b0001;
+        const WRITE = 0b0010;
+        const EXECUTE = 0b0100;
+    }
+}
+borsh::impl_bitflags!(Permissions);
+
+bitflags! {
+    #[derive(Debug, PartialEq, Eq)]
+    struct StrictPermissions: u16 {
+        const READ = 0b0001;
+        const WRITE = 0b0010;
+    }
+}
+borsh::impl_bitflags!(StrictPermissions, strict);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Eq)]
+struct File {
+    name: String,
+    permissions: Permissions,
+}
+
+#[test]
+fn test_flags_roundtrip() {
+    let permissions = Permissions::READ | Permissions::EXECUTE;
+    let data = permissions.try_to_vec().unwrap();
+    assert_eq!(data, 0b0101u16.try_to_vec().unwrap());
+    assert_eq!(Permissions::try_from_slice(&data).unwrap(), permissions);
+
+    let file = File {
+        name: "a.out".to_string(),
+        permissions: Permissions::all(),
+    };
+    let data = file.try_to_vec().unwrap();
+    assert_eq!(File::try_from_slice(&data).unwrap(), file);
+}
+
+#[test]
+fn test_flags_unknown_bits_are_truncated() {
+    let data = 0b1011u16.try_to_vec().unwrap();
+    assert_eq!(
+        Permissions::try_from_slice(&data).unwrap(),
+        Permissions::READ | Permissions::WRITE
+    );
+}
+
+#[test]
+fn test_strict_flags_reject_unknown_bits() {
+    let data = 0b0011u16.try_to_vec().unwrap();
+    assert_eq!(
+        StrictPermissions::try_from_slice(&data).unwrap(),
+        StrictPermissions::all()
+    );
+    let data = 0b0111u16.try_to_vec().unwrap();
+    assert_eq!(
+        StrictPermissions::try_from_slice(&data)
+            .unwrap_err()
+            .to_string(),
+        "Unknown bits set in flags"
+    );
+}
+
+#[test]
+fn test_flags_schema() {
+    assert_eq!(Permissions::declaration(), "u16");
+    let container = File::schema_container();
+    assert_eq!(
+        container.definitions.get("File"),
+        Some(&borsh::schema::Definition::Struct {
+            fields: borsh::schema::Fields::NamedFields(vec![
+                ("name".to_string(), "string".to_string()),
+                ("permissions".to_string(), "u16".to_string()),
+            ])
+        })
+    );
+}
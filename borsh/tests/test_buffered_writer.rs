@@ -0,0 +1,97 @@
+use borsh::{to_writer, to_writer_buffered, BorshSerialize};
+use std::io::{Error, ErrorKind, Result, Write};
+
+#[derive(BorshSerialize)]
+struct Header {
+    version: u8,
+    height: u64,
+    timestamp: u64,
+    flags: [bool; 4],
+    author: String,
+    parents: Vec<[u8; 32]>,
+    payload: Vec<u8>,
+    note: Option<String>,
+}
+
+fn header(payload_len: usize) -> Header {
+    Header {
+        version: 1,
+        height: 42,
+        timestamp: 1_650_000_000,
+        flags: [true, false, true, false],
+        author: "alice.near".to_string(),
+        parents: vec![[1; 32], [2; 32], [3; 32]],
+        payload: vec![7; payload_len],
+        note: Some("note".to_string()),
+    }
+}
+
+/// Records the bytes it receives and how many times `write` was called.
+#[derive(Default)]
+struct CountingWriter {
+    data: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.writes += 1;
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_buffered_output_is_identical() {
+    for payload_len in [0, 100, 10_000, 100_000] {
+        let value = header(payload_len);
+        let mut unbuffered = CountingWriter::default();
+        to_writer(&mut unbuffered, &value).unwrap();
+        let mut buffered = CountingWriter::default();
+        to_writer_buffered(&mut buffered, &value).unwrap();
+        assert_eq!(buffered.data, unbuffered.data);
+        assert_eq!(buffered.data, value.try_to_vec().unwrap());
+    }
+}
+
+#[test]
+fn test_buffered_write_calls() {
+    let value = header(100);
+    let mut unbuffered = CountingWriter::default();
+    to_writer(&mut unbuffered, &value).unwrap();
+    let mut buffered = CountingWriter::default();
+    to_writer_buffered(&mut buffered, &value).unwrap();
+    assert_eq!(unbuffered.writes, 15);
+    assert_eq!(buffered.writes, 1);
+
+    // A payload larger than the buffer goes straight to the writer.
+    let mut buffered = CountingWriter::default();
+    to_writer_buffered(&mut buffered, &header(100_000)).unwrap();
+    assert_eq!(buffered.writes, 3);
+}
+
+#[test]
+fn test_buffered_writer_error() {
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+            Err(Error::new(ErrorKind::BrokenPipe, "connection closed"))
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    assert_eq!(
+        to_writer_buffered(FailingWriter, &header(0))
+            .unwrap_err()
+            .to_string(),
+        "connection closed"
+    );
+}
@@ -0,0 +1,115 @@
+//! Non-canonical inputs, which the `canonical` feature rejects during deserialization.
+use borsh::maybestd::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use borsh::maybestd::io::ErrorKind;
+use borsh::{assert_canonical_roundtrip, BorshDeserialize, BorshSerialize};
+use indexmap::{IndexMap, IndexSet};
+
+fn unsorted_entries() -> Vec<u8> {
+    vec![(2u8, 20u8), (1u8, 10u8)].try_to_vec().unwrap()
+}
+
+fn duplicate_entries() -> Vec<u8> {
+    vec![(1u8, 10u8), (1u8, 20u8)].try_to_vec().unwrap()
+}
+
+fn unsorted_items() -> Vec<u8> {
+    vec![2u8, 1u8].try_to_vec().unwrap()
+}
+
+fn duplicate_items() -> Vec<u8> {
+    vec![1u8, 1u8].try_to_vec().unwrap()
+}
+
+/// Checks that `T` accepts `data` without the `canonical` feature and rejects it with the feature.
+fn check<T: BorshDeserialize>(data: &[u8]) {
+    let result = T::try_from_slice(data);
+    if cfg!(feature = "canonical") {
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidData);
+    } else {
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn test_unsorted_maps() {
+    check::<HashMap<u8, u8>>(&unsorted_entries());
+    check::<BTreeMap<u8, u8>>(&unsorted_entries());
+}
+
+#[test]
+fn test_maps_with_duplicate_keys() {
+    check::<HashMap<u8, u8>>(&duplicate_entries());
+    check::<BTreeMap<u8, u8>>(&duplicate_entries());
+    check::<IndexMap<u8, u8>>(&duplicate_entries());
+}
+
+#[test]
+fn test_unsorted_sets() {
+    check::<HashSet<u8>>(&unsorted_items());
+    check::<BTreeSet<u8>>(&unsorted_items());
+}
+
+#[test]
+fn test_sets_with_duplicate_items() {
+    check::<HashSet<u8>>(&duplicate_items());
+    check::<BTreeSet<u8>>(&duplicate_items());
+    check::<IndexSet<u8>>(&duplicate_items());
+}
+
+#[test]
+fn test_index_maps_keep_any_order() {
+    // Index maps are serialized in insertion order, so unsorted keys are canonical.
+    let map = IndexMap::<u8, u8>::try_from_slice(&unsorted_entries()).unwrap();
+    assert_eq!(map.try_to_vec().unwrap(), unsorted_entries());
+}
+
+#[test]
+fn test_bool_is_always_strict() {
+    assert!(bool::try_from_slice(&[2]).is_err());
+    assert!(assert_canonical_roundtrip::<bool>(&[2]).is_err());
+}
+
+#[test]
+fn test_canonical_inputs_are_accepted() {
+    let map: HashMap<String, Vec<u8>> = vec![
+        ("b".to_string(), vec![2]),
+        ("a".to_string(), vec![1]),
+        ("c".to_string(), vec![]),
+    ]
+    .into_iter()
+    .collect();
+    let data = map.try_to_vec().unwrap();
+    assert_eq!(HashMap::try_from_slice(&data).unwrap(), map);
+    assert_eq!(
+        assert_canonical_roundtrip::<HashMap<_, _>>(&data).unwrap(),
+        map
+    );
+
+    let set: BTreeSet<i32> = vec![-1, 0, 7].into_iter().collect();
+    let data = set.try_to_vec().unwrap();
+    assert_eq!(
+        assert_canonical_roundtrip::<BTreeSet<i32>>(&data).unwrap(),
+        set
+    );
+}
+
+#[test]
+fn test_roundtrip_rejects_non_canonical_inputs() {
+    for data in [unsorted_entries(), duplicate_entries()] {
+        let err = assert_canonical_roundtrip::<BTreeMap<u8, u8>>(&data).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(assert_canonical_roundtrip::<HashMap<u8, u8>>(&data).is_err());
+    }
+    assert!(assert_canonical_roundtrip::<IndexMap<u8, u8>>(&duplicate_entries()).is_err());
+    // Bytes left over after the value.
+    assert!(assert_canonical_roundtrip::<u8>(&[1, 2]).is_err());
+}
+
+#[test]
+fn test_produced_bytes_do_not_depend_on_the_feature() {
+    let map: BTreeMap<u8, u8> = vec![(2, 20), (1, 10)].into_iter().collect();
+    assert_eq!(
+        map.try_to_vec().unwrap(),
+        vec![(1u8, 10u8), (2u8, 20u8)].try_to_vec().unwrap()
+    );
+}
@@ -0,0 +1,92 @@
+use borsh::nan::{F32_CANONICAL_NAN, F64_CANONICAL_NAN};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct Measurement {
+    id: u8,
+    #[borsh(canonical_nan)]
+    value: f64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct Pair(#[borsh(canonical_nan)] f32, f32);
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+enum Reading {
+    Missing,
+    Single(#[borsh(canonical_nan)] f32),
+}
+
+#[test]
+fn test_different_nans_serialize_identically() {
+    let nans = [
+        f64::NAN,
+        -f64::NAN,
+        f64::from_bits(0x7ff8_0000_0000_0001),
+        // Signaling NaN.
+        f64::from_bits(0x7ff0_0000_0000_0001),
+    ];
+    let encodings: Vec<Vec<u8>> = nans
+        .iter()
+        .map(|value| {
+            Measurement {
+                id: 1,
+                value: *value,
+            }
+            .try_to_vec()
+            .unwrap()
+        })
+        .collect();
+    for encoding in &encodings {
+        assert_eq!(
+            encoding[..],
+            [&[1u8][..], &F64_CANONICAL_NAN.to_le_bytes()].concat()[..]
+        );
+    }
+    assert!(Measurement::try_from_slice(&encodings[0])
+        .unwrap()
+        .value
+        .is_nan());
+}
+
+#[test]
+fn test_f32_fields() {
+    let a = Pair(f32::NAN, 1.5).try_to_vec().unwrap();
+    let b = Pair(f32::from_bits(0xffc0_1234), 1.5).try_to_vec().unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a[..4], F32_CANONICAL_NAN.to_le_bytes());
+
+    let a = Reading::Single(f32::from_bits(0x7f80_0001))
+        .try_to_vec()
+        .unwrap();
+    assert_eq!(a, [&[1u8][..], &F32_CANONICAL_NAN.to_le_bytes()].concat());
+    assert!(matches!(Reading::try_from_slice(&a).unwrap(), Reading::Single(v) if v.is_nan()));
+}
+
+#[test]
+fn test_numbers_are_unchanged() {
+    let value = Measurement {
+        id: 2,
+        value: -0.25,
+    };
+    assert_eq!(
+        value.try_to_vec().unwrap(),
+        (2u8, -0.25f64).try_to_vec().unwrap()
+    );
+    assert_eq!(
+        Measurement::try_from_slice(&value.try_to_vec().unwrap())
+            .unwrap()
+            .value,
+        -0.25
+    );
+}
+
+#[test]
+fn test_non_canonical_nan_is_rejected() {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&0x7ff8_0000_0000_0001u64.to_le_bytes());
+    assert_eq!(
+        Measurement::try_from_slice(&data).unwrap_err().to_string(),
+        "NaN does not have the canonical bit pattern"
+    );
+}
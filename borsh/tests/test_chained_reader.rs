@@ -0,0 +1,58 @@
+use borsh::{BorshDeserialize, BorshSerialize, ChainedReader};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Message {
+    id: u64,
+    flags: [u8; 3],
+    body: String,
+    values: Vec<u32>,
+}
+
+#[test]
+fn test_u64_split_across_chunks() {
+    let data = 0x0102_0304_0506_0708u64.to_le_bytes();
+    let mut reader = ChainedReader::new([&data[..3], &data[3..]]);
+    assert_eq!(
+        u64::deserialize_reader(&mut reader).unwrap(),
+        0x0102_0304_0506_0708
+    );
+}
+
+#[test]
+fn test_every_split_position() {
+    let message = Message {
+        id: u64::MAX - 1,
+        flags: [1, 2, 3],
+        body: "hello".to_string(),
+        values: vec![10, 20, 30],
+    };
+    let data = message.try_to_vec().unwrap();
+    for split in 0..=data.len() {
+        let (head, tail) = data.split_at(split);
+        let mut reader = ChainedReader::new(vec![head.to_vec(), Vec::new(), tail.to_vec()]);
+        assert_eq!(Message::deserialize_reader(&mut reader).unwrap(), message);
+    }
+}
+
+#[test]
+fn test_one_byte_chunks() {
+    let value = (7u16, "chunked".to_string(), Some(-5i128));
+    let data = value.try_to_vec().unwrap();
+    let mut reader = ChainedReader::new(data.chunks(1));
+    assert_eq!(
+        <(u16, String, Option<i128>)>::deserialize_reader(&mut reader).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_missing_bytes() {
+    let data = 5u32.to_le_bytes();
+    let mut reader = ChainedReader::new([&data[..2], &data[2..3]]);
+    assert_eq!(
+        u32::deserialize_reader(&mut reader)
+            .unwrap_err()
+            .to_string(),
+        "Unexpected length of input"
+    );
+}
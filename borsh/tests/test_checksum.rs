@@ -0,0 +1,69 @@
+use borsh::checksum::crc32;
+use borsh::maybestd::io::ErrorKind;
+use borsh::{deserialize_with_checksum, serialize_with_checksum, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Blob {
+    id: u64,
+    name: String,
+    data: Vec<u8>,
+}
+
+fn blob() -> Blob {
+    Blob {
+        id: 42,
+        name: "archive".to_string(),
+        data: (0..=255).collect(),
+    }
+}
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+}
+
+#[test]
+fn test_round_trip() {
+    let data = serialize_with_checksum(&blob()).unwrap();
+    let payload = blob().try_to_vec().unwrap();
+    assert_eq!(&data[..payload.len()], payload.as_slice());
+    assert_eq!(data[payload.len()..], crc32(&payload).to_le_bytes());
+    assert_eq!(deserialize_with_checksum::<Blob>(&data).unwrap(), blob());
+}
+
+#[test]
+fn test_bit_flip() {
+    let data = serialize_with_checksum(&blob()).unwrap();
+    for bit in [0, 77, 8 * (data.len() - 5) + 7] {
+        let mut corrupted = data.clone();
+        corrupted[bit / 8] ^= 1 << (bit % 8);
+        let err = deserialize_with_checksum::<Blob>(&corrupted).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("Checksum mismatch"), "{}", err);
+        #[cfg(feature = "std")]
+        assert_eq!(
+            err.get_ref()
+                .unwrap()
+                .downcast_ref::<borsh::checksum::ChecksumMismatch>(),
+            Some(&borsh::checksum::ChecksumMismatch {
+                expected: crc32(&data[..data.len() - 4]),
+                actual: crc32(&corrupted[..data.len() - 4]),
+            })
+        );
+    }
+}
+
+#[test]
+fn test_corrupted_checksum() {
+    let mut data = serialize_with_checksum(&blob()).unwrap();
+    let last = data.len() - 1;
+    data[last] ^= 0x80;
+    assert!(deserialize_with_checksum::<Blob>(&data).is_err());
+}
+
+#[test]
+fn test_missing_checksum() {
+    let err = deserialize_with_checksum::<u8>(&[1, 2, 3]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,74 @@
+use borsh::schema::{BorshSchema, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSerialize};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+fn timestamp() -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(2022, 3, 22)
+        .unwrap()
+        .and_hms_nano_opt(12, 30, 15, 123_456_789)
+        .unwrap()
+        .and_utc()
+}
+
+#[test]
+fn test_date_time_roundtrip() {
+    let value = timestamp();
+    let data = value.try_to_vec().unwrap();
+    let mut expected = 1_647_952_215i64.to_le_bytes().to_vec();
+    expected.extend_from_slice(&123_456_789u32.to_le_bytes());
+    assert_eq!(data, expected);
+    assert_eq!(DateTime::<Utc>::try_from_slice(&data).unwrap(), value);
+}
+
+#[test]
+fn test_naive_date_time_roundtrip() {
+    let value = timestamp().naive_utc();
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, timestamp().try_to_vec().unwrap());
+    assert_eq!(NaiveDateTime::try_from_slice(&data).unwrap(), value);
+}
+
+#[test]
+fn test_before_epoch() {
+    let value = NaiveDate::from_ymd_opt(1900, 1, 1)
+        .unwrap()
+        .and_hms_nano_opt(0, 0, 0, 500)
+        .unwrap()
+        .and_utc();
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(DateTime::<Utc>::try_from_slice(&data).unwrap(), value);
+}
+
+#[test]
+fn test_out_of_range() {
+    let data = (i64::MAX, 0u32).try_to_vec().unwrap();
+    assert_eq!(
+        DateTime::<Utc>::try_from_slice(&data)
+            .unwrap_err()
+            .to_string(),
+        "Timestamp is out of range"
+    );
+    let data = (0i64, 2_000_000_000u32).try_to_vec().unwrap();
+    assert_eq!(
+        NaiveDateTime::try_from_slice(&data)
+            .unwrap_err()
+            .to_string(),
+        "Timestamp is out of range"
+    );
+}
+
+#[test]
+fn test_schema() {
+    let container = DateTime::<Utc>::schema_container();
+    assert_eq!(container.declaration, "DateTime<Utc>");
+    assert_eq!(
+        container.definitions["DateTime<Utc>"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("secs".to_string(), "i64".to_string()),
+                ("nanos".to_string(), "u32".to_string()),
+            ])
+        }
+    );
+    assert_eq!(NaiveDateTime::declaration(), "NaiveDateTime");
+}
@@ -0,0 +1,9 @@
+// Diagnostics of the derive macros are checked against the `.stderr` files next to the sources.
+// Run with `TRYBUILD=overwrite` to regenerate them after an intended change. The sources in
+// `compile_pass` must compile and run without panicking.
+#[test]
+fn test_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+    t.pass("tests/compile_pass/*.rs");
+}
@@ -0,0 +1,39 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use borsh::schema::{BorshSchema, Definition};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[test]
+fn test_cow_map_keys_roundtrip() {
+    let owned = "beta".to_string();
+    let mut map: BTreeMap<Cow<str>, u64> = BTreeMap::new();
+    map.insert(Cow::Borrowed("alpha"), 1);
+    map.insert(Cow::Owned(owned), 2);
+    let data = map.try_to_vec().unwrap();
+
+    let mut plain = BTreeMap::new();
+    plain.insert("alpha".to_string(), 1u64);
+    plain.insert("beta".to_string(), 2u64);
+    assert_eq!(data, plain.try_to_vec().unwrap());
+
+    let decoded = BTreeMap::<Cow<str>, u64>::try_from_slice(&data).unwrap();
+    assert_eq!(decoded, map);
+    assert!(decoded.keys().all(|key| matches!(key, Cow::Owned(_))));
+}
+
+#[test]
+fn test_cow_map_keys_schema() {
+    assert_eq!(
+        BTreeMap::<Cow<str>, u64>::declaration(),
+        "BTreeMap<string, u64>"
+    );
+    let container = BTreeMap::<Cow<str>, u64>::schema_container();
+    assert_eq!(
+        container.definitions["Tuple<string, u64>"],
+        Definition::Tuple {
+            elements: vec!["string".to_string(), "u64".to_string()]
+        }
+    );
+    assert_eq!(container, BTreeMap::<String, u64>::schema_container());
+}
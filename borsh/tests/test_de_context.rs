@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use borsh::de::BorshDeserializeWith;
+use borsh::maybestd::io::{Read, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Stores every distinct string once.
+#[derive(Default)]
+struct Interner {
+    strings: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, string: String) -> Symbol {
+        let next = self.strings.len() as u32;
+        let id = *self.ids.entry(string.clone()).or_insert(next);
+        if id == next {
+            self.strings.push(string);
+        }
+        Symbol(id)
+    }
+}
+
+/// A string stored in the `Interner`, serialized as the string itself.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct Symbol(u32);
+
+impl BorshDeserializeWith<Interner> for Symbol {
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Interner) -> Result<Self> {
+        Ok(ctx.intern(String::deserialize_reader(reader)?))
+    }
+}
+
+#[derive(BorshDeserialize, PartialEq, Debug)]
+#[borsh(de_context = "Interner")]
+struct Transfer {
+    #[borsh(with_context)]
+    from: Symbol,
+    #[borsh(with_context)]
+    to: Symbol,
+    amount: u64,
+    #[borsh(with_context)]
+    tags: Vec<(Symbol, u8)>,
+}
+
+#[derive(BorshDeserialize, PartialEq, Debug)]
+#[borsh(de_context = "Interner")]
+enum Event {
+    Transfer(#[borsh(with_context)] Transfer),
+    Rename {
+        #[borsh(with_context)]
+        from: Option<Symbol>,
+        #[borsh(with_context)]
+        to: Symbol,
+    },
+    Clear,
+}
+
+#[derive(BorshSerialize)]
+struct PlainTransfer {
+    from: String,
+    to: String,
+    amount: u64,
+    tags: Vec<(String, u8)>,
+}
+
+#[derive(BorshSerialize)]
+enum PlainEvent {
+    Transfer(PlainTransfer),
+    Rename { from: Option<String>, to: String },
+    Clear,
+}
+
+#[test]
+fn test_strings_are_interned() {
+    let events = vec![
+        PlainEvent::Transfer(PlainTransfer {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            amount: 10,
+            tags: vec![("fee".to_string(), 1), ("alice".to_string(), 2)],
+        }),
+        PlainEvent::Rename {
+            from: Some("bob".to_string()),
+            to: "carol".to_string(),
+        },
+        PlainEvent::Clear,
+    ];
+    let data = events.try_to_vec().unwrap();
+
+    let mut interner = Interner::default();
+    let decoded = Vec::<Event>::try_from_slice_with(&data, &mut interner).unwrap();
+    assert_eq!(interner.strings, ["alice", "bob", "fee", "carol"]);
+    assert_eq!(
+        decoded,
+        vec![
+            Event::Transfer(Transfer {
+                from: Symbol(0),
+                to: Symbol(1),
+                amount: 10,
+                tags: vec![(Symbol(2), 1), (Symbol(0), 2)],
+            }),
+            Event::Rename {
+                from: Some(Symbol(1)),
+                to: Symbol(3),
+            },
+            Event::Clear,
+        ]
+    );
+}
+
+#[test]
+fn test_context_is_kept_between_values() {
+    let mut interner = Interner::default();
+    let data = "x".to_string().try_to_vec().unwrap();
+    assert_eq!(
+        Symbol::try_from_slice_with(&data, &mut interner).unwrap(),
+        Symbol(0)
+    );
+    let data = ("y".to_string(), "x".to_string()).try_to_vec().unwrap();
+    assert_eq!(
+        <(Symbol, Symbol)>::try_from_slice_with(&data, &mut interner).unwrap(),
+        (Symbol(1), Symbol(0))
+    );
+}
+
+#[test]
+fn test_unexpected_variant_tag() {
+    let mut interner = Interner::default();
+    assert_eq!(
+        Event::try_from_slice_with(&[3], &mut interner)
+            .unwrap_err()
+            .to_string(),
+        "Unexpected variant tag: 3"
+    );
+}
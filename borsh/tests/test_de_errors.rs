@@ -1,4 +1,5 @@
-use borsh::BorshDeserialize;
+use borsh::maybestd::io::ErrorKind;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 #[derive(BorshDeserialize, Debug)]
 enum A {
@@ -44,12 +45,32 @@ fn test_extra_bytes() {
     );
 }
 
+#[test]
+fn test_bool_in_struct() {
+    #[derive(BorshDeserialize, Debug)]
+    struct Flags {
+        #[allow(unused)]
+        id: u8,
+        enabled: bool,
+    }
+
+    assert!(Flags::try_from_slice(&[7, 1]).unwrap().enabled);
+    assert!(!Flags::try_from_slice(&[7, 0]).unwrap().enabled);
+    let err = Flags::try_from_slice(&[7, 2]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "Invalid bool representation: 2");
+}
+
 #[test]
 fn test_invalid_bool() {
+    assert!(!<bool>::try_from_slice(&[0]).unwrap());
+    assert!(<bool>::try_from_slice(&[1]).unwrap());
     for i in 2u8..=255 {
         let bytes = [i];
+        let err = <bool>::try_from_slice(&bytes).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
         assert_eq!(
-            <bool>::try_from_slice(&bytes).unwrap_err().to_string(),
+            err.to_string(),
             format!("Invalid bool representation: {}", i)
         );
     }
@@ -200,3 +221,48 @@ fn test_zero_on_nonzero_integer_missing_byte() {
         ERROR_UNEXPECTED_LENGTH_OF_INPUT
     );
 }
+
+#[test]
+fn test_usize_beyond_u32() {
+    let bytes = (u32::MAX as u64 + 1).try_to_vec().unwrap();
+    let result = usize::try_from_slice(&bytes);
+    if cfg!(target_pointer_width = "64") {
+        assert_eq!(result.unwrap() as u64, u32::MAX as u64 + 1);
+    } else {
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Overflow on machine with 32 bit usize");
+    }
+}
+
+#[test]
+fn test_isize_beyond_i32() {
+    let bytes = (i32::MIN as i64 - 1).try_to_vec().unwrap();
+    let result = isize::try_from_slice(&bytes);
+    if cfg!(target_pointer_width = "64") {
+        assert_eq!(result.unwrap() as i64, i32::MIN as i64 - 1);
+    } else {
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Overflow on machine with 32 bit isize");
+    }
+}
+
+#[test]
+fn test_infallible() {
+    let err = core::convert::Infallible::try_from_slice(&[]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "Cannot deserialize a value of an uninhabited type"
+    );
+
+    let bytes = Result::<u64, core::convert::Infallible>::Ok(7)
+        .try_to_vec()
+        .unwrap();
+    assert_eq!(
+        Result::<u64, core::convert::Infallible>::try_from_slice(&bytes).unwrap(),
+        Ok(7)
+    );
+    assert!(Result::<u64, core::convert::Infallible>::try_from_slice(&[0]).is_err());
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)] // Local structures are only serialized.
+use borsh::debug::annotate;
+use borsh::{BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshSchema)]
+enum Shape {
+    Circle { radius: u32 },
+    Square(u16),
+}
+
+#[derive(BorshSerialize, BorshSchema)]
+struct Drawing {
+    name: String,
+    shapes: Vec<Shape>,
+    hidden: Option<bool>,
+}
+
+fn drawing() -> Vec<u8> {
+    Drawing {
+        name: "sketch".to_string(),
+        shapes: vec![Shape::Square(9), Shape::Circle { radius: 300 }],
+        hidden: Some(true),
+    }
+    .try_to_vec()
+    .unwrap()
+}
+
+#[test]
+fn test_annotate_valid() {
+    assert_eq!(
+        annotate::<Drawing>(&drawing()),
+        "\
+00000000  06 00 00 00 73 6b 65 74 ..  name: string = \"sketch\"
+0000000a  02 00 00 00                 shapes.len: Vec<Shape> = 2
+0000000e  01                          shapes[0].tag: Shape = 1 (Square)
+0000000f  09 00                       shapes[0].Square.0: u16 = 9
+00000011  00                          shapes[1].tag: Shape = 0 (Circle)
+00000012  2c 01 00 00                 shapes[1].Circle.radius: u32 = 300
+00000016  01                          hidden.tag: Option<bool> = 1 (Some)
+00000017  01                          hidden: bool = true
+"
+    );
+}
+
+#[test]
+fn test_annotate_corrupted_enum_tag() {
+    let mut data = drawing();
+    data[0x11] = 7;
+    assert_eq!(
+        annotate::<Drawing>(&data),
+        "\
+00000000  06 00 00 00 73 6b 65 74 ..  name: string = \"sketch\"
+0000000a  02 00 00 00                 shapes.len: Vec<Shape> = 2
+0000000e  01                          shapes[0].tag: Shape = 1 (Square)
+0000000f  09 00                       shapes[0].Square.0: u16 = 9
+00000011  07 2c 01 00 00 01 01        !! shapes[1].tag: Shape: Unexpected variant tag: 7
+"
+    );
+}
+
+#[test]
+fn test_annotate_truncated_and_left_over() {
+    let data = drawing();
+    assert_eq!(
+        annotate::<Drawing>(&data[..0x14]),
+        "\
+00000000  06 00 00 00 73 6b 65 74 ..  name: string = \"sketch\"
+0000000a  02 00 00 00                 shapes.len: Vec<Shape> = 2
+0000000e  01                          shapes[0].tag: Shape = 1 (Square)
+0000000f  09 00                       shapes[0].Square.0: u16 = 9
+00000011  00                          shapes[1].tag: Shape = 0 (Circle)
+00000012  2c 01                       !! shapes[1].Circle.radius: u32: Unexpected length of input
+"
+    );
+    assert_eq!(
+        annotate::<u16>(&[1, 0, 2]),
+        "\
+00000000  01 00                       <root>: u16 = 1
+00000002  02                          !! 1 bytes left over after the value
+"
+    );
+}
@@ -0,0 +1,79 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+use borsh::schema::{BorshSchema, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSchema as BorshSchemaMacro, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchemaMacro, PartialEq, Debug)]
+struct Buffer<T = u16, const N: usize = 2> {
+    items: [T; N],
+    tail: T,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchemaMacro, PartialEq, Debug)]
+enum Message<T = String, const N: usize = 3> {
+    Text(T),
+    Bytes([u8; N]),
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Store<K, V, S = RandomState>
+where
+    K: Eq + Hash + Ord,
+    S: BuildHasher + Default,
+{
+    map: HashMap<K, V, S>,
+}
+
+#[test]
+fn test_default_type_and_const_params() {
+    let buffer: Buffer = Buffer {
+        items: [1, 2],
+        tail: 3,
+    };
+    let data = buffer.try_to_vec().unwrap();
+    assert_eq!(data, vec![1, 0, 2, 0, 3, 0]);
+    assert_eq!(Buffer::try_from_slice(&data).unwrap(), buffer);
+
+    let message: Message = Message::Bytes([7, 8, 9]);
+    let data = message.try_to_vec().unwrap();
+    assert_eq!(data, vec![1, 7, 8, 9]);
+    assert_eq!(Message::try_from_slice(&data).unwrap(), message);
+
+    let other = Buffer::<u8, 1> {
+        items: [1],
+        tail: 2,
+    };
+    assert_eq!(
+        Buffer::<u8, 1>::try_from_slice(&other.try_to_vec().unwrap()).unwrap(),
+        other
+    );
+}
+
+#[test]
+fn test_default_params_schema() {
+    assert_eq!(<Buffer>::declaration(), "Buffer<u16>");
+    assert_eq!(<Message>::declaration(), "Message<string>");
+    let container = <Buffer>::schema_container();
+    assert_eq!(
+        container.definitions["Buffer<u16>"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("items".to_string(), "Array<u16, 2>".to_string()),
+                ("tail".to_string(), "u16".to_string()),
+            ])
+        }
+    );
+}
+
+#[test]
+fn test_default_hasher_param() {
+    let mut map = HashMap::new();
+    map.insert(2u8, "b".to_string());
+    map.insert(1u8, "a".to_string());
+    let store: Store<u8, String> = Store { map };
+    let data = store.try_to_vec().unwrap();
+    let actual: Store<u8, String> = Store::try_from_slice(&data).unwrap();
+    assert_eq!(actual.map, store.map);
+}
@@ -0,0 +1,58 @@
+use borsh::de::deserialize_seq_into;
+use borsh::maybestd::collections::{BTreeSet, HashSet};
+use borsh::BorshSerialize;
+
+/// Keeps the sum and count of the elements instead of the elements.
+#[derive(Default, PartialEq, Debug)]
+struct Stats {
+    count: usize,
+    sum: u64,
+}
+
+impl Extend<u32> for Stats {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        for value in iter {
+            self.count += 1;
+            self.sum += value as u64;
+        }
+    }
+}
+
+fn data() -> Vec<u8> {
+    vec![5u32, 1, 5, 3].try_to_vec().unwrap()
+}
+
+#[test]
+fn test_into_vec() {
+    let vec: Vec<u32> = deserialize_seq_into::<_, u32, _>(&mut &data()[..]).unwrap();
+    assert_eq!(vec, vec![5, 1, 5, 3]);
+}
+
+#[test]
+fn test_into_sets() {
+    let set: HashSet<u32> = deserialize_seq_into::<_, u32, _>(&mut &data()[..]).unwrap();
+    assert_eq!(set, [1, 3, 5].iter().copied().collect());
+    let set: BTreeSet<u32> = deserialize_seq_into::<_, u32, _>(&mut &data()[..]).unwrap();
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![1, 3, 5]);
+}
+
+#[test]
+fn test_into_custom_collection() {
+    let stats: Stats = deserialize_seq_into(&mut &data()[..]).unwrap();
+    assert_eq!(stats, Stats { count: 4, sum: 14 });
+}
+
+#[test]
+fn test_advances_the_reader() {
+    let mut data = data();
+    data.push(0xff);
+    let mut buf = &data[..];
+    let _: Vec<u32> = deserialize_seq_into::<_, u32, _>(&mut buf).unwrap();
+    assert_eq!(buf, [0xff]);
+}
+
+#[test]
+fn test_truncated_input() {
+    let data = data();
+    assert!(deserialize_seq_into::<Vec<u32>, u32, _>(&mut &data[..data.len() - 1]).is_err());
+}
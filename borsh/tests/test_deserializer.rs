@@ -0,0 +1,135 @@
+use borsh::de::Deserializer;
+use borsh::maybestd::io::{Read, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Account {
+    id: u64,
+    name: String,
+    balance: i128,
+    frozen: bool,
+    rate: f32,
+    key: [u8; 4],
+    tags: Vec<u16>,
+    parent: Option<u32>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum Event {
+    Created(Account),
+    Renamed { id: u64, name: String },
+    Closed,
+}
+
+fn account() -> Account {
+    Account {
+        id: 42,
+        name: "alice".to_string(),
+        balance: -1_000,
+        frozen: false,
+        rate: 0.5,
+        key: [1, 2, 3, 4],
+        tags: vec![10, 20, 30],
+        parent: Some(7),
+    }
+}
+
+fn read_account<R: Read>(deserializer: &mut Deserializer<R>) -> Result<Account> {
+    let id = deserializer.read_u64()?;
+    let name = deserializer.read_str()?;
+    let balance = deserializer.read_i128()?;
+    let frozen = deserializer.read_bool()?;
+    let rate = deserializer.read_f32()?;
+    let mut key = [0; 4];
+    deserializer.read_raw(&mut key)?;
+    let len = deserializer.read_seq_header()?;
+    let tags = (0..len)
+        .map(|_| deserializer.read_u16())
+        .collect::<Result<_>>()?;
+    let parent = if deserializer.read_option_tag()? {
+        Some(deserializer.read_u32()?)
+    } else {
+        None
+    };
+    Ok(Account {
+        id,
+        name,
+        balance,
+        frozen,
+        rate,
+        key,
+        tags,
+        parent,
+    })
+}
+
+fn read_event<R: Read>(deserializer: &mut Deserializer<R>) -> Result<Event> {
+    Ok(match deserializer.read_variant_tag()? {
+        0 => Event::Created(read_account(deserializer)?),
+        1 => Event::Renamed {
+            id: deserializer.read_u64()?,
+            name: deserializer.read_str()?,
+        },
+        _ => Event::Closed,
+    })
+}
+
+#[test]
+fn test_struct() {
+    let data = account().try_to_vec().unwrap();
+    let mut deserializer = Deserializer::new(&data[..]);
+    let manual = read_account(&mut deserializer).unwrap();
+    assert_eq!(manual, Account::try_from_slice(&data).unwrap());
+    assert_eq!(deserializer.position(), data.len());
+}
+
+#[test]
+fn test_enum() {
+    let events = vec![
+        Event::Created(account()),
+        Event::Renamed {
+            id: 42,
+            name: "bob".to_string(),
+        },
+        Event::Closed,
+    ];
+    let mut data = Vec::new();
+    for event in &events {
+        event.serialize(&mut data).unwrap();
+    }
+
+    let mut deserializer = Deserializer::new(&data[..]);
+    for event in &events {
+        assert_eq!(&read_event(&mut deserializer).unwrap(), event);
+    }
+    assert!(deserializer.into_inner().is_empty());
+}
+
+#[test]
+fn test_mixed_with_values() {
+    let data = (b"abc".to_vec(), account(), None::<u8>)
+        .try_to_vec()
+        .unwrap();
+    let mut deserializer = Deserializer::new(&data[..]);
+    assert_eq!(deserializer.read_bytes().unwrap(), b"abc");
+    assert_eq!(deserializer.deserialize::<Account>().unwrap(), account());
+    assert!(!deserializer.read_option_tag().unwrap());
+}
+
+#[test]
+fn test_errors_match_derived() {
+    let data = account().try_to_vec().unwrap();
+    let truncated = &data[..data.len() - 2];
+    let manual = read_account(&mut Deserializer::new(truncated)).unwrap_err();
+    let derived = Account::try_from_slice(truncated).unwrap_err();
+    assert_eq!(manual.kind(), derived.kind());
+    assert_eq!(manual.to_string(), derived.to_string());
+
+    let mut deserializer = Deserializer::new(&[5u8, 2][..]);
+    assert!(deserializer.read_bool().is_err());
+    assert_eq!(
+        deserializer.read_option_tag().unwrap_err().to_string(),
+        "Invalid Option representation: 2. The first byte must be 0 or 1"
+    );
+    assert_eq!(deserializer.position(), 2);
+}
@@ -0,0 +1,67 @@
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::{BorshSchema, Definition};
+use borsh::{BorshDeserialize, BorshSerialize};
+use either::Either;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum LocalEither {
+    Left(u32),
+    Right(String),
+}
+
+#[test]
+fn test_either_roundtrip() {
+    let left: Either<u32, String> = Either::Left(7);
+    let right: Either<u32, String> = Either::Right("seven".to_string());
+    for value in [left, right] {
+        let data = value.try_to_vec().unwrap();
+        assert_eq!(Either::<u32, String>::try_from_slice(&data).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_either_same_bytes_as_enum() {
+    let left: Either<u32, String> = Either::Left(7);
+    assert_eq!(
+        left.try_to_vec().unwrap(),
+        LocalEither::Left(7).try_to_vec().unwrap()
+    );
+    let right: Either<u32, String> = Either::Right("seven".to_string());
+    let data = right.try_to_vec().unwrap();
+    assert_eq!(
+        data,
+        LocalEither::Right("seven".to_string())
+            .try_to_vec()
+            .unwrap()
+    );
+    assert_eq!(
+        LocalEither::try_from_slice(&data).unwrap(),
+        LocalEither::Right("seven".to_string())
+    );
+}
+
+#[test]
+fn test_either_unknown_tag() {
+    let err = Either::<u32, String>::try_from_slice(&[2, 0, 0, 0, 0]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid Either representation: 2. The first byte must be 0 or 1"
+    );
+}
+
+#[test]
+fn test_either_schema() {
+    assert_eq!(Either::<u32, String>::declaration(), "Either<u32, string>");
+    let mut defs = HashMap::new();
+    Either::<u32, Vec<u8>>::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        defs["Either<u32, Vec<u8>>"],
+        Definition::Enum {
+            variants: vec![
+                ("Left".to_string(), "u32".to_string()),
+                ("Right".to_string(), "Vec<u8>".to_string()),
+            ]
+        }
+    );
+    assert!(defs.contains_key("Vec<u8>"));
+}
@@ -0,0 +1,72 @@
+use borsh::{BorshSerialize, BorshTagged};
+
+#[derive(BorshSerialize)]
+#[repr(u8)]
+enum Command<T> {
+    Nop,
+    Push(T) = 5,
+    Pop { count: u8 },
+    Clear = 200,
+    Swap(u8, u8),
+}
+
+fn commands() -> Vec<Command<String>> {
+    vec![
+        Command::Nop,
+        Command::Push("x".to_string()),
+        Command::Pop { count: 2 },
+        Command::Clear,
+        Command::Swap(0, 1),
+    ]
+}
+
+#[test]
+fn test_borsh_tag_matches_serialized_tag() {
+    for command in commands() {
+        assert_eq!(command.borsh_tag(), command.try_to_vec().unwrap()[0]);
+    }
+}
+
+#[test]
+fn test_variant_tags() {
+    assert_eq!(
+        Command::<String>::VARIANT_TAGS,
+        &[
+            (0, "Nop"),
+            (5, "Push"),
+            (6, "Pop"),
+            (200, "Clear"),
+            (201, "Swap")
+        ]
+    );
+    let tags: Vec<u8> = commands().iter().map(BorshTagged::borsh_tag).collect();
+    let expected: Vec<u8> = Command::<String>::VARIANT_TAGS
+        .iter()
+        .map(|(tag, _)| *tag)
+        .collect();
+    assert_eq!(tags, expected);
+}
+
+#[test]
+fn test_variant_name() {
+    #[derive(BorshSerialize)]
+    #[allow(dead_code)] // Only the tags are used.
+    enum Plain {
+        A,
+        B(u8),
+        C { c: u16 },
+    }
+
+    assert_eq!(Plain::variant_tags(), &[(0, "A"), (1, "B"), (2, "C")]);
+    assert_eq!(Plain::variant_name(1), Some("B"));
+    assert_eq!(Plain::variant_name(3), None);
+
+    assert_eq!(
+        Command::<String>::variant_tags(),
+        Command::<String>::VARIANT_TAGS
+    );
+    assert_eq!(Command::<String>::variant_name(0), Some("Nop"));
+    assert_eq!(Command::<String>::variant_name(6), Some("Pop"));
+    assert_eq!(Command::<String>::variant_name(201), Some("Swap"));
+    assert_eq!(Command::<String>::variant_name(1), None);
+}
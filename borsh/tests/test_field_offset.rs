@@ -0,0 +1,61 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+use borsh::schema::field_offset;
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(BorshSchema)]
+enum Kind {
+    Small(u32),
+    Large(u16, u16),
+}
+
+#[derive(BorshSchema)]
+struct Fixed {
+    flag: bool,
+    id: u64,
+    position: Point,
+    hash: [u8; 32],
+    pair: (u16, u128),
+    kind: Kind,
+    last: u8,
+}
+
+#[derive(BorshSchema)]
+struct LeadingVec {
+    items: Vec<u8>,
+    id: u64,
+}
+
+#[test]
+fn test_fixed_size_fields() {
+    let container = Fixed::schema_container();
+    let offset = |field| field_offset(&container, "Fixed", field);
+    assert_eq!(offset("flag"), Some(0));
+    assert_eq!(offset("id"), Some(1));
+    assert_eq!(offset("position"), Some(9));
+    assert_eq!(offset("hash"), Some(17));
+    assert_eq!(offset("pair"), Some(49));
+    assert_eq!(offset("kind"), Some(67));
+    assert_eq!(offset("last"), Some(72));
+    assert_eq!(offset("missing"), None);
+    assert_eq!(field_offset(&container, "Point", "y"), Some(4));
+}
+
+#[test]
+fn test_variable_size_field() {
+    let container = LeadingVec::schema_container();
+    assert_eq!(field_offset(&container, "LeadingVec", "items"), Some(0));
+    assert_eq!(field_offset(&container, "LeadingVec", "id"), None);
+}
+
+#[test]
+fn test_not_a_struct_with_named_fields() {
+    let container = Fixed::schema_container();
+    assert_eq!(field_offset(&container, "Kind", "Small"), None);
+    assert_eq!(field_offset(&container, "u64", "id"), None);
+}
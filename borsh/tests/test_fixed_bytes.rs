@@ -0,0 +1,86 @@
+use borsh::bytes::FixedBytes;
+use borsh::schema::{is_fixed_bytes, Definition};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Transfer {
+    from: FixedBytes<32>,
+    signature: FixedBytes<64>,
+    amount: u64,
+}
+
+fn roundtrip<const N: usize>(bytes: [u8; N]) {
+    let value = FixedBytes::from(bytes);
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, bytes);
+    assert_eq!(data, bytes.try_to_vec().unwrap());
+    assert_eq!(FixedBytes::<N>::try_from_slice(&data).unwrap(), value);
+}
+
+#[test]
+fn test_roundtrip() {
+    roundtrip([]);
+    roundtrip([7; 32]);
+    let mut bytes = [0u8; 64];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    roundtrip(bytes);
+}
+
+#[test]
+fn test_too_short() {
+    assert!(FixedBytes::<32>::try_from_slice(&[0; 31]).is_err());
+    assert!(FixedBytes::<0>::try_from_slice(&[0]).is_err());
+}
+
+#[test]
+fn test_struct() {
+    let transfer = Transfer {
+        from: FixedBytes([1; 32]),
+        signature: FixedBytes([2; 64]),
+        amount: 5,
+    };
+    let data = transfer.try_to_vec().unwrap();
+    assert_eq!(data.len(), 32 + 64 + 8);
+    assert_eq!(Transfer::try_from_slice(&data).unwrap(), transfer);
+}
+
+#[test]
+fn test_schema() {
+    assert_eq!(FixedBytes::<0>::declaration(), "Array<u8, 0>");
+    assert_eq!(FixedBytes::<32>::declaration(), "Array<u8, 32>");
+    assert_eq!(
+        FixedBytes::<64>::schema_container(),
+        <[u8; 64]>::schema_container()
+    );
+    let container = Transfer::schema_container();
+    assert_eq!(
+        container.definitions["Array<u8, 32>"],
+        Definition::Array {
+            length: 32,
+            elements: "u8".to_string()
+        }
+    );
+    assert_eq!(
+        is_fixed_bytes(&container.definitions["Array<u8, 64>"]),
+        Some(64)
+    );
+    assert_eq!(is_fixed_bytes(&container.definitions["Transfer"]), None);
+    assert_eq!(
+        is_fixed_bytes(&<[u16; 4]>::schema_container().definitions["Array<u16, 4>"]),
+        None
+    );
+}
+
+#[test]
+fn test_conveniences() {
+    let mut value = FixedBytes::new([0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(format!("{:?}", value), "FixedBytes(deadbeef)");
+    assert_eq!(format!("{:x}", value), "deadbeef");
+    assert_eq!(value.len(), 4);
+    value[0] = 0;
+    assert_eq!(value.as_ref(), &[0, 0xad, 0xbe, 0xef][..]);
+    assert_eq!(<[u8; 4]>::from(value), [0, 0xad, 0xbe, 0xef]);
+    assert_eq!(FixedBytes::<64>::default().into_inner(), [0; 64]);
+}
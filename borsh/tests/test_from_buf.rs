@@ -0,0 +1,58 @@
+use bytes::{Buf, Bytes};
+
+use borsh::{from_buf, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Packet {
+    id: u64,
+    sender: String,
+    rows: Vec<Vec<u64>>,
+    checksum: u64,
+}
+
+fn packet() -> Packet {
+    Packet {
+        id: 0x0102_0304_0506_0708,
+        sender: "node-1".to_string(),
+        rows: vec![vec![1, u64::MAX], vec![], vec![3]],
+        checksum: u64::MAX - 7,
+    }
+}
+
+/// Chains one `Bytes` per byte of `data`, so that every value is split across chunks.
+fn one_byte_chunks(data: &[u8]) -> Box<dyn Buf> {
+    let mut buf: Box<dyn Buf> = Box::new(Bytes::new());
+    for byte in data {
+        buf = Box::new(buf.chain(Bytes::copy_from_slice(&[*byte])));
+    }
+    buf
+}
+
+#[test]
+fn test_one_byte_chunks() {
+    let data = packet().try_to_vec().unwrap();
+    let mut buf = one_byte_chunks(&data);
+    assert_eq!(buf.chunk().len(), 1);
+    assert_eq!(from_buf::<Packet, _>(&mut buf).unwrap(), packet());
+    assert!(!buf.has_remaining());
+}
+
+#[test]
+fn test_rest_of_buf_is_left() {
+    let mut data = packet().try_to_vec().unwrap();
+    data.extend_from_slice(&[9, 8]);
+    let mut buf = one_byte_chunks(&data);
+    assert_eq!(from_buf::<Packet, _>(&mut buf).unwrap(), packet());
+    assert_eq!(buf.remaining(), 2);
+    assert_eq!(from_buf::<[u8; 2], _>(&mut buf).unwrap(), [9, 8]);
+}
+
+#[test]
+fn test_truncated_buf() {
+    let data = packet().try_to_vec().unwrap();
+    let mut buf = one_byte_chunks(&data[..data.len() - 1]);
+    assert_eq!(
+        from_buf::<Packet, _>(&mut buf).unwrap_err().to_string(),
+        "Unexpected length of input"
+    );
+}
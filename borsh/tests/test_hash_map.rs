@@ -2,8 +2,10 @@
 use core::hash::BuildHasher;
 #[cfg(feature = "std")]
 use std::collections::hash_map::{DefaultHasher, RandomState};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
 
-use borsh::maybestd::collections::HashMap;
+use borsh::maybestd::collections::{BTreeMap, HashMap};
 use borsh::{BorshDeserialize, BorshSerialize};
 
 #[test]
@@ -40,3 +42,77 @@ fn test_generic_hash_hashmap() {
     let actual_map = HashMap::<String, String, NewHasher>::try_from_slice(&data).unwrap();
     assert_eq!(map, actual_map);
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn test_generic_hash_hashset() {
+    let mut set = HashSet::with_hasher(NewHasher::default());
+    set.insert("foo".to_string());
+    set.insert("bar".to_string());
+
+    let data = set.try_to_vec().unwrap();
+    let default_set: HashSet<String> = set.iter().cloned().collect();
+    assert_eq!(data, default_set.try_to_vec().unwrap());
+    let actual_set = HashSet::<String, NewHasher>::try_from_slice(&data).unwrap();
+    assert_eq!(set, actual_set);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_generic_hash_derive() {
+    use borsh::BorshSchema;
+
+    #[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+    struct Balances {
+        accounts: HashMap<String, u64, NewHasher>,
+        frozen: HashSet<String, NewHasher>,
+    }
+
+    let mut balances = Balances {
+        accounts: HashMap::with_hasher(NewHasher::default()),
+        frozen: HashSet::with_hasher(NewHasher::default()),
+    };
+    balances.accounts.insert("alice".to_string(), 10);
+    balances.accounts.insert("bob".to_string(), 20);
+    balances.frozen.insert("bob".to_string());
+
+    let data = balances.try_to_vec().unwrap();
+    assert_eq!(Balances::try_from_slice(&data).unwrap(), balances);
+    assert_eq!(
+        <HashMap<String, u64, NewHasher>>::declaration(),
+        "HashMap<string, u64>"
+    );
+    assert_eq!(
+        <HashSet<String, NewHasher>>::declaration(),
+        "HashSet<string>"
+    );
+}
+
+#[test]
+fn test_serialize_map_ordered() {
+    let map: HashMap<String, u32> = vec![
+        ("a".to_string(), 1),
+        ("b".to_string(), 3),
+        ("c".to_string(), 2),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut data = Vec::new();
+    borsh::serialize_map_ordered(&map, &mut data, |(_, a), (_, b)| b.cmp(a)).unwrap();
+    let expected = vec![
+        ("b".to_string(), 3u32),
+        ("c".to_string(), 2),
+        ("a".to_string(), 1),
+    ];
+    assert_eq!(data, expected.try_to_vec().unwrap());
+    assert_ne!(data, map.try_to_vec().unwrap());
+
+    if cfg!(feature = "canonical") {
+        assert!(HashMap::<String, u32>::try_from_slice(&data).is_err());
+    } else {
+        assert_eq!(HashMap::<String, u32>::try_from_slice(&data).unwrap(), map);
+        let btree_map = BTreeMap::<String, u32>::try_from_slice(&data).unwrap();
+        assert!(btree_map.iter().eq(map.iter().collect::<BTreeMap<_, _>>()));
+    }
+}
@@ -0,0 +1,74 @@
+use borsh::schema::BorshSchema;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[test]
+fn test_vec_same_bytes_as_std() {
+    let value: heapless::Vec<u16, 4> = heapless::Vec::from_slice(&[1, 2, 3]).unwrap();
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, vec![1u16, 2, 3].try_to_vec().unwrap());
+    assert_eq!(
+        heapless::Vec::<u16, 4>::try_from_slice(&data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_vec_exact_capacity() {
+    let data = vec![7u8, 8, 9].try_to_vec().unwrap();
+    let value = heapless::Vec::<u8, 3>::try_from_slice(&data).unwrap();
+    assert_eq!(value.as_slice(), [7, 8, 9]);
+}
+
+#[test]
+fn test_vec_over_capacity() {
+    let data = vec![7u8, 8, 9].try_to_vec().unwrap();
+    let err = heapless::Vec::<u8, 2>::try_from_slice(&data).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Length exceeds the capacity of the container"
+    );
+}
+
+#[test]
+fn test_string_same_bytes_as_std() {
+    let mut value = heapless::String::<8>::new();
+    value.push_str("hello").unwrap();
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, "hello".to_string().try_to_vec().unwrap());
+    assert_eq!(heapless::String::<8>::try_from_slice(&data).unwrap(), value);
+}
+
+#[test]
+fn test_string_exact_capacity() {
+    let data = "hello".to_string().try_to_vec().unwrap();
+    let value = heapless::String::<5>::try_from_slice(&data).unwrap();
+    assert_eq!(value.as_str(), "hello");
+}
+
+#[test]
+fn test_string_over_capacity() {
+    let data = "hello".to_string().try_to_vec().unwrap();
+    let err = heapless::String::<4>::try_from_slice(&data).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Length exceeds the capacity of the container"
+    );
+}
+
+#[test]
+fn test_invalid_utf8_string() {
+    let data = vec![0xffu8].try_to_vec().unwrap();
+    assert!(heapless::String::<4>::try_from_slice(&data).is_err());
+}
+
+#[test]
+fn test_schema_same_as_std() {
+    assert_eq!(
+        heapless::Vec::<u16, 4>::schema_container(),
+        Vec::<u16>::schema_container()
+    );
+    assert_eq!(
+        heapless::String::<4>::schema_container(),
+        String::schema_container()
+    );
+}
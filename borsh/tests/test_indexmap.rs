@@ -0,0 +1,58 @@
+use std::collections::hash_map::RandomState;
+
+use borsh::schema::{BorshSchema, BorshSchemaContainer, Definition};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+type IndexMap<K, V> = indexmap::IndexMap<K, V, RandomState>;
+type IndexSet<T> = indexmap::IndexSet<T, RandomState>;
+
+#[test]
+fn test_map_keeps_insertion_order() {
+    let mut map = IndexMap::default();
+    map.insert("zebra".to_string(), 1u32);
+    map.insert("apple".to_string(), 2);
+    map.insert("mango".to_string(), 3);
+
+    let data = map.try_to_vec().unwrap();
+    assert_eq!(
+        data,
+        vec![("zebra", 1u32), ("apple", 2), ("mango", 3)]
+            .try_to_vec()
+            .unwrap()
+    );
+    let actual = IndexMap::<String, u32>::try_from_slice(&data).unwrap();
+    assert_eq!(
+        actual.keys().collect::<Vec<_>>(),
+        ["zebra", "apple", "mango"]
+    );
+    assert_eq!(actual, map);
+}
+
+#[test]
+fn test_set_keeps_insertion_order() {
+    let set: IndexSet<u16> = [30, 10, 20].iter().copied().collect();
+
+    let data = set.try_to_vec().unwrap();
+    assert_eq!(data, vec![30u16, 10, 20].try_to_vec().unwrap());
+    let actual = IndexSet::<u16>::try_from_slice(&data).unwrap();
+    assert_eq!(actual.iter().copied().collect::<Vec<_>>(), [30, 10, 20]);
+}
+
+#[test]
+fn test_schema() {
+    assert_eq!(
+        IndexMap::<String, u8>::declaration(),
+        "IndexMap<string, u8>"
+    );
+    assert_eq!(IndexSet::<u64>::declaration(), "IndexSet<u64>");
+
+    let BorshSchemaContainer { definitions, .. } = IndexMap::<String, u8>::schema_container();
+    assert_eq!(
+        definitions["IndexMap<string, u8>"],
+        Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "Tuple<string, u8>".to_string(),
+        }
+    );
+}
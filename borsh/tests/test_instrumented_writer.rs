@@ -0,0 +1,81 @@
+use borsh::ser::InstrumentedWriter;
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct Header {
+    height: u64,
+    parent: [u8; 32],
+    timestamp: u64,
+}
+
+#[derive(BorshSerialize)]
+struct Block {
+    header: Header,
+    transactions: Vec<String>,
+}
+
+fn block() -> Block {
+    Block {
+        header: Header {
+            height: 10,
+            parent: [7; 32],
+            timestamp: 1_000,
+        },
+        transactions: vec!["a".to_string(), "bc".to_string()],
+    }
+}
+
+#[test]
+fn test_counts_of_a_struct() {
+    let mut writer = InstrumentedWriter::new(Vec::new());
+    block().serialize(&mut writer).unwrap();
+    // 8 + 32 + 8 bytes of the header, 4 bytes of length and 5 + 6 bytes of strings.
+    assert_eq!(writer.bytes_written(), 63);
+    // 3 writes of the header, 1 of the length and 2 per string.
+    assert_eq!(writer.calls(), 8);
+    assert_eq!(writer.into_inner(), block().try_to_vec().unwrap());
+}
+
+#[test]
+fn test_counts_per_field() {
+    let block = block();
+    let mut writer = InstrumentedWriter::new(Vec::new());
+    block.header.serialize(&mut writer).unwrap();
+    assert_eq!((writer.bytes_written(), writer.calls()), (48, 3));
+    writer.reset();
+    block.transactions.serialize(&mut writer).unwrap();
+    assert_eq!((writer.bytes_written(), writer.calls()), (15, 5));
+    assert_eq!(writer.get_ref().len(), 63);
+}
+
+#[test]
+fn test_slices_are_written_in_chunks() {
+    let mut writer = InstrumentedWriter::new(Vec::new());
+    vec![0u8; 5000].serialize(&mut writer).unwrap();
+    assert_eq!((writer.bytes_written(), writer.calls()), (5004, 2));
+
+    // 1024-byte chunks of 256 integers.
+    let mut writer = InstrumentedWriter::new(Vec::new());
+    vec![1u32; 600].serialize(&mut writer).unwrap();
+    assert_eq!((writer.bytes_written(), writer.calls()), (2404, 4));
+}
+
+#[test]
+fn test_buffered_writer_batches_the_writes() {
+    let mut writer = InstrumentedWriter::new(Vec::new());
+    borsh::to_writer_buffered(&mut writer, &block()).unwrap();
+    assert_eq!((writer.bytes_written(), writer.calls()), (63, 1));
+}
+
+#[test]
+fn test_write_counts_the_bytes_accepted() {
+    let mut buf = [0u8; 3];
+    let mut writer = InstrumentedWriter::new(&mut buf[..]);
+    assert_eq!(
+        borsh::maybestd::io::Write::write(&mut writer, &[1, 2, 3, 4]).unwrap(),
+        3
+    );
+    assert_eq!((writer.bytes_written(), writer.calls()), (3, 1));
+    assert!(1u8.serialize(&mut writer).is_err());
+    assert_eq!(writer.bytes_written(), 3);
+}
@@ -0,0 +1,89 @@
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::{BorshSchemaContainer, Definition, Value};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize, InvertedOption};
+
+macro_rules! map(
+    () => { HashMap::new() };
+    { $($key:expr => $value:expr),+ } => {
+        {
+            let mut m = HashMap::new();
+            $(
+                m.insert($key.to_string(), $value);
+            )+
+            m
+        }
+     };
+);
+
+#[test]
+fn test_tags_are_inverted() {
+    let none = InvertedOption::<u32>(None).try_to_vec().unwrap();
+    assert_eq!(none, vec![1]);
+    let some = InvertedOption(Some(5u32)).try_to_vec().unwrap();
+    assert_eq!(some, vec![0, 5, 0, 0, 0]);
+    assert_eq!(
+        InvertedOption::<u32>::try_from_slice(&none).unwrap(),
+        InvertedOption(None)
+    );
+    assert_eq!(
+        InvertedOption::<u32>::try_from_slice(&some).unwrap(),
+        InvertedOption(Some(5))
+    );
+    // The same values as a plain `Option` have the other tags.
+    assert_eq!(None::<u32>.try_to_vec().unwrap(), vec![0]);
+    assert_eq!(Some(5u32).try_to_vec().unwrap(), vec![1, 5, 0, 0, 0]);
+}
+
+#[test]
+fn test_invalid_tag() {
+    assert_eq!(
+        InvertedOption::<u8>::try_from_slice(&[2, 0])
+            .unwrap_err()
+            .to_string(),
+        "Invalid InvertedOption representation: 2. The first byte must be 0 or 1"
+    );
+}
+
+#[test]
+fn test_conversions() {
+    let value: InvertedOption<String> = Some("a".to_string()).into();
+    assert_eq!(value.as_deref(), Some("a"));
+    assert_eq!(Option::from(value), Some("a".to_string()));
+}
+
+#[test]
+fn test_schema() {
+    assert_eq!("InvertedOption<u64>", InvertedOption::<u64>::declaration());
+    let mut defs = Default::default();
+    InvertedOption::<u64>::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        map! {
+            "InvertedOption<u64>" => Definition::Enum {
+                variants: vec![
+                    ("Some".to_string(), "u64".to_string()),
+                    ("None".to_string(), "nil".to_string()),
+                ]
+            }
+        },
+        defs
+    );
+}
+
+#[test]
+fn test_schema_decodes_value() {
+    let container: BorshSchemaContainer = InvertedOption::<u8>::schema_container();
+    assert_eq!(
+        container.value_from_slice(&[0, 9]).unwrap(),
+        Value::Enum {
+            variant: "Some".to_string(),
+            value: Box::new(Value::U8(9)),
+        }
+    );
+    assert_eq!(
+        container.value_from_slice(&[1]).unwrap(),
+        Value::Enum {
+            variant: "None".to_string(),
+            value: Box::new(Value::Nil),
+        }
+    );
+}
@@ -0,0 +1,56 @@
+use borsh::maybestd::collections::{BTreeMap, HashMap};
+use borsh::{is_canonical, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Record {
+    id: u64,
+    name: String,
+    attributes: HashMap<String, u32>,
+}
+
+#[test]
+fn test_serialized_value_is_canonical() {
+    let mut attributes = HashMap::new();
+    attributes.insert("b".to_string(), 2);
+    attributes.insert("a".to_string(), 1);
+    attributes.insert("c".to_string(), 3);
+    let record = Record {
+        id: 1,
+        name: "record".to_string(),
+        attributes,
+    };
+    let data = record.try_to_vec().unwrap();
+    assert!(is_canonical::<Record>(&data));
+}
+
+#[test]
+fn test_unsorted_map_is_not_canonical() {
+    let data = vec![("b".to_string(), 2u32), ("a".to_string(), 1u32)]
+        .try_to_vec()
+        .unwrap();
+    assert_eq!(
+        HashMap::<String, u32>::try_from_slice(&data).is_ok(),
+        cfg!(not(feature = "canonical"))
+    );
+    assert!(!is_canonical::<HashMap<String, u32>>(&data));
+    assert!(!is_canonical::<BTreeMap<String, u32>>(&data));
+
+    let sorted = vec![("a".to_string(), 1u32), ("b".to_string(), 2u32)]
+        .try_to_vec()
+        .unwrap();
+    assert!(is_canonical::<HashMap<String, u32>>(&sorted));
+}
+
+#[test]
+fn test_duplicate_keys_are_not_canonical() {
+    let data = vec![(1u8, 1u8), (1u8, 2u8)].try_to_vec().unwrap();
+    assert!(!is_canonical::<HashMap<u8, u8>>(&data));
+}
+
+#[test]
+fn test_invalid_input_is_not_canonical() {
+    assert!(!is_canonical::<u32>(&[1, 0, 0]));
+    assert!(!is_canonical::<u32>(&[1, 0, 0, 0, 0]));
+    assert!(!is_canonical::<bool>(&[2]));
+    assert!(is_canonical::<u32>(&[1, 0, 0, 0]));
+}
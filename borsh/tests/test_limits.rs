@@ -0,0 +1,268 @@
+use borsh::de::{Limit, LimitExceeded, Limits};
+use borsh::maybestd::collections::{BTreeMap, HashMap};
+use borsh::maybestd::io::ErrorKind;
+use borsh::{BorshDeserialize, BorshSerialize, LossyString, RawString};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Message {
+    sender: String,
+    tags: Vec<u16>,
+    attributes: BTreeMap<u8, Vec<u8>>,
+}
+
+fn message() -> Message {
+    Message {
+        sender: "alice".to_string(),
+        tags: vec![1, 2, 3],
+        attributes: vec![(1, vec![10, 11]), (2, vec![])].into_iter().collect(),
+    }
+}
+
+fn exceeded<T>(data: &[u8], limits: &Limits) -> LimitExceeded
+where
+    T: BorshDeserialize + core::fmt::Debug,
+{
+    let err = T::try_from_slice_limited(data, limits).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    err.get_ref()
+        .unwrap()
+        .downcast_ref::<LimitExceeded>()
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn test_default_is_unlimited() {
+    assert_eq!(Limits::default(), Limits::UNLIMITED);
+    let data = message().try_to_vec().unwrap();
+    assert_eq!(
+        Message::try_from_slice_limited(&data, &Limits::default()).unwrap(),
+        message()
+    );
+}
+
+#[test]
+fn test_compliant_payload_decodes_identically() {
+    let data = message().try_to_vec().unwrap();
+    let limits = Limits {
+        max_total_len: data.len(),
+        max_sequence_elements: 3,
+        max_string_bytes: 5,
+        max_depth: 2,
+        max_zst_sequence_elements: 0,
+    };
+    assert_eq!(
+        Message::try_from_slice_limited(&data, &limits).unwrap(),
+        Message::try_from_slice(&data).unwrap()
+    );
+}
+
+#[test]
+fn test_max_total_len() {
+    let data = message().try_to_vec().unwrap();
+    let limits = Limits {
+        max_total_len: data.len() - 1,
+        ..Limits::default()
+    };
+    assert_eq!(
+        exceeded::<Message>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::TotalLen,
+            actual: data.len(),
+            max: data.len() - 1,
+        }
+    );
+}
+
+#[test]
+fn test_max_sequence_elements() {
+    let limits = Limits {
+        max_sequence_elements: 2,
+        ..Limits::default()
+    };
+    let data = message().try_to_vec().unwrap();
+    assert_eq!(
+        exceeded::<Message>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::SequenceElements,
+            actual: 3,
+            max: 2,
+        }
+    );
+
+    let map: HashMap<u8, u8> = vec![(1, 1), (2, 2), (3, 3)].into_iter().collect();
+    let data = map.try_to_vec().unwrap();
+    assert_eq!(
+        exceeded::<HashMap<u8, u8>>(&data, &limits).limit,
+        Limit::SequenceElements
+    );
+}
+
+#[test]
+fn test_max_sequence_elements_fails_before_reading_elements() {
+    // Only the length prefix of a huge vector.
+    let data = u32::MAX.try_to_vec().unwrap();
+    let limits = Limits {
+        max_sequence_elements: 1024,
+        ..Limits::default()
+    };
+    assert_eq!(
+        exceeded::<Vec<u64>>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::SequenceElements,
+            actual: u32::MAX as usize,
+            max: 1024,
+        }
+    );
+}
+
+#[test]
+fn test_max_string_bytes() {
+    let limits = Limits {
+        max_string_bytes: 4,
+        ..Limits::default()
+    };
+    let data = message().try_to_vec().unwrap();
+    assert_eq!(
+        exceeded::<Message>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::StringBytes,
+            actual: 5,
+            max: 4,
+        }
+    );
+    // The strings that are not validated as UTF-8 have the same limit.
+    let data = "hello".to_string().try_to_vec().unwrap();
+    for result in [
+        LossyString::try_from_slice_limited(&data, &limits).map(|_| ()),
+        RawString::try_from_slice_limited(&data, &limits).map(|_| ()),
+    ] {
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Exceeded the max_string_bytes limit of 4: 5"
+        );
+    }
+    // Byte vectors are sequences, not strings.
+    let data = vec![0u8; 5].try_to_vec().unwrap();
+    assert_eq!(
+        Vec::<u8>::try_from_slice_limited(&data, &limits).unwrap(),
+        vec![0u8; 5]
+    );
+}
+
+#[test]
+fn test_max_depth() {
+    let value = vec![vec![vec![1u8]]];
+    let data = value.try_to_vec().unwrap();
+    let limits = Limits {
+        max_depth: 2,
+        ..Limits::default()
+    };
+    assert_eq!(
+        exceeded::<Vec<Vec<Vec<u8>>>>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::Depth,
+            actual: 3,
+            max: 2,
+        }
+    );
+    let limits = Limits {
+        max_depth: 3,
+        ..Limits::default()
+    };
+    assert_eq!(
+        Vec::<Vec<Vec<u8>>>::try_from_slice_limited(&data, &limits).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_max_depth_of_boxes() {
+    let value: Box<Box<Box<u8>>> = Box::new(Box::new(Box::new(7)));
+    let data = value.try_to_vec().unwrap();
+    let limits = Limits {
+        max_depth: 2,
+        ..Limits::default()
+    };
+    assert_eq!(
+        exceeded::<Box<Box<Box<u8>>>>(&data, &limits).limit,
+        Limit::Depth
+    );
+    let limits = Limits {
+        max_depth: 3,
+        ..Limits::default()
+    };
+    assert_eq!(
+        Box::<Box<Box<u8>>>::try_from_slice_limited(&data, &limits).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_limits_end_with_the_call() {
+    let data = message().try_to_vec().unwrap();
+    let limits = Limits {
+        max_string_bytes: 0,
+        ..Limits::default()
+    };
+    assert!(Message::try_from_slice_limited(&data, &limits).is_err());
+    assert_eq!(Message::try_from_slice(&data).unwrap(), message());
+}
+
+#[test]
+fn test_error_message_names_the_limit() {
+    let data = "hello".to_string().try_to_vec().unwrap();
+    let limits = Limits {
+        max_string_bytes: 4,
+        ..Limits::default()
+    };
+    assert_eq!(
+        String::try_from_slice_limited(&data, &limits)
+            .unwrap_err()
+            .to_string(),
+        "Exceeded the max_string_bytes limit of 4: 5"
+    );
+}
+
+#[test]
+fn test_max_zst_sequence_elements() {
+    let data = vec![(); 100_000].try_to_vec().unwrap();
+    assert_eq!(
+        Vec::<()>::try_from_slice_limited(&data, &Limits::default())
+            .unwrap()
+            .len(),
+        100_000
+    );
+    let limits = Limits {
+        max_zst_sequence_elements: 99_999,
+        ..Limits::default()
+    };
+    assert_eq!(
+        exceeded::<Vec<()>>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::ZstSequenceElements,
+            actual: 100_000,
+            max: 99_999,
+        }
+    );
+
+    let limits = Limits {
+        max_zst_sequence_elements: 2,
+        ..Limits::default()
+    };
+    let data = vec![(); 3].try_to_vec().unwrap();
+    assert_eq!(
+        exceeded::<Vec<()>>(&data, &limits),
+        LimitExceeded {
+            limit: Limit::ZstSequenceElements,
+            actual: 3,
+            max: 2,
+        }
+    );
+    // Only zero-sized elements are limited.
+    let data = vec![0u8; 3].try_to_vec().unwrap();
+    assert_eq!(
+        Vec::<u8>::try_from_slice_limited(&data, &limits).unwrap(),
+        vec![0; 3]
+    );
+}
@@ -1,5 +1,6 @@
 // Borsh macros should not collide with the local modules:
 // https://github.com/near/borsh-rs/issues/11
+#![allow(dead_code)] // Local structures are only used to check that the derives compile.
 mod std {}
 mod core {}
 
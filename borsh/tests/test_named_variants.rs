@@ -0,0 +1,99 @@
+#![allow(dead_code)] // Structures generated by the schema derive for the variants are never read.
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::{BorshSchema, Definition, Value};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+mod v1 {
+    use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+    #[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+    #[borsh(named_variants)]
+    pub enum Event {
+        Created { id: u32 },
+        Renamed(String),
+        Deleted,
+    }
+}
+
+mod v2 {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    /// `v1::Event` with the variants reordered and a new one inserted.
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    #[borsh(named_variants)]
+    pub enum Event {
+        Deleted,
+        Archived,
+        Renamed(String),
+        Created { id: u32 },
+    }
+}
+
+#[test]
+fn test_named_variants_roundtrip() {
+    let events = vec![
+        v1::Event::Created { id: 7 },
+        v1::Event::Renamed("x".to_string()),
+        v1::Event::Deleted,
+    ];
+    let data = events.try_to_vec().unwrap();
+    assert_eq!(Vec::<v1::Event>::try_from_slice(&data).unwrap(), events);
+
+    let created = v1::Event::Created { id: 7 }.try_to_vec().unwrap();
+    assert_eq!(
+        created,
+        vec![7, 0, 0, 0, b'C', b'r', b'e', b'a', b't', b'e', b'd', 7, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_named_variants_reordering() {
+    let old = vec![
+        v1::Event::Created { id: 7 },
+        v1::Event::Renamed("x".to_string()),
+        v1::Event::Deleted,
+    ]
+    .try_to_vec()
+    .unwrap();
+    assert_eq!(
+        Vec::<v2::Event>::try_from_slice(&old).unwrap(),
+        vec![
+            v2::Event::Created { id: 7 },
+            v2::Event::Renamed("x".to_string()),
+            v2::Event::Deleted,
+        ]
+    );
+
+    let new = v2::Event::Archived.try_to_vec().unwrap();
+    assert_eq!(
+        v1::Event::try_from_slice(&new).unwrap_err().to_string(),
+        "Unexpected variant name: \"Archived\""
+    );
+}
+
+#[test]
+fn test_named_variants_schema() {
+    let mut defs = HashMap::new();
+    v1::Event::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        defs["Event"],
+        Definition::NamedEnum {
+            variants: vec![
+                ("Created".to_string(), "EventCreated".to_string()),
+                ("Renamed".to_string(), "EventRenamed".to_string()),
+                ("Deleted".to_string(), "EventDeleted".to_string()),
+            ]
+        }
+    );
+
+    let data = v1::Event::Renamed("x".to_string()).try_to_vec().unwrap();
+    assert_eq!(
+        v1::Event::schema_container()
+            .value_from_slice(&data)
+            .unwrap(),
+        Value::Enum {
+            variant: "Renamed".to_string(),
+            value: Box::new(Value::TupleStruct(vec![Value::String("x".to_string())])),
+        }
+    );
+}
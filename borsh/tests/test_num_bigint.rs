@@ -0,0 +1,105 @@
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::{BorshSchema, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSerialize};
+use num_bigint::{BigInt, BigUint};
+
+fn around_u128() -> Vec<BigUint> {
+    let max = BigUint::from(u128::MAX);
+    vec![
+        BigUint::from(0u8),
+        BigUint::from(1u8),
+        BigUint::from(256u32),
+        &max - 1u8,
+        max.clone(),
+        &max + 1u8,
+        &max * &max,
+    ]
+}
+
+#[test]
+fn test_big_uint_roundtrip() {
+    for value in around_u128() {
+        let data = value.try_to_vec().unwrap();
+        assert_eq!(BigUint::try_from_slice(&data).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_big_uint_bytes() {
+    assert_eq!(BigUint::from(0u8).try_to_vec().unwrap(), [0, 0, 0, 0]);
+    assert_eq!(
+        BigUint::from(0x0102u32).try_to_vec().unwrap(),
+        [2, 0, 0, 0, 2, 1]
+    );
+    let above_u128 = BigUint::from(u128::MAX) + 1u8;
+    let mut expected = vec![17, 0, 0, 0];
+    expected.extend_from_slice(&[0; 16]);
+    expected.push(1);
+    assert_eq!(above_u128.try_to_vec().unwrap(), expected);
+}
+
+#[test]
+fn test_big_int_roundtrip() {
+    for magnitude in around_u128() {
+        for value in [BigInt::from(magnitude.clone()), -BigInt::from(magnitude)] {
+            let data = value.try_to_vec().unwrap();
+            assert_eq!(BigInt::try_from_slice(&data).unwrap(), value);
+        }
+    }
+    assert_eq!(BigInt::from(0).try_to_vec().unwrap(), [0, 0, 0, 0, 0]);
+    assert_eq!(BigInt::from(-1).try_to_vec().unwrap(), [2, 1, 0, 0, 0, 1]);
+}
+
+#[test]
+fn test_trailing_zeros_rejected() {
+    let err = BigUint::try_from_slice(&[2, 0, 0, 0, 1, 0]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Magnitude of a big integer has trailing zero bytes"
+    );
+    assert!(BigUint::try_from_slice(&[1, 0, 0, 0, 0]).is_err());
+    assert!(BigInt::try_from_slice(&[1, 2, 0, 0, 0, 1, 0]).is_err());
+}
+
+#[test]
+fn test_non_canonical_sign_rejected() {
+    // -0
+    let err = BigInt::try_from_slice(&[2, 0, 0, 0, 0]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Sign of a big integer does not match its magnitude"
+    );
+    // +0
+    assert!(BigInt::try_from_slice(&[1, 0, 0, 0, 0]).is_err());
+    // Zero sign with a magnitude.
+    assert!(BigInt::try_from_slice(&[0, 1, 0, 0, 0, 1]).is_err());
+    assert_eq!(
+        BigInt::try_from_slice(&[3, 0, 0, 0, 0])
+            .unwrap_err()
+            .to_string(),
+        "Invalid BigInt representation: 3. The first byte must be 0, 1 or 2"
+    );
+}
+
+#[test]
+fn test_schema() {
+    let mut defs = HashMap::new();
+    BigInt::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        defs["BigUint"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![("magnitude_le".to_string(), "Vec<u8>".to_string())])
+        }
+    );
+    assert_eq!(
+        defs["BigInt"],
+        Definition::Enum {
+            variants: vec![
+                ("Zero".to_string(), "BigUint".to_string()),
+                ("Positive".to_string(), "BigUint".to_string()),
+                ("Negative".to_string(), "BigUint".to_string()),
+            ]
+        }
+    );
+    assert!(defs.contains_key("Vec<u8>"));
+}
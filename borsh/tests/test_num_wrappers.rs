@@ -0,0 +1,84 @@
+#![allow(clippy::incompatible_msrv)] // `Saturating<T>` requires Rust 1.74, see the `saturating` feature.
+use core::num::{Saturating, Wrapping};
+
+use borsh::schema::assert_transparent_wrappers_consistent;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Balances {
+    total: Saturating<u128>,
+    counter: Wrapping<u32>,
+    fee: Amount,
+    plain: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+struct PlainBalances {
+    total: u128,
+    counter: u32,
+    fee: u128,
+    plain: u64,
+}
+
+/// A fixed-point amount with 9 decimals.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Amount(u128);
+
+borsh::impl_borsh_schema_as!(Amount => u128);
+
+#[test]
+fn test_same_bytes_as_the_inner_value() {
+    let value = u128::MAX - 5;
+    assert_eq!(
+        Saturating(value).try_to_vec().unwrap(),
+        value.try_to_vec().unwrap()
+    );
+    assert_eq!(
+        Wrapping(-3i16).try_to_vec().unwrap(),
+        (-3i16).try_to_vec().unwrap()
+    );
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(
+        Saturating::<u128>::try_from_slice(&data).unwrap(),
+        Saturating(value)
+    );
+    assert_eq!(
+        Wrapping::<u128>::try_from_slice(&data).unwrap(),
+        Wrapping(value)
+    );
+}
+
+#[test]
+fn test_struct_mixing_wrapped_and_plain_fields() {
+    let balances = Balances {
+        total: Saturating(1_000_000),
+        counter: Wrapping(u32::MAX),
+        fee: Amount(1_500_000_000),
+        plain: 7,
+    };
+    let data = balances.try_to_vec().unwrap();
+    let plain = PlainBalances {
+        total: 1_000_000,
+        counter: u32::MAX,
+        fee: 1_500_000_000,
+        plain: 7,
+    };
+    assert_eq!(data, plain.try_to_vec().unwrap());
+    assert_eq!(Balances::try_from_slice(&data).unwrap(), balances);
+}
+
+#[test]
+fn test_schema_forwards_to_the_inner_type() {
+    assert_eq!(Saturating::<u128>::declaration(), "u128");
+    assert_eq!(Wrapping::<u32>::declaration(), "u32");
+    assert_eq!(
+        Wrapping::<Vec<u8>>::schema_container(),
+        Vec::<u8>::schema_container()
+    );
+    let container = Balances::schema_container();
+    assert_transparent_wrappers_consistent(&container);
+    assert_eq!(
+        container.definitions["Balances"],
+        PlainBalances::schema_container().definitions["PlainBalances"]
+    );
+}
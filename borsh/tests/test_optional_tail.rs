@@ -0,0 +1,127 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct RecordV1 {
+    id: u32,
+    name: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct RecordV2 {
+    id: u32,
+    name: String,
+    #[borsh(optional_tail)]
+    score: u64,
+    #[borsh(optional_tail = "default_tags")]
+    tags: Vec<String>,
+}
+
+fn default_tags() -> Vec<String> {
+    vec!["legacy".to_string()]
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Pair(u8, #[borsh(optional_tail)] Option<u16>);
+
+#[test]
+fn test_old_bytes_get_defaults() {
+    let old = RecordV1 {
+        id: 7,
+        name: "seven".to_string(),
+    }
+    .try_to_vec()
+    .unwrap();
+    assert_eq!(
+        RecordV2::try_from_slice(&old).unwrap(),
+        RecordV2 {
+            id: 7,
+            name: "seven".to_string(),
+            score: 0,
+            tags: default_tags(),
+        }
+    );
+    assert_eq!(Pair::try_from_slice(&[3]).unwrap(), Pair(3, None));
+}
+
+#[test]
+fn test_new_bytes_round_trip() {
+    let record = RecordV2 {
+        id: 7,
+        name: "seven".to_string(),
+        score: 70,
+        tags: vec!["new".to_string()],
+    };
+    let data = record.try_to_vec().unwrap();
+    // The optional fields are always written.
+    assert_eq!(
+        data,
+        (7u32, "seven", 70u64, vec!["new"]).try_to_vec().unwrap()
+    );
+    assert_eq!(RecordV2::try_from_slice(&data).unwrap(), record);
+
+    let pair = Pair(3, Some(4));
+    assert_eq!(
+        Pair::try_from_slice(&pair.try_to_vec().unwrap()).unwrap(),
+        pair
+    );
+}
+
+#[test]
+fn test_partial_tail() {
+    // A first revision of the struct that only added `score`.
+    let data = (7u32, "seven", 70u64).try_to_vec().unwrap();
+    let record = RecordV2::try_from_slice(&data).unwrap();
+    assert_eq!(record.score, 70);
+    assert_eq!(record.tags, default_tags());
+
+    // An optional field that starts but does not end is an error, not a default.
+    assert_eq!(
+        RecordV2::try_from_slice(&data[..data.len() - 1])
+            .unwrap_err()
+            .to_string(),
+        "Unexpected length of input"
+    );
+}
+
+#[test]
+fn test_tail_starting_with_a_collection() {
+    // An empty collection still writes its length, so it is told apart from a missing one.
+    let record = RecordV2 {
+        id: 1,
+        name: String::new(),
+        score: 0,
+        tags: vec![],
+    };
+    let data = record.try_to_vec().unwrap();
+    assert_eq!(RecordV2::try_from_slice(&data).unwrap(), record);
+    assert_eq!(
+        RecordV2::try_from_slice(&data[..data.len() - 4])
+            .unwrap()
+            .tags,
+        default_tags()
+    );
+}
+
+#[test]
+fn test_tail_followed_by_more_data() {
+    // In a `Vec`, the input does not end after the first old record, so its optional fields
+    // read the bytes of the next one.
+    let old = vec![
+        RecordV1 {
+            id: 1,
+            name: "a".to_string(),
+        },
+        RecordV1 {
+            id: 2,
+            name: "b".to_string(),
+        },
+    ]
+    .try_to_vec()
+    .unwrap();
+    assert_eq!(
+        Vec::<RecordV2>::try_from_slice(&old)
+            .unwrap_err()
+            .to_string(),
+        "Unexpected length of input"
+    );
+}
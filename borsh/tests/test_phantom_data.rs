@@ -0,0 +1,69 @@
+use std::marker::PhantomData;
+
+use borsh::schema::{Definition, Fields};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// Implements none of the Borsh traits.
+#[derive(PartialEq, Debug)]
+struct NonBorsh;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Unit<T>(PhantomData<T>);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Tagged<T> {
+    id: u32,
+    marker: PhantomData<T>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum Event<T> {
+    Empty(PhantomData<T>),
+    Id { id: u8, marker: PhantomData<T> },
+}
+
+#[test]
+fn test_phantom_data_is_not_serialized() {
+    let unit = Unit::<NonBorsh>(PhantomData);
+    assert!(unit.try_to_vec().unwrap().is_empty());
+    assert_eq!(Unit::<NonBorsh>::try_from_slice(&[]).unwrap(), unit);
+
+    let tagged = Tagged::<NonBorsh> {
+        id: 7,
+        marker: PhantomData,
+    };
+    assert_eq!(tagged.try_to_vec().unwrap(), 7u32.try_to_vec().unwrap());
+    assert_eq!(
+        Tagged::<NonBorsh>::try_from_slice(&[7, 0, 0, 0]).unwrap(),
+        tagged
+    );
+
+    let event = Event::<NonBorsh>::Id {
+        id: 3,
+        marker: PhantomData,
+    };
+    assert_eq!(event.try_to_vec().unwrap(), vec![1, 3]);
+    assert_eq!(Event::<NonBorsh>::try_from_slice(&[1, 3]).unwrap(), event);
+    assert_eq!(
+        Event::<NonBorsh>::Empty(PhantomData).try_to_vec().unwrap(),
+        vec![0]
+    );
+}
+
+#[test]
+fn test_phantom_data_is_not_in_schema() {
+    let container = Unit::<NonBorsh>::schema_container();
+    assert_eq!(
+        container.definitions["Unit"],
+        Definition::Struct {
+            fields: Fields::Empty
+        }
+    );
+    let container = Tagged::<NonBorsh>::schema_container();
+    assert_eq!(
+        container.definitions["Tagged"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![("id".to_string(), "u32".to_string())])
+        }
+    );
+}
@@ -1,3 +1,4 @@
+#![allow(clippy::legacy_numeric_constants)] // The tests predate `isize::MIN` and the like.
 use borsh::{BorshDeserialize, BorshSerialize};
 
 macro_rules! test_primitive {
@@ -19,3 +19,32 @@ fn test_arc_roundtrip() {
     let deserialized = Arc::<u8>::try_from_slice(&serialized).unwrap();
     assert_eq!(value, deserialized);
 }
+
+#[test]
+fn test_rc_schema_forwards_to_inner() {
+    use borsh::schema::{Definition, Fields};
+    use borsh::BorshSchema;
+
+    #[derive(BorshSerialize, BorshSchema)]
+    struct Config {
+        name: String,
+    }
+
+    #[derive(BorshSerialize, BorshSchema)]
+    struct Service {
+        config: Arc<Config>,
+        label: Rc<str>,
+    }
+
+    let container = Service::schema_container();
+    assert_eq!(
+        container.definitions["Service"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("config".to_string(), "Config".to_string()),
+                ("label".to_string(), "string".to_string()),
+            ])
+        }
+    );
+    assert!(container.definitions.contains_key("Config"));
+}
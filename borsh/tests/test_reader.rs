@@ -0,0 +1,88 @@
+use borsh::maybestd::io::{Error, ErrorKind, Read, Result};
+use borsh::{BorshDeserialize, BorshSerialize, Reader};
+
+/// A hand-written implementation that rejects values above 100 after reading them.
+#[derive(BorshSerialize, Debug)]
+struct Percent(u8);
+
+impl BorshDeserialize for Percent {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let value = u8::deserialize_reader(reader)?;
+        if value > 100 {
+            return Err(Error::new(ErrorKind::InvalidData, "Percent above 100"));
+        }
+        Ok(Percent(value))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct Entry {
+    id: u32,
+    name: String,
+    share: Percent,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct Table {
+    version: u8,
+    entries: Vec<Entry>,
+}
+
+fn table(share: u8) -> Vec<u8> {
+    Table {
+        version: 1,
+        entries: vec![
+            Entry {
+                id: 1,
+                name: "a".to_string(),
+                share: Percent(50),
+            },
+            Entry {
+                id: 2,
+                name: "bc".to_string(),
+                share: Percent(share),
+            },
+        ],
+    }
+    .try_to_vec()
+    .unwrap()
+}
+
+#[test]
+fn test_truncation_position() {
+    let data = table(50);
+    for len in 0..data.len() {
+        let mut reader = Reader::new(&data[..len]);
+        let err = reader.deserialize::<Table>().unwrap_err();
+        assert_eq!(err.position, len);
+        assert_eq!(err.error.kind(), ErrorKind::InvalidInput);
+    }
+    let mut reader = Reader::new(&data[..]);
+    reader.deserialize::<Table>().unwrap();
+    assert_eq!(reader.position(), data.len());
+}
+
+#[test]
+fn test_error_position_in_custom_impl() {
+    let data = table(101);
+    let mut reader = Reader::new(&data[..]);
+    let err = reader.deserialize::<Table>().unwrap_err();
+    // version, length, first entry (4 + 5 + 1), second entry's id and name (4 + 6) and its share.
+    assert_eq!(err.position, 1 + 4 + 10 + 10 + 1);
+    assert_eq!(err.position, data.len());
+    assert_eq!(err.to_string(), "Percent above 100 at byte 26");
+    let err: Error = err.into();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_read_helpers() {
+    let data = [7u8, 1, 2, 3];
+    let mut reader = Reader::with_position(&data[..], 10);
+    assert_eq!(reader.read_u8().unwrap(), 7);
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1, 2]);
+    assert_eq!(reader.position(), 13);
+    assert_eq!(reader.into_inner(), &[3]);
+}
@@ -0,0 +1,158 @@
+use borsh::de::Limits;
+use borsh::maybestd::io::{Read, Result, Write};
+use borsh::recursive::{self, Chain};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+const DEPTH: u32 = 100_000;
+
+#[derive(PartialEq, Debug)]
+struct Node {
+    value: u32,
+    label: String,
+    next: Option<Box<Node>>,
+}
+
+impl Chain for Node {
+    fn next(&self) -> Option<&Self> {
+        self.next.as_deref()
+    }
+
+    fn take_next(&mut self) -> Option<Box<Self>> {
+        self.next.take()
+    }
+
+    fn set_next(&mut self, next: Option<Box<Self>>) {
+        self.next = next;
+    }
+}
+
+impl BorshSerialize for Node {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        recursive::serialize_chain(self, writer, |node, writer| {
+            node.value.serialize(writer)?;
+            node.label.serialize(writer)
+        })
+    }
+}
+
+impl BorshDeserialize for Node {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        recursive::deserialize_chain(reader, |reader| {
+            Ok(Node {
+                value: u32::deserialize_reader(reader)?,
+                label: String::deserialize_reader(reader)?,
+                next: None,
+            })
+        })
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        recursive::drop_iteratively(self);
+    }
+}
+
+fn node(value: u32, next: Option<Node>) -> Node {
+    Node {
+        value,
+        label: value.to_string(),
+        next: next.map(Box::new),
+    }
+}
+
+/// The bytes of a chain of `depth` links, written without going through `Node`.
+fn chain_bytes(depth: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    for value in (0..depth).rev() {
+        value.serialize(&mut data).unwrap();
+        value.to_string().serialize(&mut data).unwrap();
+        (value != 0).serialize(&mut data).unwrap();
+    }
+    data
+}
+
+/// Runs `f` on a thread with a stack far too small for a recursive walk of the chain.
+fn with_small_stack<F: FnOnce() + Send + 'static>(f: F) {
+    std::thread::Builder::new()
+        .stack_size(128 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_same_bytes_as_nested_options() {
+    let chain = node(1, Some(node(2, Some(node(3, None)))));
+    let nested = (
+        1u32,
+        "1".to_string(),
+        Some((
+            2u32,
+            "2".to_string(),
+            Some((3u32, "3".to_string(), None::<()>)),
+        )),
+    );
+    let data = chain.try_to_vec().unwrap();
+    assert_eq!(data, nested.try_to_vec().unwrap());
+    assert_eq!(Node::try_from_slice(&data).unwrap(), chain);
+}
+
+#[test]
+fn test_deep_chain() {
+    with_small_stack(|| {
+        let data = chain_bytes(DEPTH);
+        let chain = Node::try_from_slice(&data).unwrap();
+
+        let mut link = Some(&chain);
+        let mut expected = DEPTH;
+        while let Some(current) = link {
+            expected -= 1;
+            assert_eq!(current.value, expected);
+            link = current.next();
+        }
+        assert_eq!(expected, 0);
+
+        assert_eq!(chain.try_to_vec().unwrap(), data);
+    });
+}
+
+#[test]
+fn test_deep_chain_built_by_hand() {
+    with_small_stack(|| {
+        let mut chain = node(0, None);
+        for value in 1..DEPTH {
+            chain = node(value, Some(chain));
+        }
+        assert_eq!(chain.try_to_vec().unwrap(), chain_bytes(DEPTH));
+    });
+}
+
+#[test]
+fn test_invalid_tag() {
+    let mut data = chain_bytes(3);
+    let last = data.len() - 1;
+    data[last] = 2;
+    assert_eq!(
+        Node::try_from_slice(&data).unwrap_err().to_string(),
+        "Invalid Option representation: 2. The first byte must be 0 or 1"
+    );
+    assert!(Node::try_from_slice(&chain_bytes(3)[..10]).is_err());
+}
+
+#[test]
+fn test_max_depth() {
+    let data = chain_bytes(1000);
+    let limits = |max_depth| Limits {
+        max_depth,
+        ..Limits::default()
+    };
+    let chain = Node::try_from_slice_limited(&data, &limits(1)).unwrap();
+    assert_eq!(chain.try_to_vec().unwrap(), data);
+
+    // The chain is one level below the `Vec`.
+    let data = vec![chain].try_to_vec().unwrap();
+    assert!(Vec::<Node>::try_from_slice_limited(&data, &limits(1)).is_err());
+    assert!(Vec::<Node>::try_from_slice_limited(&data, &limits(2)).is_ok());
+}
@@ -0,0 +1,51 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use borsh::maybestd::io::{Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// Implements neither `BorshSchema` nor the (de)serialization traits.
+#[derive(Default, PartialEq, Debug)]
+struct Opaque(u16);
+
+mod opaque {
+    use super::*;
+
+    pub fn serialize<W: Write>(value: &Opaque, writer: &mut W) -> Result<()> {
+        value.0.serialize(writer)
+    }
+
+    pub fn deserialize<R: Read>(reader: &mut R) -> Result<Opaque> {
+        Ok(Opaque(u16::deserialize_reader(reader)?))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+#[borsh(require_schema)]
+struct Account<T> {
+    id: u64,
+    inner: T,
+    #[borsh(skip)]
+    cache: Opaque,
+    #[borsh(with = "opaque", schema(declaration = "u16"))]
+    raw: Opaque,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+#[borsh(require_schema)]
+enum Message {
+    Ping,
+    Account(Account<String>),
+}
+
+#[test]
+fn test_require_schema() {
+    let message = Message::Account(Account {
+        id: 1,
+        inner: "a".to_string(),
+        cache: Opaque::default(),
+        raw: Opaque(2),
+    });
+    let data = message.try_to_vec().unwrap();
+    assert_eq!(Message::try_from_slice(&data).unwrap(), message);
+    assert!(Message::schema_container().value_from_slice(&data).is_ok());
+}
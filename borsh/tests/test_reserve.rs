@@ -0,0 +1,72 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Batch {
+    id: u32,
+    #[borsh(reserve = 5000)]
+    amounts: Vec<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct PlainBatch {
+    id: u32,
+    amounts: Vec<u64>,
+}
+
+#[test]
+fn test_reserve_does_not_change_the_bytes() {
+    let batch = Batch {
+        id: 7,
+        amounts: (0..3000).collect(),
+    };
+    let plain = PlainBatch {
+        id: 7,
+        amounts: (0..3000).collect(),
+    };
+    let data = batch.try_to_vec().unwrap();
+    assert_eq!(data, plain.try_to_vec().unwrap());
+    assert_eq!(Batch::try_from_slice(&data).unwrap(), batch);
+}
+
+#[test]
+fn test_capacity_is_reserved_up_to_the_length() {
+    let batch = Batch {
+        id: 1,
+        amounts: (0..8000).collect(),
+    };
+    let data = batch.try_to_vec().unwrap();
+    assert!(Batch::try_from_slice(&data).unwrap().amounts.capacity() >= 5000);
+
+    // Never more than the length prefix claims.
+    let batch = Batch {
+        id: 2,
+        amounts: (0..3000).collect(),
+    };
+    let data = batch.try_to_vec().unwrap();
+    let amounts = Batch::try_from_slice(&data).unwrap().amounts;
+    assert!(amounts.capacity() >= 3000);
+    assert_eq!(amounts.len(), 3000);
+}
+
+#[test]
+fn test_length_prefix_beyond_the_input_is_not_trusted() {
+    // Claims `u32::MAX` elements but has none.
+    let mut data = 1u32.try_to_vec().unwrap();
+    data.extend_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Batch::try_from_slice(&data).is_err());
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Generic<T> {
+    #[borsh(reserve = 16)]
+    items: Vec<T>,
+}
+
+#[test]
+fn test_generic_elements() {
+    let value = Generic {
+        items: vec!["a".to_string(), "b".to_string()],
+    };
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(Generic::<String>::try_from_slice(&data).unwrap(), value);
+}
@@ -0,0 +1,92 @@
+use core::str::FromStr;
+
+use borsh::schema::{BorshSchema, Definition, Fields, Value};
+use borsh::{BorshDeserialize, BorshSerialize};
+use rust_decimal::Decimal;
+
+fn roundtrip(value: Decimal) {
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, value.serialize());
+    let actual = Decimal::try_from_slice(&data).unwrap();
+    assert_eq!(actual.serialize(), value.serialize());
+    assert_eq!(actual.scale(), value.scale());
+    assert_eq!(actual.is_sign_negative(), value.is_sign_negative());
+}
+
+#[test]
+fn test_positive() {
+    let value = Decimal::from_str("1234.5600").unwrap();
+    roundtrip(value);
+    let data = value.try_to_vec().unwrap();
+    // The scale is in the third byte of the flags, the mantissa follows.
+    assert_eq!(data[..4], [0, 0, 4, 0]);
+    assert_eq!(data[4..8], 12_345_600u32.to_le_bytes());
+    assert_eq!(data[8..], [0; 8]);
+}
+
+#[test]
+fn test_negative() {
+    let value = Decimal::from_str("-0.001").unwrap();
+    roundtrip(value);
+    assert_eq!(value.try_to_vec().unwrap()[3], 0x80);
+    roundtrip(Decimal::MIN);
+}
+
+#[test]
+fn test_high_scale() {
+    roundtrip(Decimal::from_i128_with_scale(
+        -123_456_789_012_345_678_901_234_567,
+        28,
+    ));
+    roundtrip(Decimal::from_i128_with_scale(1, 28));
+    roundtrip(Decimal::MAX);
+}
+
+#[test]
+fn test_trailing_zeros_and_negative_zero_are_kept() {
+    let value = Decimal::from_str("1.50").unwrap();
+    let actual = Decimal::try_from_slice(&value.try_to_vec().unwrap()).unwrap();
+    assert_eq!(actual.to_string(), "1.50");
+
+    let mut negative_zero = Decimal::ZERO;
+    negative_zero.set_sign_negative(true);
+    roundtrip(negative_zero);
+}
+
+#[test]
+fn test_invalid_flags() {
+    let mut data = Decimal::ONE.try_to_vec().unwrap();
+    data[0] = 1;
+    assert!(Decimal::try_from_slice(&data).is_err());
+
+    let mut data = Decimal::ONE.try_to_vec().unwrap();
+    data[2] = 29;
+    assert!(Decimal::try_from_slice(&data).is_err());
+}
+
+#[test]
+fn test_schema() {
+    let container = Decimal::schema_container();
+    assert_eq!(container.declaration, "Decimal");
+    assert_eq!(
+        container.definitions["Decimal"],
+        Definition::Struct {
+            fields: Fields::NamedFields(
+                ["flags", "lo", "mid", "hi"]
+                    .iter()
+                    .map(|field| (field.to_string(), "u32".to_string()))
+                    .collect()
+            )
+        }
+    );
+    let data = Decimal::from_str("-2.5").unwrap().try_to_vec().unwrap();
+    assert_eq!(
+        container.deserialize_value(&mut data.as_slice()).unwrap(),
+        Value::Struct(vec![
+            ("flags".to_string(), Value::U32(0x8001_0000)),
+            ("lo".to_string(), Value::U32(25)),
+            ("mid".to_string(), Value::U32(0)),
+            ("hi".to_string(), Value::U32(0)),
+        ])
+    );
+}
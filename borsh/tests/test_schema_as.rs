@@ -0,0 +1,32 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::BorshSchema;
+
+struct AccountId(u64);
+
+struct Bitmap {
+    words: Vec<u64>,
+}
+
+borsh::impl_borsh_schema_as!(AccountId => u64, Bitmap => Vec<u64>);
+
+#[derive(borsh::BorshSchema)]
+struct Account {
+    id: AccountId,
+    permissions: Bitmap,
+}
+
+#[test]
+fn test_schema_as_primitive() {
+    assert_eq!(AccountId::declaration(), "u64");
+    let mut defs = HashMap::new();
+    AccountId::add_definitions_recursively(&mut defs);
+    assert!(defs.is_empty());
+}
+
+#[test]
+fn test_schema_as_container() {
+    assert_eq!(Bitmap::schema_container(), Vec::<u64>::schema_container());
+    let container = Account::schema_container();
+    assert!(container.definitions.contains_key("Vec<u64>"));
+}
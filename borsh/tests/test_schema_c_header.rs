@@ -0,0 +1,95 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+use borsh::schema::{to_c_header, BorshSchema};
+use borsh::BorshSchema as BorshSchemaMacro;
+
+#[derive(BorshSchemaMacro)]
+struct Header {
+    version: u8,
+    flags: u16,
+    hash: [u8; 32],
+    amount: u128,
+    ratio: f64,
+    valid: bool,
+}
+
+#[derive(BorshSchemaMacro)]
+struct Coord(i32, i32);
+
+#[derive(BorshSchemaMacro)]
+enum Shape {
+    Empty,
+    Polygon(Vec<Coord>),
+    Circle { center: Coord, radius: Option<u32> },
+}
+
+#[derive(BorshSchemaMacro)]
+struct Scene {
+    name: String,
+    shapes: [Shape; 2],
+    grid: [[u8; 3]; 2],
+    pair: (u64, i8),
+}
+
+#[test]
+fn test_fixed_size_struct() {
+    assert_eq!(
+        to_c_header(&Header::schema_container()),
+        "\
+/* Generated by borsh::schema::to_c_header. */
+#include <stdint.h>
+
+typedef struct Header Header;
+
+/* Header */
+struct Header {
+    uint8_t version;
+    uint16_t flags;
+    uint8_t hash[32];
+    uint8_t amount[16];
+    double ratio;
+    uint8_t valid;
+};
+"
+    );
+}
+
+#[test]
+fn test_enums_and_sequences() {
+    let header = to_c_header(&Scene::schema_container());
+    assert_eq!(header, to_c_header(&Scene::schema_container()));
+    assert!(header.contains(
+        "\
+/* Vec<Coord> */
+struct VecCoord {
+    uint32_t len;
+    Coord *items; /* serialized inline after len */
+};"
+    ));
+    assert!(header.contains(
+        "\
+/* Shape */
+struct Shape {
+    uint8_t tag; /* 0: Empty, 1: Polygon, 2: Circle */
+    union {
+        ShapePolygon Polygon;
+        ShapeCircle Circle;
+    } value; /* the variant of tag */
+};"
+    ));
+    assert!(header.contains(
+        "\
+/* Scene */
+struct Scene {
+    BorshString name;
+    Shape shapes[2];
+    uint8_t grid[2][3];
+    TupleU64I8 pair;
+};"
+    ));
+    // Definitions come after the definitions they contain by value.
+    let position = |name: &str| header.find(&format!("struct {} {{", name)).unwrap();
+    assert!(position("Coord") < position("ShapeCircle"));
+    assert!(position("ShapeCircle") < position("Shape"));
+    assert!(position("Shape") < position("Scene"));
+    assert!(!header.contains("Array"));
+}
@@ -0,0 +1,133 @@
+#![allow(dead_code)] // Local and generated structures are only serialized.
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use borsh::schema::{generate_rust, BorshSchema};
+use borsh::{BorshDeserialize, BorshSchema as BorshSchemaMacro, BorshSerialize, SeqWithLen};
+
+mod generated {
+    include!("codegen/generated.rs");
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+struct Position(i32, i32);
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+enum Action {
+    Idle,
+    Move { to: Position, speed: Option<u16> },
+    Say(String, [u8; 2]),
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+#[borsh(named_variants)]
+enum Mode {
+    Fast,
+    Safe(u8),
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+enum Priority {
+    #[borsh(tag = 10)]
+    Low,
+    #[borsh(tag = 11)]
+    High(u8),
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+struct World {
+    actions: Vec<Action>,
+    names: HashMap<u32, String>,
+    scores: BTreeMap<String, (u8, i64)>,
+    last: Result<Position, String>,
+    mode: Mode,
+    short: SeqWithLen<u8, bool>,
+    queue: VecDeque<u16>,
+    priority: Priority,
+    r#type: u128,
+}
+
+fn world() -> World {
+    let mut names = HashMap::new();
+    names.insert(7, "seven".to_string());
+    names.insert(3, "three".to_string());
+    let mut scores = BTreeMap::new();
+    scores.insert("a".to_string(), (1, -1));
+    World {
+        actions: vec![
+            Action::Idle,
+            Action::Move {
+                to: Position(1, -2),
+                speed: Some(3),
+            },
+            Action::Say("hi".to_string(), [4, 5]),
+        ],
+        names,
+        scores,
+        last: Err("none".to_string()),
+        mode: Mode::Safe(6),
+        short: SeqWithLen::new(vec![true, false]),
+        queue: VecDeque::from(vec![8, 9]),
+        priority: Priority::High(2),
+        r#type: u128::MAX,
+    }
+}
+
+#[test]
+fn test_generated_code() {
+    assert_eq!(
+        generate_rust(&World::schema_container()),
+        include_str!("codegen/generated.rs")
+    );
+}
+
+#[test]
+fn test_generated_types_have_the_same_schema() {
+    assert_eq!(
+        generated::World::schema_container(),
+        World::schema_container()
+    );
+}
+
+#[test]
+fn test_generated_types_roundtrip() {
+    let data = world().try_to_vec().unwrap();
+    let decoded = generated::World::try_from_slice(&data).unwrap();
+    assert_eq!(decoded.try_to_vec().unwrap(), data);
+}
+
+#[test]
+fn test_undefined_declarations_are_listed() {
+    use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+
+    let mut container = BorshSchemaContainer {
+        declaration: "Wrapper".to_string(),
+        definitions: HashMap::new(),
+    };
+    container.definitions.insert(
+        "Wrapper".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["Opaque<u8>".to_string(), "Rest".to_string()]),
+        },
+    );
+    container.definitions.insert(
+        "Rest".to_string(),
+        Definition::Sequence {
+            length_width: 0,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "u8".to_string(),
+        },
+    );
+    assert_eq!(
+        generate_rust(&container),
+        "\
+// Generated by borsh::schema::generate_rust.
+// Types to define by hand:
+// - Opaque<u8> as OpaqueU8
+// - Rest as Rest
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct Wrapper(pub OpaqueU8, pub Rest);
+"
+    );
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use borsh::{BorshSchema, BorshSerialize};
+
+/// A container with many definitions that refer to the same long declarations.
+fn large_container() -> BorshSchemaContainer {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Tuple<u64, string>".to_string(),
+        Definition::Tuple {
+            elements: vec!["u64".to_string(), "string".to_string()],
+        },
+    );
+    definitions.insert(
+        "Vec<Tuple<u64, string>>".to_string(),
+        Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "Tuple<u64, string>".to_string(),
+        },
+    );
+    for i in 0..200 {
+        let previous = if i == 0 {
+            "u8".to_string()
+        } else {
+            format!("Record{}", i - 1)
+        };
+        definitions.insert(
+            format!("Record{}", i),
+            Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("entry".to_string(), "Tuple<u64, string>".to_string()),
+                    ("entries".to_string(), "Vec<Tuple<u64, string>>".to_string()),
+                    ("previous".to_string(), previous),
+                ]),
+            },
+        );
+        definitions.insert(
+            format!("Event{}", i),
+            Definition::Enum {
+                variants: vec![
+                    ("Created".to_string(), format!("Record{}", i)),
+                    ("Listed".to_string(), "Vec<Tuple<u64, string>>".to_string()),
+                    ("Empty".to_string(), "nil".to_string()),
+                ],
+            },
+        );
+    }
+    definitions.insert(
+        "Option<Record199>".to_string(),
+        Definition::Option {
+            inner: "Record199".to_string(),
+        },
+    );
+    BorshSchemaContainer {
+        declaration: "Option<Record199>".to_string(),
+        definitions,
+    }
+}
+
+#[test]
+fn test_large_container_roundtrip() {
+    let container = large_container();
+    let compact = container.to_compact_bytes().unwrap();
+    let regular = container.try_to_vec().unwrap();
+    assert!(
+        compact.len() * 4 < regular.len() * 3,
+        "compact: {}, regular: {}",
+        compact.len(),
+        regular.len()
+    );
+    assert_eq!(
+        BorshSchemaContainer::from_compact_bytes(&compact).unwrap(),
+        container
+    );
+}
+
+#[test]
+fn test_compact_bytes_are_deterministic() {
+    let compact = large_container().to_compact_bytes().unwrap();
+    for _ in 0..5 {
+        assert_eq!(large_container().to_compact_bytes().unwrap(), compact);
+    }
+}
+
+#[test]
+fn test_derived_schema_roundtrip() {
+    let container = BorshSchemaContainer::schema_container();
+    let compact = container.to_compact_bytes().unwrap();
+    assert_eq!(
+        BorshSchemaContainer::from_compact_bytes(&compact).unwrap(),
+        container
+    );
+}
+
+#[test]
+fn test_primitive_without_definitions() {
+    let container = u64::schema_container();
+    let compact = container.to_compact_bytes().unwrap();
+    assert_eq!(
+        BorshSchemaContainer::from_compact_bytes(&compact).unwrap(),
+        container
+    );
+}
+
+#[test]
+fn test_invalid_index() {
+    // One declaration, the container's declaration at index 1 and no definitions.
+    let data = (vec!["u8"], 1u32, 0u32).try_to_vec().unwrap();
+    assert_eq!(
+        BorshSchemaContainer::from_compact_bytes(&data)
+            .unwrap_err()
+            .to_string(),
+        "Declaration index is out of range"
+    );
+}
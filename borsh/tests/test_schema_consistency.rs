@@ -0,0 +1,218 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use core::num::Wrapping;
+
+use borsh::bytes::FixedBytes;
+use borsh::maybestd::borrow::Cow;
+use borsh::maybestd::collections::{
+    BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque,
+};
+use borsh::maybestd::io::{Result, Write};
+use borsh::schema::{check_consistency, ConsistencyError, Declaration, Definition, Fields};
+use borsh::{BorshSchema, BorshSerialize};
+
+macro_rules! check {
+    ($($value: expr),* $(,)?) => {
+        $(
+            if let Err(err) = check_consistency(&$value) {
+                panic!("{}: {}", stringify!($value), err);
+            }
+        )*
+    };
+}
+
+#[test]
+fn test_primitives() {
+    check!(
+        (),
+        true,
+        0u8,
+        u16::MAX,
+        u32::MAX,
+        u64::MAX,
+        u128::MAX,
+        -1i8,
+        i16::MIN,
+        i32::MIN,
+        i64::MIN,
+        i128::MIN,
+        1.5f32,
+        -2.5f64,
+        "string".to_string(),
+        String::new(),
+        Wrapping(7u16),
+    );
+}
+
+#[test]
+fn test_options_and_results() {
+    check!(
+        None::<u64>,
+        Some(5u64),
+        Some(Some(false)),
+        Some(None::<String>),
+        Ok::<u8, String>(1),
+        Err::<u8, String>("error".to_string()),
+        Box::new(Some(1i16)),
+        Cow::Borrowed("borrowed"),
+    );
+}
+
+#[test]
+fn test_sequences() {
+    check!(
+        Vec::<u8>::new(),
+        vec![1u32, 2, 3],
+        vec![vec![String::new()], vec![]],
+        vec![Some(1u8), None],
+        VecDeque::from(vec![1i64, -1]),
+        vec![1u16, 2].into_iter().collect::<LinkedList<_>>(),
+        vec![3u8, 1, 2].into_iter().collect::<BinaryHeap<_>>(),
+    );
+}
+
+#[test]
+fn test_arrays_and_tuples() {
+    check!(
+        [0u8; 0],
+        [1u32; 3],
+        [[true; 2]; 2],
+        [Some("a".to_string()), None],
+        FixedBytes::from([0xab; 32]),
+        (1u8,),
+        (1u8, "two".to_string(), [3u64; 2]),
+        (None::<u8>, vec![(1u8, 2u16)], ((), 4i32)),
+    );
+}
+
+#[test]
+fn test_maps_and_sets() {
+    let hash_map: HashMap<String, Vec<u8>> =
+        vec![("a".to_string(), vec![1]), (String::new(), vec![])]
+            .into_iter()
+            .collect();
+    let btree_map: BTreeMap<u32, Option<bool>> =
+        vec![(2, None), (1, Some(true))].into_iter().collect();
+    let hash_set: HashSet<u64> = vec![3, 1, 2].into_iter().collect();
+    let btree_set: BTreeSet<String> = vec!["b".to_string(), "a".to_string()].into_iter().collect();
+    check!(
+        hash_map,
+        btree_map,
+        hash_set,
+        btree_set,
+        HashMap::<u8, u8>::new(),
+        BTreeSet::<u8>::new(),
+    );
+}
+
+#[test]
+fn test_derived() {
+    #[derive(BorshSerialize, BorshSchema)]
+    struct Inner(u8, String);
+
+    #[derive(BorshSerialize, BorshSchema)]
+    enum Message {
+        Empty,
+        Text { text: String, inner: Option<Inner> },
+        Batch(Vec<Inner>, [u8; 2]),
+    }
+
+    check!(
+        Message::Empty,
+        Message::Text {
+            text: "hi".to_string(),
+            inner: Some(Inner(1, "x".to_string())),
+        },
+        Message::Batch(vec![Inner(2, String::new())], [3, 4]),
+    );
+}
+
+/// Serializes a `u64` but declares a `u32`.
+struct WiderThanDeclared(u64);
+
+impl BorshSerialize for WiderThanDeclared {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl BorshSchema for WiderThanDeclared {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        u32::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        u32::declaration()
+    }
+}
+
+/// Serializes a `u8` but declares a struct with a `u32`.
+struct NarrowerThanDeclared(u8);
+
+impl BorshSerialize for NarrowerThanDeclared {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl BorshSchema for NarrowerThanDeclared {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let fields = Fields::UnnamedFields(vec![u32::declaration()]);
+        Self::add_definition(
+            Self::declaration(),
+            Definition::Struct { fields },
+            definitions,
+        );
+    }
+
+    fn declaration() -> Declaration {
+        "NarrowerThanDeclared".to_string()
+    }
+}
+
+#[test]
+fn test_bytes_left_over() {
+    assert_eq!(
+        check_consistency(&WiderThanDeclared(1)).unwrap_err(),
+        ConsistencyError {
+            offset: 4,
+            declaration: "u32".to_string(),
+            path: vec!["u32".to_string()],
+            message: "The schema describes 4 of the 8 serialized bytes".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_divergence_is_located() {
+    #[derive(BorshSerialize, BorshSchema)]
+    struct Outer {
+        id: u16,
+        fields: Vec<NarrowerThanDeclared>,
+    }
+
+    let value = Outer {
+        id: 1,
+        fields: vec![NarrowerThanDeclared(2)],
+    };
+    let err = check_consistency(&value).unwrap_err();
+    // The `u16`, then the length of the vector.
+    assert_eq!(err.offset, 6);
+    assert_eq!(err.declaration, "u32");
+    assert_eq!(
+        err.path,
+        vec![
+            "Outer",
+            "Vec<NarrowerThanDeclared>",
+            "NarrowerThanDeclared",
+            "u32"
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{} at byte 6 of u32 (Outer > Vec<NarrowerThanDeclared> > NarrowerThanDeclared > u32)",
+            err.message
+        )
+    );
+}
@@ -0,0 +1,96 @@
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::*;
+
+fn tuple(elements: &[&str]) -> Definition {
+    Definition::Tuple {
+        elements: elements.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+fn sequence(elements: &str) -> Definition {
+    Definition::Sequence {
+        length_width: 4,
+        length_range: 0..=u32::MAX as u64,
+        elements: elements.to_string(),
+    }
+}
+
+#[test]
+fn test_identical_tuples_are_merged() {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Segment".to_string(),
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("start".to_string(), "(i32, i32)".to_string()),
+                ("end".to_string(), "Pair".to_string()),
+                ("path".to_string(), "Vec<Pair>".to_string()),
+                ("marks".to_string(), "Vec<(i32, i32)>".to_string()),
+            ]),
+        },
+    );
+    definitions.insert("(i32, i32)".to_string(), tuple(&["i32", "i32"]));
+    definitions.insert("Pair".to_string(), tuple(&["i32", "i32"]));
+    definitions.insert("Vec<Pair>".to_string(), sequence("Pair"));
+    definitions.insert("Vec<(i32, i32)>".to_string(), sequence("(i32, i32)"));
+    let mut container = BorshSchemaContainer {
+        declaration: "Segment".to_string(),
+        definitions,
+    };
+
+    container.dedup_structurally();
+    assert_eq!(container.definitions.len(), 3);
+    assert_eq!(container.declaration, "Segment");
+    assert_eq!(
+        container.definitions["Segment"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("start".to_string(), "(i32, i32)".to_string()),
+                ("end".to_string(), "(i32, i32)".to_string()),
+                ("path".to_string(), "Vec<(i32, i32)>".to_string()),
+                ("marks".to_string(), "Vec<(i32, i32)>".to_string()),
+            ]),
+        }
+    );
+    assert_eq!(
+        container.definitions["Vec<(i32, i32)>"],
+        sequence("(i32, i32)")
+    );
+}
+
+#[test]
+fn test_declaration_of_container_is_kept() {
+    let mut definitions = HashMap::new();
+    definitions.insert("Wrapper".to_string(), tuple(&["Pair", "(u8, u8)"]));
+    definitions.insert("Pair".to_string(), tuple(&["u8", "u8"]));
+    definitions.insert("(u8, u8)".to_string(), tuple(&["u8", "u8"]));
+    let mut container = BorshSchemaContainer {
+        declaration: "Pair".to_string(),
+        definitions,
+    };
+
+    container.dedup_structurally();
+    assert_eq!(container.declaration, "Pair");
+    assert_eq!(container.definitions["Wrapper"], tuple(&["Pair", "Pair"]));
+    assert!(!container.definitions.contains_key("(u8, u8)"));
+}
+
+#[test]
+fn test_different_field_names_are_kept() {
+    let mut definitions = HashMap::new();
+    for (declaration, field) in [("A", "a"), ("B", "b")] {
+        definitions.insert(
+            declaration.to_string(),
+            Definition::Struct {
+                fields: Fields::NamedFields(vec![(field.to_string(), "u8".to_string())]),
+            },
+        );
+    }
+    let mut container = BorshSchemaContainer {
+        declaration: "A".to_string(),
+        definitions,
+    };
+    let expected = container.clone();
+    container.dedup_structurally();
+    assert_eq!(container, expected);
+}
@@ -2,6 +2,7 @@
 use borsh::maybestd::collections::HashMap;
 use borsh::schema::*;
 use borsh::schema_helpers::{try_from_slice_with_schema, try_to_vec_with_schema};
+use borsh::{BorshDeserialize, BorshSerialize};
 
 macro_rules! map(
     () => { HashMap::new() };
@@ -207,3 +208,137 @@ pub fn complex_enum_generics() {
         defs
     );
 }
+
+#[test]
+pub fn generic_enum_with_builtin_params() {
+    #[derive(borsh::BorshSchema)]
+    enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+    let container = <Either<u64, String>>::schema_container();
+    assert_eq!(container.declaration, "Either<u64, string>");
+    assert_eq!(
+        map! {
+        "Either<u64, string>" => Definition::Enum {
+            variants: vec![
+            ("Left".to_string(), "EitherLeft<u64, string>".to_string()),
+            ("Right".to_string(), "EitherRight<u64, string>".to_string())
+            ]
+        },
+        "EitherLeft<u64, string>" => Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u64".to_string()])
+        },
+        "EitherRight<u64, string>" => Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["string".to_string()])
+        }
+        },
+        container
+            .definitions
+            .into_iter()
+            .filter(|(declaration, _)| declaration.starts_with("Either"))
+            .collect()
+    );
+    assert_eq!(
+        "Either<Vec<u8>, Option<string>>",
+        <Either<Vec<u8>, Option<String>>>::declaration()
+    );
+}
+
+#[test]
+pub fn skip_type_params() {
+    struct NotBorsh;
+
+    #[derive(borsh::BorshSchema)]
+    #[borsh(schema(skip_type_params))]
+    enum Handle<T> {
+        Empty,
+        Id(u32, core::marker::PhantomData<T>),
+    }
+    assert_eq!("Handle".to_string(), <Handle<NotBorsh>>::declaration());
+    let mut defs = Default::default();
+    <Handle<NotBorsh>>::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        map! {
+        "Handle" => Definition::Enum {
+            variants: vec![
+            ("Empty".to_string(), "HandleEmpty".to_string()),
+            ("Id".to_string(), "HandleId".to_string())
+            ]
+        },
+        "HandleEmpty" => Definition::Struct {fields: Fields::Empty},
+        "HandleId" => Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u32".to_string()])
+        }
+        },
+        defs
+    );
+}
+
+const BASE: u8 = 20;
+
+#[derive(borsh::BorshSchema, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[repr(u8)]
+enum Flag {
+    A = 1 << 3,
+    B,
+    C(u16) = BASE + 1,
+}
+
+#[test]
+pub fn const_expression_discriminants() {
+    let mut defs = Default::default();
+    Flag::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        map! {
+        "Flag" => Definition::TaggedEnum {
+            variants: vec![
+            (8, "A".to_string(), "FlagA".to_string()),
+            (9, "B".to_string(), "FlagB".to_string()),
+            (21, "C".to_string(), "FlagC".to_string())
+            ]
+        },
+        "FlagA" => Definition::Struct {fields: Fields::Empty},
+        "FlagB" => Definition::Struct {fields: Fields::Empty},
+        "FlagC" => Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u16".to_string()])
+        }
+        },
+        defs
+    );
+
+    // The tags of the schema are the ones written by serialization.
+    assert_eq!(Flag::B.try_to_vec().unwrap(), vec![9]);
+    let data = Flag::C(7).try_to_vec().unwrap();
+    assert_eq!(data, vec![21, 7, 0]);
+    let container = Flag::schema_container();
+    assert_eq!(
+        container.value_from_slice(&data).unwrap(),
+        Value::Enum {
+            variant: "C".to_string(),
+            value: Box::new(Value::TupleStruct(vec![Value::U16(7)])),
+        }
+    );
+    assert_eq!(
+        try_from_slice_with_schema::<Flag>(&try_to_vec_with_schema(&Flag::A).unwrap()).unwrap(),
+        Flag::A
+    );
+}
+
+#[test]
+pub fn positional_discriminants() {
+    #[derive(borsh::BorshSchema)]
+    enum Explicit {
+        A = 0,
+        B = 1,
+    }
+    assert_eq!(
+        Explicit::schema_container().definitions["Explicit"],
+        Definition::Enum {
+            variants: vec![
+                ("A".to_string(), "ExplicitA".to_string()),
+                ("B".to_string(), "ExplicitB".to_string())
+            ]
+        }
+    );
+}
@@ -0,0 +1,156 @@
+#![allow(dead_code)] // Local structures are only used for their schemas.
+use std::collections::HashMap;
+
+use borsh::schema::{
+    structural_difference, structurally_equal, BorshSchemaContainer, Definition,
+    StructuralDifference,
+};
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+struct Account {
+    owner: [u8; 32],
+    balance: u64,
+    tags: Vec<String>,
+}
+
+mod renamed {
+    use borsh::BorshSchema;
+
+    #[derive(BorshSchema)]
+    pub struct Wallet {
+        pub owner: [u8; 32],
+        pub balance: u64,
+        pub tags: Vec<String>,
+    }
+
+    #[derive(BorshSchema)]
+    pub struct Reordered {
+        pub balance: u64,
+        pub owner: [u8; 32],
+        pub tags: Vec<String>,
+    }
+
+    #[derive(BorshSchema)]
+    pub struct Narrower {
+        pub owner: [u8; 32],
+        pub balance: u32,
+        pub tags: Vec<String>,
+    }
+
+    #[derive(BorshSchema)]
+    pub struct Holder {
+        pub accounts: Vec<Wallet>,
+    }
+}
+
+#[derive(BorshSchema)]
+struct Holder {
+    accounts: Vec<Account>,
+}
+
+/// A linked list of `elements`, built by hand since derived types cannot be recursive.
+fn list(name: &str, elements: &str) -> BorshSchemaContainer {
+    let cons = format!("{}Cons", name);
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        name.to_string(),
+        Definition::Enum {
+            variants: vec![
+                ("Nil".to_string(), "nil".to_string()),
+                ("Cons".to_string(), cons.clone()),
+            ],
+        },
+    );
+    definitions.insert(
+        cons,
+        Definition::Tuple {
+            elements: vec![elements.to_string(), name.to_string()],
+        },
+    );
+    BorshSchemaContainer {
+        declaration: name.to_string(),
+        definitions,
+    }
+}
+
+#[test]
+fn test_renamed_struct_is_equal() {
+    assert!(structurally_equal(
+        &Account::schema_container(),
+        &renamed::Wallet::schema_container()
+    ));
+    assert!(structurally_equal(
+        &Holder::schema_container(),
+        &renamed::Holder::schema_container()
+    ));
+}
+
+#[test]
+fn test_reordered_fields_are_different() {
+    assert_eq!(
+        structural_difference(
+            &Account::schema_container(),
+            &renamed::Reordered::schema_container()
+        ),
+        Some(StructuralDifference {
+            path: "<root>".to_string(),
+            left: "Account".to_string(),
+            right: "Reordered".to_string(),
+            reason: r#"The field "owner" is "balance" in the other one"#.to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_difference_path() {
+    let difference = structural_difference(
+        &Holder::schema_container(),
+        &renamed::Holder::schema_container(),
+    );
+    assert_eq!(difference, None);
+
+    #[derive(BorshSchema)]
+    struct NarrowHolder {
+        accounts: Vec<renamed::Narrower>,
+    }
+    let difference = structural_difference(
+        &Holder::schema_container(),
+        &NarrowHolder::schema_container(),
+    )
+    .unwrap();
+    assert_eq!(
+        difference.to_string(),
+        "accounts[].balance: u64 and u32 differ: Different primitives"
+    );
+}
+
+#[test]
+fn test_recursive_types_terminate() {
+    assert!(structurally_equal(
+        &list("List", "u8"),
+        &list("Chain", "u8")
+    ));
+    assert_eq!(
+        structural_difference(&list("List", "u8"), &list("Chain", "u16"))
+            .unwrap()
+            .to_string(),
+        "Cons.0: u8 and u16 differ: Different primitives"
+    );
+}
+
+#[test]
+fn test_primitives_match_by_name() {
+    assert!(structurally_equal(
+        &u64::schema_container(),
+        &u64::schema_container()
+    ));
+    assert!(!structurally_equal(
+        &u64::schema_container(),
+        &i64::schema_container()
+    ));
+    assert!(!structurally_equal(
+        &Option::<u8>::schema_container(),
+        &Vec::<u8>::schema_container()
+    ));
+}
@@ -0,0 +1,132 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+use std::time::Duration;
+
+use borsh::maybestd::collections::HashMap;
+use borsh::maybestd::io::{Read, Result, Write};
+use borsh::schema::{BorshSchema, Declaration, Definition, ValidationError, Value};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// `Duration` as a `u64` number of milliseconds.
+mod millis {
+    use super::*;
+
+    pub fn serialize<W: Write>(duration: &Duration, writer: &mut W) -> Result<()> {
+        (duration.as_millis() as u64).serialize(writer)
+    }
+
+    pub fn deserialize<R: Read>(reader: &mut R) -> Result<Duration> {
+        Ok(Duration::from_millis(u64::deserialize_reader(reader)?))
+    }
+
+    pub fn declaration() -> Declaration {
+        u64::declaration()
+    }
+
+    pub fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        u64::add_definitions_recursively(definitions);
+    }
+}
+
+/// `Duration` as the seconds and nanoseconds in a tuple.
+mod parts {
+    use super::*;
+
+    pub fn serialize<W: Write>(duration: &Duration, writer: &mut W) -> Result<()> {
+        (duration.as_secs(), duration.subsec_nanos()).serialize(writer)
+    }
+
+    pub fn deserialize<R: Read>(reader: &mut R) -> Result<Duration> {
+        let (secs, nanos) = <(u64, u32)>::deserialize_reader(reader)?;
+        Ok(Duration::new(secs, nanos))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, borsh::BorshSchema, PartialEq, Debug)]
+struct Timeouts {
+    #[borsh(with = "millis", schema(with = "millis"))]
+    connect: Duration,
+    // The tuple is defined by the other field.
+    #[borsh(with = "parts", schema(declaration = "Tuple<u64, u32>"))]
+    read: Duration,
+    fallback: (u64, u32),
+}
+
+#[test]
+fn test_overridden_field_schemas() {
+    let container = Timeouts::schema_container();
+    assert_eq!(
+        container.definitions["Timeouts"],
+        Definition::Struct {
+            fields: borsh::schema::Fields::NamedFields(vec![
+                ("connect".to_string(), "u64".to_string()),
+                ("read".to_string(), "Tuple<u64, u32>".to_string()),
+                ("fallback".to_string(), "Tuple<u64, u32>".to_string()),
+            ])
+        }
+    );
+    assert_eq!(container.validate(), Ok(()));
+}
+
+#[test]
+fn test_schema_matches_the_bytes() {
+    let timeouts = Timeouts {
+        connect: Duration::from_millis(1500),
+        read: Duration::new(3, 250),
+        fallback: (7, 8),
+    };
+    let data = timeouts.try_to_vec().unwrap();
+    let value = Timeouts::schema_container()
+        .value_from_slice(&data)
+        .unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            ("connect".to_string(), Value::U64(1500)),
+            (
+                "read".to_string(),
+                Value::Tuple(vec![Value::U64(3), Value::U32(250)])
+            ),
+            (
+                "fallback".to_string(),
+                Value::Tuple(vec![Value::U64(7), Value::U32(8)])
+            ),
+        ])
+    );
+    assert_eq!(Timeouts::try_from_slice(&data).unwrap(), timeouts);
+}
+
+#[derive(BorshSerialize, borsh::BorshSchema)]
+enum Event {
+    Tick(#[borsh(with = "millis", schema(with = "millis"))] Duration),
+    Stop,
+}
+
+#[test]
+fn test_overridden_variant_field() {
+    let container = Event::schema_container();
+    assert_eq!(
+        container.definitions["EventTick"],
+        Definition::Struct {
+            fields: borsh::schema::Fields::UnnamedFields(vec!["u64".to_string()])
+        }
+    );
+    let data = Event::Tick(Duration::from_millis(20)).try_to_vec().unwrap();
+    assert!(container.value_from_slice(&data).is_ok());
+}
+
+#[derive(borsh::BorshSchema)]
+struct Blob {
+    #[borsh(schema(declaration = "Bytes"))]
+    bytes: Vec<u8>,
+}
+
+#[test]
+fn test_undefined_declaration_fails_validation() {
+    assert_eq!(
+        Blob::schema_container().validate(),
+        Err(ValidationError::UndefinedDeclaration {
+            declaration: "Bytes".to_string(),
+            used_by: Some("Blob".to_string()),
+        })
+    );
+}
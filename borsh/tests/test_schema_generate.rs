@@ -0,0 +1,188 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use borsh::maybestd::collections::{BTreeMap, HashMap};
+use borsh::schema::{generate_value, BorshSchemaContainer, Definition, Fields, GenConfig, Value};
+use borsh::{BorshDeserialize, BorshSchema};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+#[derive(BorshDeserialize, BorshSchema, Debug)]
+struct Primitives {
+    unit: (),
+    flag: bool,
+    a: u8,
+    b: u16,
+    c: u32,
+    d: u64,
+    e: u128,
+    f: i8,
+    g: i16,
+    h: i32,
+    i: i64,
+    j: i128,
+    k: f32,
+    l: f64,
+    text: String,
+}
+
+#[derive(BorshDeserialize, BorshSchema, Debug)]
+enum Message {
+    Ping,
+    Text { from: u64, lines: Vec<String> },
+    Batch(Vec<Option<Primitives>>, [u16; 3]),
+    Pair((u8, Option<String>)),
+}
+
+#[derive(BorshDeserialize, BorshSchema, Debug)]
+struct Envelope {
+    id: u32,
+    messages: Vec<Message>,
+    sizes: BTreeMap<u8, u64>,
+    reply: Option<Message>,
+}
+
+fn round_trip(container: &BorshSchemaContainer, value: &Value) -> Vec<u8> {
+    let data = container.serialize_value(value).unwrap();
+    assert_eq!(&container.value_from_slice(&data).unwrap(), value);
+    data
+}
+
+#[test]
+fn test_round_trip_through_schema() {
+    let container = Envelope::schema_container();
+    let mut rng = SmallRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let value = generate_value(&container, &mut rng, &GenConfig::default());
+        round_trip(&container, &value);
+    }
+}
+
+#[test]
+fn test_generated_bytes_decode_natively() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let container = Message::schema_container();
+    for _ in 0..200 {
+        let value = generate_value(&container, &mut rng, &GenConfig::default());
+        let data = round_trip(&container, &value);
+        Message::try_from_slice(&data).unwrap();
+    }
+    let container = Primitives::schema_container();
+    for _ in 0..200 {
+        let value = generate_value(&container, &mut rng, &GenConfig::default());
+        let data = round_trip(&container, &value);
+        Primitives::try_from_slice(&data).unwrap();
+    }
+}
+
+#[test]
+fn test_lengths_are_bounded() {
+    let config = GenConfig {
+        max_sequence_length: 3,
+        max_string_length: 2,
+        ..GenConfig::default()
+    };
+    let container = <Vec<String>>::schema_container();
+    let mut rng = SmallRng::seed_from_u64(2);
+    let mut longest = 0;
+    for _ in 0..100 {
+        let strings = match generate_value(&container, &mut rng, &config) {
+            Value::Sequence(strings) => strings,
+            value => panic!("{:?}", value),
+        };
+        assert!(strings.len() <= 3);
+        longest = longest.max(strings.len());
+        for string in strings {
+            match string {
+                Value::String(string) => assert!(string.chars().count() <= 2),
+                value => panic!("{:?}", value),
+            }
+        }
+    }
+    assert_eq!(longest, 3);
+}
+
+/// `Tree` is `enum Tree { Leaf(u8), Node(Vec<Tree>, Tree) }`, whose only finite values end with
+/// a `Leaf`.
+fn tree() -> BorshSchemaContainer {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Tree".to_string(),
+        Definition::Enum {
+            variants: vec![
+                ("Leaf".to_string(), "u8".to_string()),
+                ("Node".to_string(), "Node".to_string()),
+            ],
+        },
+    );
+    definitions.insert(
+        "Node".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["Vec<Tree>".to_string(), "Tree".to_string()]),
+        },
+    );
+    definitions.insert(
+        "Vec<Tree>".to_string(),
+        Definition::Sequence {
+            length_width: 4,
+            length_range: 0..=u32::MAX as u64,
+            elements: "Tree".to_string(),
+        },
+    );
+    BorshSchemaContainer {
+        declaration: "Tree".to_string(),
+        definitions,
+    }
+}
+
+fn depth(value: &Value) -> usize {
+    let children: Vec<&Value> = match value {
+        Value::Array(values)
+        | Value::Sequence(values)
+        | Value::Tuple(values)
+        | Value::TupleStruct(values) => values.iter().collect(),
+        Value::Struct(fields) => fields.iter().map(|(_, value)| value).collect(),
+        Value::Enum { value, .. } => vec![value],
+        Value::Option(Some(value)) => vec![value],
+        _ => return 0,
+    };
+    1 + children.into_iter().map(depth).max().unwrap_or(0)
+}
+
+#[test]
+fn test_recursive_schema_terminates() {
+    let container = tree();
+    let mut rng = SmallRng::seed_from_u64(3);
+    for max_depth in 0..8 {
+        let config = GenConfig {
+            max_depth,
+            ..GenConfig::default()
+        };
+        for _ in 0..50 {
+            let value = generate_value(&container, &mut rng, &config);
+            // A `Leaf`, the shallowest tree, is one definition deep.
+            assert!(depth(&value) <= max_depth.max(1));
+            round_trip(&container, &value);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Loop has no value of finite depth in the schema")]
+fn test_no_finite_value() {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Loop".to_string(),
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![("next".to_string(), "Loop".to_string())]),
+        },
+    );
+    let container = BorshSchemaContainer {
+        declaration: "Loop".to_string(),
+        definitions,
+    };
+    generate_value(
+        &container,
+        &mut SmallRng::seed_from_u64(4),
+        &GenConfig::default(),
+    );
+}
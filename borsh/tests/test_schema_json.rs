@@ -0,0 +1,123 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+use borsh::schema::BorshSchema;
+use borsh::{BorshSchema as BorshSchemaMacro, BorshSerialize};
+use serde_json::json;
+
+fn to_json<T: BorshSerialize + BorshSchema>(value: &T) -> serde_json::Value {
+    let data = value.try_to_vec().unwrap();
+    let mut buf = data.as_slice();
+    let json = T::schema_container().deserialize_to_json(&mut buf).unwrap();
+    assert!(buf.is_empty());
+    json
+}
+
+fn from_json<T: BorshSchema>(json: serde_json::Value) -> Vec<u8> {
+    T::schema_container().serialize_json(&json).unwrap()
+}
+
+#[test]
+fn test_result() {
+    let ok: Result<u64, String> = Ok(5);
+    let err: Result<u64, String> = Err("msg".to_string());
+    assert_eq!(to_json(&ok), json!({"Ok": 5}));
+    assert_eq!(to_json(&err), json!({"Err": "msg"}));
+    assert_eq!(
+        from_json::<Result<u64, String>>(json!({"Ok": 5})),
+        ok.try_to_vec().unwrap()
+    );
+    assert_eq!(
+        from_json::<Result<u64, String>>(json!({"Err": "msg"})),
+        err.try_to_vec().unwrap()
+    );
+}
+
+#[test]
+fn test_option_string_is_nullable() {
+    let none: Option<String> = None;
+    let some = Some("x".to_string());
+    assert_eq!(to_json(&none), json!(null));
+    assert_eq!(to_json(&some), json!("x"));
+    assert_eq!(
+        from_json::<Option<String>>(json!(null)),
+        none.try_to_vec().unwrap()
+    );
+    assert_eq!(
+        from_json::<Option<String>>(json!("x")),
+        some.try_to_vec().unwrap()
+    );
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+enum Command {
+    Stop,
+    Move { x: i32, y: i32 },
+    Say(String, Option<u8>),
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+#[borsh(named_variants)]
+enum Named {
+    First(u8),
+    Second,
+}
+
+#[derive(BorshSerialize, BorshSchemaMacro)]
+struct Script {
+    commands: Vec<Command>,
+    tags: [Named; 2],
+    total: u128,
+}
+
+#[test]
+fn test_nested_roundtrip() {
+    let script = Script {
+        commands: vec![
+            Command::Move { x: -1, y: 2 },
+            Command::Say("hi".to_string(), None),
+            Command::Stop,
+        ],
+        tags: [Named::First(3), Named::Second],
+        total: u128::MAX,
+    };
+    let json = to_json(&script);
+    assert_eq!(
+        json,
+        json!({
+            "commands": [
+                {"Move": {"x": -1, "y": 2}},
+                {"Say": ["hi", null]},
+                {"Stop": null},
+            ],
+            "tags": [{"First": [3]}, {"Second": null}],
+            "total": u128::MAX.to_string(),
+        })
+    );
+    assert_eq!(from_json::<Script>(json), script.try_to_vec().unwrap());
+}
+
+#[test]
+fn test_variant_without_fields_as_string() {
+    assert_eq!(
+        from_json::<Command>(json!("Stop")),
+        Command::Stop.try_to_vec().unwrap()
+    );
+}
+
+#[test]
+fn test_invalid_json() {
+    let container = <Result<u64, String>>::schema_container();
+    assert_eq!(
+        container
+            .serialize_json(&json!({"Maybe": 1}))
+            .unwrap_err()
+            .to_string(),
+        "Unknown variant Maybe of Result<u64, string>"
+    );
+    assert_eq!(
+        container
+            .serialize_json(&json!({"Ok": -1}))
+            .unwrap_err()
+            .to_string(),
+        "Expected u64, found -1"
+    );
+}
@@ -0,0 +1,118 @@
+#![allow(dead_code)] // Local structures are only used for their schemas.
+use std::collections::HashMap;
+
+use borsh::schema::{BorshSchemaContainer, Declaration, Fields, VariantName};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// `Definition` as borsh 0.10 serialized it.
+#[derive(BorshSerialize)]
+enum OldDefinition {
+    Array {
+        length: u32,
+        elements: Declaration,
+    },
+    Sequence {
+        elements: Declaration,
+    },
+    Tuple {
+        elements: Vec<Declaration>,
+    },
+    Enum {
+        variants: Vec<(VariantName, Declaration)>,
+    },
+    Struct {
+        fields: Fields,
+    },
+}
+
+#[derive(BorshSerialize)]
+struct OldContainer {
+    declaration: Declaration,
+    definitions: HashMap<Declaration, OldDefinition>,
+}
+
+#[derive(BorshSchema)]
+enum Event {
+    Created { id: u64, tags: Vec<String> },
+    Moved([u8; 2], (u8, u16)),
+    Deleted,
+}
+
+#[test]
+fn test_legacy_container() {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Event".to_string(),
+        OldDefinition::Enum {
+            variants: vec![
+                ("Created".to_string(), "EventCreated".to_string()),
+                ("Moved".to_string(), "EventMoved".to_string()),
+                ("Deleted".to_string(), "EventDeleted".to_string()),
+            ],
+        },
+    );
+    definitions.insert(
+        "EventCreated".to_string(),
+        OldDefinition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("id".to_string(), "u64".to_string()),
+                ("tags".to_string(), "Vec<string>".to_string()),
+            ]),
+        },
+    );
+    definitions.insert(
+        "EventMoved".to_string(),
+        OldDefinition::Struct {
+            fields: Fields::UnnamedFields(vec![
+                "Array<u8, 2>".to_string(),
+                "Tuple<u8, u16>".to_string(),
+            ]),
+        },
+    );
+    definitions.insert(
+        "EventDeleted".to_string(),
+        OldDefinition::Struct {
+            fields: Fields::Empty,
+        },
+    );
+    definitions.insert(
+        "Vec<string>".to_string(),
+        OldDefinition::Sequence {
+            elements: "string".to_string(),
+        },
+    );
+    definitions.insert(
+        "Array<u8, 2>".to_string(),
+        OldDefinition::Array {
+            length: 2,
+            elements: "u8".to_string(),
+        },
+    );
+    definitions.insert(
+        "Tuple<u8, u16>".to_string(),
+        OldDefinition::Tuple {
+            elements: vec!["u8".to_string(), "u16".to_string()],
+        },
+    );
+    let data = OldContainer {
+        declaration: "Event".to_string(),
+        definitions,
+    }
+    .try_to_vec()
+    .unwrap();
+
+    let container = BorshSchemaContainer::from_legacy_bytes(&data).unwrap();
+    assert_eq!(container, Event::schema_container());
+    assert!(BorshSchemaContainer::try_from_slice(&data).is_err());
+}
+
+#[test]
+fn test_legacy_rejects_current_definitions() {
+    // `Definition::Option` did not exist in borsh 0.10.
+    let data = Option::<u8>::schema_container().try_to_vec().unwrap();
+    assert!(BorshSchemaContainer::from_legacy_bytes(&data).is_err());
+    assert_eq!(
+        BorshSchemaContainer::try_from_slice(&data).unwrap(),
+        Option::<u8>::schema_container()
+    );
+}
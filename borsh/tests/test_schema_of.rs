@@ -0,0 +1,37 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+
+use borsh::schema::BorshSchemaContainer;
+use borsh::{declaration_of, schema_container_of, BorshSchema};
+
+#[derive(BorshSchema)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+/// Generic code that only has the value.
+fn describe<T: BorshSchema>(value: &T) -> (String, BorshSchemaContainer) {
+    (declaration_of(value), schema_container_of(value))
+}
+
+#[test]
+fn test_schema_container_of() {
+    let some_vec: Vec<u64> = vec![1, 2, 3];
+    assert_eq!(
+        schema_container_of(&some_vec),
+        Vec::<u64>::schema_container()
+    );
+    assert_eq!(declaration_of(&some_vec), "Vec<u64>");
+
+    let (declaration, container) = describe(&Wrapper { inner: Some(1u8) });
+    assert_eq!(declaration, "Wrapper<Option<u8>>");
+    assert_eq!(container, Wrapper::<Option<u8>>::schema_container());
+}
+
+#[test]
+fn test_unsized() {
+    assert_eq!(declaration_of("text"), "string");
+    assert_eq!(
+        schema_container_of(&[1u16, 2][..]),
+        <[u16]>::schema_container()
+    );
+}
@@ -0,0 +1,89 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::*;
+
+#[test]
+fn test_struct_of_structs() {
+    #[derive(borsh::BorshSchema)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+    #[derive(borsh::BorshSchema)]
+    struct Segment {
+        from: Point,
+        to: Point,
+    }
+    #[derive(borsh::BorshSchema)]
+    struct Drawing {
+        segments: Vec<Segment>,
+        origin: Point,
+        title: Option<String>,
+    }
+
+    let order = Drawing::schema_container().topological_order().unwrap();
+    assert_eq!(
+        order,
+        vec![
+            "Point",
+            "Segment",
+            "Vec<Segment>",
+            "Option<string>",
+            "Drawing"
+        ]
+    );
+}
+
+#[test]
+fn test_every_definition_follows_its_dependencies() {
+    #[derive(borsh::BorshSchema)]
+    enum Shape {
+        Circle { radius: u32 },
+        Polygon(Vec<(i32, i32)>),
+    }
+
+    let container = <(Shape, [Shape; 2])>::schema_container();
+    let order = container.topological_order().unwrap();
+    assert_eq!(order.len(), container.definitions.len());
+    let position = |declaration: &str| order.iter().position(|d| d == declaration).unwrap();
+    assert!(position("Tuple<i32, i32>") < position("Vec<Tuple<i32, i32>>"));
+    assert!(position("Vec<Tuple<i32, i32>>") < position("ShapePolygon"));
+    assert!(position("ShapeCircle") < position("Shape"));
+    assert!(position("ShapePolygon") < position("Shape"));
+    assert!(position("Shape") < position("Array<Shape, 2>"));
+    assert_eq!(order.last().unwrap(), "Tuple<Shape, Array<Shape, 2>>");
+}
+
+#[test]
+fn test_self_referential_type() {
+    // struct Node { value: u8, children: Vec<Node> }
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Node".to_string(),
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("value".to_string(), "u8".to_string()),
+                ("children".to_string(), "Vec<Node>".to_string()),
+            ]),
+        },
+    );
+    definitions.insert(
+        "Vec<Node>".to_string(),
+        Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "Node".to_string(),
+        },
+    );
+    let container = BorshSchemaContainer {
+        declaration: "Node".to_string(),
+        definitions,
+    };
+
+    let err = container.topological_order().unwrap_err();
+    assert_eq!(err.cycle, vec!["Node", "Vec<Node>"]);
+    assert_eq!(
+        err.to_string(),
+        "Recursive definitions: Node -> Vec<Node> -> Node"
+    );
+}
@@ -23,3 +23,38 @@ fn usize_schema() {
         }
     )
 }
+
+#[test]
+fn primitive_declarations_are_the_constants() {
+    assert_eq!(<()>::declaration(), declarations::NIL);
+    assert_eq!(bool::declaration(), declarations::BOOL);
+    assert_eq!(u8::declaration(), declarations::U8);
+    assert_eq!(u16::declaration(), declarations::U16);
+    assert_eq!(u32::declaration(), declarations::U32);
+    assert_eq!(u64::declaration(), declarations::U64);
+    assert_eq!(u128::declaration(), declarations::U128);
+    assert_eq!(i8::declaration(), declarations::I8);
+    assert_eq!(i16::declaration(), declarations::I16);
+    assert_eq!(i32::declaration(), declarations::I32);
+    assert_eq!(i64::declaration(), declarations::I64);
+    assert_eq!(i128::declaration(), declarations::I128);
+    assert_eq!(f32::declaration(), declarations::F32);
+    assert_eq!(f64::declaration(), declarations::F64);
+    assert_eq!(String::declaration(), declarations::STRING);
+    assert_eq!(str::declaration(), declarations::STRING);
+    assert_eq!(usize::declaration(), declarations::U64);
+    assert_eq!(isize::declaration(), declarations::I64);
+}
+
+#[test]
+fn primitive_declarations_are_decodable() {
+    // Enough zeros for the widest primitive, and an empty string.
+    let zeros = [0u8; 16];
+    for declaration in declarations::ALL.iter() {
+        let container = BorshSchemaContainer {
+            declaration: declaration.to_string(),
+            definitions: Default::default(),
+        };
+        assert!(container.deserialize_value(&mut &zeros[..]).is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::*;
+
+#[derive(borsh::BorshSchema)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(borsh::BorshSchema)]
+enum Shape {
+    Circle { center: Point, radius: u32 },
+    Polygon(Vec<Point>),
+    Empty,
+}
+
+#[derive(borsh::BorshSchema)]
+struct Unrelated {
+    names: Vec<String>,
+}
+
+#[test]
+fn test_orphaned_definitions_are_removed() {
+    let expected = Shape::schema_container();
+    let mut container = expected.clone();
+    Unrelated::add_definitions_recursively(&mut container.definitions);
+    container.definitions.insert(
+        "Orphan".to_string(),
+        Definition::Tuple {
+            elements: vec!["Point".to_string(), "Unrelated".to_string()],
+        },
+    );
+    assert!(container.definitions.contains_key("Vec<string>"));
+
+    assert_eq!(container.reachable_subset(), expected);
+    container.retain_reachable();
+    assert_eq!(container, expected);
+}
+
+#[test]
+fn test_recursive_schema_is_kept() {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "List".to_string(),
+        Definition::Enum {
+            variants: vec![
+                ("Nil".to_string(), "nil".to_string()),
+                ("Cons".to_string(), "Cons".to_string()),
+            ],
+        },
+    );
+    definitions.insert(
+        "Cons".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u8".to_string(), "List".to_string()]),
+        },
+    );
+    let expected = BorshSchemaContainer {
+        declaration: "List".to_string(),
+        definitions,
+    };
+    let mut container = expected.clone();
+    container.definitions.insert(
+        "Array".to_string(),
+        Definition::Array {
+            length: 2,
+            elements: "List".to_string(),
+        },
+    );
+
+    container.retain_reachable();
+    assert_eq!(container, expected);
+}
+
+#[test]
+fn test_undefined_root() {
+    let mut container = BorshSchemaContainer {
+        declaration: "Missing".to_string(),
+        definitions: Point::schema_container().definitions,
+    };
+    assert!(container.reachable_subset().definitions.is_empty());
+    container.retain_reachable();
+    assert!(container.definitions.is_empty());
+    assert_eq!(container.declaration, "Missing");
+
+    let mut container = u64::schema_container();
+    container.retain_reachable();
+    assert_eq!(container, u64::schema_container());
+}
@@ -0,0 +1,151 @@
+#![allow(dead_code)] // Local structures are only used to generate the schema.
+
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::*;
+use borsh::{BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshSchema)]
+struct Config {
+    retries: u8,
+}
+
+#[derive(BorshSerialize, BorshSchema)]
+enum Source {
+    Default,
+    File(Config),
+    Inline { config: Config },
+}
+
+#[derive(BorshSerialize, BorshSchema)]
+struct Settings {
+    config: Config,
+    history: Vec<Config>,
+    pair: (Config, u32),
+    fallback: Option<Config>,
+    source: Source,
+}
+
+/// Every declaration used by the definitions of the container.
+fn references(container: &BorshSchemaContainer) -> Vec<&str> {
+    let mut references = Vec::new();
+    for definition in container.definitions.values() {
+        match definition {
+            Definition::Array { elements, .. } | Definition::Sequence { elements, .. } => {
+                references.push(elements.as_str())
+            }
+            Definition::Tuple { elements } => {
+                references.extend(elements.iter().map(|e| e.as_str()))
+            }
+            Definition::Enum { variants } | Definition::NamedEnum { variants } => {
+                references.extend(variants.iter().map(|(_, d)| d.as_str()))
+            }
+            Definition::TaggedEnum { variants } => {
+                references.extend(variants.iter().map(|(_, _, d)| d.as_str()))
+            }
+            Definition::U32TaggedEnum { variants } => {
+                references.extend(variants.iter().map(|(_, _, d)| d.as_str()))
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    references.extend(fields.iter().map(|(_, d)| d.as_str()))
+                }
+                Fields::UnnamedFields(fields) => {
+                    references.extend(fields.iter().map(|d| d.as_str()))
+                }
+                Fields::Empty => {}
+            },
+            Definition::Option { inner } => references.push(inner.as_str()),
+        }
+    }
+    references
+}
+
+fn settings() -> Settings {
+    Settings {
+        config: Config { retries: 1 },
+        history: vec![Config { retries: 2 }],
+        pair: (Config { retries: 3 }, 4),
+        fallback: Some(Config { retries: 5 }),
+        source: Source::Inline {
+            config: Config { retries: 6 },
+        },
+    }
+}
+
+#[test]
+fn test_rename_nested() {
+    let original = Settings::schema_container();
+    let mut container = original.clone();
+    container.rename_declaration("Config", "app::Config");
+
+    assert!(!container.definitions.contains_key("Config"));
+    assert_eq!(
+        container.definitions["app::Config"],
+        original.definitions["Config"]
+    );
+    assert!(!references(&container).contains(&"Config"));
+    // A field of `Settings`, the elements of `Vec`, `Tuple` and `Option`, and the fields of two
+    // variants of `Source`.
+    let renamed = references(&container)
+        .into_iter()
+        .filter(|d| *d == "app::Config")
+        .count();
+    assert_eq!(renamed, 6);
+    assert_eq!(container.definitions.len(), original.definitions.len());
+
+    let data = settings().try_to_vec().unwrap();
+    assert_eq!(
+        container.value_from_slice(&data).unwrap(),
+        original.value_from_slice(&data).unwrap()
+    );
+}
+
+#[test]
+fn test_rename_root() {
+    let mut container = Settings::schema_container();
+    container.rename_declaration("Settings", "app::Settings");
+    assert_eq!(container.declaration, "app::Settings");
+    assert!(container.definitions.contains_key("app::Settings"));
+    assert!(!container.definitions.contains_key("Settings"));
+}
+
+#[test]
+fn test_rename_recursive() {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "List".to_string(),
+        Definition::Option {
+            inner: "Node".to_string(),
+        },
+    );
+    definitions.insert(
+        "Node".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u8".to_string(), "List".to_string()]),
+        },
+    );
+    let mut container = BorshSchemaContainer {
+        declaration: "List".to_string(),
+        definitions,
+    };
+    container.rename_declaration("List", "Chain");
+    assert_eq!(container.declaration, "Chain");
+    assert_eq!(
+        container.definitions["Node"],
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u8".to_string(), "Chain".to_string()]),
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Vec<Config> is already a declaration of the container")]
+fn test_rename_to_existing() {
+    Settings::schema_container().rename_declaration("Config", "Vec<Config>");
+}
+
+#[test]
+#[should_panic(expected = "u32 is already a declaration of the container")]
+fn test_rename_to_primitive() {
+    Settings::schema_container().rename_declaration("Config", "u32");
+}
@@ -0,0 +1,117 @@
+use borsh::maybestd::collections::HashMap;
+use borsh::maybestd::io::ErrorKind;
+use borsh::schema::{
+    BorshSchemaContainer, Declaration, Definition, Fields, SchemaReader, SchemaWriter,
+};
+use borsh::BorshSerialize;
+
+/// A schema with a few thousand definitions of every kind.
+fn large_container() -> BorshSchemaContainer {
+    let mut definitions = HashMap::new();
+    for i in 0..3000 {
+        let definition = match i % 4 {
+            0 => Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("id".to_string(), "u64".to_string()),
+                    ("next".to_string(), format!("Type{}", i + 1)),
+                ]),
+            },
+            1 => Definition::Enum {
+                variants: vec![
+                    ("Empty".to_string(), "()".to_string()),
+                    ("Value".to_string(), format!("Type{}", i + 1)),
+                ],
+            },
+            2 => Definition::Sequence {
+                length_width: 4,
+                length_range: 0..=u32::MAX as u64,
+                elements: format!("Type{}", i + 1),
+            },
+            _ => Definition::Array {
+                length: i,
+                elements: "u8".to_string(),
+            },
+        };
+        definitions.insert(format!("Type{}", i), definition);
+    }
+    BorshSchemaContainer {
+        declaration: "Type0".to_string(),
+        definitions,
+    }
+}
+
+fn sorted(container: &BorshSchemaContainer) -> Vec<(&Declaration, &Definition)> {
+    let mut definitions: Vec<_> = container.definitions.iter().collect();
+    definitions.sort_by(|a, b| a.0.cmp(b.0));
+    definitions
+}
+
+#[test]
+fn test_stream_matches_container() {
+    let container = large_container();
+    let mut writer = SchemaWriter::new(
+        Vec::new(),
+        &container.declaration,
+        container.definitions.len(),
+    )
+    .unwrap();
+    for (declaration, definition) in sorted(&container) {
+        writer.write_definition(declaration, definition).unwrap();
+    }
+    let data = writer.finish().unwrap();
+    assert_eq!(data, container.try_to_vec().unwrap());
+
+    let mut reader = SchemaReader::new(data.as_slice()).unwrap();
+    assert_eq!(reader.declaration(), "Type0");
+    assert_eq!(reader.remaining(), 3000);
+    let mut definitions = HashMap::new();
+    for entry in &mut reader {
+        let (declaration, definition) = entry.unwrap();
+        assert!(definitions.insert(declaration, definition).is_none());
+    }
+    assert_eq!(definitions, container.definitions);
+    assert!(reader.into_inner().is_empty());
+}
+
+#[test]
+fn test_writer_rejects_unsorted() {
+    let definition = Definition::Array {
+        length: 1,
+        elements: "u8".to_string(),
+    };
+    let mut writer = SchemaWriter::new(Vec::new(), "B", 3).unwrap();
+    writer.write_definition("B", &definition).unwrap();
+    let err = writer.write_definition("A", &definition).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = writer.write_definition("B", &definition).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_writer_checks_count() {
+    let definition = Definition::Array {
+        length: 1,
+        elements: "u8".to_string(),
+    };
+    let mut writer = SchemaWriter::new(Vec::new(), "A", 1).unwrap();
+    writer.write_definition("A", &definition).unwrap();
+    let err = writer.write_definition("B", &definition).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let mut writer = SchemaWriter::new(Vec::new(), "A", 2).unwrap();
+    writer.write_definition("A", &definition).unwrap();
+    let err = writer.finish().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_stops_after_error() {
+    let data = large_container().try_to_vec().unwrap();
+    let mut reader = SchemaReader::new(&data[..data.len() / 2]).unwrap();
+    let mut read = 0;
+    while reader.next().unwrap().is_ok() {
+        read += 1;
+    }
+    assert!(read > 0);
+    assert!(reader.next().is_none());
+}
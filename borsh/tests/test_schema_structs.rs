@@ -63,7 +63,11 @@ pub fn boxed() {
     A::add_definitions_recursively(&mut defs);
     assert_eq!(
         map! {
-        "Vec<u8>" => Definition::Sequence { elements: "u8".to_string() },
+        "Vec<u8>" => Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "u8".to_string()
+        },
         "A" => Definition::Struct{ fields: Fields::NamedFields(vec![
         ("_f1".to_string(), "u64".to_string()),
         ("_f2".to_string(), "string".to_string()),
@@ -91,6 +95,7 @@ pub fn wrapper_struct() {
 
 #[test]
 pub fn tuple_struct() {
+    #[allow(dead_code)] // Only the schema is used.
     #[derive(borsh::BorshSchema)]
     struct A(u64, String);
     assert_eq!("A".to_string(), A::declaration());
@@ -147,9 +152,73 @@ pub fn simple_generics() {
         ("_f2".to_string(), "string".to_string())
         ])
         },
-        "HashMap<u64, string>" => Definition::Sequence {elements: "Tuple<u64, string>".to_string()},
+        "HashMap<u64, string>" => Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "Tuple<u64, string>".to_string()
+        },
         "Tuple<u64, string>" => Definition::Tuple{elements: vec!["u64".to_string(), "string".to_string()]}
         },
         defs
     );
 }
+
+#[test]
+pub fn skip_type_params() {
+    struct NotBorsh;
+
+    #[allow(dead_code)] // Only the schema is used.
+    #[derive(borsh::BorshSchema)]
+    #[borsh(schema(skip_type_params))]
+    struct Opaque<T> {
+        bytes: Vec<u8>,
+        marker: core::marker::PhantomData<T>,
+    }
+    assert_eq!("Opaque".to_string(), <Opaque<NotBorsh>>::declaration());
+    assert_eq!(
+        <Opaque<NotBorsh>>::declaration(),
+        <Opaque<u64>>::declaration()
+    );
+    let mut defs = Default::default();
+    <Opaque<NotBorsh>>::add_definitions_recursively(&mut defs);
+    assert_eq!(
+        map! {
+        "Opaque" => Definition::Struct {
+        fields: Fields::NamedFields(vec![
+        ("bytes".to_string(), "Vec<u8>".to_string())
+        ])
+        },
+        "Vec<u8>" => Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "u8".to_string()
+        }
+        },
+        defs
+    );
+}
+
+#[test]
+pub fn borrowed_fields() {
+    mod borrowed {
+        #[derive(borsh::BorshSerialize, borsh::BorshSchema)]
+        pub struct View<'a> {
+            pub name: &'a str,
+            pub ids: &'a [u64],
+        }
+    }
+    mod owned {
+        #[allow(dead_code)] // Only the schema is used.
+        #[derive(borsh::BorshSchema)]
+        pub struct View {
+            pub name: String,
+            pub ids: Vec<u64>,
+        }
+    }
+    assert_eq!(
+        borrowed::View::schema_container(),
+        owned::View::schema_container()
+    );
+    assert_eq!(<&&[u64]>::declaration(), "Vec<u64>");
+    assert_eq!(<&mut Option<u8>>::declaration(), "Option<u8>");
+}
@@ -0,0 +1,90 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use borsh::maybestd::borrow::Cow;
+use borsh::maybestd::collections::HashMap;
+use borsh::schema::{assert_transparent_wrappers_consistent, BorshSchemaContainer, Definition};
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+struct Wrapped {
+    boxed: Box<u64>,
+    nested: Vec<Option<Box<(u8, u16)>>>,
+    cow: Cow<'static, str>,
+}
+
+#[derive(BorshSchema)]
+struct Unwrapped {
+    boxed: u64,
+    nested: Vec<Option<(u8, u16)>>,
+    cow: String,
+}
+
+#[test]
+fn test_wrappers_are_declared_like_the_wrapped_type() {
+    let expected = u64::schema_container();
+    assert_eq!(expected.declaration, "u64");
+    assert_eq!(Box::<u64>::schema_container(), expected);
+    assert_eq!(Box::<Box<u64>>::schema_container(), expected);
+    assert_transparent_wrappers_consistent(&Box::<u64>::schema_container());
+}
+
+#[cfg(feature = "rc")]
+#[test]
+fn test_shared_pointers_are_declared_like_the_wrapped_type() {
+    use borsh::maybestd::rc::Rc;
+    use borsh::maybestd::sync::Arc;
+
+    let expected = u64::schema_container();
+    assert_eq!(Arc::<u64>::schema_container(), expected);
+    assert_eq!(Rc::<u64>::schema_container(), expected);
+    assert_eq!(
+        Vec::<Arc<Box<u64>>>::schema_container(),
+        Vec::<u64>::schema_container()
+    );
+    assert_transparent_wrappers_consistent(&Arc::<Vec<Rc<u64>>>::schema_container());
+}
+
+#[test]
+fn test_derived_struct_with_wrappers() {
+    let wrapped = Wrapped::schema_container();
+    assert_transparent_wrappers_consistent(&wrapped);
+    let unwrapped = Unwrapped::schema_container();
+    assert_eq!(
+        wrapped.definitions["Wrapped"],
+        unwrapped.definitions["Unwrapped"]
+    );
+    assert!(borsh::schema::structurally_equal(&wrapped, &unwrapped));
+}
+
+fn container_with(declaration: &str, field: &str) -> BorshSchemaContainer {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        declaration.to_string(),
+        Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: field.to_string(),
+        },
+    );
+    BorshSchemaContainer {
+        declaration: declaration.to_string(),
+        definitions,
+    }
+}
+
+#[test]
+#[should_panic(expected = "Declaration Box<u64> names Box")]
+fn test_wrapper_declaration_panics() {
+    assert_transparent_wrappers_consistent(&container_with("Vec<u64>", "Box<u64>"));
+}
+
+#[test]
+#[should_panic(expected = "Declaration Vec<Arc<u64>> names Arc")]
+fn test_nested_wrapper_declaration_panics() {
+    assert_transparent_wrappers_consistent(&container_with("Vec<Arc<u64>>", "u64"));
+}
+
+#[test]
+fn test_longer_names_are_not_wrappers() {
+    assert_transparent_wrappers_consistent(&container_with("Vec<MyBox<u64>>", "Arcade<u8>"));
+}
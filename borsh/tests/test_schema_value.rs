@@ -0,0 +1,283 @@
+#![allow(dead_code)] // Local structures are only serialized, their fields are never read.
+use borsh::maybestd::collections::HashMap;
+use borsh::maybestd::io::ErrorKind;
+use borsh::schema::*;
+use borsh::{BorshSchema, BorshSerialize};
+
+/// A vector with a `u8` length prefix.
+struct ShortVec(Vec<u16>);
+
+impl BorshSerialize for ShortVec {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        (self.0.len() as u8).serialize(writer)?;
+        for element in &self.0 {
+            element.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshSchema for ShortVec {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Sequence {
+            length_width: 1,
+            length_range: 0..=u8::MAX as u64,
+            elements: u16::declaration(),
+        };
+        Self::add_definition(Self::declaration(), definition, definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "ShortVec".to_string()
+    }
+}
+
+#[derive(BorshSerialize, BorshSchema)]
+struct Message {
+    short: ShortVec,
+    long: Vec<u8>,
+    tail: u8,
+}
+
+#[test]
+fn test_custom_length_width() {
+    let message = Message {
+        short: ShortVec(vec![1, 2]),
+        long: vec![3],
+        tail: 4,
+    };
+    let data = message.try_to_vec().unwrap();
+    assert_eq!(data, vec![2, 1, 0, 2, 0, 1, 0, 0, 0, 3, 4]);
+    assert_eq!(
+        Message::schema_container().value_from_slice(&data).unwrap(),
+        Value::Struct(vec![
+            (
+                "short".to_string(),
+                Value::Sequence(vec![Value::U16(1), Value::U16(2)])
+            ),
+            ("long".to_string(), Value::Sequence(vec![Value::U8(3)])),
+            ("tail".to_string(), Value::U8(4)),
+        ])
+    );
+}
+
+fn container(
+    length_width: u8,
+    length_range: core::ops::RangeInclusive<u64>,
+) -> BorshSchemaContainer {
+    let mut definitions = HashMap::new();
+    definitions.insert(
+        "Bytes".to_string(),
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements: "u8".to_string(),
+        },
+    );
+    BorshSchemaContainer {
+        declaration: "Bytes".to_string(),
+        definitions,
+    }
+}
+
+#[test]
+fn test_sequence_until_end_of_input() {
+    let container = container(0, 0..=u64::MAX);
+    assert_eq!(
+        container.value_from_slice(&[5, 6, 7]).unwrap(),
+        Value::Sequence(vec![Value::U8(5), Value::U8(6), Value::U8(7)])
+    );
+    assert_eq!(
+        container.value_from_slice(&[]).unwrap(),
+        Value::Sequence(vec![])
+    );
+}
+
+#[test]
+fn test_sequence_length_range() {
+    let container = container(2, 1..=2);
+    assert_eq!(
+        container.value_from_slice(&[1, 0, 9]).unwrap(),
+        Value::Sequence(vec![Value::U8(9)])
+    );
+    assert_eq!(
+        container.value_from_slice(&[0, 0]).unwrap_err().to_string(),
+        "Sequence length 0 is outside of the allowed range 1..=2"
+    );
+    assert_eq!(
+        container
+            .value_from_slice(&[3, 0, 1, 2, 3])
+            .unwrap_err()
+            .to_string(),
+        "Sequence length 3 is outside of the allowed range 1..=2"
+    );
+}
+
+#[test]
+fn test_unsupported_length_width() {
+    assert_eq!(
+        container(3, 0..=u64::MAX)
+            .value_from_slice(&[0, 0, 0])
+            .unwrap_err()
+            .to_string(),
+        "Unsupported sequence length width: 3"
+    );
+}
+
+#[test]
+fn test_builtin_types() {
+    #[derive(BorshSerialize, BorshSchema)]
+    enum Shape {
+        Circle(u32),
+        Point,
+    }
+
+    let value = (
+        Some(String::from("borsh")),
+        [true, false],
+        vec![Shape::Point, Shape::Circle(7)],
+    );
+    let data = value.try_to_vec().unwrap();
+    let container = <(Option<String>, [bool; 2], Vec<Shape>)>::schema_container();
+    assert_eq!(
+        container.value_from_slice(&data).unwrap(),
+        Value::Tuple(vec![
+            Value::Option(Some(Box::new(Value::String("borsh".to_string())))),
+            Value::Array(vec![Value::Bool(true), Value::Bool(false)]),
+            Value::Sequence(vec![
+                Value::Enum {
+                    variant: "Point".to_string(),
+                    value: Box::new(Value::UnitStruct),
+                },
+                Value::Enum {
+                    variant: "Circle".to_string(),
+                    value: Box::new(Value::TupleStruct(vec![Value::U32(7)])),
+                },
+            ]),
+        ])
+    );
+    let mut trailing = data;
+    trailing.push(0);
+    assert_eq!(
+        container
+            .value_from_slice(&trailing)
+            .unwrap_err()
+            .to_string(),
+        "Not all bytes read"
+    );
+}
+
+#[test]
+fn test_validate_bytes() {
+    let value = (
+        Some(String::from("borsh")),
+        vec![(1u64, false)],
+        Message {
+            short: ShortVec(vec![1, 2]),
+            long: vec![3],
+            tail: 4,
+        },
+    );
+    let data = value.try_to_vec().unwrap();
+    let container = <(Option<String>, Vec<(u64, bool)>, Message)>::schema_container();
+    container.validate_bytes(&data).unwrap();
+
+    // Every truncation fails with the error of `value_from_slice`.
+    for len in 0..data.len() {
+        assert_eq!(
+            container
+                .validate_bytes(&data[..len])
+                .unwrap_err()
+                .to_string(),
+            container
+                .value_from_slice(&data[..len])
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert_eq!(
+        container.validate_bytes(&trailing).unwrap_err().to_string(),
+        "Not all bytes read"
+    );
+
+    let mut invalid = data;
+    // The first byte of the string, after the tag of the `Option` and the length.
+    invalid[5] = 0xff;
+    assert_eq!(
+        container.validate_bytes(&invalid).unwrap_err().to_string(),
+        container
+            .value_from_slice(&invalid)
+            .unwrap_err()
+            .to_string(),
+    );
+}
+
+#[test]
+fn test_zero_sized_elements_hostile_length() {
+    let container = <Vec<()>>::schema_container();
+    let expected = format!(
+        "Exceeded the max_zst_sequence_elements limit of {}: {}",
+        borsh::de::Limits::VALUE_MAX_ZST_SEQUENCE_ELEMENTS,
+        1 << 24
+    );
+    let hostile = [0, 0, 0, 1];
+    let err = container.value_from_slice(&hostile).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), expected);
+    let err = container.validate_bytes(&hostile).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), expected);
+
+    let data = vec![(); 3].try_to_vec().unwrap();
+    assert_eq!(
+        container.value_from_slice(&data).unwrap(),
+        Value::Sequence(vec![Value::Nil; 3])
+    );
+    container.validate_bytes(&data).unwrap();
+}
+
+#[test]
+fn test_serialize_value() {
+    let value = (
+        Some(String::from("borsh")),
+        vec![(1u64, false)],
+        Message {
+            short: ShortVec(vec![1, 2]),
+            long: vec![3],
+            tail: 4,
+        },
+    );
+    let data = value.try_to_vec().unwrap();
+    let tuple = <(Option<String>, Vec<(u64, bool)>, Message)>::schema_container();
+    let decoded = tuple.value_from_slice(&data).unwrap();
+    assert_eq!(tuple.serialize_value(&decoded).unwrap(), data);
+
+    assert_eq!(
+        tuple
+            .serialize_value(&Value::Tuple(vec![Value::Nil]))
+            .unwrap_err()
+            .to_string(),
+        "Expected Tuple<Option<string>, Vec<Tuple<u64, bool>>, Message>, found Tuple([Nil])"
+    );
+    let bytes = container(1, 0..=2);
+    assert_eq!(
+        bytes
+            .serialize_value(&Value::Sequence(vec![Value::U8(1); 3]))
+            .unwrap_err()
+            .to_string(),
+        "Sequence length 3 is outside of the allowed range 0..=2"
+    );
+    assert_eq!(
+        bytes
+            .serialize_value(&Value::Sequence(vec![Value::U16(1)]))
+            .unwrap_err()
+            .to_string(),
+        "Expected u8, found U16(1)"
+    );
+}
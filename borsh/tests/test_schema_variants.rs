@@ -0,0 +1,155 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+use borsh::schema::*;
+
+#[derive(borsh::BorshSchema)]
+enum Request {
+    Get { key: u64 },
+    Stop,
+}
+
+#[derive(borsh::BorshSchema)]
+enum Response {
+    Get { value: Option<String> },
+    Stop,
+}
+
+#[derive(borsh::BorshSchema)]
+struct Exchange {
+    request: Request,
+    response: Response,
+}
+
+#[test]
+fn enums_with_the_same_variant_names_coexist() {
+    let container = Exchange::try_schema_container().unwrap();
+    assert_eq!(container, Exchange::schema_container());
+    assert_eq!(
+        enum_variant_definition(&container, "Request", "Get"),
+        Some(&Definition::Struct {
+            fields: Fields::NamedFields(vec![("key".to_string(), "u64".to_string())])
+        })
+    );
+    assert_eq!(
+        enum_variant_definition(&container, "Response", "Get"),
+        Some(&Definition::Struct {
+            fields: Fields::NamedFields(vec![("value".to_string(), "Option<string>".to_string())])
+        })
+    );
+    assert_eq!(
+        enum_variant_definition(&container, "Response", "Stop"),
+        Some(&Definition::Struct {
+            fields: Fields::Empty
+        })
+    );
+}
+
+#[test]
+fn variant_structs_are_declared_with_the_type_parameters() {
+    #[derive(borsh::BorshSchema)]
+    enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+    let container = Either::<u8, String>::schema_container();
+    assert_eq!(
+        container.definitions["Either<u8, string>"],
+        Definition::Enum {
+            variants: vec![
+                ("Left".to_string(), "EitherLeft<u8, string>".to_string()),
+                ("Right".to_string(), "EitherRight<u8, string>".to_string()),
+            ]
+        }
+    );
+    assert_eq!(
+        enum_variant_definition(&container, "Either<u8, string>", "Right"),
+        Some(&Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["string".to_string()])
+        })
+    );
+}
+
+#[test]
+fn tagged_enum_variants() {
+    #[derive(borsh::BorshSchema)]
+    #[repr(u8)]
+    enum Level {
+        Low = 1,
+        High(u8) = 2,
+    }
+    let container = Level::schema_container();
+    assert_eq!(
+        enum_variant_definition(&container, "Level", "High"),
+        Some(&Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u8".to_string()])
+        })
+    );
+}
+
+#[test]
+fn missing_enum_or_variant() {
+    let container = Exchange::schema_container();
+    assert_eq!(enum_variant_definition(&container, "Request", "Put"), None);
+    assert_eq!(enum_variant_definition(&container, "Exchange", "Get"), None);
+    assert_eq!(enum_variant_definition(&container, "Query", "Get"), None);
+}
+
+// `A::BC` and `AB::C` are both described by a struct declared as `ABC`.
+#[derive(borsh::BorshSchema)]
+enum A {
+    BC(u8),
+}
+
+#[derive(borsh::BorshSchema)]
+enum AB {
+    C(u16),
+}
+
+#[derive(borsh::BorshSchema)]
+struct Both {
+    a: A,
+    ab: AB,
+}
+
+#[test]
+fn colliding_variant_structs_are_an_error() {
+    assert_eq!(
+        Both::try_schema_container().unwrap_err(),
+        ConflictingDefinitions {
+            declaration: "ABC".to_string(),
+            existing: Box::new(Definition::Struct {
+                fields: Fields::UnnamedFields(vec!["u8".to_string()])
+            }),
+            conflicting: Box::new(Definition::Struct {
+                fields: Fields::UnnamedFields(vec!["u16".to_string()])
+            }),
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Redefining type schema for the same type name")]
+fn colliding_variant_structs_panic_in_schema_container() {
+    Both::schema_container();
+}
+
+#[test]
+fn nested_try_schema_containers_report_their_own_conflicts() {
+    struct Outer;
+    impl BorshSchema for Outer {
+        fn add_definitions_recursively(
+            definitions: &mut borsh::maybestd::collections::HashMap<Declaration, Definition>,
+        ) {
+            // A conflict in a nested call does not leak into this one.
+            assert!(Both::try_schema_container().is_err());
+            Exchange::add_definitions_recursively(definitions);
+        }
+
+        fn declaration() -> Declaration {
+            Exchange::declaration()
+        }
+    }
+    assert_eq!(
+        Outer::try_schema_container().unwrap(),
+        Exchange::schema_container()
+    );
+}
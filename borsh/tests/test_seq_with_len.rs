@@ -0,0 +1,55 @@
+use borsh::schema::{BorshSchema, Definition};
+use borsh::{BorshDeserialize, BorshSerialize, SeqWithLen};
+
+#[test]
+fn test_u16_length_roundtrip() {
+    let seq = SeqWithLen::<u16, u64>::new(vec![1, u64::MAX]);
+    let data = seq.try_to_vec().unwrap();
+    let mut expected = vec![2, 0];
+    expected.extend_from_slice(&1u64.to_le_bytes());
+    expected.extend_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(data, expected);
+    assert_eq!(SeqWithLen::<u16, u64>::try_from_slice(&data).unwrap(), seq);
+}
+
+#[test]
+fn test_u16_length_overflow() {
+    let seq = SeqWithLen::<u16, u64>::new(vec![0; u16::MAX as usize + 1]);
+    assert_eq!(
+        seq.try_to_vec().unwrap_err().to_string(),
+        "Sequence is too long for its length prefix"
+    );
+    let seq = SeqWithLen::<u16, u64>::new(vec![0; u16::MAX as usize]);
+    assert_eq!(seq.try_to_vec().unwrap().len(), 2 + 8 * u16::MAX as usize);
+}
+
+#[test]
+fn test_u8_length_roundtrip() {
+    let seq = SeqWithLen::<u8, String>::from(vec!["a".to_string()]);
+    let data = seq.try_to_vec().unwrap();
+    assert_eq!(data, vec![1, 1, 0, 0, 0, b'a']);
+    assert_eq!(
+        SeqWithLen::<u8, String>::try_from_slice(&data).unwrap(),
+        seq
+    );
+}
+
+#[test]
+fn test_schema() {
+    let container = SeqWithLen::<u16, u64>::schema_container();
+    assert_eq!(container.declaration, "SeqWithLen<u16, u64>");
+    assert_eq!(
+        container.definitions["SeqWithLen<u16, u64>"],
+        Definition::Sequence {
+            length_width: 2,
+            length_range: 0..=u16::MAX as u64,
+            elements: "u64".to_string(),
+        }
+    );
+
+    let data = SeqWithLen::<u16, u64>::new(vec![7]).try_to_vec().unwrap();
+    assert_eq!(
+        container.value_from_slice(&data).unwrap(),
+        borsh::schema::Value::Sequence(vec![borsh::schema::Value::U64(7)])
+    );
+}
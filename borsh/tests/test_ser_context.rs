@@ -0,0 +1,111 @@
+use borsh::de::BorshDeserializeWith;
+use borsh::maybestd::io::{Read, Result, Write};
+use borsh::ser::BorshSerializeWith;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// The version of the protocol that the data is encoded for.
+struct Version(u8);
+
+/// A field that was added in version 2 and is not encoded for version 1.
+#[derive(PartialEq, Debug)]
+struct SinceV2<T>(Option<T>);
+
+impl<T: BorshSerialize> BorshSerializeWith<Version> for SinceV2<T> {
+    fn serialize_with<W: Write>(&self, writer: &mut W, ctx: &Version) -> Result<()> {
+        match &self.0 {
+            Some(value) if ctx.0 >= 2 => value.serialize(writer),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T: BorshDeserialize> BorshDeserializeWith<Version> for SinceV2<T> {
+    fn deserialize_reader_with<R: Read>(reader: &mut R, ctx: &mut Version) -> Result<Self> {
+        if ctx.0 >= 2 {
+            Ok(SinceV2(Some(T::deserialize_reader(reader)?)))
+        } else {
+            Ok(SinceV2(None))
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(context = "Version")]
+struct Account {
+    balance: u64,
+    #[borsh(with_context)]
+    memo: SinceV2<String>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(context = "Version")]
+enum Message {
+    Open(#[borsh(with_context)] Account),
+    Close {
+        id: u32,
+        #[borsh(with_context)]
+        reason: SinceV2<u8>,
+    },
+}
+
+#[derive(BorshSerialize)]
+#[borsh(ser_context = "Version")]
+struct Batch(#[borsh(with_context)] Vec<Message>, u8);
+
+#[test]
+fn test_versioned_struct() {
+    let account = Account {
+        balance: 7,
+        memo: SinceV2(Some("hi".to_string())),
+    };
+
+    let v1 = account.try_to_vec_with(&Version(1)).unwrap();
+    assert_eq!(v1, 7u64.try_to_vec().unwrap());
+    let decoded = Account::try_from_slice_with(&v1, &mut Version(1)).unwrap();
+    assert_eq!(decoded.memo, SinceV2(None));
+
+    let v2 = account.try_to_vec_with(&Version(2)).unwrap();
+    assert_eq!(v2, (7u64, "hi").try_to_vec().unwrap());
+    let decoded = Account::try_from_slice_with(&v2, &mut Version(2)).unwrap();
+    assert_eq!(decoded, account);
+}
+
+#[test]
+fn test_versioned_enum() {
+    let messages = vec![
+        Message::Open(Account {
+            balance: 1,
+            memo: SinceV2(Some("a".to_string())),
+        }),
+        Message::Close {
+            id: 3,
+            reason: SinceV2(Some(4)),
+        },
+    ];
+
+    let v1 = messages.try_to_vec_with(&Version(1)).unwrap();
+    assert_eq!(v1, (2u32, 0u8, 1u64, 1u8, 3u32).try_to_vec().unwrap());
+
+    let v2 = messages.try_to_vec_with(&Version(2)).unwrap();
+    assert_eq!(
+        v2,
+        (2u32, 0u8, 1u64, "a", 1u8, 3u32, 4u8).try_to_vec().unwrap()
+    );
+    let decoded = Vec::<Message>::try_from_slice_with(&v2, &mut Version(2)).unwrap();
+    assert_eq!(decoded, messages);
+}
+
+#[test]
+fn test_tuple_struct() {
+    let batch = Batch(
+        vec![Message::Close {
+            id: 1,
+            reason: SinceV2(Some(2)),
+        }],
+        9,
+    );
+    assert_eq!(
+        batch.try_to_vec_with(&Version(1)).unwrap(),
+        (1u32, 1u8, 1u32, 9u8).try_to_vec().unwrap()
+    );
+}
@@ -0,0 +1,81 @@
+use std::io::{Result, Write};
+
+use borsh::{serialize_slice, BorshSerialize};
+
+/// Records the bytes it receives and how many times `write` was called.
+#[derive(Default)]
+struct CountingWriter {
+    data: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.writes += 1;
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The encoding of `slice` with one `serialize` call per element.
+fn element_by_element<T: BorshSerialize>(slice: &[T]) -> Vec<u8> {
+    let mut data = (slice.len() as u32).try_to_vec().unwrap();
+    for item in slice {
+        item.serialize(&mut data).unwrap();
+    }
+    data
+}
+
+fn check<T: BorshSerialize>(slice: &[T]) {
+    let mut data = Vec::new();
+    serialize_slice(slice, &mut data).unwrap();
+    assert_eq!(data, element_by_element(slice));
+}
+
+#[test]
+fn test_same_bytes_as_generic_path() {
+    check::<u8>(&[]);
+    check(&[1u8, 2, 3]);
+    check(&(0..1000u16).collect::<Vec<_>>());
+    check(&(0..5000u64).map(|i| i * 0x0101_0101).collect::<Vec<_>>());
+    check(&(0..777i128).map(|i| -i << 70).collect::<Vec<_>>());
+    check(&(0..3000).map(|i| i as f32 / 3.0).collect::<Vec<_>>());
+    check(&(0..3000).map(|i| i % 3 == 0).collect::<Vec<_>>());
+    check(&vec![[7u64; 5]; 300]);
+    check(&vec![[1u8; 2000]; 3]);
+    check(&[(); 10]);
+    check(&["a".to_string(), "bc".to_string()]);
+}
+
+#[test]
+fn test_vec_uses_the_same_path() {
+    let values: Vec<u32> = (0..10_000).collect();
+    let mut data = Vec::new();
+    serialize_slice(&values, &mut data).unwrap();
+    assert_eq!(data, values.try_to_vec().unwrap());
+}
+
+#[test]
+fn test_fixed_size_elements_are_written_in_chunks() {
+    // 80_000 bytes of elements, written in 1 KiB chunks after the length prefix.
+    let values: Vec<u64> = (0..10_000).collect();
+    let mut writer = CountingWriter::default();
+    serialize_slice(&values, &mut writer).unwrap();
+    assert_eq!(writer.data, element_by_element(&values));
+    assert_eq!(writer.writes, 1 + 79);
+
+    let bytes = vec![5u8; 10_000];
+    let mut writer = CountingWriter::default();
+    serialize_slice(&bytes, &mut writer).unwrap();
+    assert_eq!(writer.writes, 2);
+
+    // Strings do not have a fixed size and are written one by one.
+    let strings = vec!["x".to_string(); 10];
+    let mut writer = CountingWriter::default();
+    serialize_slice(&strings, &mut writer).unwrap();
+    assert_eq!(writer.writes, 1 + 10 * 2);
+}
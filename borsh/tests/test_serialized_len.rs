@@ -0,0 +1,75 @@
+use borsh::maybestd::collections::{BTreeMap, HashMap};
+use borsh::{serialized_len, BorshSerialize};
+
+#[derive(BorshSerialize)]
+struct Inner {
+    id: u64,
+    tags: Vec<String>,
+}
+
+#[derive(BorshSerialize)]
+enum Kind {
+    Empty,
+    Pair(u8, i128),
+}
+
+#[derive(BorshSerialize)]
+struct Outer {
+    name: String,
+    inner: Inner,
+    children: Vec<Inner>,
+    kind: Kind,
+    extra: Option<Box<Inner>>,
+}
+
+fn check<T: BorshSerialize + ?Sized>(value: &T) {
+    assert_eq!(serialized_len(value), value.try_to_vec().unwrap().len());
+}
+
+#[test]
+fn test_primitives() {
+    check(&0u8);
+    check(&-1i64);
+    check(&u128::MAX);
+    check(&1.5f32);
+    check(&true);
+    check(&());
+}
+
+#[test]
+fn test_collections() {
+    check(&String::new());
+    check("a str");
+    check(&vec![1u16, 2, 3]);
+    check(&[7u8; 33]);
+    check(&Some(vec![Some(1u32), None]));
+    check(&(1u8, "two".to_string(), [3u64; 2]));
+    let map: BTreeMap<String, Vec<u8>> = vec![("a".to_string(), vec![1, 2])].into_iter().collect();
+    check(&map);
+    let map: HashMap<u32, bool> = (0..10).map(|key| (key, key % 2 == 0)).collect();
+    check(&map);
+}
+
+#[test]
+fn test_nested_struct() {
+    let inner = || Inner {
+        id: 7,
+        tags: vec!["x".to_string(), "yz".to_string()],
+    };
+    let value = Outer {
+        name: "root".to_string(),
+        inner: inner(),
+        children: vec![inner(), inner()],
+        kind: Kind::Pair(1, -2),
+        extra: Some(Box::new(inner())),
+    };
+    check(&value);
+    let value = Outer {
+        name: String::new(),
+        inner: inner(),
+        children: vec![],
+        kind: Kind::Empty,
+        extra: None,
+    };
+    check(&value);
+}
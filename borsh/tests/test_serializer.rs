@@ -0,0 +1,102 @@
+use borsh::maybestd::io::{Result, Write};
+use borsh::ser::Serializer;
+use borsh::BorshSerialize;
+
+#[derive(BorshSerialize)]
+struct Account {
+    id: u64,
+    name: String,
+    balance: i128,
+    frozen: bool,
+    rate: f32,
+    key: [u8; 4],
+    tags: Vec<u16>,
+    parent: Option<u32>,
+}
+
+#[derive(BorshSerialize)]
+enum Event {
+    Created(Account),
+    Renamed { id: u64, name: String },
+    Closed,
+}
+
+fn account() -> Account {
+    Account {
+        id: 42,
+        name: "alice".to_string(),
+        balance: -1_000,
+        frozen: false,
+        rate: 0.5,
+        key: [1, 2, 3, 4],
+        tags: vec![10, 20, 30],
+        parent: Some(7),
+    }
+}
+
+fn write_account<W: Write>(serializer: &mut Serializer<W>, account: &Account) -> Result<()> {
+    serializer.serialize_u64(account.id)?;
+    serializer.serialize_str(&account.name)?;
+    serializer.serialize_i128(account.balance)?;
+    serializer.serialize_bool(account.frozen)?;
+    serializer.serialize_f32(account.rate)?;
+    serializer.serialize_raw(&account.key)?;
+    serializer.serialize_seq_header(account.tags.len())?;
+    for tag in &account.tags {
+        serializer.serialize_u16(*tag)?;
+    }
+    serializer.serialize_option_tag(account.parent.is_some())?;
+    if let Some(parent) = account.parent {
+        serializer.serialize_u32(parent)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_struct() {
+    let mut serializer = Serializer::new(Vec::new());
+    write_account(&mut serializer, &account()).unwrap();
+    assert_eq!(serializer.into_inner(), account().try_to_vec().unwrap());
+}
+
+#[test]
+fn test_enum() {
+    let mut serializer = Serializer::new(Vec::new());
+    serializer.serialize_variant(0).unwrap();
+    write_account(&mut serializer, &account()).unwrap();
+    serializer.serialize_variant(1).unwrap();
+    serializer.serialize_u64(42).unwrap();
+    serializer.serialize_str("bob").unwrap();
+    serializer.serialize_variant(2).unwrap();
+
+    let mut expected = Event::Created(account()).try_to_vec().unwrap();
+    Event::Renamed {
+        id: 42,
+        name: "bob".to_string(),
+    }
+    .serialize(&mut expected)
+    .unwrap();
+    Event::Closed.serialize(&mut expected).unwrap();
+    assert_eq!(serializer.into_inner(), expected);
+}
+
+#[test]
+fn test_mixed_with_values() {
+    let mut data = Vec::new();
+    let mut serializer = Serializer::new(&mut data);
+    serializer.serialize_bytes(b"abc").unwrap();
+    serializer.serialize(&account()).unwrap();
+    serializer.serialize_option_tag(false).unwrap();
+
+    let expected = (b"abc".to_vec(), account(), None::<u8>)
+        .try_to_vec()
+        .unwrap();
+    assert_eq!(data, expected);
+}
+
+#[test]
+#[should_panic(expected = "For portability reasons we do not allow to serialize NaNs.")]
+fn test_nan() {
+    let mut serializer = Serializer::new(Vec::new());
+    serializer.serialize_f64(f64::NAN).unwrap();
+}
@@ -111,6 +111,7 @@ fn test_deserialize_invalid_discriminant() {
 }
 
 #[test]
+#[allow(clippy::legacy_numeric_constants)] // The test predates `u64::MAX`.
 fn test_simple_struct() {
     let mut map: HashMap<String, String> = HashMap::new();
     map.insert("test".into(), "test".into());
@@ -196,3 +197,17 @@ fn test_simple_struct() {
     assert_eq!(decoded_f2.aa.len(), 2);
     assert!(decoded_f2.aa.iter().all(|f2_a| f2_a == &expected_a));
 }
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Pair(u64, u64);
+
+#[test]
+fn test_tuple_struct_matches_tuple() {
+    let data = Pair(1, 2).try_to_vec().unwrap();
+    assert_eq!(data, (1u64, 2u64).try_to_vec().unwrap());
+    assert_eq!(<(u64, u64)>::try_from_slice(&data).unwrap(), (1, 2));
+    assert_eq!(
+        Pair::try_from_slice(&(3u64, 4u64).try_to_vec().unwrap()).unwrap(),
+        Pair(3, 4)
+    );
+}
@@ -0,0 +1,148 @@
+#![allow(dead_code)] // The structs generated for the schemas of enum variants are never read.
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct LegacySkip {
+    x: u64,
+    #[borsh_skip]
+    cache: Vec<u8>,
+    y: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct NestedSkip {
+    x: u64,
+    #[borsh(skip)]
+    cache: Vec<u8>,
+    y: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum LegacySkipEnum {
+    A {
+        x: u64,
+        #[borsh_skip]
+        cache: Vec<u8>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum NestedSkipEnum {
+    A {
+        x: u64,
+        #[borsh(skip)]
+        cache: Vec<u8>,
+    },
+}
+
+#[test]
+fn test_skip_spellings_are_equivalent() {
+    let legacy = LegacySkip {
+        x: 13,
+        cache: vec![1, 2, 3],
+        y: "y".to_string(),
+    };
+    let nested = NestedSkip {
+        x: 13,
+        cache: vec![1, 2, 3],
+        y: "y".to_string(),
+    };
+    let data = legacy.try_to_vec().unwrap();
+    assert_eq!(data, nested.try_to_vec().unwrap());
+    assert_eq!(
+        NestedSkip::try_from_slice(&data).unwrap(),
+        NestedSkip {
+            x: 13,
+            cache: vec![],
+            y: "y".to_string(),
+        }
+    );
+
+    let legacy = LegacySkipEnum::A {
+        x: 13,
+        cache: vec![1],
+    };
+    let nested = NestedSkipEnum::A {
+        x: 13,
+        cache: vec![1],
+    };
+    let data = legacy.try_to_vec().unwrap();
+    assert_eq!(data, nested.try_to_vec().unwrap());
+    assert_eq!(
+        NestedSkipEnum::try_from_slice(&data).unwrap(),
+        NestedSkipEnum::A {
+            x: 13,
+            cache: vec![]
+        }
+    );
+}
+
+#[test]
+fn test_skip_spellings_have_equal_schema() {
+    let legacy = LegacySkip::schema_container();
+    let nested = NestedSkip::schema_container();
+    assert_eq!(
+        legacy.definitions.get("LegacySkip"),
+        nested.definitions.get("NestedSkip")
+    );
+}
+
+#[derive(Default, PartialEq, Debug)]
+struct Cache(Vec<u8>);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct SkipFirst(#[borsh_skip] Cache, u64, u8);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct SkipLast(u64, u8, #[borsh(skip)] Cache);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+enum SkipInVariant {
+    A(u64, #[borsh_skip] Cache, u8),
+}
+
+#[test]
+fn test_skip_first_tuple_field() {
+    let value = SkipFirst(Cache(vec![1, 2]), 7, 8);
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, (7u64, 8u8).try_to_vec().unwrap());
+    assert_eq!(
+        SkipFirst::try_from_slice(&data).unwrap(),
+        SkipFirst(Cache::default(), 7, 8)
+    );
+}
+
+#[test]
+fn test_skip_last_tuple_field() {
+    let value = SkipLast(7, 8, Cache(vec![1, 2]));
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, (7u64, 8u8).try_to_vec().unwrap());
+    assert_eq!(
+        SkipLast::try_from_slice(&data).unwrap(),
+        SkipLast(7, 8, Cache::default())
+    );
+}
+
+#[test]
+fn test_skip_field_in_tuple_variant() {
+    let value = SkipInVariant::A(7, Cache(vec![1, 2]), 8);
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, (0u8, 7u64, 8u8).try_to_vec().unwrap());
+    assert_eq!(
+        SkipInVariant::try_from_slice(&data).unwrap(),
+        SkipInVariant::A(7, Cache::default(), 8)
+    );
+}
+
+#[test]
+fn test_skipped_tuple_fields_not_in_schema() {
+    let expected = Some(&borsh::schema::Definition::Struct {
+        fields: borsh::schema::Fields::UnnamedFields(vec!["u64".to_string(), "u8".to_string()]),
+    });
+    let container = SkipFirst::schema_container();
+    assert_eq!(container.definitions.get("SkipFirst"), expected);
+    let container = SkipLast::schema_container();
+    assert_eq!(container.definitions.get("SkipLast"), expected);
+    let container = SkipInVariant::schema_container();
+    assert_eq!(container.definitions.get("SkipInVariantA"), expected);
+}
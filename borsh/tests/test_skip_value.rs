@@ -0,0 +1,139 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use borsh::schema::{skip_value, skip_value_checked};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+enum Kind {
+    Empty,
+    Named(String),
+    Pair(u8, Option<u16>),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Record {
+    id: u32,
+    values: Vec<u64>,
+    kinds: Vec<Kind>,
+    label: Option<String>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+struct Message {
+    record: Record,
+    trailer: u16,
+}
+
+fn record() -> Record {
+    Record {
+        id: 9,
+        values: vec![1, 2, 3],
+        kinds: vec![
+            Kind::Named("x".to_string()),
+            Kind::Empty,
+            Kind::Pair(1, Some(2)),
+        ],
+        label: Some("label".to_string()),
+    }
+}
+
+#[test]
+fn test_skip_vec() {
+    let mut data = vec![10u64, 20, 30].try_to_vec().unwrap();
+    data.extend(0xbeefu16.try_to_vec().unwrap());
+    let container = Vec::<u64>::schema_container();
+    let mut cursor = &data[..];
+    skip_value(&container, "Vec<u64>", &mut cursor).unwrap();
+    assert_eq!(cursor.len(), 2);
+    assert_eq!(u16::try_from_slice(cursor).unwrap(), 0xbeef);
+}
+
+#[test]
+fn test_skip_struct() {
+    let message = Message {
+        record: record(),
+        trailer: 77,
+    };
+    let data = message.try_to_vec().unwrap();
+    let container = Message::schema_container();
+    let mut cursor = &data[..];
+    skip_value(&container, "Record", &mut cursor).unwrap();
+    assert_eq!(cursor, &77u16.to_le_bytes()[..]);
+
+    let mut cursor = &data[..];
+    skip_value(&container, "Message", &mut cursor).unwrap();
+    assert!(cursor.is_empty());
+}
+
+#[test]
+fn test_skip_each_field() {
+    let data = record().try_to_vec().unwrap();
+    let container = Record::schema_container();
+    let mut cursor = &data[..];
+    skip_value(&container, "u32", &mut cursor).unwrap();
+    skip_value(&container, "Vec<u64>", &mut cursor).unwrap();
+    assert_eq!(
+        Vec::<Kind>::deserialize(&mut cursor).unwrap(),
+        record().kinds
+    );
+    skip_value(&container, "Option<string>", &mut cursor).unwrap();
+    assert!(cursor.is_empty());
+}
+
+#[test]
+fn test_truncated_input() {
+    let data = record().try_to_vec().unwrap();
+    let container = Record::schema_container();
+    for len in 0..data.len() {
+        let mut cursor = &data[..len];
+        assert!(skip_value(&container, "Record", &mut cursor).is_err());
+    }
+    // A length prefix that is larger than the input.
+    let mut cursor = &u32::MAX.to_le_bytes()[..];
+    assert!(skip_value(&container, "Vec<u64>", &mut cursor).is_err());
+}
+
+#[test]
+fn test_invalid_tag() {
+    let container = Kind::schema_container();
+    let mut cursor = &[3u8][..];
+    assert_eq!(
+        skip_value(&container, "Kind", &mut cursor)
+            .unwrap_err()
+            .to_string(),
+        "Unexpected variant tag: 3"
+    );
+}
+
+#[test]
+fn test_checked_contents() {
+    let container = <(Vec<(u8, bool)>, String)>::schema_container();
+    let declaration = &container.declaration;
+    let data = (vec![(1u8, true), (2, false)], "ok".to_string())
+        .try_to_vec()
+        .unwrap();
+    let mut cursor = &data[..];
+    skip_value_checked(&container, declaration, &mut cursor).unwrap();
+    assert!(cursor.is_empty());
+
+    // The second `bool`, after the length and the first pair.
+    let mut invalid_bool = data.clone();
+    invalid_bool[7] = 2;
+    // The first byte of the string, after the sequence and the length of the string.
+    let mut invalid_utf8 = data;
+    invalid_utf8[12] = 0xff;
+    for invalid in [invalid_bool, invalid_utf8] {
+        let mut cursor = &invalid[..];
+        skip_value(&container, declaration, &mut cursor).unwrap();
+        let mut cursor = &invalid[..];
+        assert_eq!(
+            skip_value_checked(&container, declaration, &mut cursor)
+                .unwrap_err()
+                .to_string(),
+            container
+                .value_from_slice(&invalid)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
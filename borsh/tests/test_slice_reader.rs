@@ -0,0 +1,130 @@
+use borsh::maybestd::io::{Error, ErrorKind, Read, Result};
+use borsh::{BorshDeserialize, BorshSerialize, Reader};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Entry {
+    key: String,
+    values: Vec<u64>,
+    parent: Option<u32>,
+}
+
+/// A record of a hand-written format: a magic number, a count, then the entries in Borsh,
+/// borrowing a trailing payload from the input.
+#[derive(PartialEq, Debug)]
+struct Record<'a> {
+    entries: Vec<Entry>,
+    payload: &'a [u8],
+}
+
+const MAGIC: u32 = 0x4853_5242;
+
+impl<'a> Record<'a> {
+    fn parse(reader: &mut Reader<&'a [u8]>) -> Result<Self> {
+        let start = reader.position();
+        if reader.read_u32()? != MAGIC {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid magic number at byte {}", start),
+            ));
+        }
+        let count = reader.read_u8()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            entries.push(reader.deserialize()?);
+        }
+        let len = reader.read_u16()? as usize;
+        let payload = reader.read_exact_n(len)?;
+        Ok(Record { entries, payload })
+    }
+}
+
+fn entries() -> Vec<Entry> {
+    vec![
+        Entry {
+            key: "a".to_string(),
+            values: vec![1, 2],
+            parent: None,
+        },
+        Entry {
+            key: "bc".to_string(),
+            values: vec![],
+            parent: Some(7),
+        },
+    ]
+}
+
+fn record_bytes() -> Vec<u8> {
+    let mut data = MAGIC.try_to_vec().unwrap();
+    data.push(2);
+    for entry in entries() {
+        entry.serialize(&mut data).unwrap();
+    }
+    3u16.serialize(&mut data).unwrap();
+    data.extend_from_slice(b"xyz");
+    data
+}
+
+#[test]
+fn test_custom_parser() {
+    let data = record_bytes();
+    let mut reader = Reader::new(data.as_slice());
+    let record = Record::parse(&mut reader).unwrap();
+    assert_eq!(record.entries, entries());
+    assert_eq!(record.payload, b"xyz");
+    assert_eq!(reader.position(), data.len());
+    assert_eq!(reader.remaining(), 0);
+
+    let mut reader = Reader::new(&data[1..]);
+    assert_eq!(
+        Record::parse(&mut reader).unwrap_err().to_string(),
+        "Invalid magic number at byte 0"
+    );
+}
+
+#[test]
+fn test_position_after_nested_values() {
+    let data = record_bytes();
+    let mut reader = Reader::new(data.as_slice());
+    reader.read_exact_n(5).unwrap();
+    let mut expected = 5;
+    for entry in entries() {
+        assert_eq!(reader.deserialize::<Entry>().unwrap(), entry);
+        expected += entry.try_to_vec().unwrap().len();
+        assert_eq!(reader.position(), expected);
+        assert_eq!(reader.remaining(), data.len() - expected);
+    }
+    assert_eq!(reader.rest(), &data[expected..]);
+}
+
+#[test]
+fn test_truncated() {
+    let data = record_bytes();
+    let truncated = &data[..data.len() - 1];
+    let mut reader = Reader::new(truncated);
+    let err = Record::parse(&mut reader).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+    // The payload is not read, the reader stops before it.
+    assert_eq!(reader.position(), data.len() - 3);
+
+    let mut reader = Reader::new(&data[..10]);
+    reader.read_exact_n(5).unwrap();
+    assert!(reader.deserialize::<Entry>().is_err());
+}
+
+#[test]
+fn test_read() {
+    let data = record_bytes();
+    let mut reader = Reader::new(data.as_slice());
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic).unwrap();
+    assert_eq!(magic, MAGIC.to_le_bytes());
+    reader.read_u8().unwrap();
+    assert_eq!(
+        Entry::deserialize_reader(&mut reader).unwrap(),
+        entries()[0]
+    );
+    assert_eq!(
+        reader.position(),
+        5 + entries()[0].try_to_vec().unwrap().len()
+    );
+}
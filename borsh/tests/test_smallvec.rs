@@ -0,0 +1,32 @@
+use borsh::schema::BorshSchema;
+use borsh::{BorshDeserialize, BorshSerialize};
+use smallvec::SmallVec;
+
+#[test]
+fn test_inline_roundtrip() {
+    let value: SmallVec<[u32; 4]> = SmallVec::from_slice(&[1, 2, 3]);
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, vec![1u32, 2, 3].try_to_vec().unwrap());
+    let actual = SmallVec::<[u32; 4]>::try_from_slice(&data).unwrap();
+    assert!(!actual.spilled());
+    assert_eq!(actual, value);
+}
+
+#[test]
+fn test_spilled_roundtrip() {
+    let items: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+    let value: SmallVec<[String; 2]> = SmallVec::from_vec(items.clone());
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data, items.try_to_vec().unwrap());
+    let actual = SmallVec::<[String; 2]>::try_from_slice(&data).unwrap();
+    assert!(actual.spilled());
+    assert_eq!(actual, value);
+}
+
+#[test]
+fn test_schema_same_as_vec() {
+    assert_eq!(
+        SmallVec::<[u64; 8]>::schema_container(),
+        Vec::<u64>::schema_container()
+    );
+}
@@ -1,4 +1,7 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "std")]
+use std::ffi::{CStr, CString};
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize, LossyString, RawString};
 
 macro_rules! test_string {
     ($test_name: ident, $str: expr) => {
@@ -20,3 +23,92 @@ test_string!(test_x_4096, "x".repeat(4096));
 test_string!(test_x_65535, "x".repeat(65535));
 test_string!(test_hello_1000, "hello world!".repeat(1000));
 test_string!(test_non_ascii, "💩");
+
+/// Serialized string with the bytes `f`, `o`, an invalid byte and a truncated 4-byte sequence.
+const INVALID_UTF8: [u8; 9] = [5, 0, 0, 0, b'f', b'o', 0xff, 0xf0, 0x9f];
+
+#[test]
+fn test_invalid_utf8_string() {
+    assert!(<String>::try_from_slice(&INVALID_UTF8).is_err());
+}
+
+#[test]
+fn test_lossy_string() {
+    let s = LossyString::try_from_slice(&INVALID_UTF8).unwrap();
+    assert_eq!(s.0, "fo\u{FFFD}\u{FFFD}");
+
+    let valid = LossyString::from("💩".to_string());
+    let buf = valid.try_to_vec().unwrap();
+    assert_eq!(buf, "💩".to_string().try_to_vec().unwrap());
+    assert_eq!(LossyString::try_from_slice(&buf).unwrap(), valid);
+}
+
+#[test]
+fn test_raw_string() {
+    let s = RawString::try_from_slice(&INVALID_UTF8).unwrap();
+    assert_eq!(s.0, INVALID_UTF8[4..]);
+    assert_eq!(s.try_to_vec().unwrap(), INVALID_UTF8);
+
+    let valid = RawString::from("hello world".to_string());
+    assert_eq!(
+        valid.try_to_vec().unwrap(),
+        "hello world".to_string().try_to_vec().unwrap()
+    );
+}
+
+#[test]
+fn test_raw_string_as_str_checked() {
+    let s = RawString::try_from_slice(&INVALID_UTF8).unwrap();
+    assert_eq!(s.as_str_checked().unwrap_err().valid_up_to(), 2);
+
+    let bytes: Vec<u8> = (0..=255).collect();
+    let s = RawString::from(bytes.clone());
+    let buf = s.try_to_vec().unwrap();
+    assert_eq!(buf, bytes.try_to_vec().unwrap());
+    assert_eq!(RawString::try_from_slice(&buf).unwrap(), s);
+    assert!(s.as_str_checked().is_err());
+
+    let valid = "💩 and more".to_string().try_to_vec().unwrap();
+    let s = RawString::try_from_slice(&valid).unwrap();
+    assert_eq!(s.as_str_checked().unwrap(), "💩 and more");
+}
+
+#[test]
+fn test_string_wrapper_schemas() {
+    assert_eq!(LossyString::declaration(), String::declaration());
+    assert_eq!(RawString::declaration(), String::declaration());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_c_string_roundtrip() {
+    let s = CString::new("hello").unwrap();
+    let buf = s.try_to_vec().unwrap();
+    assert_eq!(buf, b"hello".to_vec().try_to_vec().unwrap());
+    assert_eq!(s.as_c_str().try_to_vec().unwrap(), buf);
+    assert_eq!(CString::try_from_slice(&buf).unwrap(), s);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_empty_c_string() {
+    let s = CString::default();
+    let buf = s.try_to_vec().unwrap();
+    assert_eq!(buf, [0, 0, 0, 0]);
+    assert_eq!(CString::try_from_slice(&buf).unwrap(), s);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_c_string_interior_nul() {
+    let buf = b"he\0llo".to_vec().try_to_vec().unwrap();
+    let err = CString::try_from_slice(&buf).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_c_string_schemas() {
+    assert_eq!(CString::declaration(), Vec::<u8>::declaration());
+    assert_eq!(CStr::declaration(), Vec::<u8>::declaration());
+}
@@ -0,0 +1,116 @@
+use borsh::dynamic::{BorshSerializeDyn, TaggedBox};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+trait Handler: BorshSerializeDyn {
+    fn handle(&self, message: &str) -> String;
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Logger {
+    prefix: String,
+}
+
+impl Handler for Logger {
+    fn handle(&self, message: &str) -> String {
+        format!("{}{}", self.prefix, message)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Counter(u32);
+
+impl Handler for Counter {
+    fn handle(&self, message: &str) -> String {
+        (message.len() as u32 + self.0).to_string()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Unregistered;
+
+impl Handler for Unregistered {
+    fn handle(&self, _message: &str) -> String {
+        String::new()
+    }
+}
+
+borsh::register_type!(Handler);
+borsh::register_type!(Handler, Logger = 1, Counter = 7);
+
+#[test]
+fn test_heterogeneous_roundtrip() {
+    let handlers: Vec<TaggedBox<dyn Handler>> = vec![
+        TaggedBox::new(Box::new(Logger {
+            prefix: "> ".to_string(),
+        })),
+        TaggedBox::new(Box::new(Counter(10))),
+    ];
+    let data = handlers.try_to_vec().unwrap();
+
+    let mut expected = vec![2, 0, 0, 0];
+    expected.extend(1u32.try_to_vec().unwrap());
+    expected.extend("> ".to_string().try_to_vec().unwrap());
+    expected.extend(7u32.try_to_vec().unwrap());
+    expected.extend(10u32.try_to_vec().unwrap());
+    assert_eq!(data, expected);
+
+    let actual = Vec::<TaggedBox<dyn Handler>>::try_from_slice(&data).unwrap();
+    let results: Vec<String> = actual.iter().map(|h| h.handle("hi")).collect();
+    assert_eq!(results, vec!["> hi".to_string(), "12".to_string()]);
+}
+
+#[test]
+fn test_unknown_tag() {
+    let data = (3u32, 10u32).try_to_vec().unwrap();
+    let err = match TaggedBox::<dyn Handler>::try_from_slice(&data) {
+        Ok(_) => panic!("expected an error"),
+        Err(err) => err,
+    };
+    assert_eq!(
+        err.to_string(),
+        "Unknown tag 3 for dyn test_tagged_box::Handler"
+    );
+}
+
+#[test]
+fn test_unregistered_type() {
+    let handler: TaggedBox<dyn Handler> = TaggedBox::new(Box::new(Unregistered));
+    let err = handler.try_to_vec().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "test_tagged_box::Unregistered is not registered for dyn test_tagged_box::Handler"
+    );
+}
+
+#[test]
+fn test_box_roundtrip() {
+    let boxes: Vec<Box<dyn Handler>> = vec![
+        Box::new(Logger {
+            prefix: "$ ".to_string(),
+        }),
+        Box::new(Counter(3)),
+    ];
+    for (handler, tag) in boxes.iter().zip([1u32, 7]) {
+        let data = handler.try_to_vec().unwrap();
+        assert_eq!(data[..4], tag.to_le_bytes());
+        let decoded = Box::<dyn Handler>::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.handle("abc"), handler.handle("abc"));
+        // The same encoding as `TaggedBox`.
+        let tagged = TaggedBox::<dyn Handler>::try_from_slice(&data).unwrap();
+        assert_eq!(tagged.try_to_vec().unwrap(), data);
+    }
+
+    let data = boxes.try_to_vec().unwrap();
+    let decoded = Vec::<Box<dyn Handler>>::try_from_slice(&data).unwrap();
+    let results: Vec<String> = decoded.iter().map(|h| h.handle("hi")).collect();
+    assert_eq!(results, vec!["$ hi".to_string(), "5".to_string()]);
+
+    let err = match Box::<dyn Handler>::try_from_slice(&(3u32, 10u32).try_to_vec().unwrap()) {
+        Ok(_) => panic!("expected an error"),
+        Err(err) => err,
+    };
+    assert_eq!(
+        err.to_string(),
+        "Unknown tag 3 for dyn test_tagged_box::Handler"
+    );
+}
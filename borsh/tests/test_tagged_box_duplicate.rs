@@ -0,0 +1,38 @@
+use borsh::dynamic::{validate_registry, BorshSerializeDyn};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+trait Handler: BorshSerializeDyn {}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct First;
+
+impl Handler for First {}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Second;
+
+impl Handler for Second {}
+
+borsh::register_type!(Handler);
+borsh::register_type!(Handler, First = 1);
+borsh::register_type!(Handler, Second = 1);
+
+#[test]
+fn test_duplicate_tag() {
+    let expected = "Tag 1 of Handler is registered for both";
+    assert!(validate_registry()
+        .unwrap_err()
+        .to_string()
+        .starts_with(expected));
+    let handler: Box<dyn Handler> = Box::new(First);
+    assert!(handler
+        .try_to_vec()
+        .unwrap_err()
+        .to_string()
+        .starts_with(expected));
+    assert!(Box::<dyn Handler>::try_from_slice(&[1, 0, 0, 0])
+        .err()
+        .unwrap()
+        .to_string()
+        .starts_with(expected));
+}
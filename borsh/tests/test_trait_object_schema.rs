@@ -0,0 +1,88 @@
+use borsh::dynamic::{BorshSerializeDyn, TaggedBox};
+use borsh::schema::{Definition, Fields, Value};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+trait Handler: BorshSerializeDyn {}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+struct Logger {
+    prefix: String,
+}
+
+impl Handler for Logger {}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+struct Counter(u32);
+
+impl Handler for Counter {}
+
+borsh::register_type!(Handler, Logger = 1, Counter = 7);
+borsh::borsh_trait_object_schema!(Handler => [Logger, Counter]);
+
+trait Unlisted: BorshSerializeDyn {}
+
+impl Unlisted for Counter {}
+
+borsh::borsh_trait_object_schema!(Unlisted => [Counter]);
+
+#[test]
+fn test_variants_are_the_registered_types() {
+    let container = Box::<dyn Handler>::schema_container();
+    assert_eq!(container.declaration, "dyn Handler");
+    assert_eq!(
+        container.definitions["dyn Handler"],
+        Definition::U32TaggedEnum {
+            variants: vec![
+                (1, "Logger".to_string(), "Logger".to_string()),
+                (7, "Counter".to_string(), "Counter".to_string()),
+            ]
+        }
+    );
+    assert_eq!(
+        container.definitions["Logger"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![("prefix".to_string(), "string".to_string())])
+        }
+    );
+    assert_eq!(
+        container.definitions["Counter"],
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["u32".to_string()])
+        }
+    );
+    assert_eq!(TaggedBox::<dyn Handler>::schema_container(), container);
+}
+
+#[test]
+fn test_schema_decodes_tagged_boxes() {
+    let handlers: Vec<TaggedBox<dyn Handler>> = vec![
+        TaggedBox::new(Box::new(Logger {
+            prefix: "> ".to_string(),
+        })),
+        TaggedBox::new(Box::new(Counter(10))),
+    ];
+    let data = handlers.try_to_vec().unwrap();
+    let container = Vec::<TaggedBox<dyn Handler>>::schema_container();
+    assert_eq!(
+        container.deserialize_value(&mut data.as_slice()).unwrap(),
+        Value::Sequence(vec![
+            Value::Enum {
+                variant: "Logger".to_string(),
+                value: Box::new(Value::Struct(vec![(
+                    "prefix".to_string(),
+                    Value::String("> ".to_string())
+                )])),
+            },
+            Value::Enum {
+                variant: "Counter".to_string(),
+                value: Box::new(Value::TupleStruct(vec![Value::U32(10)])),
+            },
+        ])
+    );
+}
+
+#[test]
+#[should_panic(expected = "Counter is not registered for Unlisted")]
+fn test_unregistered_type_panics() {
+    Box::<dyn Unlisted>::schema_container();
+}
@@ -0,0 +1,125 @@
+use std::io::{Error, ErrorKind};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(validate = "Transfer::check")]
+struct Transfer {
+    amount: u64,
+    recipients: Vec<u8>,
+}
+
+impl Transfer {
+    fn check(&self) -> Result<(), Error> {
+        if self.amount == 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "amount must be nonzero"));
+        }
+        if !self.recipients.windows(2).all(|w| w[0] < w[1]) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "recipients must be sorted",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_validation_rejects() {
+    let zero = Transfer {
+        amount: 0,
+        recipients: vec![1, 2],
+    };
+    let data = zero.try_to_vec().unwrap();
+    assert_eq!(data, (0u64, vec![1u8, 2]).try_to_vec().unwrap());
+    assert_eq!(
+        Transfer::try_from_slice(&data).unwrap_err().to_string(),
+        "amount must be nonzero"
+    );
+
+    let unsorted = (5u64, vec![2u8, 1]).try_to_vec().unwrap();
+    let err = Transfer::try_from_slice(&unsorted).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "recipients must be sorted");
+
+    let valid = Transfer {
+        amount: 5,
+        recipients: vec![1, 2],
+    };
+    assert_eq!(
+        Transfer::try_from_slice(&valid.try_to_vec().unwrap()).unwrap(),
+        valid
+    );
+}
+
+fn not_empty(batch: &Batch) -> Result<(), ErrorKind> {
+    match batch {
+        Batch::Single(_) => Ok(()),
+        Batch::Many(transfers) if !transfers.is_empty() => Ok(()),
+        Batch::Many(_) => Err(ErrorKind::InvalidInput),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(validate = "not_empty")]
+enum Batch {
+    Single(Transfer),
+    Many(Vec<Transfer>),
+}
+
+#[test]
+fn test_nested_validation() {
+    let valid = Batch::Many(vec![Transfer {
+        amount: 1,
+        recipients: vec![],
+    }]);
+    let data = valid.try_to_vec().unwrap();
+    assert_eq!(Batch::try_from_slice(&data).unwrap(), valid);
+
+    // The inner validation fails before the outer one runs.
+    let inner = Batch::Single(Transfer {
+        amount: 0,
+        recipients: vec![],
+    });
+    assert_eq!(
+        Batch::try_from_slice(&inner.try_to_vec().unwrap())
+            .unwrap_err()
+            .to_string(),
+        "amount must be nonzero"
+    );
+
+    let outer = Batch::Many(vec![]).try_to_vec().unwrap();
+    assert_eq!(
+        Batch::try_from_slice(&outer).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[borsh_init(normalize)]
+#[borsh(validate = "Range::check")]
+struct Range {
+    start: u32,
+    end: u32,
+}
+
+impl Range {
+    fn normalize(&mut self) {
+        if self.start > self.end {
+            core::mem::swap(&mut self.start, &mut self.end);
+        }
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.start > self.end {
+            return Err(Error::new(ErrorKind::InvalidData, "not normalized"));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_validation_runs_after_init() {
+    let range = Range::try_from_slice(&(9u32, 3u32).try_to_vec().unwrap()).unwrap();
+    assert_eq!((range.start, range.end), (3, 9));
+}
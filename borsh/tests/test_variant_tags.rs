@@ -0,0 +1,147 @@
+#![allow(dead_code)] // Local structures do not have their fields used.
+
+use borsh::schema::Definition;
+use borsh::ser::BorshTagged;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+mod v1 {
+    use super::*;
+
+    #[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+    pub enum Message {
+        #[borsh(tag = 0)]
+        Ping,
+        #[borsh(tag = 3)]
+        Text(String),
+        #[borsh(tag = 7)]
+        Transfer { amount: u64 },
+    }
+}
+
+mod v2 {
+    use super::*;
+
+    /// The variants of `v1::Message` reordered, with a new one inserted.
+    #[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+    pub enum Message {
+        #[borsh(tag = 7)]
+        Transfer { amount: u64 },
+        #[borsh(tag = 9)]
+        Close,
+        #[borsh(tag = 3)]
+        Text(String),
+        #[borsh(tag = 0)]
+        Ping,
+    }
+}
+
+#[test]
+fn test_reordered_variants_keep_their_bytes() {
+    let pairs = vec![
+        (v1::Message::Ping, v2::Message::Ping),
+        (
+            v1::Message::Text("hi".to_string()),
+            v2::Message::Text("hi".to_string()),
+        ),
+        (
+            v1::Message::Transfer { amount: 5 },
+            v2::Message::Transfer { amount: 5 },
+        ),
+    ];
+    for (old, new) in pairs {
+        let data = old.try_to_vec().unwrap();
+        assert_eq!(data, new.try_to_vec().unwrap());
+        assert_eq!(v2::Message::try_from_slice(&data).unwrap(), new);
+    }
+    assert_eq!(
+        v1::Message::Transfer { amount: 5 }.try_to_vec().unwrap(),
+        [7, 5, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_unknown_tag() {
+    let data = v2::Message::Close.try_to_vec().unwrap();
+    assert_eq!(data, [9]);
+    assert_eq!(
+        v1::Message::try_from_slice(&data).unwrap_err().to_string(),
+        "Unexpected variant tag: 9"
+    );
+    assert!(v1::Message::try_from_slice(&[1]).is_err());
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug)]
+enum Mixed {
+    First,
+    #[borsh(tag = 200)]
+    Pinned(u8),
+    Third,
+}
+
+#[test]
+fn test_untagged_variants_keep_their_position() {
+    assert_eq!(Mixed::First.try_to_vec().unwrap(), [0]);
+    assert_eq!(Mixed::Pinned(1).try_to_vec().unwrap(), [200, 1]);
+    assert_eq!(Mixed::Third.try_to_vec().unwrap(), [2]);
+    assert_eq!(Mixed::try_from_slice(&[200, 1]).unwrap(), Mixed::Pinned(1));
+    assert_eq!(
+        Mixed::VARIANT_TAGS,
+        &[(0, "First"), (200, "Pinned"), (2, "Third")]
+    );
+}
+
+#[test]
+fn test_schema() {
+    let container = Mixed::schema_container();
+    assert_eq!(
+        container.definitions["Mixed"],
+        Definition::TaggedEnum {
+            variants: vec![
+                (0, "First".to_string(), "MixedFirst".to_string()),
+                (200, "Pinned".to_string(), "MixedPinned".to_string()),
+                (2, "Third".to_string(), "MixedThird".to_string()),
+            ]
+        }
+    );
+    let data = Mixed::Pinned(4).try_to_vec().unwrap();
+    assert!(container.value_from_slice(&data).is_ok());
+}
+
+/// Checks that `VARIANT_TAGS` lists the variants of the schema of `T`, with their tags.
+fn assert_matches_schema<T: BorshTagged + BorshSchema>() {
+    let container = T::schema_container();
+    let variants: Vec<(u8, String)> = match &container.definitions[&container.declaration] {
+        Definition::Enum { variants } => variants
+            .iter()
+            .enumerate()
+            .map(|(tag, (name, _))| (tag as u8, name.clone()))
+            .collect(),
+        Definition::TaggedEnum { variants } => variants
+            .iter()
+            .map(|(tag, name, _)| (*tag, name.clone()))
+            .collect(),
+        definition => panic!("{:?}", definition),
+    };
+    let tags: Vec<(u8, String)> = T::VARIANT_TAGS
+        .iter()
+        .map(|(tag, name)| (*tag, name.to_string()))
+        .collect();
+    assert_eq!(tags, variants);
+    assert_eq!(T::variant_count(), variants.len());
+}
+
+#[derive(BorshSerialize, BorshSchema)]
+enum Plain {
+    A,
+    B(u8),
+    C { c: u16 },
+}
+
+#[test]
+fn test_variant_tags_match_schema() {
+    assert_matches_schema::<Plain>();
+    assert_matches_schema::<Mixed>();
+    assert_matches_schema::<v1::Message>();
+    assert_matches_schema::<v2::Message>();
+    assert_eq!(v2::Message::variant_count(), 4);
+}
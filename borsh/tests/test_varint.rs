@@ -0,0 +1,152 @@
+use borsh::varint::{self, read_varint, write_varint, Varint};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+fn encode<T: Varint>(value: T) -> Vec<u8> {
+    let mut data = Vec::new();
+    varint::serialize(&value, &mut data).unwrap();
+    data
+}
+
+fn decode<T: Varint>(mut data: &[u8]) -> std::io::Result<T> {
+    let value = varint::deserialize(&mut data)?;
+    assert!(data.is_empty());
+    Ok(value)
+}
+
+#[test]
+fn test_boundaries() {
+    let cases: [(u64, &[u8]); 7] = [
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (127, &[0x7f]),
+        (128, &[0x80, 0x01]),
+        (16383, &[0xff, 0x7f]),
+        (16384, &[0x80, 0x80, 0x01]),
+        (
+            u64::MAX,
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+    ];
+    for (value, bytes) in cases.iter() {
+        assert_eq!(encode(*value), *bytes);
+        assert_eq!(decode::<u64>(bytes).unwrap(), *value);
+
+        let mut data = Vec::new();
+        write_varint(&mut data, *value).unwrap();
+        assert_eq!(data, *bytes);
+        assert_eq!(read_varint(&mut &data[..]).unwrap(), *value);
+    }
+}
+
+#[test]
+fn test_widths() {
+    assert_eq!(encode(u16::MAX), [0xff, 0xff, 0x03]);
+    assert_eq!(decode::<u16>(&[0xff, 0xff, 0x03]).unwrap(), u16::MAX);
+    assert_eq!(encode(u32::MAX), [0xff, 0xff, 0xff, 0xff, 0x0f]);
+    assert_eq!(decode::<u32>(&encode(u32::MAX)).unwrap(), u32::MAX);
+    let max = encode(u128::MAX);
+    assert_eq!(max.len(), 19);
+    assert_eq!(decode::<u128>(&max).unwrap(), u128::MAX);
+}
+
+#[test]
+fn test_zigzag() {
+    let cases: [(i64, &[u8]); 6] = [
+        (0, &[0x00]),
+        (-1, &[0x01]),
+        (1, &[0x02]),
+        (-64, &[0x7f]),
+        (64, &[0x80, 0x01]),
+        (
+            i64::MIN,
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+    ];
+    for (value, bytes) in cases.iter() {
+        assert_eq!(encode(*value), *bytes);
+        assert_eq!(decode::<i64>(bytes).unwrap(), *value);
+    }
+    for value in [i16::MIN, -300, 300, i16::MAX].iter() {
+        assert_eq!(decode::<i16>(&encode(*value)).unwrap(), *value);
+    }
+    for value in [i128::MIN, -1, i128::MAX].iter() {
+        assert_eq!(decode::<i128>(&encode(*value)).unwrap(), *value);
+    }
+}
+
+#[test]
+fn test_non_canonical() {
+    for bytes in [
+        &[0x80, 0x00][..],
+        &[0xff, 0x80, 0x00],
+        &[0x81, 0x80, 0x80, 0x00],
+    ]
+    .iter()
+    {
+        assert_eq!(
+            decode::<u64>(bytes).unwrap_err().to_string(),
+            "Varint has unnecessary trailing bytes"
+        );
+    }
+}
+
+#[test]
+fn test_overflow() {
+    let overflows: [&[u8]; 3] = [
+        // 65536.
+        &[0x80, 0x80, 0x04],
+        // A fourth byte.
+        &[0x80, 0x80, 0x80, 0x01],
+        &[0xff, 0xff, 0x7f],
+    ];
+    for bytes in overflows.iter() {
+        assert_eq!(
+            decode::<u16>(bytes).unwrap_err().to_string(),
+            "Varint does not fit into the integer type"
+        );
+    }
+    let mut too_long = vec![0xff; 10];
+    too_long[9] = 0x02;
+    assert!(decode::<u64>(&too_long).is_err());
+    assert!(read_varint(&mut &too_long[..]).is_err());
+}
+
+#[test]
+fn test_truncated() {
+    assert_eq!(
+        decode::<u32>(&[0x80, 0x80]).unwrap_err().to_string(),
+        "Unexpected length of input"
+    );
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Transfer {
+    #[borsh(with = "borsh::varint")]
+    nonce: u64,
+    #[borsh(with = "varint")]
+    delta: i32,
+    memo: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum Event {
+    Ping,
+    Count(#[borsh(with = "borsh::varint")] u128, u8),
+}
+
+#[test]
+fn test_derive() {
+    let transfer = Transfer {
+        nonce: 200,
+        delta: -3,
+        memo: "hi".to_string(),
+    };
+    let data = transfer.try_to_vec().unwrap();
+    assert_eq!(data, [0xc8, 0x01, 0x05, 2, 0, 0, 0, b'h', b'i']);
+    assert_eq!(Transfer::try_from_slice(&data).unwrap(), transfer);
+
+    let event = Event::Count(5, 9);
+    let data = event.try_to_vec().unwrap();
+    assert_eq!(data, [1, 5, 9]);
+    assert_eq!(Event::try_from_slice(&data).unwrap(), event);
+}
@@ -0,0 +1,68 @@
+use borsh::maybestd::collections::HashSet;
+use borsh::test_vectors::{check_all, to_json, TestVector, VECTORS};
+
+#[test]
+fn test_check_all() {
+    check_all().unwrap();
+}
+
+#[test]
+fn test_names_are_unique() {
+    let names: HashSet<&str> = VECTORS.iter().map(|vector| vector.name).collect();
+    assert_eq!(names.len(), VECTORS.len());
+}
+
+#[test]
+fn test_changed_bytes_fail() {
+    let vector = VECTORS
+        .iter()
+        .find(|vector| vector.name == "u32_byte_order")
+        .unwrap();
+    let changed = TestVector {
+        bytes: &[1, 2, 3, 4],
+        ..*vector
+    };
+    assert_eq!(
+        changed.check().unwrap_err().to_string(),
+        "Test vector u32_byte_order serialized to 04030201, expected 01020304"
+    );
+}
+
+#[test]
+fn test_json_encodes_to_bytes() {
+    let json = to_json().unwrap();
+    let entries = json.as_array().unwrap();
+    assert_eq!(entries.len(), VECTORS.len());
+    for (entry, vector) in entries.iter().zip(VECTORS) {
+        assert_eq!(entry["name"], vector.name);
+        let schema = (vector.schema)();
+        assert_eq!(entry["declaration"], schema.declaration.as_str());
+        assert_eq!(
+            schema.serialize_json(&entry["value"]).unwrap(),
+            vector.bytes,
+            "{}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn test_json_format() {
+    let json = to_json().unwrap();
+    let entry = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["name"] == "option_some")
+        .unwrap();
+    assert_eq!(
+        entry,
+        &serde_json::json!({
+            "name": "option_some",
+            "declaration": "Option<u32>",
+            "schema": "0b0000004f7074696f6e3c7533323e010000000b0000004f7074696f6e3c7533323e0503000000753332",
+            "value": 7,
+            "bytes": "0107000000",
+        })
+    );
+}
@@ -0,0 +1,81 @@
+use borsh::maybestd::io::{Error, ErrorKind};
+use borsh::schema::{BorshSchema, Definition, Fields};
+use borsh::versioned::peek_version;
+use borsh::{BorshDeserialize, BorshSchema as BorshSchemaMacro, BorshSerialize, Versioned};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchemaMacro, PartialEq, Debug)]
+struct Account {
+    owner: String,
+    balance: u64,
+}
+
+#[test]
+fn test_roundtrip() {
+    let value = Versioned::new(
+        2,
+        Account {
+            owner: "alice".to_string(),
+            balance: 10,
+        },
+    );
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(data[..4], 2u32.to_le_bytes());
+    assert_eq!(data[4..], value.inner.try_to_vec().unwrap()[..]);
+    assert_eq!(Versioned::<Account>::try_from_slice(&data).unwrap(), value);
+}
+
+#[test]
+fn test_peek_version_without_inner() {
+    // The payload is not a valid `Account`, but only the version is read.
+    let mut data = 7u32.try_to_vec().unwrap();
+    data.extend_from_slice(&[0xff; 3]);
+    assert_eq!(peek_version(&data).unwrap(), 7);
+    assert!(Versioned::<Account>::try_from_slice(&data).is_err());
+    assert!(peek_version(&[1, 0]).is_err());
+}
+
+#[test]
+fn test_deserialize_by_version() {
+    let v1 = Versioned::new(1, "bob".to_string()).try_to_vec().unwrap();
+    let v2 = Versioned::new(
+        2,
+        Account {
+            owner: "bob".to_string(),
+            balance: 5,
+        },
+    )
+    .try_to_vec()
+    .unwrap();
+
+    let read = |data: &[u8]| {
+        Versioned::deserialize_by_version(&mut &data[..], |version, reader| match version {
+            1 => Ok(Account {
+                owner: String::deserialize_reader(reader)?,
+                balance: 0,
+            }),
+            2 => Account::deserialize_reader(reader),
+            _ => Err(Error::new(ErrorKind::InvalidData, "Unknown version")),
+        })
+    };
+    assert_eq!(read(&v1).unwrap().inner.balance, 0);
+    assert_eq!(read(&v2).unwrap().inner.balance, 5);
+    assert_eq!(read(&v2).unwrap().version, 2);
+    let v3 = Versioned::new(3, ()).try_to_vec().unwrap();
+    assert_eq!(read(&v3).unwrap_err().to_string(), "Unknown version");
+}
+
+#[test]
+fn test_schema() {
+    let container = Versioned::<Account>::schema_container();
+    assert_eq!(container.declaration, "Versioned<Account>");
+    assert_eq!(
+        container.definitions["Versioned<Account>"],
+        Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("version".to_string(), "u32".to_string()),
+                ("inner".to_string(), "Account".to_string()),
+            ])
+        }
+    );
+    assert!(container.definitions.contains_key("Account"));
+}
@@ -0,0 +1,102 @@
+use borsh::wire_format::{check_snapshot_file, check_wire_format, diff, from_hex, to_hex};
+use borsh::{assert_wire_format, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+struct Greeting {
+    id: u8,
+    text: String,
+}
+
+fn sample() -> Greeting {
+    Greeting {
+        id: 10,
+        text: "hello".to_string(),
+    }
+}
+
+#[test]
+fn test_hex_roundtrip() {
+    let bytes = vec![0x00, 0x0a, 0xff, 0x7f];
+    assert_eq!(to_hex(&bytes), "000aff7f");
+    assert_eq!(from_hex("000aff7f").unwrap(), bytes);
+    assert_eq!(from_hex("00 0A_ff\n7F").unwrap(), bytes);
+}
+
+#[test]
+fn test_invalid_hex() {
+    assert_eq!(
+        from_hex("0g").unwrap_err().to_string(),
+        "Invalid hex digit 'g'"
+    );
+    assert_eq!(
+        from_hex("abc").unwrap_err().to_string(),
+        "Odd number of hex digits"
+    );
+}
+
+#[test]
+fn test_assert_wire_format() {
+    assert_wire_format!(Greeting, sample(), "0a 05000000 68656c6c6f");
+    assert_wire_format!(Vec<u16>, vec![1, 2], "02000000 0100 0200");
+}
+
+#[test]
+#[should_panic(expected = "Wire format of Greeting changed: Serialized bytes differ at byte 9")]
+fn test_assert_wire_format_mismatch() {
+    assert_wire_format!(Greeting, sample(), "0a 05000000 68656c6c21");
+}
+
+#[test]
+fn test_diff() {
+    assert_eq!(diff(&[1, 2, 3], &[1, 2, 3]), None);
+    assert_eq!(
+        diff(&[1, 2, 3], &[1, 4, 3]).unwrap(),
+        "Serialized bytes differ at byte 1 (expected 3 bytes, got 3 bytes)\n\
+         00000000 - 010203\n\
+         00000000 + 010403\n\
+         \x20            ^^\n\
+         actual: 010403"
+    );
+}
+
+#[test]
+fn test_diff_of_lengths() {
+    let expected: Vec<u8> = (0..20).collect();
+    let diff = diff(&expected, &expected[..17]).unwrap();
+    assert!(diff.starts_with(
+        "Serialized bytes differ at byte 17 (expected 20 bytes, got 17 bytes)\n\
+         00000010 - 10111213\n\
+         00000010 + 10\n"
+    ));
+}
+
+#[test]
+fn test_check_decodes_expected_bytes() {
+    // The expected bytes must deserialize, not only match the serialized value.
+    let err = check_wire_format(&true, "02").unwrap_err();
+    assert!(err.to_string().contains("differ at byte 0"));
+    assert!(check_wire_format(&Some(1u8), "0101").is_ok());
+    assert!(check_wire_format(&Some(1u8), "0101 00").is_err());
+}
+
+#[test]
+fn test_snapshot_file() {
+    let path = std::env::temp_dir().join(format!("borsh-test-snapshot-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    check_snapshot_file(&sample(), &path).unwrap();
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        sample().try_to_vec().unwrap()
+    );
+    check_snapshot_file(&sample(), &path).unwrap();
+
+    let other = Greeting {
+        id: 11,
+        text: "hello".to_string(),
+    };
+    let err = check_snapshot_file(&other, &path).unwrap_err();
+    assert!(err.to_string().contains("differ at byte 0"));
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,11 +1,93 @@
-use borsh::BorshDeserialize;
+use core::marker::PhantomData;
+
+use borsh::de::Limits;
+use borsh::maybestd::collections::{BTreeMap, HashSet};
+use borsh::maybestd::io::ErrorKind;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 #[derive(BorshDeserialize, PartialEq, Debug)]
 struct A;
 
+/// A length prefix of `len` elements, without the elements, which take no bytes when zero-sized.
+fn prefix(len: u32) -> Vec<u8> {
+    len.try_to_vec().unwrap()
+}
+
+fn limits(max_zst_sequence_elements: usize) -> Limits {
+    Limits {
+        max_zst_sequence_elements,
+        ..Limits::default()
+    }
+}
+
+fn assert_rejected<T: BorshDeserialize + core::fmt::Debug>(data: &[u8], len: u32, max: usize) {
+    let err = T::try_from_slice_limited(data, &limits(max)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Exceeded the max_zst_sequence_elements limit of {}: {}",
+            max, len
+        )
+    );
+}
+
 #[test]
 fn test_deserialize_vector_to_many_zero_size_struct() {
     let v = [0u8, 0u8, 0u8, 64u8];
     let a = Vec::<A>::try_from_slice(&v).unwrap();
     assert_eq!(A {}, a[usize::pow(2, 30) - 1])
 }
+
+#[test]
+fn test_hostile_length_prefix() {
+    for len in [u32::MAX, 1 << 20, 1001] {
+        let data = prefix(len);
+        assert_rejected::<Vec<()>>(&data, len, 1000);
+        assert_rejected::<Vec<PhantomData<String>>>(&data, len, 1000);
+        assert_rejected::<HashSet<()>>(&data, len, 1000);
+        assert_rejected::<BTreeMap<(), ()>>(&data, len, 1000);
+    }
+}
+
+#[test]
+fn test_round_trip_at_the_limit() {
+    // Without limits, sequences of any length round-trip.
+    for len in [0, 1, 65_536, 65_537, 1 << 20] {
+        let value = vec![(); len];
+        let data = value.try_to_vec().unwrap();
+        assert_eq!(data, prefix(len as u32));
+        assert_eq!(Vec::<()>::try_from_slice(&data).unwrap(), value);
+    }
+
+    let max = 65_536;
+    let value = vec![(); max];
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(
+        Vec::<()>::try_from_slice_limited(&data, &limits(max)).unwrap(),
+        value
+    );
+    let data = vec![(); max + 1].try_to_vec().unwrap();
+    assert_rejected::<Vec<()>>(&data, max as u32 + 1, max);
+
+    let set: HashSet<()> = Some(()).into_iter().collect();
+    let data = set.try_to_vec().unwrap();
+    assert_eq!(HashSet::<()>::try_from_slice(&data).unwrap(), set);
+}
+
+#[test]
+fn test_nested() {
+    let value = vec![vec![(); 3], vec![], vec![(); 1000]];
+    let data = value.try_to_vec().unwrap();
+    assert_eq!(Vec::<Vec<()>>::try_from_slice(&data).unwrap(), value);
+    assert_eq!(
+        Vec::<Vec<()>>::try_from_slice_limited(&data, &limits(1000)).unwrap(),
+        value
+    );
+
+    // The outer `Vec` has elements of 4 bytes, so only the inner one is limited.
+    let mut data = prefix(2);
+    data.extend(prefix(1));
+    data.extend(prefix(u32::MAX));
+    assert_rejected::<Vec<Vec<()>>>(&data, u32::MAX, 1000);
+}